@@ -112,8 +112,14 @@ fn bench_drive_metrics_update(c: &mut Criterion) {
             classification: Default::default(),
             metrics: None,
             smart: None,
+            health: None,
+            failure_risk: None,
             pool_ref: None,
             healthy: true,
+            in_use: vec![],
+            wwid: None,
+            paths: vec![],
+            location: None,
         },
     ];
     let _ = registry.register("node-001", "host.local".to_string(), status);
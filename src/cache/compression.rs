@@ -4,13 +4,15 @@
 
 use crate::error::{Error, Result};
 use bytes::Bytes;
+use serde::Deserialize;
 
 // =============================================================================
 // Compression Configuration
 // =============================================================================
 
 /// Configuration for compression
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "camelCase")]
 pub struct CompressionConfig {
     /// Default algorithm to use
     pub default_algorithm: CompressionAlgorithm,
@@ -9,6 +9,7 @@ use crate::cache::tier::CacheTier;
 use chrono::Utc;
 use indexmap::IndexMap;
 use parking_lot::RwLock;
+use serde::Deserialize;
 use std::sync::atomic::{AtomicU64, Ordering};
 
 // =============================================================================
@@ -23,7 +24,8 @@ pub const LRU_SHARD_COUNT: usize = 64;
 // =============================================================================
 
 /// Policy for selecting eviction candidates
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub enum EvictionPolicy {
     /// Least Recently Used - evict entries that haven't been accessed recently
     Lru,
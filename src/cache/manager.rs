@@ -10,11 +10,12 @@ use crate::cache::lru::{EvictionPolicy, ShardedLruTracker};
 use crate::cache::metrics::{CacheMetrics, CacheStatsSnapshot};
 use crate::cache::prefetch::{PrefetchConfig, PrefetchRequest, Prefetcher};
 use crate::cache::storage::{LocalStorage, MemoryStorage, PersistentStorage, TierStorage};
-use crate::cache::tier::{CacheTier, TierConfig, CACHE_BYPASS_SIZE_BYTES};
+use crate::cache::tier::{self, CacheTier, TierConfig, CACHE_BYPASS_SIZE_BYTES};
 use crate::cache::{CacheLookupResult, StorageCache};
 use crate::error::{Error, Result};
 use async_trait::async_trait;
 use bytes::Bytes;
+use serde::Deserialize;
 use std::sync::Arc;
 use tokio::sync::broadcast;
 use tracing::{debug, info, warn};
@@ -24,13 +25,17 @@ use tracing::{debug, info, warn};
 // =============================================================================
 
 /// Configuration for the multi-tier cache
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "camelCase")]
 pub struct MultiTierCacheConfig {
     /// L1 Memory tier configuration
+    #[serde(deserialize_with = "tier::deserialize_l1")]
     pub l1: TierConfig,
     /// L2 Local tier configuration
+    #[serde(deserialize_with = "tier::deserialize_l2")]
     pub l2: TierConfig,
     /// L3 Persistent tier configuration
+    #[serde(deserialize_with = "tier::deserialize_l3")]
     pub l3: TierConfig,
     /// Compression configuration
     pub compression: CompressionConfig,
@@ -5,6 +5,7 @@
 use crate::cache::entry::CacheKey;
 use crate::cache::tier::CacheTier;
 use crate::error::Result;
+use serde::Deserialize;
 use std::collections::VecDeque;
 use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use tokio::sync::{mpsc, Mutex};
@@ -14,7 +15,8 @@ use tokio::sync::{mpsc, Mutex};
 // =============================================================================
 
 /// Configuration for the prefetcher
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "camelCase")]
 pub struct PrefetchConfig {
     /// Maximum concurrent prefetch operations
     pub max_concurrent: usize,
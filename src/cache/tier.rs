@@ -2,7 +2,7 @@
 //!
 //! Defines the three-tier caching hierarchy with size-based placement rules.
 
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
 use std::fmt;
 
 // =============================================================================
@@ -190,6 +190,58 @@ impl TierConfig {
     pub fn should_evict(&self, current_bytes: u64) -> bool {
         current_bytes > self.eviction_watermark()
     }
+
+    fn with_overrides(mut self, overrides: TierOverrides) -> Self {
+        if let Some(v) = overrides.capacity_bytes {
+            self.capacity_bytes = v;
+        }
+        if let Some(v) = overrides.eviction_threshold {
+            self.eviction_threshold = v;
+        }
+        if let Some(v) = overrides.enable_demotion {
+            self.enable_demotion = v;
+        }
+        if let Some(v) = overrides.enable_compression {
+            self.enable_compression = v;
+        }
+        if let Some(v) = overrides.target_compression_ratio {
+            self.target_compression_ratio = v;
+        }
+        self
+    }
+}
+
+/// Tier settings given in a configuration file; unset fields keep the
+/// tier's defaults and the tier itself is implied by the position
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "camelCase")]
+struct TierOverrides {
+    capacity_bytes: Option<u64>,
+    eviction_threshold: Option<f32>,
+    enable_demotion: Option<bool>,
+    enable_compression: Option<bool>,
+    target_compression_ratio: Option<f32>,
+}
+
+/// Deserialize L1 settings on top of [`TierConfig::l1_default`]
+pub(crate) fn deserialize_l1<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> std::result::Result<TierConfig, D::Error> {
+    TierOverrides::deserialize(deserializer).map(|o| TierConfig::l1_default().with_overrides(o))
+}
+
+/// Deserialize L2 settings on top of [`TierConfig::l2_default`]
+pub(crate) fn deserialize_l2<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> std::result::Result<TierConfig, D::Error> {
+    TierOverrides::deserialize(deserializer).map(|o| TierConfig::l2_default().with_overrides(o))
+}
+
+/// Deserialize L3 settings on top of [`TierConfig::l3_default`]
+pub(crate) fn deserialize_l3<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> std::result::Result<TierConfig, D::Error> {
+    TierOverrides::deserialize(deserializer).map(|o| TierConfig::l3_default().with_overrides(o))
 }
 
 #[cfg(test)]
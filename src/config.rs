@@ -0,0 +1,536 @@
+//! Operator Configuration File
+//!
+//! Loads the API server, orchestrator (backends, platforms, resolver),
+//! cache and tiering settings from one YAML file. Every section and field is optional;
+//! unset fields keep their defaults, unknown fields are rejected.
+//!
+//! Before parsing, `${...}` references are expanded:
+//!
+//! - `${NAME}`: environment variable (an error if unset)
+//! - `${NAME:-fallback}`: environment variable, or `fallback` if unset or empty
+//! - `${file:/run/secrets/password}`: contents of a file, without the
+//!   trailing newline (relative paths resolve against the config file)
+//! - `$$`: a literal `$`
+//!
+//! References in comment lines are left alone. Values are substituted as
+//! text, so quote them where the value could be read as another YAML type.
+
+use crate::cache::MultiTierCacheConfig;
+use crate::controlplane::api::ApiServerConfig;
+use crate::controlplane::backends::{MayastorConfig, RustFSConfig, SeaweedFSConfig};
+use crate::controlplane::{OrchestratorConfig, TieringConfig};
+use crate::domain::ports::Platform;
+use crate::error::{Error, Result};
+use serde::Deserialize;
+use std::collections::BTreeSet;
+use std::path::Path;
+
+// =============================================================================
+// Operator Configuration
+// =============================================================================
+
+/// Everything configurable through `--config`
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "camelCase")]
+pub struct OperatorConfig {
+    /// REST/gRPC server settings
+    pub api: ApiServerConfig,
+    /// Orchestrator, backend, platform and resolver settings
+    pub orchestrator: OrchestratorConfig,
+    /// Multi-tier cache settings
+    pub cache: MultiTierCacheConfig,
+    /// Heat-based tiering mode and rules; tiering is off when unset
+    pub tiering: Option<TieringConfig>,
+}
+
+impl OperatorConfig {
+    /// Read, interpolate, parse and validate a configuration file
+    pub fn load(path: &Path) -> Result<Self> {
+        let source = std::fs::read_to_string(path)
+            .map_err(|e| Error::Configuration(format!("{}: {}", path.display(), e)))?;
+        let base_dir = path.parent().unwrap_or_else(|| Path::new("."));
+        Self::parse(&source, base_dir, |name| std::env::var(name).ok())
+            .map_err(|e| Error::Configuration(format!("{}: {}", path.display(), e)))
+    }
+
+    /// Interpolate, parse and validate configuration text
+    ///
+    /// Errors name the offending field and its line and column.
+    pub fn parse(
+        source: &str,
+        base_dir: &Path,
+        env: impl Fn(&str) -> Option<String>,
+    ) -> std::result::Result<Self, String> {
+        let expanded = interpolate(source, base_dir, env)?;
+        if expanded.trim().is_empty() {
+            return Ok(Self::default());
+        }
+
+        // serde_yaml errors carry the field path, line and column
+        let config: Self = serde_yaml::from_str(&expanded).map_err(|e| e.to_string())?;
+
+        let problems = config.validate();
+        if problems.is_empty() {
+            Ok(config)
+        } else {
+            Err(problems.join("; "))
+        }
+    }
+
+    /// Check values that parse but cannot work, as `field: problem` messages
+    pub fn validate(&self) -> Vec<String> {
+        let mut problems = Problems::default();
+
+        let api = &self.api;
+        if api.tls_enabled {
+            problems.require(
+                api.tls_cert_path.is_some(),
+                "api.tlsCertPath",
+                "required when tlsEnabled is set",
+            );
+            problems.require(
+                api.tls_key_path.is_some(),
+                "api.tlsKeyPath",
+                "required when tlsEnabled is set",
+            );
+        }
+        problems.require(
+            api.request_timeout_secs > 0,
+            "api.requestTimeoutSecs",
+            "must be positive",
+        );
+        problems.require(api.max_body_size > 0, "api.maxBodySize", "must be positive");
+
+        let orchestrator = &self.orchestrator;
+        let backends = &orchestrator.backends;
+        problems.mayastor("orchestrator.backends.mayastor", &backends.mayastor);
+        problems.seaweedfs("orchestrator.backends.seaweedfs", &backends.seaweedfs);
+        problems.rustfs("orchestrator.backends.rustfs", &backends.rustfs);
+
+        let mut names = BTreeSet::new();
+        for (i, instance) in backends.instances.iter().enumerate() {
+            let field = format!("orchestrator.backends.instances[{}]", i);
+            problems.require(
+                names.insert(instance.name.as_str()),
+                &format!("{}.name", field),
+                &format!("duplicate instance name {}", instance.name),
+            );
+            problems.require(
+                matches!(
+                    instance.kind.to_lowercase().as_str(),
+                    "mayastor" | "seaweedfs" | "rustfs" | "block" | "file" | "object"
+                ),
+                &format!("{}.kind", field),
+                &format!(
+                    "unknown backend kind {} (mayastor, seaweedfs or rustfs)",
+                    instance.kind
+                ),
+            );
+
+            // Only the section for the instance's own kind is used
+            let kind = instance.kind.to_lowercase();
+            let other_kind = format!("does not apply to a {} instance", instance.kind);
+            if let Some(mayastor) = &instance.mayastor {
+                let section = format!("{}.mayastor", field);
+                problems.require(matches!(kind.as_str(), "mayastor" | "block"), &section, &other_kind);
+                problems.mayastor(&section, mayastor);
+            }
+            if let Some(seaweedfs) = &instance.seaweedfs {
+                let section = format!("{}.seaweedfs", field);
+                problems.require(matches!(kind.as_str(), "seaweedfs" | "file"), &section, &other_kind);
+                problems.seaweedfs(&section, seaweedfs);
+            }
+            if let Some(rustfs) = &instance.rustfs {
+                let section = format!("{}.rustfs", field);
+                problems.require(matches!(kind.as_str(), "rustfs" | "object"), &section, &other_kind);
+                problems.rustfs(&section, rustfs);
+            }
+        }
+
+        let platforms = &orchestrator.platforms;
+        problems.optional_url(
+            "orchestrator.platforms.harvester.apiEndpoint",
+            &platforms.harvester.api_endpoint,
+        );
+        problems.require(
+            platforms.harvester.default_replicas > 0,
+            "orchestrator.platforms.harvester.defaultReplicas",
+            "must be at least 1",
+        );
+        problems.require(
+            matches!(
+                platforms.harvester.data_locality.as_str(),
+                "disabled" | "best-effort" | "strict-local"
+            ),
+            "orchestrator.platforms.harvester.dataLocality",
+            "must be disabled, best-effort or strict-local",
+        );
+        problems.url(
+            "orchestrator.platforms.openstack.authUrl",
+            &platforms.openstack.auth_url,
+        );
+        if orchestrator.default_platform == Platform::OpenStack {
+            problems.require(
+                !platforms.openstack.password.is_empty(),
+                "orchestrator.platforms.openstack.password",
+                "required when defaultPlatform is openstack",
+            );
+        }
+
+        let resolver = &orchestrator.resolver;
+        problems.require(
+            resolver.warm_iops_threshold <= resolver.hot_iops_threshold,
+            "orchestrator.resolver.warmIopsThreshold",
+            "must not exceed hotIopsThreshold",
+        );
+        problems.require(
+            resolver.min_headroom_percent < 100,
+            "orchestrator.resolver.minHeadroomPercent",
+            "must be below 100",
+        );
+
+        if let Some(tiering) = &self.tiering {
+            problems.require(
+                tiering.max_concurrent_moves > 0,
+                "tiering.maxConcurrentMoves",
+                "must be at least 1",
+            );
+            problems.require(
+                tiering.evaluation_interval_secs > 0,
+                "tiering.evaluationIntervalSecs",
+                "must be positive",
+            );
+            let mut names = BTreeSet::new();
+            for (i, rule) in tiering.rules.iter().enumerate() {
+                problems.require(
+                    names.insert(rule.name.as_str()),
+                    &format!("tiering.rules[{}].name", i),
+                    &format!("duplicate rule name {}", rule.name),
+                );
+            }
+        }
+
+        let cache = &self.cache;
+        for (name, tier) in [("l1", &cache.l1), ("l2", &cache.l2), ("l3", &cache.l3)] {
+            problems.require(
+                tier.eviction_threshold > 0.0 && tier.eviction_threshold <= 1.0,
+                &format!("cache.{}.evictionThreshold", name),
+                "must be in (0, 1]",
+            );
+            problems.require(
+                tier.target_compression_ratio > 0.0 && tier.target_compression_ratio <= 1.0,
+                &format!("cache.{}.targetCompressionRatio", name),
+                "must be in (0, 1]",
+            );
+        }
+        problems.require(
+            cache.event_channel_capacity > 0,
+            "cache.eventChannelCapacity",
+            "must be positive",
+        );
+        problems.require(
+            cache.prefetch.max_concurrent > 0,
+            "cache.prefetch.maxConcurrent",
+            "must be positive",
+        );
+
+        problems.0
+    }
+}
+
+/// Collected validation messages
+#[derive(Default)]
+struct Problems(Vec<String>);
+
+impl Problems {
+    fn require(&mut self, ok: bool, field: &str, problem: &str) {
+        if !ok {
+            self.0.push(format!("{}: {}", field, problem));
+        }
+    }
+
+    fn url(&mut self, field: &str, value: &str) {
+        match reqwest::Url::parse(value) {
+            Ok(url) if matches!(url.scheme(), "http" | "https") => {}
+            Ok(_) => self
+                .0
+                .push(format!("{}: {} is not an http(s) URL", field, value)),
+            Err(e) => self
+                .0
+                .push(format!("{}: invalid URL {}: {}", field, value, e)),
+        }
+    }
+
+    fn optional_url(&mut self, field: &str, value: &Option<String>) {
+        if let Some(value) = value {
+            self.url(field, value);
+        }
+    }
+
+    fn mayastor(&mut self, section: &str, config: &MayastorConfig) {
+        self.optional_url(&format!("{}.apiEndpoint", section), &config.api_endpoint);
+        self.require(
+            config.default_replicas > 0,
+            &format!("{}.defaultReplicas", section),
+            "must be at least 1",
+        );
+    }
+
+    fn seaweedfs(&mut self, section: &str, config: &SeaweedFSConfig) {
+        self.url(&format!("{}.masterEndpoint", section), &config.master_endpoint);
+        self.url(&format!("{}.filerEndpoint", section), &config.filer_endpoint);
+        let replication = &config.default_replication;
+        self.require(
+            replication.len() == 3 && replication.bytes().all(|b| b.is_ascii_digit()),
+            &format!("{}.defaultReplication", section),
+            "must be three digits (data center, rack, server copies)",
+        );
+    }
+
+    fn rustfs(&mut self, section: &str, config: &RustFSConfig) {
+        self.url(&format!("{}.apiEndpoint", section), &config.api_endpoint);
+        self.require(
+            config.ec_data_shards > 0,
+            &format!("{}.ecDataShards", section),
+            "must be at least 1",
+        );
+        self.require(
+            config.access_key.is_some() == config.secret_key.is_some(),
+            section,
+            "accessKey and secretKey must be set together",
+        );
+    }
+}
+
+// =============================================================================
+// Interpolation
+// =============================================================================
+
+/// Expand `${...}` references in configuration text
+///
+/// Errors give the line and column of the reference.
+pub fn interpolate(
+    source: &str,
+    base_dir: &Path,
+    env: impl Fn(&str) -> Option<String>,
+) -> std::result::Result<String, String> {
+    let mut out = String::with_capacity(source.len());
+
+    for (index, line) in source.split_inclusive('\n').enumerate() {
+        if line.trim_start().starts_with('#') {
+            out.push_str(line);
+            continue;
+        }
+
+        let mut rest = line;
+        while let Some(start) = rest.find('$') {
+            out.push_str(&rest[..start]);
+            let column = line.len() - rest.len() + start + 1;
+            let at = |message: String| format!("line {} column {}: {}", index + 1, column, message);
+            let tail = &rest[start..];
+
+            if let Some(after) = tail.strip_prefix("$$") {
+                out.push('$');
+                rest = after;
+            } else if let Some(body) = tail.strip_prefix("${") {
+                let end = body
+                    .find('}')
+                    .ok_or_else(|| at("unterminated ${ reference".into()))?;
+                let reference = &body[..end];
+                out.push_str(&resolve(reference, base_dir, &env).map_err(at)?);
+                rest = &body[end + 1..];
+            } else {
+                out.push('$');
+                rest = &tail[1..];
+            }
+        }
+        out.push_str(rest);
+    }
+
+    Ok(out)
+}
+
+fn resolve(
+    reference: &str,
+    base_dir: &Path,
+    env: &impl Fn(&str) -> Option<String>,
+) -> std::result::Result<String, String> {
+    if let Some(file) = reference.strip_prefix("file:") {
+        let path = base_dir.join(file.trim());
+        let content = std::fs::read_to_string(&path)
+            .map_err(|e| format!("cannot read secret file {}: {}", path.display(), e))?;
+        let value = content.strip_suffix('\n').unwrap_or(&content);
+        let value = value.strip_suffix('\r').unwrap_or(value);
+        if value.contains('\n') {
+            return Err(format!(
+                "secret file {} has more than one line",
+                path.display()
+            ));
+        }
+        return Ok(value.to_string());
+    }
+
+    let (name, fallback) = match reference.split_once(":-") {
+        Some((name, fallback)) => (name, Some(fallback)),
+        None => (reference, None),
+    };
+    let name = name.trim();
+    if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+        return Err(format!("invalid variable name {:?}", name));
+    }
+
+    match (env(name).filter(|v| !v.is_empty()), fallback) {
+        (Some(value), _) => Ok(value),
+        (None, Some(fallback)) => Ok(fallback.to_string()),
+        (None, None) => Err(format!("environment variable {} is not set", name)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    fn parse(source: &str, vars: &[(&str, &str)]) -> std::result::Result<OperatorConfig, String> {
+        let vars: BTreeMap<String, String> = vars
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        OperatorConfig::parse(source, Path::new("."), |name| vars.get(name).cloned())
+    }
+
+    #[test]
+    fn test_partial_config_keeps_defaults() {
+        let config = parse(
+            r#"
+api:
+  restAddr: "127.0.0.1:${PORT:-9090}"
+orchestrator:
+  defaultPlatform: harvester
+  backends:
+    rustfs:
+      apiEndpoint: "${RUSTFS_URL}"
+    instances:
+      - name: mayastor-a
+        kind: mayastor
+        tags: [nvme]
+        mayastor:
+          apiEndpoint: "http://mayastor-a:8080"
+cache:
+  l2:
+    capacityBytes: 1024
+"#,
+            &[("RUSTFS_URL", "http://rustfs.storage:9000")],
+        )
+        .unwrap();
+
+        assert_eq!(config.api.rest_addr.to_string(), "127.0.0.1:9090");
+        assert_eq!(config.api.request_timeout_secs, 30);
+        assert_eq!(config.orchestrator.default_platform, Platform::Harvester);
+        assert_eq!(
+            config.orchestrator.backends.rustfs.api_endpoint,
+            "http://rustfs.storage:9000"
+        );
+        assert_eq!(config.orchestrator.backends.rustfs.ec_data_shards, 4);
+        assert_eq!(config.orchestrator.backends.instances[0].weight, 100);
+        let instance = &config.orchestrator.backends.instances[0];
+        let mayastor = instance.mayastor.as_ref().unwrap();
+        assert_eq!(mayastor.api_endpoint.as_deref(), Some("http://mayastor-a:8080"));
+        assert_eq!(mayastor.default_replicas, 3);
+        assert_eq!(config.cache.l2.capacity_bytes, 1024);
+        // Unset tier fields come from that tier's defaults
+        assert_eq!(config.cache.l2.tier, crate::cache::CacheTier::L2Local);
+        assert!(config.cache.l2.enable_compression);
+    }
+
+    #[test]
+    fn test_tiering_rules_from_config() {
+        let config = parse(
+            r#"
+tiering:
+  mode: enforce
+  rules:
+    - name: idle-30d-to-cold
+      fromTiers: [hot, warm]
+      target: cold
+      condition: { type: idleFor, secs: 2592000 }
+"#,
+            &[],
+        )
+        .unwrap();
+
+        let tiering = config.tiering.unwrap();
+        assert_eq!(tiering.mode, crate::controlplane::TieringMode::Enforce);
+        assert_eq!(tiering.rules.len(), 1);
+        assert_eq!(tiering.rules[0].name, "idle-30d-to-cold");
+        assert_eq!(tiering.max_concurrent_moves, 2);
+
+        let err = parse("tiering:\n  maxConcurrentMoves: 0\n", &[]).unwrap_err();
+        assert!(err.contains("tiering.maxConcurrentMoves"), "{}", err);
+    }
+
+    #[test]
+    fn test_errors_name_field_and_location() {
+        let err = parse(
+            "orchestrator:\n  backends:\n    rustfs:\n      apiEndpont: x\n",
+            &[],
+        )
+        .unwrap_err();
+        assert!(err.starts_with("orchestrator.backends.rustfs"), "{}", err);
+        assert!(
+            err.contains("apiEndpont") && err.contains("line 4"),
+            "{}",
+            err
+        );
+
+        let err = parse("api:\n  maxBodySize: lots\n", &[]).unwrap_err();
+        assert!(err.starts_with("api.maxBodySize"), "{}", err);
+
+        let err = parse("api:\n  restAddr: \"${API_ADDR}\"\n", &[]).unwrap_err();
+        assert_eq!(
+            err,
+            "line 2 column 14: environment variable API_ADDR is not set"
+        );
+
+        let err = parse(
+            "orchestrator:\n  backends:\n    seaweedfs:\n      defaultReplication: \"12\"\n",
+            &[],
+        )
+        .unwrap_err();
+        assert!(
+            err.starts_with("orchestrator.backends.seaweedfs.defaultReplication"),
+            "{}",
+            err
+        );
+
+        let err = parse(
+            "orchestrator:\n  backends:\n    instances:\n      - name: files\n        kind: seaweedfs\n        mayastor: {}\n",
+            &[],
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            "orchestrator.backends.instances[0].mayastor: does not apply to a seaweedfs instance"
+        );
+    }
+
+    #[test]
+    fn test_secret_files_and_escapes() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("password"), "hunter2\n").unwrap();
+
+        let source = "# ${NOT_EXPANDED}\norchestrator:\n  platforms:\n    openstack:\n      password: \"${file:password}\"\n      username: \"a$$b\"\n";
+        let config = OperatorConfig::parse(source, dir.path(), |_| None).unwrap();
+        assert_eq!(config.orchestrator.platforms.openstack.password, "hunter2");
+        assert_eq!(config.orchestrator.platforms.openstack.username, "a$b");
+
+        let err =
+            OperatorConfig::parse("api:\n  restAddr: ${file:missing}\n", dir.path(), |_| None)
+                .unwrap_err();
+        assert!(
+            err.starts_with("line 2 column 13: cannot read secret file"),
+            "{}",
+            err
+        );
+    }
+}
@@ -3,24 +3,43 @@
 //! Implements the REST API endpoints for storage provisioning,
 //! node management, and capacity queries.
 
-use crate::controlplane::Orchestrator;
+use crate::controlplane::audit::{AuditAction, AuditContext, AuditEvent, AuditQuery};
+use crate::controlplane::events::{EventFilter, EventHub, StreamItem};
+use crate::controlplane::import::{ImportSelection, Importer};
+use crate::controlplane::inventory::StorageQuery;
+use crate::controlplane::policy::CallerIdentity;
+use crate::controlplane::quota::QuotaLimits;
+use crate::controlplane::reconciler::Reconciler;
+use crate::controlplane::tiering::TieringEngine;
+use crate::controlplane::resolver::StorageIntent;
+use crate::controlplane::webhooks::{WebhookDispatcher, WebhookSubscription};
+use crate::controlplane::{Orchestrator, StorageRecord};
 use crate::crd::{
-    BackendType, CapacitySpec, RedundancySpec, UnifiedStorageClass, UnifiedStorageClassSpec,
-    UnifiedTier, UnifiedStorageType,
+    BackendType, CapacitySpec, DriveLocationStatus, DriveStatus, RedundancySpec,
+    StorageQuotaSpec, UnifiedStorageClass, UnifiedStorageClassSpec, UnifiedTier,
+    UnifiedStorageType,
 };
-use crate::domain::ports::{ProvisionRequest, StorageTier, StorageType};
+use crate::domain::capacity::parse_capacity;
+use crate::domain::ports::{AccessMode, BackendCapabilities, Platform, Resolution, StorageType};
 use crate::error::{Error, Result};
+use crate::hardware::classification::{FingerprintMatch, FingerprintSource};
 use crate::hardware::registry::{GlobalStatsSnapshot, NodeId, NodeRegistry};
 use axum::{
-    extract::{Json, Path, State},
-    http::StatusCode,
-    response::IntoResponse,
-    routing::{delete, get, post},
+    extract::{Json, Path, Query, Request, State},
+    http::{HeaderMap, HeaderValue, StatusCode},
+    middleware::{self, Next},
+    response::{
+        sse::{Event as SseEvent, KeepAlive, Sse},
+        IntoResponse, Response,
+    },
+    routing::{delete, get, patch, post},
     Router,
 };
 use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
+use futures::StreamExt;
 use std::sync::Arc;
+use std::time::Duration;
 use tracing::{debug, error, info};
 
 // =============================================================================
@@ -33,7 +52,7 @@ use tracing::{debug, error, info};
 pub struct ProvisionStorageRequest {
     /// Name for the storage resource
     pub name: String,
-    /// Type of storage: block, file, object
+    /// Type of storage: block, file, object, auto
     pub storage_type: String,
     /// Capacity (e.g., "100Gi", "1Ti")
     pub capacity: String,
@@ -46,9 +65,27 @@ pub struct ProvisionStorageRequest {
     /// Replication factor
     #[serde(default)]
     pub replication: Option<u32>,
+    /// Access modes: RWO, ROX, RWX, S3 (drive type selection for "auto")
+    #[serde(default)]
+    pub access_modes: Vec<String>,
+    /// Pin a specific backend instead of letting the resolver choose
+    #[serde(default)]
+    pub backend: Option<String>,
+    /// Capability tags the backend instance must carry
+    #[serde(default)]
+    pub backend_tags: Vec<String>,
+    /// Feature parameters (erasureCoding, encrypted, versioning, snapshotSource, cloneSource)
+    #[serde(default)]
+    pub parameters: BTreeMap<String, String>,
     /// Labels
     #[serde(default)]
     pub labels: BTreeMap<String, String>,
+    /// Annotations (kept on the record, not pushed to backends)
+    #[serde(default)]
+    pub annotations: BTreeMap<String, String>,
+    /// Tenant (namespace) charged for the storage; quotas apply per tenant
+    #[serde(default)]
+    pub tenant: Option<String>,
 }
 
 /// Storage provision response
@@ -62,6 +99,139 @@ pub struct ProvisionStorageResponse {
     pub pool_name: String,
     pub backend: String,
     pub status: String,
+    /// How type, tier and backend were chosen (provision only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resolution: Option<Resolution>,
+    /// Admission policy warnings (provision only)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<String>,
+}
+
+/// Query parameters for the event stream
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EventStreamParams {
+    /// Comma-separated event kinds (`registry`, `cache`, `orchestrator`) or types
+    pub kind: Option<String>,
+    /// Only events concerning this node
+    pub node: Option<String>,
+    /// Resume after this event (when the client cannot send `Last-Event-ID`)
+    pub last_event_id: Option<String>,
+}
+
+/// Storage resize request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResizeStorageRequest {
+    /// New capacity, e.g. "20Gi"
+    pub capacity: String,
+}
+
+/// I/O and cache lookups observed on a volume since the previous report
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct AccessReportRequest {
+    pub read_ops: u64,
+    pub write_ops: u64,
+    pub bytes_read: u64,
+    pub bytes_written: u64,
+    pub cache_hits: u64,
+    pub cache_misses: u64,
+}
+
+/// Query parameters for listing storage
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListStorageParams {
+    /// block, file or object
+    pub storage_type: Option<String>,
+    /// hot, warm or cold
+    pub tier: Option<String>,
+    pub backend: Option<String>,
+    pub pool: Option<String>,
+    /// Label selector, e.g. `app=db,env!=prod`
+    pub label_selector: Option<String>,
+    pub name_prefix: Option<String>,
+    /// RFC 3339 lower bound (inclusive) on creation time
+    pub created_after: Option<String>,
+    /// RFC 3339 upper bound (exclusive) on creation time
+    pub created_before: Option<String>,
+    /// Cursor from a previous page's `nextCursor`
+    pub cursor: Option<String>,
+    pub limit: Option<usize>,
+}
+
+impl ListStorageParams {
+    fn into_query(self) -> Result<StorageQuery> {
+        let parse_time = |name: &str, value: Option<String>| -> Result<Option<_>> {
+            value
+                .map(|v| {
+                    chrono::DateTime::parse_from_rfc3339(&v)
+                        .map(|t| t.with_timezone(&chrono::Utc))
+                        .map_err(|e| Error::ApiValidation(format!("Invalid {}: {}", name, e)))
+                })
+                .transpose()
+        };
+
+        Ok(StorageQuery {
+            storage_type: self.storage_type.map(|t| t.parse()).transpose()?,
+            tier: self.tier.map(|t| t.parse()).transpose()?,
+            backend: self.backend,
+            pool: self.pool,
+            selector: self.label_selector.unwrap_or_default().parse()?,
+            name_prefix: self.name_prefix,
+            created_after: parse_time("createdAfter", self.created_after)?,
+            created_before: parse_time("createdBefore", self.created_before)?,
+            cursor: self.cursor,
+            limit: self.limit,
+        })
+    }
+}
+
+/// Provisioned storage summary
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageSummaryResponse {
+    pub storage_id: String,
+    pub name: String,
+    pub storage_type: String,
+    pub capacity_bytes: u64,
+    pub backend: String,
+    pub pool_name: String,
+    pub tier: Option<String>,
+    pub platform: Platform,
+    pub lost: bool,
+    pub labels: BTreeMap<String, String>,
+    pub annotations: BTreeMap<String, String>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl From<StorageRecord> for StorageSummaryResponse {
+    fn from(record: StorageRecord) -> Self {
+        Self {
+            storage_id: record.id,
+            name: record.name,
+            storage_type: record.storage_type.to_string(),
+            capacity_bytes: record.capacity_bytes,
+            backend: record.backend,
+            pool_name: record.pool_name,
+            tier: record.tier.map(|t| t.to_string()),
+            platform: record.platform,
+            lost: record.lost,
+            labels: record.labels,
+            annotations: record.annotations,
+            created_at: record.created_at,
+        }
+    }
+}
+
+/// One page of storage
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageListResponse {
+    pub items: Vec<StorageSummaryResponse>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<String>,
 }
 
 /// Node info response
@@ -76,6 +246,105 @@ pub struct NodeInfoResponse {
     pub total_capacity_bytes: u64,
     pub available_capacity_bytes: u64,
     pub fault_domain: Option<String>,
+    /// Per-drive detail, only in the single-node view
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub drives: Vec<NodeDriveResponse>,
+}
+
+/// Drive entry in the node view
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NodeDriveResponse {
+    pub id: String,
+    pub device_path: String,
+    pub model: String,
+    pub serial: String,
+    pub capacity_bytes: u64,
+    pub healthy: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pool_ref: Option<String>,
+    /// Enclosure bay, PCIe address and NUMA node
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub location: Option<DriveLocationStatus>,
+}
+
+impl From<&DriveStatus> for NodeDriveResponse {
+    fn from(drive: &DriveStatus) -> Self {
+        Self {
+            id: drive.id.clone(),
+            device_path: drive.device_path.clone(),
+            model: drive.model.clone(),
+            serial: drive.serial.clone(),
+            capacity_bytes: drive.capacity_bytes,
+            healthy: drive.healthy,
+            pool_ref: drive.pool_ref.clone(),
+            location: drive.location.clone(),
+        }
+    }
+}
+
+/// Fingerprint coverage across the fleet
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FingerprintCoverageResponse {
+    pub total_drives: u32,
+    pub matched_drives: u32,
+    pub nodes: Vec<NodeFingerprintCoverage>,
+    /// Models without a fingerprint, most common first
+    pub unmatched_models: Vec<UnmatchedModelResponse>,
+}
+
+/// Fingerprint coverage of one node's drives
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NodeFingerprintCoverage {
+    pub node_id: String,
+    pub drives: Vec<DriveFingerprintCoverage>,
+}
+
+/// The fingerprint that matched a drive, if any
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DriveFingerprintCoverage {
+    pub id: String,
+    pub device_path: String,
+    pub model: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fingerprint: Option<MatchedFingerprintResponse>,
+}
+
+/// A matched fingerprint and where it came from
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MatchedFingerprintResponse {
+    pub model_pattern: String,
+    pub vendor: String,
+    pub source: FingerprintSource,
+    /// File path or resource name
+    pub origin: String,
+    pub enterprise: bool,
+    pub high_performance: bool,
+}
+
+impl From<FingerprintMatch> for MatchedFingerprintResponse {
+    fn from(found: FingerprintMatch) -> Self {
+        Self {
+            model_pattern: found.fingerprint.model_pattern,
+            vendor: found.fingerprint.vendor,
+            source: found.source,
+            origin: found.origin,
+            enterprise: found.fingerprint.enterprise,
+            high_performance: found.fingerprint.high_performance,
+        }
+    }
+}
+
+/// A fleet model with no fingerprint
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UnmatchedModelResponse {
+    pub model: String,
+    pub drive_count: u32,
 }
 
 /// Cluster capacity response
@@ -104,6 +373,19 @@ pub struct PoolInfoResponse {
     pub utilization_percent: u32,
 }
 
+/// Backend info response
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackendInfoResponse {
+    pub name: String,
+    pub kind: String,
+    pub weight: u32,
+    pub tags: Vec<String>,
+    pub healthy: bool,
+    pub supported_types: Vec<StorageType>,
+    pub capabilities: BackendCapabilities,
+}
+
 /// API error response
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -122,6 +404,10 @@ pub struct ApiErrorResponse {
 pub struct RestRouter {
     orchestrator: Arc<Orchestrator>,
     registry: Arc<NodeRegistry>,
+    reconciler: Option<Arc<Reconciler>>,
+    tiering: Option<Arc<TieringEngine>>,
+    events: Option<Arc<EventHub>>,
+    webhooks: Option<Arc<WebhookDispatcher>>,
 }
 
 impl RestRouter {
@@ -130,33 +416,104 @@ impl RestRouter {
         Self {
             orchestrator,
             registry,
+            reconciler: None,
+            tiering: None,
+            events: None,
+            webhooks: None,
         }
     }
 
+    /// Serve reconciliation reports from `reconciler`
+    pub fn with_reconciler(mut self, reconciler: Option<Arc<Reconciler>>) -> Self {
+        self.reconciler = reconciler;
+        self
+    }
+
+    /// Serve tiering reports from, and feed volume I/O into, `tiering`
+    pub fn with_tiering(mut self, tiering: Option<Arc<TieringEngine>>) -> Self {
+        self.tiering = tiering;
+        self
+    }
+
+    /// Stream events from `events` on `/v1/events`
+    pub fn with_events(mut self, events: Option<Arc<EventHub>>) -> Self {
+        self.events = events;
+        self
+    }
+
+    /// Manage webhook subscriptions of `webhooks` on `/v1/webhooks`
+    pub fn with_webhooks(mut self, webhooks: Option<Arc<WebhookDispatcher>>) -> Self {
+        self.webhooks = webhooks;
+        self
+    }
+
     /// Build the Axum router
     pub fn build(self) -> Router {
         let state = AppState {
             orchestrator: self.orchestrator,
             registry: self.registry,
+            reconciler: self.reconciler,
+            tiering: self.tiering,
+            events: self.events,
+            webhooks: self.webhooks,
         };
 
         Router::new()
             // Storage endpoints
-            .route("/v1/storage", post(provision_storage))
+            .route("/v1/storage", get(list_storage).post(provision_storage))
             .route("/v1/storage/:id", get(get_storage))
             .route("/v1/storage/:id", delete(delete_storage))
+            .route("/v1/storage/:id/labels", patch(patch_storage_labels))
+            .route("/v1/storage/:id/resize", post(resize_storage))
+            .route("/v1/storage/:id/access", post(report_storage_access))
             // Node endpoints
             .route("/v1/nodes", get(list_nodes))
             .route("/v1/nodes/:name", get(get_node))
             .route("/v1/nodes/:name/classify", post(classify_node))
+            // Fingerprints
+            .route("/v1/fingerprints/coverage", get(get_fingerprint_coverage))
+            // Backend endpoints
+            .route("/v1/backends", get(list_backends))
             // Pool endpoints
             .route("/v1/pools", get(list_pools))
             .route("/v1/pools/:name", get(get_pool))
+            // Reconciliation endpoint
+            .route("/v1/reconcile/report", get(get_reconcile_report))
+            // Tiering endpoint
+            .route("/v1/tiering/report", get(get_tiering_report))
+            // Import endpoints
+            .route("/v1/import/candidates", get(list_import_candidates))
+            .route("/v1/import", post(import_storage))
             // Capacity endpoint
             .route("/v1/capacity", get(get_capacity))
+            // Quotas
+            .route("/v1/quotas", get(list_quotas))
+            .route(
+                "/v1/quotas/:tenant",
+                get(get_quota).put(put_quota).delete(delete_quota),
+            )
+            // Audit log
+            .route("/v1/audit", get(query_audit))
+            // Event stream
+            .route("/v1/events", get(stream_events))
+            // Webhooks
+            .route("/v1/webhooks", get(list_webhooks))
+            .route(
+                "/v1/webhooks/dead-letters",
+                get(list_dead_letters).delete(clear_dead_letters),
+            )
+            .route(
+                "/v1/webhooks/dead-letters/:id/redeliver",
+                post(redeliver_dead_letter),
+            )
+            .route(
+                "/v1/webhooks/:name",
+                get(get_webhook).put(put_webhook).delete(delete_webhook),
+            )
             // Health endpoint
             .route("/health", get(health_check))
             .route("/ready", get(readiness_check))
+            .layer(middleware::from_fn(audit_context))
             .with_state(state)
     }
 }
@@ -166,6 +523,10 @@ impl RestRouter {
 struct AppState {
     orchestrator: Arc<Orchestrator>,
     registry: Arc<NodeRegistry>,
+    reconciler: Option<Arc<Reconciler>>,
+    tiering: Option<Arc<TieringEngine>>,
+    events: Option<Arc<EventHub>>,
+    webhooks: Option<Arc<WebhookDispatcher>>,
 }
 
 // =============================================================================
@@ -175,22 +536,25 @@ struct AppState {
 /// Provision storage
 async fn provision_storage(
     State(state): State<AppState>,
+    headers: HeaderMap,
     Json(request): Json<ProvisionStorageRequest>,
 ) -> impl IntoResponse {
     info!("Provisioning storage: {}", request.name);
+    let caller = caller_identity(&headers);
 
     // Parse storage type
     let storage_type = match request.storage_type.to_lowercase().as_str() {
-        "block" => StorageType::Block,
-        "file" => StorageType::File,
-        "object" => StorageType::Object,
+        "block" => UnifiedStorageType::Block,
+        "file" => UnifiedStorageType::File,
+        "object" => UnifiedStorageType::Object,
+        "auto" => UnifiedStorageType::Auto,
         _ => {
             return (
                 StatusCode::BAD_REQUEST,
                 Json(ApiErrorResponse {
                     error: "invalid_storage_type".into(),
                     message: format!(
-                        "Invalid storage type: {}. Use 'block', 'file', or 'object'",
+                        "Invalid storage type: {}. Use 'block', 'file', 'object', or 'auto'",
                         request.storage_type
                     ),
                     details: None,
@@ -216,49 +580,105 @@ async fn provision_storage(
         }
     };
 
+    // Parse access modes
+    let access_modes = match request
+        .access_modes
+        .iter()
+        .map(|m| m.parse::<AccessMode>())
+        .collect::<Result<Vec<_>>>()
+    {
+        Ok(modes) => modes,
+        Err(e) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(ApiErrorResponse {
+                    error: "invalid_access_mode".into(),
+                    message: e.to_string(),
+                    details: None,
+                }),
+            )
+                .into_response();
+        }
+    };
+
     // Parse tier
-    let tier = request.tier.as_ref().and_then(|t| match t.to_lowercase().as_str() {
-        "hot" => Some(StorageTier::Hot),
-        "warm" => Some(StorageTier::Warm),
-        "cold" => Some(StorageTier::Cold),
-        _ => None,
-    });
-
-    // Build provision request
-    let provision_req = ProvisionRequest {
+    let tier = match request.tier.as_deref().map(str::to_lowercase).as_deref() {
+        Some("hot") => UnifiedTier::Hot,
+        Some("warm") => UnifiedTier::Warm,
+        Some("cold") => UnifiedTier::Cold,
+        _ => UnifiedTier::Auto,
+    };
+
+    // Build storage intent
+    let intent = StorageIntent {
         request_id: uuid_v4(),
         name: request.name.clone(),
         storage_type,
-        capacity_bytes,
         tier,
+        backend: request.backend.clone(),
+        backend_tags: request.backend_tags.clone(),
+        access_modes,
+        capacity_bytes,
         max_iops: request.max_iops,
         labels: request.labels.clone(),
-        platform_params: BTreeMap::new(),
+        annotations: request.annotations.clone(),
+        tenant: request.tenant.clone(),
+        platform_params: request.parameters.clone(),
     };
 
     // Provision via orchestrator
-    match state.orchestrator.provision(provision_req).await {
+    match state.orchestrator.provision_intent_as(intent, &caller).await {
         Ok(response) => {
-            let backend = match storage_type {
-                StorageType::Block => "mayastor",
-                StorageType::File => "seaweedfs",
-                StorageType::Object => "rustfs",
-            };
+            let backend = response
+                .resolution
+                .as_ref()
+                .map(|r| r.backend.clone())
+                .unwrap_or_default();
+            let warnings = response
+                .resolution
+                .as_ref()
+                .map(|r| r.warnings.clone())
+                .unwrap_or_default();
 
             (
                 StatusCode::CREATED,
                 Json(ProvisionStorageResponse {
                     storage_id: response.storage_id,
                     name: response.name,
-                    storage_type: request.storage_type,
+                    storage_type: response.storage_type.to_string(),
                     capacity_bytes: response.capacity_bytes,
                     pool_name: response.pool_name,
-                    backend: backend.into(),
+                    backend,
                     status: "provisioned".into(),
+                    resolution: response.resolution,
+                    warnings,
                 }),
             )
                 .into_response()
         }
+        Err(e @ Error::ApiValidation(_)) => (
+            StatusCode::BAD_REQUEST,
+            Json(ApiErrorResponse {
+                error: "invalid_request".into(),
+                message: e.to_string(),
+                details: None,
+            }),
+        )
+            .into_response(),
+        Err(e @ Error::QuotaExceeded { .. }) => quota_exceeded(e),
+        Err(Error::PolicyDenied {
+            policy,
+            rule,
+            message,
+        }) => (
+            StatusCode::FORBIDDEN,
+            Json(ApiErrorResponse {
+                error: "policy_denied".into(),
+                message,
+                details: Some(format!("policy {} rule {}", policy, rule)),
+            }),
+        )
+            .into_response(),
         Err(e) => {
             error!("Provision failed: {}", e);
             (
@@ -290,6 +710,8 @@ async fn get_storage(
                 pool_name: response.pool_name,
                 backend: response.platform_details.get("backend").cloned().unwrap_or_default(),
                 status: "active".into(),
+                resolution: None,
+                warnings: Vec::new(),
             }),
         )
             .into_response(),
@@ -314,6 +736,138 @@ async fn get_storage(
     }
 }
 
+/// List storage with filters and cursor pagination
+async fn list_storage(
+    State(state): State<AppState>,
+    Query(params): Query<ListStorageParams>,
+) -> impl IntoResponse {
+    let page = match params.into_query() {
+        Ok(query) => state.orchestrator.query_storage(&query).await,
+        Err(e) => Err(e),
+    };
+
+    match page {
+        Ok(page) => (
+            StatusCode::OK,
+            Json(StorageListResponse {
+                items: page.items.into_iter().map(Into::into).collect(),
+                next_cursor: page.next_cursor,
+            }),
+        )
+            .into_response(),
+        Err(Error::ApiValidation(message)) => (
+            StatusCode::BAD_REQUEST,
+            Json(ApiErrorResponse {
+                error: "invalid_query".into(),
+                message,
+                details: None,
+            }),
+        )
+            .into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiErrorResponse {
+                error: "list_failed".into(),
+                message: e.to_string(),
+                details: None,
+            }),
+        )
+            .into_response(),
+    }
+}
+
+/// Merge-patch labels and annotations: `{"labels": {"k": "v", "old": null}}`
+async fn patch_storage_labels(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Json(patch): Json<serde_json::Value>,
+) -> impl IntoResponse {
+    match state.orchestrator.patch_storage_metadata(&id, &patch).await {
+        Ok(record) => (StatusCode::OK, Json(StorageSummaryResponse::from(record))).into_response(),
+        Err(Error::ResourceNotFound { .. }) => (
+            StatusCode::NOT_FOUND,
+            Json(ApiErrorResponse {
+                error: "not_found".into(),
+                message: format!("Storage not found: {}", id),
+                details: None,
+            }),
+        )
+            .into_response(),
+        Err(Error::ApiValidation(message)) => (
+            StatusCode::BAD_REQUEST,
+            Json(ApiErrorResponse {
+                error: "invalid_patch".into(),
+                message,
+                details: None,
+            }),
+        )
+            .into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiErrorResponse {
+                error: "label_update_failed".into(),
+                message: e.to_string(),
+                details: None,
+            }),
+        )
+            .into_response(),
+    }
+}
+
+/// Grow or shrink storage: `{"capacity": "20Gi"}`
+async fn resize_storage(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Json(request): Json<ResizeStorageRequest>,
+) -> impl IntoResponse {
+    let capacity_bytes = match parse_capacity(&request.capacity) {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(ApiErrorResponse {
+                    error: "invalid_capacity".into(),
+                    message: format!("Invalid capacity: {}", e),
+                    details: None,
+                }),
+            )
+                .into_response();
+        }
+    };
+
+    match state.orchestrator.resize_storage(&id, capacity_bytes).await {
+        Ok(record) => (StatusCode::OK, Json(StorageSummaryResponse::from(record))).into_response(),
+        Err(Error::ResourceNotFound { .. }) => (
+            StatusCode::NOT_FOUND,
+            Json(ApiErrorResponse {
+                error: "not_found".into(),
+                message: format!("Storage not found: {}", id),
+                details: None,
+            }),
+        )
+            .into_response(),
+        Err(Error::ApiValidation(message)) => (
+            StatusCode::BAD_REQUEST,
+            Json(ApiErrorResponse {
+                error: "invalid_resize".into(),
+                message,
+                details: None,
+            }),
+        )
+            .into_response(),
+        Err(e @ Error::QuotaExceeded { .. }) => quota_exceeded(e),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiErrorResponse {
+                error: "resize_failed".into(),
+                message: e.to_string(),
+                details: None,
+            }),
+        )
+            .into_response(),
+    }
+}
+
 /// Delete storage
 async fn delete_storage(
     State(state): State<AppState>,
@@ -349,6 +903,7 @@ async fn list_nodes(State(state): State<AppState>) -> impl IntoResponse {
                 total_capacity_bytes: entry.status.total_capacity_bytes,
                 available_capacity_bytes: entry.status.available_capacity_bytes,
                 fault_domain: entry.fault_domain.clone(),
+                drives: Vec::new(),
             });
         }
     }
@@ -373,6 +928,7 @@ async fn get_node(
                 total_capacity_bytes: entry.status.total_capacity_bytes,
                 available_capacity_bytes: entry.status.available_capacity_bytes,
                 fault_domain: entry.fault_domain.clone(),
+                drives: entry.status.drives.iter().map(NodeDriveResponse::from).collect(),
             }),
         )
             .into_response(),
@@ -415,6 +971,56 @@ async fn classify_node(
     }
 }
 
+/// Which fingerprint matched each drive, and fleet models with none
+async fn get_fingerprint_coverage(State(state): State<AppState>) -> impl IntoResponse {
+    let fingerprints = state.orchestrator.fingerprints();
+    let mut response = FingerprintCoverageResponse {
+        total_drives: 0,
+        matched_drives: 0,
+        nodes: Vec::new(),
+        unmatched_models: Vec::new(),
+    };
+    let mut unmatched: BTreeMap<String, u32> = BTreeMap::new();
+
+    let mut node_ids = state.registry.all_node_ids();
+    node_ids.sort_by_key(|id| id.to_string());
+    for node_id in node_ids {
+        let Some(entry) = state.registry.get(node_id.clone()) else {
+            continue;
+        };
+        let mut drives = Vec::new();
+        for drive in &entry.status.drives {
+            let fingerprint = fingerprints.lookup(&drive.model);
+            response.total_drives += 1;
+            if fingerprint.is_some() {
+                response.matched_drives += 1;
+            } else {
+                *unmatched.entry(drive.model.clone()).or_default() += 1;
+            }
+            drives.push(DriveFingerprintCoverage {
+                id: drive.id.clone(),
+                device_path: drive.device_path.clone(),
+                model: drive.model.clone(),
+                fingerprint: fingerprint.map(MatchedFingerprintResponse::from),
+            });
+        }
+        response.nodes.push(NodeFingerprintCoverage {
+            node_id: node_id.to_string(),
+            drives,
+        });
+    }
+
+    response.unmatched_models = unmatched
+        .into_iter()
+        .map(|(model, drive_count)| UnmatchedModelResponse { model, drive_count })
+        .collect();
+    response
+        .unmatched_models
+        .sort_by_key(|m| std::cmp::Reverse(m.drive_count));
+
+    (StatusCode::OK, Json(response))
+}
+
 /// List pools
 async fn list_pools(State(state): State<AppState>) -> impl IntoResponse {
     match state.orchestrator.list_pools().await {
@@ -446,6 +1052,503 @@ async fn list_pools(State(state): State<AppState>) -> impl IntoResponse {
     }
 }
 
+/// List backend instances and their capabilities
+async fn list_backends(State(state): State<AppState>) -> impl IntoResponse {
+    let backends: Vec<BackendInfoResponse> = state
+        .orchestrator
+        .list_backends()
+        .await
+        .into_iter()
+        .map(|b| BackendInfoResponse {
+            name: b.name,
+            kind: b.kind,
+            weight: b.weight,
+            tags: b.tags.into_iter().collect(),
+            healthy: b.healthy,
+            supported_types: b.supported_types,
+            capabilities: b.capabilities,
+        })
+        .collect();
+
+    (StatusCode::OK, Json(backends)).into_response()
+}
+
+/// Latest reconciliation report (runs a report-only scan if none exists yet)
+async fn get_reconcile_report(State(state): State<AppState>) -> impl IntoResponse {
+    let reconciler = match &state.reconciler {
+        Some(reconciler) => reconciler,
+        None => {
+            return (
+                StatusCode::SERVICE_UNAVAILABLE,
+                Json(ApiErrorResponse {
+                    error: "reconciler_disabled".into(),
+                    message: "Reconciliation is not enabled".into(),
+                    details: None,
+                }),
+            )
+                .into_response();
+        }
+    };
+
+    let report = match reconciler.last_report() {
+        Some(report) => Ok(report),
+        None => reconciler.scan().await,
+    };
+
+    match report {
+        Ok(report) => (StatusCode::OK, Json(report)).into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiErrorResponse {
+                error: "reconcile_failed".into(),
+                message: e.to_string(),
+                details: None,
+            }),
+        )
+            .into_response(),
+    }
+}
+
+/// Latest tiering report (plans a cycle without moving anything if none
+/// has run yet)
+async fn get_tiering_report(State(state): State<AppState>) -> Response {
+    let Some(tiering) = &state.tiering else {
+        return tiering_disabled();
+    };
+
+    let report = match tiering.last_report() {
+        Some(report) => report,
+        None => tiering.plan().await,
+    };
+    (StatusCode::OK, Json(report)).into_response()
+}
+
+/// Record I/O and cache lookups reported by a volume's data-path agent for
+/// tiering.
+///
+/// Telemetry rather than a change to the volume, so it is not audited.
+async fn report_storage_access(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Json(request): Json<AccessReportRequest>,
+) -> Response {
+    let Some(tiering) = &state.tiering else {
+        return tiering_disabled();
+    };
+    if state.orchestrator.get_storage_record(&id).await.is_none() {
+        return (
+            StatusCode::NOT_FOUND,
+            Json(ApiErrorResponse {
+                error: "not_found".into(),
+                message: format!("Storage {} not found", id),
+                details: None,
+            }),
+        )
+            .into_response();
+    }
+
+    tiering.record_access(
+        &id,
+        request.read_ops,
+        request.write_ops,
+        request.bytes_read,
+        request.bytes_written,
+    );
+    tiering.record_cache_lookups(&id, request.cache_hits, request.cache_misses);
+    StatusCode::NO_CONTENT.into_response()
+}
+
+fn tiering_disabled() -> Response {
+    (
+        StatusCode::SERVICE_UNAVAILABLE,
+        Json(ApiErrorResponse {
+            error: "tiering_disabled".into(),
+            message: "Automatic tiering is not enabled".into(),
+            details: None,
+        }),
+    )
+        .into_response()
+}
+
+/// Unmanaged storage that can be imported, with proposed records
+async fn list_import_candidates(State(state): State<AppState>) -> impl IntoResponse {
+    match Importer::new(state.orchestrator.clone()).discover().await {
+        Ok(candidates) => (StatusCode::OK, Json(candidates)).into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiErrorResponse {
+                error: "import_discovery_failed".into(),
+                message: e.to_string(),
+                details: None,
+            }),
+        )
+            .into_response(),
+    }
+}
+
+/// Import selected (or all) unmanaged storage without touching its data
+async fn import_storage(
+    State(state): State<AppState>,
+    Json(selection): Json<ImportSelection>,
+) -> impl IntoResponse {
+    match Importer::new(state.orchestrator.clone()).import(&selection).await {
+        Ok(result) => (StatusCode::OK, Json(result)).into_response(),
+        Err(Error::ApiValidation(message)) => (
+            StatusCode::BAD_REQUEST,
+            Json(ApiErrorResponse {
+                error: "invalid_import".into(),
+                message,
+                details: None,
+            }),
+        )
+            .into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiErrorResponse {
+                error: "import_failed".into(),
+                message: e.to_string(),
+                details: None,
+            }),
+        )
+            .into_response(),
+    }
+}
+
+/// Limits and usage for every tenant
+async fn list_quotas(State(state): State<AppState>) -> impl IntoResponse {
+    (StatusCode::OK, Json(state.orchestrator.quotas().list()))
+}
+
+/// Limits and usage for one tenant
+async fn get_quota(
+    State(state): State<AppState>,
+    Path(tenant): Path<String>,
+) -> impl IntoResponse {
+    (StatusCode::OK, Json(state.orchestrator.quotas().tenant(&tenant)))
+}
+
+/// Set a tenant's limits from a `StorageQuota` spec (`tenant` is ignored)
+async fn put_quota(
+    State(state): State<AppState>,
+    Path(tenant): Path<String>,
+    Json(spec): Json<StorageQuotaSpec>,
+) -> impl IntoResponse {
+    match QuotaLimits::from_spec(&spec) {
+        Ok(limits) => {
+            let quotas = state.orchestrator.quotas();
+            let event = AuditEvent::new(AuditAction::SetQuota, format!("quota/{}", tenant))
+                .before(&quotas.tenant(&tenant).limits)
+                .after(&limits);
+            quotas.set_limits(&tenant, limits);
+            state.orchestrator.audit().record(event).await;
+            (StatusCode::OK, Json(quotas.tenant(&tenant))).into_response()
+        }
+        Err(e) => (
+            StatusCode::BAD_REQUEST,
+            Json(ApiErrorResponse {
+                error: "invalid_quota".into(),
+                message: e.to_string(),
+                details: None,
+            }),
+        )
+            .into_response(),
+    }
+}
+
+/// Remove a tenant's limits (usage is still tracked)
+async fn delete_quota(
+    State(state): State<AppState>,
+    Path(tenant): Path<String>,
+) -> impl IntoResponse {
+    let before = state.orchestrator.quotas().tenant(&tenant).limits;
+    if state.orchestrator.quotas().remove_limits(&tenant) {
+        let event = AuditEvent::new(AuditAction::RemoveQuota, format!("quota/{}", tenant))
+            .before(&before);
+        state.orchestrator.audit().record(event).await;
+        StatusCode::NO_CONTENT.into_response()
+    } else {
+        (
+            StatusCode::NOT_FOUND,
+            Json(ApiErrorResponse {
+                error: "not_found".into(),
+                message: format!("No quota for tenant: {}", tenant),
+                details: None,
+            }),
+        )
+            .into_response()
+    }
+}
+
+/// Audit events, oldest first (filters: `actor`, `resource`, `since`, `until`, `limit`)
+async fn query_audit(
+    State(state): State<AppState>,
+    Query(query): Query<AuditQuery>,
+) -> impl IntoResponse {
+    match state.orchestrator.audit().query(&query).await {
+        Ok(events) => (StatusCode::OK, Json(events)).into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiErrorResponse {
+                error: "audit_query_failed".into(),
+                message: e.to_string(),
+                details: None,
+            }),
+        )
+            .into_response(),
+    }
+}
+
+/// Stream registry, cache and orchestrator events as server-sent events
+///
+/// Filters: `kind` (comma-separated kinds or event types) and `node`.
+/// Resumes after the `Last-Event-ID` header (or `lastEventId` parameter)
+/// from the replay buffer.
+async fn stream_events(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(params): Query<EventStreamParams>,
+) -> Response {
+    let hub = match &state.events {
+        Some(hub) => hub,
+        None => {
+            return (
+                StatusCode::SERVICE_UNAVAILABLE,
+                Json(ApiErrorResponse {
+                    error: "events_disabled".into(),
+                    message: "Event streaming is not enabled".into(),
+                    details: None,
+                }),
+            )
+                .into_response();
+        }
+    };
+
+    let last_event_id = match headers
+        .get("last-event-id")
+        .and_then(|v| v.to_str().ok())
+        .map(str::to_string)
+        .or(params.last_event_id)
+    {
+        Some(id) => match id.trim().parse::<u64>() {
+            Ok(id) => Some(id),
+            Err(_) => {
+                return (
+                    StatusCode::BAD_REQUEST,
+                    Json(ApiErrorResponse {
+                        error: "invalid_last_event_id".into(),
+                        message: format!("Invalid Last-Event-ID: {}", id),
+                        details: None,
+                    }),
+                )
+                    .into_response();
+            }
+        },
+        None => None,
+    };
+
+    let filter = EventFilter::new(params.kind.as_deref(), params.node);
+    let stream = hub
+        .subscribe(last_event_id, filter)
+        .into_stream()
+        .map(|item| {
+            let event = match item {
+                StreamItem::Event(event) => SseEvent::default()
+                    .id(event.id.to_string())
+                    .event(event.event_type.clone())
+                    .json_data(&event)
+                    .unwrap_or_else(|_| SseEvent::default().comment("unserializable event")),
+                StreamItem::Gap { reason } => SseEvent::default().event("gap").data(reason),
+            };
+            Ok::<_, std::convert::Infallible>(event)
+        });
+
+    Sse::new(stream)
+        .keep_alive(
+            KeepAlive::new()
+                .interval(Duration::from_secs(EVENT_KEEPALIVE_SECS))
+                .text("keepalive"),
+        )
+        .into_response()
+}
+
+/// 503 response when no webhook dispatcher is configured
+fn webhooks_disabled() -> Response {
+    (
+        StatusCode::SERVICE_UNAVAILABLE,
+        Json(ApiErrorResponse {
+            error: "webhooks_disabled".into(),
+            message: "Webhook notifications are not enabled".into(),
+            details: None,
+        }),
+    )
+        .into_response()
+}
+
+/// Webhook subscriptions with delivery counters
+async fn list_webhooks(State(state): State<AppState>) -> Response {
+    match &state.webhooks {
+        Some(webhooks) => (StatusCode::OK, Json(webhooks.list())).into_response(),
+        None => webhooks_disabled(),
+    }
+}
+
+/// One webhook subscription
+async fn get_webhook(State(state): State<AppState>, Path(name): Path<String>) -> Response {
+    let Some(webhooks) = &state.webhooks else {
+        return webhooks_disabled();
+    };
+    match webhooks.get(&name) {
+        Some(status) => (StatusCode::OK, Json(status)).into_response(),
+        None => (
+            StatusCode::NOT_FOUND,
+            Json(ApiErrorResponse {
+                error: "not_found".into(),
+                message: format!("Webhook {} not found", name),
+                details: None,
+            }),
+        )
+            .into_response(),
+    }
+}
+
+/// Create or replace a webhook subscription (`name` comes from the path)
+async fn put_webhook(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+    Json(mut subscription): Json<WebhookSubscription>,
+) -> Response {
+    let Some(webhooks) = &state.webhooks else {
+        return webhooks_disabled();
+    };
+    subscription.name = name.clone();
+    let event = AuditEvent::new(AuditAction::SetWebhook, format!("webhook/{}", name))
+        .before(&webhooks.get(&name).map(|s| s.subscription))
+        .after(&subscription);
+    match webhooks.set_subscription(subscription) {
+        Ok(()) => {
+            state.orchestrator.audit().record(event).await;
+            (StatusCode::OK, Json(webhooks.get(&name))).into_response()
+        }
+        Err(e) => (
+            StatusCode::BAD_REQUEST,
+            Json(ApiErrorResponse {
+                error: "invalid_webhook".into(),
+                message: e.to_string(),
+                details: None,
+            }),
+        )
+            .into_response(),
+    }
+}
+
+/// Remove a webhook subscription
+async fn delete_webhook(State(state): State<AppState>, Path(name): Path<String>) -> Response {
+    let Some(webhooks) = &state.webhooks else {
+        return webhooks_disabled();
+    };
+    let before = webhooks.get(&name).map(|s| s.subscription);
+    if webhooks.remove_subscription(&name) {
+        let event = AuditEvent::new(AuditAction::RemoveWebhook, format!("webhook/{}", name))
+            .before(&before);
+        state.orchestrator.audit().record(event).await;
+        StatusCode::NO_CONTENT.into_response()
+    } else {
+        (
+            StatusCode::NOT_FOUND,
+            Json(ApiErrorResponse {
+                error: "not_found".into(),
+                message: format!("Webhook {} not found", name),
+                details: None,
+            }),
+        )
+            .into_response()
+    }
+}
+
+/// Events whose delivery was given up, oldest first
+async fn list_dead_letters(State(state): State<AppState>) -> Response {
+    match &state.webhooks {
+        Some(webhooks) => (StatusCode::OK, Json(webhooks.dead_letters())).into_response(),
+        None => webhooks_disabled(),
+    }
+}
+
+/// Drop all dead letters
+async fn clear_dead_letters(State(state): State<AppState>) -> Response {
+    match &state.webhooks {
+        Some(webhooks) => {
+            let cleared = webhooks.clear_dead_letters();
+            let event = AuditEvent::new(AuditAction::ClearDeadLetters, "webhook/dead-letters")
+                .before(&serde_json::json!({ "deadLetters": cleared }));
+            state.orchestrator.audit().record(event).await;
+            StatusCode::NO_CONTENT.into_response()
+        }
+        None => webhooks_disabled(),
+    }
+}
+
+/// Queue a dead letter for delivery again
+async fn redeliver_dead_letter(State(state): State<AppState>, Path(id): Path<u64>) -> Response {
+    let Some(webhooks) = &state.webhooks else {
+        return webhooks_disabled();
+    };
+    let before = webhooks.dead_letters().into_iter().find(|d| d.id == id);
+    let result = webhooks.redeliver(id);
+    let event = AuditEvent::new(AuditAction::RedeliverDeadLetter, format!("dead-letter/{}", id))
+        .before(&before)
+        .outcome(&result);
+    state.orchestrator.audit().record(event).await;
+    match result {
+        Ok(()) => StatusCode::ACCEPTED.into_response(),
+        Err(e) => (
+            StatusCode::NOT_FOUND,
+            Json(ApiErrorResponse {
+                error: "not_found".into(),
+                message: e.to_string(),
+                details: None,
+            }),
+        )
+            .into_response(),
+    }
+}
+
+/// Run each request in an `AuditContext` carrying the caller and request ID
+///
+/// The request ID is taken from `X-Request-ID` (or generated) and echoed
+/// back on the response.
+async fn audit_context(request: Request, next: Next) -> Response {
+    let request_id = request
+        .headers()
+        .get(REQUEST_ID_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .filter(|v| !v.is_empty())
+        .map(str::to_string)
+        .unwrap_or_else(uuid_v4);
+    let context = AuditContext {
+        caller: caller_identity(request.headers()),
+        request_id: Some(request_id.clone()),
+    };
+
+    let mut response = context.scope(next.run(request)).await;
+    if let Ok(value) = HeaderValue::from_str(&request_id) {
+        response.headers_mut().insert(REQUEST_ID_HEADER, value);
+    }
+    response
+}
+
+/// 403 response for a request refused by a tenant quota
+fn quota_exceeded(e: Error) -> Response {
+    (
+        StatusCode::FORBIDDEN,
+        Json(ApiErrorResponse {
+            error: "quota_exceeded".into(),
+            message: e.to_string(),
+            details: None,
+        }),
+    )
+        .into_response()
+}
+
 /// Get pool info
 async fn get_pool(
     State(state): State<AppState>,
@@ -524,46 +1627,37 @@ async fn readiness_check(State(state): State<AppState>) -> impl IntoResponse {
 // Utility Functions
 // =============================================================================
 
-/// Parse capacity string (e.g., "100Gi", "1Ti") to bytes
-fn parse_capacity(s: &str) -> Result<u64> {
-    let s = s.trim();
-    if s.is_empty() {
-        return Err(Error::CapacityParse("empty capacity string".into()));
-    }
+/// Header carrying the authenticated user (set by the authenticating proxy)
+pub const REMOTE_USER_HEADER: &str = "x-remote-user";
 
-    // Find where the number ends and unit begins
-    let mut num_end = 0;
-    for (i, c) in s.char_indices() {
-        if !c.is_ascii_digit() && c != '.' {
-            num_end = i;
-            break;
-        }
-        num_end = i + 1;
-    }
+/// Header carrying the user's groups; may repeat or hold a comma-separated list
+pub const REMOTE_GROUP_HEADER: &str = "x-remote-group";
 
-    let num_str = &s[..num_end];
-    let unit_str = s[num_end..].trim();
+/// Seconds between keepalive comments on the event stream
+pub const EVENT_KEEPALIVE_SECS: u64 = 15;
 
-    let num: f64 = num_str
-        .parse()
-        .map_err(|_| Error::CapacityParse(format!("invalid number: {}", num_str)))?;
+/// Header carrying the request ID recorded in audit events
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
 
-    let multiplier: u64 = match unit_str.to_uppercase().as_str() {
-        "" | "B" => 1,
-        "K" | "KB" | "KI" | "KIB" => 1024,
-        "M" | "MB" | "MI" | "MIB" => 1024 * 1024,
-        "G" | "GB" | "GI" | "GIB" => 1024 * 1024 * 1024,
-        "T" | "TB" | "TI" | "TIB" => 1024 * 1024 * 1024 * 1024,
-        "P" | "PB" | "PI" | "PIB" => 1024 * 1024 * 1024 * 1024 * 1024,
-        _ => {
-            return Err(Error::CapacityParse(format!(
-                "unknown unit: {}",
-                unit_str
-            )))
-        }
-    };
+/// Caller identity from the authenticating proxy's headers
+pub fn caller_identity(headers: &HeaderMap) -> CallerIdentity {
+    let user = headers
+        .get(REMOTE_USER_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+        .map(str::to_string);
+    let groups = headers
+        .get_all(REMOTE_GROUP_HEADER)
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .flat_map(|v| v.split(','))
+        .map(str::trim)
+        .filter(|g| !g.is_empty())
+        .map(str::to_string)
+        .collect();
 
-    Ok((num * multiplier as f64) as u64)
+    CallerIdentity { user, groups }
 }
 
 /// Generate a simple UUID v4
@@ -590,20 +1684,342 @@ mod tests {
     use super::*;
 
     #[test]
-    fn test_parse_capacity() {
-        assert_eq!(parse_capacity("100").unwrap(), 100);
-        assert_eq!(parse_capacity("100B").unwrap(), 100);
-        assert_eq!(parse_capacity("1K").unwrap(), 1024);
-        assert_eq!(parse_capacity("1Ki").unwrap(), 1024);
-        assert_eq!(parse_capacity("1M").unwrap(), 1024 * 1024);
-        assert_eq!(parse_capacity("1G").unwrap(), 1024 * 1024 * 1024);
-        assert_eq!(parse_capacity("1Gi").unwrap(), 1024 * 1024 * 1024);
-        assert_eq!(parse_capacity("100Gi").unwrap(), 100 * 1024 * 1024 * 1024);
-        assert_eq!(parse_capacity("1T").unwrap(), 1024 * 1024 * 1024 * 1024);
-
-        assert!(parse_capacity("").is_err());
-        assert!(parse_capacity("abc").is_err());
-        assert!(parse_capacity("100X").is_err());
+    fn test_list_storage_params() {
+        let params = ListStorageParams {
+            storage_type: Some("block".into()),
+            tier: Some("hot".into()),
+            label_selector: Some("app=db,env!=prod".into()),
+            created_after: Some("2024-01-01T00:00:00Z".into()),
+            ..Default::default()
+        };
+        let query = params.into_query().unwrap();
+        assert_eq!(query.storage_type, Some(StorageType::Block));
+        assert_eq!(query.selector.requirements.len(), 2);
+        assert!(query.created_after.is_some());
+
+        let bad_time = ListStorageParams {
+            created_before: Some("yesterday".into()),
+            ..Default::default()
+        };
+        assert!(bad_time.into_query().is_err());
+        let bad_type = ListStorageParams {
+            storage_type: Some("tape".into()),
+            ..Default::default()
+        };
+        assert!(bad_type.into_query().is_err());
+    }
+
+    #[test]
+    fn test_caller_identity() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(caller_identity(&headers), CallerIdentity::default());
+
+        headers.insert(REMOTE_USER_HEADER, "alice".parse().unwrap());
+        headers.append(REMOTE_GROUP_HEADER, "dev, ops".parse().unwrap());
+        headers.append(REMOTE_GROUP_HEADER, "storage-admins".parse().unwrap());
+        let caller = caller_identity(&headers);
+        assert_eq!(caller.user.as_deref(), Some("alice"));
+        assert_eq!(caller.groups, vec!["dev", "ops", "storage-admins"]);
+    }
+
+    #[tokio::test]
+    async fn test_requests_are_audited() {
+        use crate::controlplane::audit::AuditOutcome;
+        use crate::controlplane::OrchestratorConfig;
+        use tower::ServiceExt;
+
+        let registry = NodeRegistry::new();
+        let orchestrator = Orchestrator::new(OrchestratorConfig::default(), registry.clone());
+        orchestrator.initialize().await.unwrap();
+        let router = RestRouter::new(orchestrator.clone(), registry).build();
+
+        let request = axum::http::Request::builder()
+            .method("POST")
+            .uri("/v1/storage")
+            .header("content-type", "application/json")
+            .header(REMOTE_USER_HEADER, "alice")
+            .header(REQUEST_ID_HEADER, "req-42")
+            .body(axum::body::Body::from(
+                r#"{"name": "audited", "storageType": "block", "capacity": "1Gi"}"#,
+            ))
+            .unwrap();
+        let response = router.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+        assert_eq!(response.headers()[REQUEST_ID_HEADER], "req-42");
+
+        let events = orchestrator
+            .audit()
+            .query(&AuditQuery {
+                actor: Some("alice".into()),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].action, AuditAction::Provision);
+        assert_eq!(events[0].request_id.as_deref(), Some("req-42"));
+        assert!(events[0].after.is_some());
+
+        // Dead-letter handling is audited too, including failures
+        let router = RestRouter::new(orchestrator.clone(), NodeRegistry::new())
+            .with_webhooks(Some(WebhookDispatcher::new(Default::default()).unwrap()))
+            .build();
+        for (method, uri, status) in [
+            ("DELETE", "/v1/webhooks/dead-letters", StatusCode::NO_CONTENT),
+            ("POST", "/v1/webhooks/dead-letters/7/redeliver", StatusCode::NOT_FOUND),
+        ] {
+            let request = axum::http::Request::builder()
+                .method(method)
+                .uri(uri)
+                .header(REMOTE_USER_HEADER, "bob")
+                .body(axum::body::Body::empty())
+                .unwrap();
+            let response = router.clone().oneshot(request).await.unwrap();
+            assert_eq!(response.status(), status);
+        }
+
+        let events = orchestrator
+            .audit()
+            .query(&AuditQuery {
+                actor: Some("bob".into()),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        let recorded: Vec<_> = events.iter().map(|e| (e.action, e.outcome)).collect();
+        assert_eq!(recorded.len(), 2);
+        assert!(recorded.contains(&(AuditAction::ClearDeadLetters, AuditOutcome::Success)));
+        assert!(recorded.contains(&(AuditAction::RedeliverDeadLetter, AuditOutcome::Failed)));
+    }
+
+    #[tokio::test]
+    async fn test_event_stream_resumes_after_last_event_id() {
+        use crate::controlplane::events::{EventHub, EventKind};
+        use crate::controlplane::OrchestratorConfig;
+        use tower::ServiceExt;
+
+        let registry = NodeRegistry::new();
+        let orchestrator = Orchestrator::new(OrchestratorConfig::default(), registry.clone());
+        let hub = EventHub::new(16);
+        for name in ["first", "second"] {
+            hub.publish(
+                EventKind::Orchestrator,
+                None,
+                &crate::controlplane::OrchestratorEvent::StorageDeleted {
+                    storage_id: name.into(),
+                },
+            );
+        }
+        let router = RestRouter::new(orchestrator, registry)
+            .with_events(Some(hub))
+            .build();
+
+        let request = axum::http::Request::builder()
+            .uri("/v1/events?kind=orchestrator")
+            .header("last-event-id", "1")
+            .body(axum::body::Body::empty())
+            .unwrap();
+        let response = router.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()["content-type"], "text/event-stream");
+
+        let mut body = response.into_body().into_data_stream();
+        let frame = body.next().await.unwrap().unwrap();
+        let frame = String::from_utf8(frame.to_vec()).unwrap();
+        assert!(frame.contains("id: 2\n"), "{}", frame);
+        assert!(frame.contains("event: StorageDeleted\n"), "{}", frame);
+        assert!(frame.contains("second"), "{}", frame);
+    }
+
+    #[tokio::test]
+    async fn test_node_view_lists_drive_locations() {
+        use crate::controlplane::OrchestratorConfig;
+        use crate::crd::StorageNodeStatus;
+        use tower::ServiceExt;
+
+        let registry = NodeRegistry::new();
+        let drive: DriveStatus = serde_json::from_value(serde_json::json!({
+            "id": "sdb",
+            "devicePath": "/dev/sdb",
+            "driveType": "hdd",
+            "model": "ST16000NM002G",
+            "serial": "ZL2ABCDE",
+            "capacityBytes": 16_000_000_000_000u64,
+            "classification": {},
+            "location": {"enclosure": "500605b0000272bf", "slot": "7"}
+        }))
+        .unwrap();
+        let status = StorageNodeStatus {
+            drives: vec![drive],
+            ..Default::default()
+        };
+        registry
+            .register("node-001", "host-001.local".to_string(), status)
+            .unwrap();
+        let orchestrator = Orchestrator::new(OrchestratorConfig::default(), registry.clone());
+        let router = RestRouter::new(orchestrator, registry).build();
+
+        let request = axum::http::Request::builder()
+            .uri("/v1/nodes/node-001")
+            .body(axum::body::Body::empty())
+            .unwrap();
+        let response = router.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let node: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(
+            node["drives"][0]["location"],
+            serde_json::json!({"enclosure": "500605b0000272bf", "slot": "7"})
+        );
+
+        // The node list stays a summary
+        let request = axum::http::Request::builder()
+            .uri("/v1/nodes")
+            .body(axum::body::Body::empty())
+            .unwrap();
+        let response = router.oneshot(request).await.unwrap();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let nodes: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert!(nodes[0].get("drives").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_tiering_report_and_access_reports() {
+        use crate::controlplane::{OrchestratorConfig, TieringConfig};
+        use tower::ServiceExt;
+
+        let registry = NodeRegistry::new();
+        let orchestrator = Orchestrator::new(OrchestratorConfig::default(), registry.clone());
+        orchestrator.initialize().await.unwrap();
+        let tiering = TieringEngine::new(
+            TieringConfig {
+                min_age_secs: 0,
+                ..Default::default()
+            },
+            orchestrator.clone(),
+            registry.clone(),
+        );
+        let router = RestRouter::new(orchestrator.clone(), registry)
+            .with_tiering(Some(tiering.clone()))
+            .build();
+
+        let request = axum::http::Request::builder()
+            .method("POST")
+            .uri("/v1/storage")
+            .header("content-type", "application/json")
+            .body(axum::body::Body::from(
+                r#"{"name": "tiered", "storageType": "block", "capacity": "1Gi"}"#,
+            ))
+            .unwrap();
+        let response = router.clone().oneshot(request).await.unwrap();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let created: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        let id = created["storageId"].as_str().unwrap().to_string();
+
+        // Without statistics the volume is reported as unknown
+        let report_request = || {
+            axum::http::Request::builder()
+                .uri("/v1/tiering/report")
+                .body(axum::body::Body::empty())
+                .unwrap()
+        };
+        let response = router.clone().oneshot(report_request()).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let report: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(report["unknown"], serde_json::json!([id]));
+
+        let access = |id: &str| {
+            axum::http::Request::builder()
+                .method("POST")
+                .uri(format!("/v1/storage/{}/access", id))
+                .header("content-type", "application/json")
+                .body(axum::body::Body::from(r#"{"readOps": 120, "bytesRead": 491520}"#))
+                .unwrap()
+        };
+        let response = router.clone().oneshot(access(&id)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+        assert_eq!(tiering.access_stats(&id).unwrap().read_ops, 120);
+
+        let response = router.clone().oneshot(access("missing")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        let response = router.oneshot(report_request()).await.unwrap();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let report: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(report["unknown"], serde_json::json!([]));
+    }
+
+    #[tokio::test]
+    async fn test_fingerprint_coverage() {
+        use crate::controlplane::OrchestratorConfig;
+        use crate::crd::{DriveFingerprint, DriveFingerprintSpec, StorageNodeStatus};
+        use tower::ServiceExt;
+
+        let registry = NodeRegistry::new();
+        let drives = [
+            ("nvme0n1", "Samsung SSD 980 PRO 2TB"),
+            ("nvme1n1", "KIOXIA KCD6XLUL3T84"),
+            ("sdb", "ST16000NM002G"),
+            ("sdc", "ST16000NM002G"),
+        ];
+        let drives = drives
+            .iter()
+            .map(|(id, model)| {
+                serde_json::from_value(serde_json::json!({
+                    "id": id,
+                    "devicePath": format!("/dev/{}", id),
+                    "driveType": "nvme",
+                    "model": model,
+                    "serial": id,
+                    "capacityBytes": 1_000_000_000_000u64,
+                    "classification": {}
+                }))
+                .unwrap()
+            })
+            .collect();
+        let status = StorageNodeStatus {
+            drives,
+            ..Default::default()
+        };
+        registry
+            .register("node-001", "host-001.local".to_string(), status)
+            .unwrap();
+        let orchestrator = Orchestrator::new(OrchestratorConfig::default(), registry.clone());
+        orchestrator
+            .fingerprints()
+            .apply(&DriveFingerprint::new(
+                "kioxia-cd6",
+                DriveFingerprintSpec {
+                    model_pattern: "KCD6XLUL".into(),
+                    vendor: "Kioxia".into(),
+                    enterprise: true,
+                    ..Default::default()
+                },
+            ))
+            .unwrap();
+        let router = RestRouter::new(orchestrator, registry).build();
+
+        let request = axum::http::Request::builder()
+            .uri("/v1/fingerprints/coverage")
+            .body(axum::body::Body::empty())
+            .unwrap();
+        let response = router.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let coverage: serde_json::Value = serde_json::from_slice(&body).unwrap();
+
+        assert_eq!(coverage["totalDrives"], 4);
+        assert_eq!(coverage["matchedDrives"], 2);
+        let drives = &coverage["nodes"][0]["drives"];
+        assert_eq!(drives[0]["fingerprint"]["source"], "builtin");
+        assert_eq!(drives[1]["fingerprint"]["source"], "resource");
+        assert_eq!(drives[1]["fingerprint"]["origin"], "kioxia-cd6");
+        assert!(drives[2].get("fingerprint").is_none());
+        assert_eq!(
+            coverage["unmatchedModels"],
+            serde_json::json!([{"model": "ST16000NM002G", "driveCount": 2}])
+        );
     }
 
     #[test]
@@ -3,13 +3,14 @@
 //! Runs both gRPC and REST servers for the control plane API.
 
 use crate::error::{Error, Result};
+use serde::Deserialize;
 use std::net::SocketAddr;
 use std::sync::Arc;
 use tokio::sync::broadcast;
 use tracing::{error, info};
 
 use super::rest::RestRouter;
-use crate::controlplane::Orchestrator;
+use crate::controlplane::{EventHub, Orchestrator, Reconciler, TieringEngine, WebhookDispatcher};
 use crate::hardware::registry::NodeRegistry;
 
 // =============================================================================
@@ -17,7 +18,8 @@ use crate::hardware::registry::NodeRegistry;
 // =============================================================================
 
 /// Configuration for the API server
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "camelCase")]
 pub struct ApiServerConfig {
     /// REST API bind address
     pub rest_addr: SocketAddr,
@@ -87,6 +89,10 @@ pub struct ApiServer {
     config: ApiServerConfig,
     orchestrator: Arc<Orchestrator>,
     registry: Arc<NodeRegistry>,
+    reconciler: Option<Arc<Reconciler>>,
+    tiering: Option<Arc<TieringEngine>>,
+    events: Option<Arc<EventHub>>,
+    webhooks: Option<Arc<WebhookDispatcher>>,
     shutdown_tx: broadcast::Sender<()>,
 }
 
@@ -103,10 +109,38 @@ impl ApiServer {
             config,
             orchestrator,
             registry,
+            reconciler: None,
+            tiering: None,
+            events: None,
+            webhooks: None,
             shutdown_tx,
         }
     }
 
+    /// Expose reports from a running reconciler
+    pub fn with_reconciler(mut self, reconciler: Arc<Reconciler>) -> Self {
+        self.reconciler = Some(reconciler);
+        self
+    }
+
+    /// Expose reports from, and feed volume I/O into, a tiering engine
+    pub fn with_tiering(mut self, tiering: Arc<TieringEngine>) -> Self {
+        self.tiering = Some(tiering);
+        self
+    }
+
+    /// Stream events from `events` on `/v1/events`
+    pub fn with_events(mut self, events: Arc<EventHub>) -> Self {
+        self.events = Some(events);
+        self
+    }
+
+    /// Manage webhook subscriptions of `webhooks` on `/v1/webhooks`
+    pub fn with_webhooks(mut self, webhooks: Arc<WebhookDispatcher>) -> Self {
+        self.webhooks = Some(webhooks);
+        self
+    }
+
     /// Run the API server
     pub async fn run(&self) -> Result<()> {
         info!("Starting Unified API Server");
@@ -131,13 +165,14 @@ impl ApiServer {
     /// Spawn the REST server
     fn spawn_rest_server(&self) -> tokio::task::JoinHandle<Result<()>> {
         let addr = self.config.rest_addr;
-        let orchestrator = self.orchestrator.clone();
-        let registry = self.registry.clone();
+        let router = RestRouter::new(self.orchestrator.clone(), self.registry.clone())
+            .with_reconciler(self.reconciler.clone())
+            .with_tiering(self.tiering.clone())
+            .with_events(self.events.clone())
+            .with_webhooks(self.webhooks.clone());
         let shutdown_rx = self.shutdown_tx.subscribe();
 
-        tokio::spawn(async move {
-            run_rest_server(addr, orchestrator, registry, shutdown_rx).await
-        })
+        tokio::spawn(async move { run_rest_server(addr, router, shutdown_rx).await })
     }
 
     /// Trigger graceful shutdown
@@ -149,13 +184,9 @@ impl ApiServer {
 /// Run the REST API server
 async fn run_rest_server(
     addr: SocketAddr,
-    orchestrator: Arc<Orchestrator>,
-    registry: Arc<NodeRegistry>,
+    router: RestRouter,
     mut shutdown_rx: broadcast::Receiver<()>,
 ) -> Result<()> {
-    use axum::Router;
-
-    let router = RestRouter::new(orchestrator, registry);
     let app = router.build();
 
     info!("REST API listening on {}", addr);
@@ -0,0 +1,661 @@
+//! Audit Log
+//!
+//! Records every mutating control-plane operation (provision, delete,
+//! resize, migrate, label changes, classification, pool and quota changes)
+//! as a structured event naming the caller, request ID, before/after
+//! summary and outcome. Events go to pluggable sinks: an append-only
+//! JSON-lines file with size-based rotation and the Kubernetes Events API.
+//!
+//! The caller and request ID come from the `AuditContext` the API layer
+//! scopes each request in; work started by the operator itself (tiering,
+//! reconciliation) is attributed to `SYSTEM_ACTOR`.
+
+use crate::controlplane::policy::CallerIdentity;
+use crate::error::{Error, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use k8s_openapi::api::core::v1::{Event, EventSource, ObjectReference};
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::{MicroTime, ObjectMeta, Time};
+use kube::api::{Api, PostParams};
+use parking_lot::{Mutex, RwLock};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use tokio::io::AsyncWriteExt;
+use tracing::warn;
+
+// =============================================================================
+// Constants
+// =============================================================================
+
+/// Actor recorded for work the operator starts on its own
+pub const SYSTEM_ACTOR: &str = "system:smart-storage-operator";
+
+/// Events kept in memory for queries when no sink can be read back
+pub const DEFAULT_RECENT_EVENTS: usize = 10_000;
+
+/// Default page size for audit queries
+pub const DEFAULT_QUERY_LIMIT: usize = 100;
+
+// =============================================================================
+// Audit Context
+// =============================================================================
+
+tokio::task_local! {
+    static AUDIT_CONTEXT: AuditContext;
+}
+
+/// Who is behind the operation currently running, and under which request
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditContext {
+    pub caller: CallerIdentity,
+    pub request_id: Option<String>,
+}
+
+impl AuditContext {
+    /// Context of the current task, or the system actor outside any request
+    pub fn current() -> Self {
+        AUDIT_CONTEXT.try_with(Clone::clone).unwrap_or_else(|_| Self {
+            caller: CallerIdentity {
+                user: Some(SYSTEM_ACTOR.into()),
+                groups: Vec::new(),
+            },
+            request_id: None,
+        })
+    }
+
+    /// Run `future` with this context
+    pub async fn scope<F: Future>(self, future: F) -> F::Output {
+        AUDIT_CONTEXT.scope(self, future).await
+    }
+}
+
+// =============================================================================
+// Audit Events
+// =============================================================================
+
+/// Kind of operation recorded
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum AuditAction {
+    Provision,
+    Delete,
+    Resize,
+    Migrate,
+    UpdateLabels,
+    Import,
+    Adopt,
+    MarkLost,
+    ClearLost,
+    UpdateCapacity,
+    Classify,
+    CreatePool,
+    SetQuota,
+    RemoveQuota,
+    SetWebhook,
+    RemoveWebhook,
+    ClearDeadLetters,
+    RedeliverDeadLetter,
+}
+
+impl std::fmt::Display for AuditAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = serde_json::to_value(self)
+            .ok()
+            .and_then(|v| v.as_str().map(str::to_string))
+            .unwrap_or_default();
+        write!(f, "{}", name)
+    }
+}
+
+/// How the operation ended
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum AuditOutcome {
+    Success,
+    /// Refused by a quota or admission policy
+    Denied,
+    Failed,
+}
+
+/// One recorded operation
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditEvent {
+    /// Unique event ID
+    pub id: String,
+    pub timestamp: DateTime<Utc>,
+    /// Who performed the operation
+    pub actor: CallerIdentity,
+    /// Request the operation belongs to (None for background work)
+    #[serde(default)]
+    pub request_id: Option<String>,
+    pub action: AuditAction,
+    /// Affected resource as `<kind>/<name>`, e.g. `storage/vol-1234`
+    pub resource: String,
+    pub outcome: AuditOutcome,
+    /// Error for failed or denied operations
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    /// Resource before the operation
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub before: Option<serde_json::Value>,
+    /// Resource after the operation
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub after: Option<serde_json::Value>,
+}
+
+impl AuditEvent {
+    /// Successful event attributed to the current `AuditContext`
+    pub fn new(action: AuditAction, resource: impl Into<String>) -> Self {
+        static SEQUENCE: AtomicU64 = AtomicU64::new(0);
+
+        let context = AuditContext::current();
+        let timestamp = Utc::now();
+        Self {
+            id: format!(
+                "{:x}-{:x}",
+                timestamp.timestamp_micros(),
+                SEQUENCE.fetch_add(1, Ordering::Relaxed)
+            ),
+            timestamp,
+            actor: context.caller,
+            request_id: context.request_id,
+            action,
+            resource: resource.into(),
+            outcome: AuditOutcome::Success,
+            message: None,
+            before: None,
+            after: None,
+        }
+    }
+
+    /// Attribute the event to `actor` instead of the context caller
+    pub fn actor(mut self, actor: &CallerIdentity) -> Self {
+        self.actor = actor.clone();
+        self
+    }
+
+    /// Summary of the resource before the operation
+    pub fn before<T: Serialize>(mut self, before: &T) -> Self {
+        self.before = serde_json::to_value(before).ok().filter(|v| !v.is_null());
+        self
+    }
+
+    /// Summary of the resource after the operation
+    pub fn after<T: Serialize>(mut self, after: &T) -> Self {
+        self.after = serde_json::to_value(after).ok().filter(|v| !v.is_null());
+        self
+    }
+
+    /// Set the outcome from the operation's result
+    pub fn outcome<T>(mut self, result: &Result<T>) -> Self {
+        if let Err(e) = result {
+            self.outcome = match e {
+                Error::QuotaExceeded { .. } | Error::PolicyDenied { .. } => AuditOutcome::Denied,
+                _ => AuditOutcome::Failed,
+            };
+            self.message = Some(e.to_string());
+        }
+        self
+    }
+
+    /// Kind part of `resource`
+    pub fn resource_kind(&self) -> &str {
+        self.resource.split_once('/').map_or(&self.resource, |(kind, _)| kind)
+    }
+
+    /// Name part of `resource`
+    pub fn resource_name(&self) -> &str {
+        self.resource.split_once('/').map_or(&self.resource, |(_, name)| name)
+    }
+}
+
+/// Filters for reading the audit log back
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditQuery {
+    /// Only events by this user
+    pub actor: Option<String>,
+    /// Only events for this resource (`<kind>/<name>`, or a `<kind>/` prefix)
+    pub resource: Option<String>,
+    /// Only events at or after this time
+    pub since: Option<DateTime<Utc>>,
+    /// Only events before this time
+    pub until: Option<DateTime<Utc>>,
+    /// Newest N matching events (default `DEFAULT_QUERY_LIMIT`)
+    pub limit: Option<usize>,
+}
+
+impl AuditQuery {
+    pub fn matches(&self, event: &AuditEvent) -> bool {
+        self.actor
+            .as_deref()
+            .is_none_or(|actor| event.actor.user.as_deref() == Some(actor))
+            && self.resource.as_deref().is_none_or(|resource| {
+                if resource.ends_with('/') {
+                    event.resource.starts_with(resource)
+                } else {
+                    event.resource == resource
+                }
+            })
+            && self.since.is_none_or(|since| event.timestamp >= since)
+            && self.until.is_none_or(|until| event.timestamp < until)
+    }
+
+    /// Matching events from a chronological sequence, keeping the newest
+    pub fn select(&self, events: impl IntoIterator<Item = AuditEvent>) -> Vec<AuditEvent> {
+        let limit = self.limit.unwrap_or(DEFAULT_QUERY_LIMIT);
+        let mut selected: VecDeque<AuditEvent> = VecDeque::new();
+        for event in events.into_iter().filter(|e| self.matches(e)) {
+            if selected.len() == limit {
+                selected.pop_front();
+            }
+            selected.push_back(event);
+        }
+        selected.into()
+    }
+}
+
+// =============================================================================
+// Sinks
+// =============================================================================
+
+/// Destination for audit events
+#[async_trait]
+pub trait AuditSink: Send + Sync {
+    /// Sink name for logs
+    fn name(&self) -> &str;
+
+    /// Persist one event
+    async fn write(&self, event: &AuditEvent) -> Result<()>;
+
+    /// Read matching events back, if the sink supports it
+    async fn query(&self, _query: &AuditQuery) -> Result<Option<Vec<AuditEvent>>> {
+        Ok(None)
+    }
+}
+
+/// Append-only JSON-lines file, rotated to `<path>.1` … `<path>.N` by size
+pub struct JsonLinesAuditSink {
+    path: PathBuf,
+    max_bytes: u64,
+    max_files: usize,
+    /// Serializes appends and rotation
+    lock: tokio::sync::Mutex<()>,
+}
+
+impl JsonLinesAuditSink {
+    /// Create a sink writing to `path`, creating its directory if needed
+    pub async fn new(path: impl Into<PathBuf>, max_bytes: u64, max_files: usize) -> Result<Self> {
+        let path = path.into();
+        if let Some(dir) = path.parent().filter(|d| !d.as_os_str().is_empty()) {
+            tokio::fs::create_dir_all(dir).await?;
+        }
+        Ok(Self {
+            path,
+            max_bytes,
+            max_files,
+            lock: tokio::sync::Mutex::new(()),
+        })
+    }
+
+    fn rotated(&self, n: usize) -> PathBuf {
+        let mut name = self.path.as_os_str().to_owned();
+        name.push(format!(".{}", n));
+        PathBuf::from(name)
+    }
+
+    /// Shift `<path>` to `<path>.1`, `<path>.1` to `<path>.2`, … dropping the oldest
+    async fn rotate(&self) -> Result<()> {
+        if self.max_files == 0 {
+            return remove_if_exists(&self.path).await;
+        }
+        remove_if_exists(&self.rotated(self.max_files)).await?;
+        for n in (1..self.max_files).rev() {
+            rename_if_exists(&self.rotated(n), &self.rotated(n + 1)).await?;
+        }
+        rename_if_exists(&self.path, &self.rotated(1)).await
+    }
+}
+
+#[async_trait]
+impl AuditSink for JsonLinesAuditSink {
+    fn name(&self) -> &str {
+        "jsonl"
+    }
+
+    async fn write(&self, event: &AuditEvent) -> Result<()> {
+        let mut line = serde_json::to_vec(event)?;
+        line.push(b'\n');
+
+        let _guard = self.lock.lock().await;
+        let size = match tokio::fs::metadata(&self.path).await {
+            Ok(metadata) => metadata.len(),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => 0,
+            Err(e) => return Err(e.into()),
+        };
+        if size > 0 && size + line.len() as u64 > self.max_bytes {
+            self.rotate().await?;
+        }
+
+        let mut file = tokio::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .await?;
+        file.write_all(&line).await?;
+        file.flush().await?;
+        Ok(())
+    }
+
+    async fn query(&self, query: &AuditQuery) -> Result<Option<Vec<AuditEvent>>> {
+        let _guard = self.lock.lock().await;
+        let mut files: Vec<PathBuf> = (1..=self.max_files).rev().map(|n| self.rotated(n)).collect();
+        files.push(self.path.clone());
+
+        let mut events = Vec::new();
+        for file in files {
+            let content = match tokio::fs::read_to_string(&file).await {
+                Ok(content) => content,
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
+                Err(e) => return Err(e.into()),
+            };
+            for line in content.lines().filter(|l| !l.trim().is_empty()) {
+                match serde_json::from_str::<AuditEvent>(line) {
+                    Ok(event) => events.push(event),
+                    Err(e) => warn!("Skipping unreadable audit line in {}: {}", file.display(), e),
+                }
+            }
+        }
+
+        Ok(Some(query.select(events)))
+    }
+}
+
+async fn remove_if_exists(path: &Path) -> Result<()> {
+    match tokio::fs::remove_file(path).await {
+        Ok(()) => Ok(()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+async fn rename_if_exists(from: &Path, to: &Path) -> Result<()> {
+    match tokio::fs::rename(from, to).await {
+        Ok(()) => Ok(()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Publishes audit events as Kubernetes Events
+pub struct KubernetesEventSink {
+    events: Api<Event>,
+    namespace: String,
+}
+
+impl KubernetesEventSink {
+    /// Reporting component recorded on each Event
+    pub const COMPONENT: &'static str = "smart-storage-operator";
+
+    /// Create a sink publishing Events into `namespace`
+    pub fn new(client: kube::Client, namespace: &str) -> Self {
+        Self {
+            events: Api::namespaced(client, namespace),
+            namespace: namespace.to_string(),
+        }
+    }
+
+    /// Kubernetes Event for an audit event
+    pub fn to_event(namespace: &str, event: &AuditEvent) -> Event {
+        let kind = match event.resource_kind() {
+            "storage" => "Storage",
+            "node" => "StorageNode",
+            "pool" => "UnifiedPool",
+            "quota" => "StorageQuota",
+            other => other,
+        };
+        let actor = event.actor.user.as_deref().unwrap_or("anonymous");
+        let message = match &event.message {
+            Some(message) => format!("{} {} by {}: {}", event.action, event.resource, actor, message),
+            None => format!("{} {} by {}", event.action, event.resource, actor),
+        };
+
+        Event {
+            metadata: ObjectMeta {
+                generate_name: Some("audit-".into()),
+                namespace: Some(namespace.to_string()),
+                annotations: event
+                    .request_id
+                    .as_ref()
+                    .map(|id| [("storage.billyronks.io/request-id".to_string(), id.clone())].into()),
+                ..Default::default()
+            },
+            involved_object: ObjectReference {
+                api_version: Some("storage.billyronks.io/v1".into()),
+                kind: Some(kind.to_string()),
+                name: Some(event.resource_name().to_string()),
+                namespace: Some(namespace.to_string()),
+                ..Default::default()
+            },
+            action: Some(event.action.to_string()),
+            reason: Some(format!("{:?}", event.outcome)),
+            message: Some(message),
+            type_: Some(
+                match event.outcome {
+                    AuditOutcome::Success => "Normal",
+                    AuditOutcome::Denied | AuditOutcome::Failed => "Warning",
+                }
+                .into(),
+            ),
+            event_time: Some(MicroTime(event.timestamp)),
+            first_timestamp: Some(Time(event.timestamp)),
+            last_timestamp: Some(Time(event.timestamp)),
+            count: Some(1),
+            reporting_component: Some(Self::COMPONENT.into()),
+            reporting_instance: Some(Self::COMPONENT.into()),
+            source: Some(EventSource {
+                component: Some(Self::COMPONENT.into()),
+                host: None,
+            }),
+            ..Default::default()
+        }
+    }
+}
+
+#[async_trait]
+impl AuditSink for KubernetesEventSink {
+    fn name(&self) -> &str {
+        "kubernetes-events"
+    }
+
+    async fn write(&self, event: &AuditEvent) -> Result<()> {
+        self.events
+            .create(&PostParams::default(), &Self::to_event(&self.namespace, event))
+            .await?;
+        Ok(())
+    }
+}
+
+// =============================================================================
+// Audit Log
+// =============================================================================
+
+/// Fans audit events out to the configured sinks
+pub struct AuditLog {
+    sinks: RwLock<Vec<Arc<dyn AuditSink>>>,
+    /// Recent events, answered from when no sink can be queried
+    recent: Mutex<VecDeque<AuditEvent>>,
+    recent_capacity: usize,
+}
+
+impl Default for AuditLog {
+    fn default() -> Self {
+        Self::new(DEFAULT_RECENT_EVENTS)
+    }
+}
+
+impl AuditLog {
+    /// Create a log keeping the last `recent_capacity` events in memory
+    pub fn new(recent_capacity: usize) -> Self {
+        Self {
+            sinks: RwLock::new(Vec::new()),
+            recent: Mutex::new(VecDeque::new()),
+            recent_capacity,
+        }
+    }
+
+    /// Send future events to `sink` as well
+    pub fn add_sink(&self, sink: Arc<dyn AuditSink>) {
+        self.sinks.write().push(sink);
+    }
+
+    /// Record an event in every sink
+    ///
+    /// Sink failures are logged rather than failing the audited operation.
+    pub async fn record(&self, event: AuditEvent) {
+        {
+            let mut recent = self.recent.lock();
+            if recent.len() == self.recent_capacity {
+                recent.pop_front();
+            }
+            recent.push_back(event.clone());
+        }
+
+        let sinks: Vec<_> = self.sinks.read().clone();
+        for sink in sinks {
+            if let Err(e) = sink.write(&event).await {
+                warn!("Audit sink {} failed to record {}: {}", sink.name(), event.id, e);
+            }
+        }
+    }
+
+    /// Matching events, oldest first, from the first sink that can be read
+    /// back (or the in-memory buffer)
+    pub async fn query(&self, query: &AuditQuery) -> Result<Vec<AuditEvent>> {
+        let sinks: Vec<_> = self.sinks.read().clone();
+        for sink in sinks {
+            if let Some(events) = sink.query(query).await? {
+                return Ok(events);
+            }
+        }
+
+        let recent: Vec<AuditEvent> = self.recent.lock().iter().cloned().collect();
+        Ok(query.select(recent))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn caller(user: &str) -> AuditContext {
+        AuditContext {
+            caller: CallerIdentity {
+                user: Some(user.into()),
+                groups: Vec::new(),
+            },
+            request_id: Some(format!("req-{}", user)),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_context_attribution() {
+        let event = AuditEvent::new(AuditAction::Delete, "storage/vol-1");
+        assert_eq!(event.actor.user.as_deref(), Some(SYSTEM_ACTOR));
+        assert!(event.request_id.is_none());
+
+        let event = caller("alice")
+            .scope(async { AuditEvent::new(AuditAction::Delete, "storage/vol-1") })
+            .await;
+        assert_eq!(event.actor.user.as_deref(), Some("alice"));
+        assert_eq!(event.request_id.as_deref(), Some("req-alice"));
+
+        let denied: Result<()> = Err(Error::QuotaExceeded {
+            tenant: "t".into(),
+            reason: "full".into(),
+        });
+        let event = event.outcome(&denied);
+        assert_eq!(event.outcome, AuditOutcome::Denied);
+        assert!(event.message.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_jsonl_sink_rotation_and_query() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("audit").join("audit.jsonl");
+        let sink = Arc::new(JsonLinesAuditSink::new(&path, 600, 2).await.unwrap());
+        let log = AuditLog::new(4);
+        log.add_sink(sink);
+
+        for i in 0..12 {
+            let user = if i % 3 == 0 { "alice" } else { "bob" };
+            let event = caller(user)
+                .scope(async { AuditEvent::new(AuditAction::Provision, format!("storage/vol-{}", i)) })
+                .await;
+            log.record(event).await;
+        }
+
+        assert!(dir.path().join("audit/audit.jsonl.1").exists());
+        assert!(dir.path().join("audit/audit.jsonl.2").exists());
+        assert!(!dir.path().join("audit/audit.jsonl.3").exists());
+
+        // Everything still on disk, oldest first
+        let all = log
+            .query(&AuditQuery {
+                limit: Some(1000),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert!(all.len() < 12);
+        assert!(all.windows(2).all(|w| w[0].timestamp <= w[1].timestamp));
+        assert_eq!(all.last().unwrap().resource, "storage/vol-11");
+
+        let alice = log
+            .query(&AuditQuery {
+                actor: Some("alice".into()),
+                limit: Some(1),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(alice.len(), 1);
+        assert_eq!(alice[0].resource, "storage/vol-9");
+
+        let future = AuditQuery {
+            since: Some(Utc::now() + chrono::Duration::hours(1)),
+            ..Default::default()
+        };
+        assert!(log.query(&future).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_in_memory_query_and_k8s_event() {
+        let log = AuditLog::new(2);
+        for i in 0..3 {
+            log.record(AuditEvent::new(AuditAction::Classify, format!("node/n{}", i)))
+                .await;
+        }
+        let events = log
+            .query(&AuditQuery {
+                resource: Some("node/".into()),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].resource, "node/n1");
+
+        let event = KubernetesEventSink::to_event("storage-system", &events[1]);
+        assert_eq!(event.involved_object.kind.as_deref(), Some("StorageNode"));
+        assert_eq!(event.involved_object.name.as_deref(), Some("n2"));
+        assert_eq!(event.type_.as_deref(), Some("Normal"));
+        assert_eq!(event.action.as_deref(), Some("classify"));
+    }
+}
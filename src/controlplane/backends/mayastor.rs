@@ -2,7 +2,10 @@
 //!
 //! Provides block storage provisioning via OpenEBS Mayastor.
 
-use crate::domain::ports::{ProvisionRequest, ProvisionResponse, StorageProvisioner, StorageType};
+use crate::domain::ports::{
+    BackendCapabilities, ProvisionRequest, ProvisionResponse, StorageProvisioner, StorageTier,
+    StorageType, PARAM_CLONE_SOURCE, PARAM_ENCRYPTED,
+};
 use crate::error::{Error, Result};
 use async_trait::async_trait;
 use kube::Client;
@@ -17,7 +20,8 @@ use tracing::{debug, info, warn};
 // =============================================================================
 
 /// Configuration for Mayastor adapter
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "camelCase")]
 pub struct MayastorConfig {
     /// Mayastor namespace
     pub namespace: String,
@@ -55,9 +59,38 @@ struct VolumeState {
     capacity_bytes: u64,
     pool_name: String,
     replicas: u32,
+    /// Kubernetes labels on the MayastorVolume resource
+    #[serde(default)]
+    labels: BTreeMap<String, String>,
+    /// Encrypted at rest
+    #[serde(default)]
+    encrypted: bool,
+    /// Volume this one was cloned from
+    #[serde(default)]
+    clone_source: Option<String>,
+    /// IOPS limit enforced by the volume's QoS policy
+    #[serde(default)]
+    max_iops: Option<u64>,
     created_at: chrono::DateTime<chrono::Utc>,
 }
 
+impl VolumeState {
+    /// Platform details reported for the volume
+    fn platform_details(&self) -> BTreeMap<String, String> {
+        let mut details = BTreeMap::new();
+        details.insert("backend".to_string(), "mayastor".to_string());
+        details.insert("replicas".to_string(), self.replicas.to_string());
+        details.insert("encrypted".to_string(), self.encrypted.to_string());
+        if let Some(source) = &self.clone_source {
+            details.insert("clone_source".to_string(), source.clone());
+        }
+        if let Some(iops) = self.max_iops {
+            details.insert("max_iops".to_string(), iops.to_string());
+        }
+        details
+    }
+}
+
 // =============================================================================
 // Mayastor Adapter
 // =============================================================================
@@ -86,29 +119,55 @@ impl MayastorAdapter {
         self
     }
 
-    /// Create a MayastorVolume resource
+    /// Create a MayastorVolume resource for a request
     async fn create_volume(
         &self,
-        name: &str,
-        capacity_bytes: u64,
+        request: &ProvisionRequest,
         replicas: u32,
         pool_labels: &str,
     ) -> Result<String> {
         let volume_id = format!("vol-{}", generate_id());
+        let encrypted = request
+            .platform_params
+            .get(PARAM_ENCRYPTED)
+            .is_some_and(|v| v.eq_ignore_ascii_case("true"));
+        let clone_source = request.platform_params.get(PARAM_CLONE_SOURCE).cloned();
 
         info!(
             "Creating Mayastor volume: {} ({} bytes, {} replicas)",
-            name, capacity_bytes, replicas
+            request.name, request.capacity_bytes, replicas
         );
 
+        // A clone is a copy-on-write volume over a snapshot of its source,
+        // and cannot be smaller than it
+        if let Some(source) = &clone_source {
+            let source_state = self.get_volume(source).await.ok_or_else(|| {
+                Error::ResourceNotFound {
+                    kind: "MayastorVolume".into(),
+                    name: source.clone(),
+                }
+            })?;
+            if request.capacity_bytes < source_state.capacity_bytes {
+                return Err(Error::ApiValidation(format!(
+                    "Clone of {} needs at least {} bytes, requested {}",
+                    source, source_state.capacity_bytes, request.capacity_bytes
+                )));
+            }
+        }
+
         // In a real implementation, this would create the MayastorVolume CRD
+        // (with an encrypted storage class or a dataSource for clones)
         // For now, we track it internally
         let state = VolumeState {
             id: volume_id.clone(),
-            name: name.to_string(),
-            capacity_bytes,
+            name: request.name.clone(),
+            capacity_bytes: request.capacity_bytes,
             pool_name: format!("pool-{}", pool_labels.replace('=', "-")),
             replicas,
+            labels: BTreeMap::new(),
+            encrypted,
+            clone_source,
+            max_iops: request.max_iops,
             created_at: chrono::Utc::now(),
         };
 
@@ -138,27 +197,28 @@ impl MayastorAdapter {
     async fn get_volume(&self, volume_id: &str) -> Option<VolumeState> {
         self.volumes.read().await.get(volume_id).cloned()
     }
+
+    /// Pool labels used for a tier
+    fn pool_labels_for(&self, tier: Option<StorageTier>) -> &str {
+        match tier {
+            Some(StorageTier::Hot) => &self.config.hot_pool_label,
+            Some(StorageTier::Cold) => &self.config.cold_pool_label,
+            _ => &self.config.hot_pool_label, // Default to hot
+        }
+    }
 }
 
 #[async_trait]
 impl StorageProvisioner for MayastorAdapter {
     async fn provision(&self, request: ProvisionRequest) -> Result<ProvisionResponse> {
         // Determine pool labels based on tier
-        let pool_labels = match request.tier {
-            Some(crate::domain::ports::StorageTier::Hot) => &self.config.hot_pool_label,
-            Some(crate::domain::ports::StorageTier::Cold) => &self.config.cold_pool_label,
-            _ => &self.config.hot_pool_label, // Default to hot
-        };
+        let pool_labels = self.pool_labels_for(request.tier);
 
         // Create the volume
         let volume_id = self
-            .create_volume(
-                &request.name,
-                request.capacity_bytes,
-                self.config.default_replicas,
-                pool_labels,
-            )
+            .create_volume(&request, self.config.default_replicas, pool_labels)
             .await?;
+        self.set_labels(&volume_id, &request.labels).await?;
 
         // Get the created state
         let state = self.get_volume(&volume_id).await.ok_or_else(|| {
@@ -169,9 +229,7 @@ impl StorageProvisioner for MayastorAdapter {
             }
         })?;
 
-        let mut platform_details = BTreeMap::new();
-        platform_details.insert("backend".to_string(), "mayastor".to_string());
-        platform_details.insert("replicas".to_string(), state.replicas.to_string());
+        let mut platform_details = state.platform_details();
         platform_details.insert("namespace".to_string(), self.config.namespace.clone());
 
         Ok(ProvisionResponse {
@@ -182,6 +240,7 @@ impl StorageProvisioner for MayastorAdapter {
             pool_name: state.pool_name,
             primary_node: None,
             platform_details,
+            resolution: None,
         })
     }
 
@@ -189,15 +248,51 @@ impl StorageProvisioner for MayastorAdapter {
         self.delete_volume(storage_id).await
     }
 
+    async fn resize(&self, storage_id: &str, capacity_bytes: u64) -> Result<ProvisionResponse> {
+        {
+            let mut volumes = self.volumes.write().await;
+            let state = volumes.get_mut(storage_id).ok_or_else(|| Error::ResourceNotFound {
+                kind: "MayastorVolume".into(),
+                name: storage_id.into(),
+            })?;
+            // Mayastor volumes can only grow
+            if capacity_bytes < state.capacity_bytes {
+                return Err(Error::ApiValidation(format!(
+                    "Mayastor volume {} cannot shrink from {} to {} bytes",
+                    storage_id, state.capacity_bytes, capacity_bytes
+                )));
+            }
+            info!("Resizing Mayastor volume {} to {} bytes", storage_id, capacity_bytes);
+            state.capacity_bytes = capacity_bytes;
+        }
+
+        self.get(storage_id).await?.ok_or_else(|| Error::ResourceNotFound {
+            kind: "MayastorVolume".into(),
+            name: storage_id.into(),
+        })
+    }
+
+    async fn set_labels(&self, storage_id: &str, labels: &BTreeMap<String, String>) -> Result<()> {
+        // In a real implementation, this would patch metadata.labels on the CRD
+        match self.volumes.write().await.get_mut(storage_id) {
+            Some(state) => {
+                state.labels = labels.clone();
+                Ok(())
+            }
+            None => Err(Error::ResourceNotFound {
+                kind: "MayastorVolume".into(),
+                name: storage_id.into(),
+            }),
+        }
+    }
+
     async fn get(&self, storage_id: &str) -> Result<Option<ProvisionResponse>> {
         let state = match self.get_volume(storage_id).await {
             Some(s) => s,
             None => return Ok(None),
         };
 
-        let mut platform_details = BTreeMap::new();
-        platform_details.insert("backend".to_string(), "mayastor".to_string());
-        platform_details.insert("replicas".to_string(), state.replicas.to_string());
+        let platform_details = state.platform_details();
 
         Ok(Some(ProvisionResponse {
             storage_id: state.id,
@@ -207,6 +302,7 @@ impl StorageProvisioner for MayastorAdapter {
             pool_name: state.pool_name,
             primary_node: None,
             platform_details,
+            resolution: None,
         }))
     }
 
@@ -215,9 +311,7 @@ impl StorageProvisioner for MayastorAdapter {
         let mut responses = Vec::new();
 
         for state in volumes.values() {
-            let mut platform_details = BTreeMap::new();
-            platform_details.insert("backend".to_string(), "mayastor".to_string());
-            platform_details.insert("replicas".to_string(), state.replicas.to_string());
+            let platform_details = state.platform_details();
 
             responses.push(ProvisionResponse {
                 storage_id: state.id.clone(),
@@ -227,12 +321,45 @@ impl StorageProvisioner for MayastorAdapter {
                 pool_name: state.pool_name.clone(),
                 primary_node: None,
                 platform_details,
+                resolution: None,
             });
         }
 
         Ok(responses)
     }
 
+    async fn migrate_tier(&self, storage_id: &str, tier: StorageTier) -> Result<ProvisionResponse> {
+        let pool_name = format!("pool-{}", self.pool_labels_for(Some(tier)).replace('=', "-"));
+
+        info!("Moving Mayastor volume {} to {} ({})", storage_id, tier, pool_name);
+
+        // In a real implementation, this would add replicas on the target
+        // pools, wait for rebuild, then retire the old replicas
+        let state = {
+            let mut volumes = self.volumes.write().await;
+            let state = volumes.get_mut(storage_id).ok_or_else(|| Error::ResourceNotFound {
+                kind: "MayastorVolume".into(),
+                name: storage_id.into(),
+            })?;
+            state.pool_name = pool_name;
+            state.clone()
+        };
+
+        let mut platform_details = state.platform_details();
+        platform_details.insert("tier".to_string(), tier.to_string());
+
+        Ok(ProvisionResponse {
+            storage_id: state.id,
+            name: state.name,
+            storage_type: StorageType::Block,
+            capacity_bytes: state.capacity_bytes,
+            pool_name: state.pool_name,
+            primary_node: None,
+            platform_details,
+            resolution: None,
+        })
+    }
+
     async fn health_check(&self) -> Result<bool> {
         // In a real implementation, check Mayastor API or CRD status
         Ok(true)
@@ -245,6 +372,19 @@ impl StorageProvisioner for MayastorAdapter {
     fn supported_types(&self) -> Vec<StorageType> {
         vec![StorageType::Block]
     }
+
+    fn capabilities(&self) -> BackendCapabilities {
+        // Restoring from snapshots needs a snapshot API first
+        BackendCapabilities {
+            clone: true,
+            resize: true,
+            qos: true,
+            encryption: true,
+            tagging: true,
+            min_size_bytes: 1024 * 1024, // 1Mi
+            ..Default::default()
+        }
+    }
 }
 
 /// Generate a simple unique ID
@@ -273,6 +413,8 @@ mod tests {
             tier: Some(crate::domain::ports::StorageTier::Hot),
             max_iops: None,
             labels: BTreeMap::new(),
+            annotations: BTreeMap::new(),
+            tenant: None,
             platform_params: BTreeMap::new(),
         };
 
@@ -296,6 +438,8 @@ mod tests {
             tier: None,
             max_iops: None,
             labels: BTreeMap::new(),
+            annotations: BTreeMap::new(),
+            tenant: None,
             platform_params: BTreeMap::new(),
         };
 
@@ -307,4 +451,77 @@ mod tests {
         // Should not find it
         assert!(adapter.get(&response.storage_id).await.unwrap().is_none());
     }
+
+    #[tokio::test]
+    async fn test_migrate_tier() {
+        let adapter = MayastorAdapter::new(MayastorConfig::default());
+
+        let request = ProvisionRequest {
+            request_id: "test-req".into(),
+            name: "test-volume".into(),
+            storage_type: StorageType::Block,
+            capacity_bytes: 10 * 1024 * 1024 * 1024,
+            tier: Some(StorageTier::Hot),
+            max_iops: None,
+            labels: BTreeMap::new(),
+            annotations: BTreeMap::new(),
+            tenant: None,
+            platform_params: BTreeMap::new(),
+        };
+
+        let response = adapter.provision(request).await.unwrap();
+        assert_eq!(response.pool_name, "pool-tier-hot");
+
+        let moved = adapter
+            .migrate_tier(&response.storage_id, StorageTier::Cold)
+            .await
+            .unwrap();
+        assert_eq!(moved.pool_name, "pool-tier-cold");
+
+        let fetched = adapter.get(&response.storage_id).await.unwrap().unwrap();
+        assert_eq!(fetched.pool_name, "pool-tier-cold");
+    }
+
+    #[tokio::test]
+    async fn test_advertised_features_take_effect() {
+        use crate::domain::ports::PARAM_SNAPSHOT_SOURCE;
+
+        let adapter = MayastorAdapter::new(MayastorConfig::default());
+        let caps = adapter.capabilities();
+        let request = |name: &str, params: &[(&str, &str)]| ProvisionRequest {
+            request_id: format!("req-{}", name),
+            name: name.into(),
+            storage_type: StorageType::Block,
+            capacity_bytes: 10 * 1024 * 1024 * 1024,
+            tier: None,
+            max_iops: Some(5000),
+            labels: BTreeMap::new(),
+            annotations: BTreeMap::new(),
+            tenant: None,
+            platform_params: params
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+        };
+
+        let source = request("source", &[(PARAM_ENCRYPTED, "true")]);
+        caps.validate("mayastor", &source).unwrap();
+        let source = adapter.provision(source).await.unwrap();
+        assert_eq!(source.platform_details["encrypted"], "true");
+        assert_eq!(source.platform_details["max_iops"], "5000");
+
+        let clone = request("clone", &[(PARAM_CLONE_SOURCE, source.storage_id.as_str())]);
+        caps.validate("mayastor", &clone).unwrap();
+        let clone = adapter.provision(clone).await.unwrap();
+        let fetched = adapter.get(&clone.storage_id).await.unwrap().unwrap();
+        assert_eq!(fetched.platform_details["clone_source"], source.storage_id);
+        assert_eq!(fetched.platform_details["encrypted"], "false");
+
+        let missing = request("orphan", &[(PARAM_CLONE_SOURCE, "vol-missing")]);
+        assert!(adapter.provision(missing).await.is_err());
+
+        // Not advertised, so rejected before reaching the adapter
+        let snapshot = request("restored", &[(PARAM_SNAPSHOT_SOURCE, "snap-1")]);
+        assert!(caps.validate("mayastor", &snapshot).is_err());
+    }
 }
@@ -4,6 +4,11 @@
 //! - Mayastor: Block storage
 //! - SeaweedFS: File storage
 //! - RustFS: Object storage
+//!
+//! Several named instances of the same adapter kind can be registered
+//! (e.g. two Mayastor clusters); see [`BackendInstanceConfig`]. Each
+//! instance can carry its own adapter settings, falling back to the
+//! kind-wide `mayastor`/`seaweedfs`/`rustfs` sections.
 
 pub mod mayastor;
 pub mod seaweedfs;
@@ -15,6 +20,8 @@ pub use rustfs::*;
 
 use crate::domain::ports::{StorageProvisioner, StorageType};
 use crate::error::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
 use std::sync::Arc;
 
 /// Factory for creating storage backend adapters
@@ -33,6 +40,17 @@ impl BackendFactory {
         }
     }
 
+    /// Create the adapter for a named backend instance
+    ///
+    /// `config` supplies the kind-wide settings for sections the instance
+    /// does not set itself.
+    pub fn create_instance(
+        instance: &BackendInstanceConfig,
+        config: BackendConfig,
+    ) -> Result<Arc<dyn StorageProvisioner>> {
+        Self::create(&instance.kind, instance.backend_config(&config))
+    }
+
     /// Create a backend for a storage type
     pub fn for_storage_type(
         storage_type: StorageType,
@@ -47,9 +65,152 @@ impl BackendFactory {
 }
 
 /// Combined backend configuration
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "camelCase")]
 pub struct BackendConfig {
     pub mayastor: MayastorConfig,
     pub seaweedfs: SeaweedFSConfig,
     pub rustfs: RustFSConfig,
+    /// Named backend instances (empty registers one instance per adapter kind)
+    pub instances: Vec<BackendInstanceConfig>,
+}
+
+impl BackendConfig {
+    /// Instances to register: the configured ones, or the three defaults
+    pub fn instances(&self) -> Vec<BackendInstanceConfig> {
+        if !self.instances.is_empty() {
+            return self.instances.clone();
+        }
+
+        ["mayastor", "seaweedfs", "rustfs"]
+            .into_iter()
+            .map(|kind| BackendInstanceConfig::new(kind, kind))
+            .collect()
+    }
+}
+
+/// A named backend instance
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "camelCase")]
+pub struct BackendInstanceConfig {
+    /// Instance name (unique, used as the backend key)
+    pub name: String,
+    /// Adapter kind: mayastor, seaweedfs, or rustfs
+    pub kind: String,
+    /// Relative preference when several instances can serve a request
+    #[serde(default = "default_weight")]
+    pub weight: u32,
+    /// Capability tags requests can require (e.g. "nvme", "zone-a")
+    #[serde(default)]
+    pub tags: BTreeSet<String>,
+    /// Mayastor settings for this instance (default: `backends.mayastor`)
+    #[serde(default, skip_serializing)]
+    pub mayastor: Option<MayastorConfig>,
+    /// SeaweedFS settings for this instance (default: `backends.seaweedfs`)
+    #[serde(default, skip_serializing)]
+    pub seaweedfs: Option<SeaweedFSConfig>,
+    /// RustFS settings for this instance (default: `backends.rustfs`)
+    #[serde(default, skip_serializing)]
+    pub rustfs: Option<RustFSConfig>,
+}
+
+fn default_weight() -> u32 {
+    100
+}
+
+impl BackendInstanceConfig {
+    /// Create an instance with the default weight and no tags
+    pub fn new(name: impl Into<String>, kind: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            kind: kind.into(),
+            weight: default_weight(),
+            tags: BTreeSet::new(),
+            mayastor: None,
+            seaweedfs: None,
+            rustfs: None,
+        }
+    }
+
+    /// Adapter settings for this instance: its own sections where set,
+    /// the kind-wide ones from `defaults` otherwise
+    pub fn backend_config(&self, defaults: &BackendConfig) -> BackendConfig {
+        BackendConfig {
+            mayastor: self.mayastor.clone().unwrap_or_else(|| defaults.mayastor.clone()),
+            seaweedfs: self.seaweedfs.clone().unwrap_or_else(|| defaults.seaweedfs.clone()),
+            rustfs: self.rustfs.clone().unwrap_or_else(|| defaults.rustfs.clone()),
+            instances: Vec::new(),
+        }
+    }
+
+    /// Set the weight
+    pub fn with_weight(mut self, weight: u32) -> Self {
+        self.weight = weight;
+        self
+    }
+
+    /// Add a capability tag
+    pub fn with_tag(mut self, tag: impl Into<String>) -> Self {
+        self.tags.insert(tag.into());
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::ports::{ProvisionRequest, StorageTier};
+    use std::collections::BTreeMap;
+
+    fn request(name: &str) -> ProvisionRequest {
+        ProvisionRequest {
+            request_id: name.into(),
+            name: name.into(),
+            storage_type: StorageType::Block,
+            capacity_bytes: 1024 * 1024 * 1024,
+            tier: Some(StorageTier::Hot),
+            max_iops: None,
+            labels: BTreeMap::new(),
+            annotations: BTreeMap::new(),
+            tenant: None,
+            platform_params: BTreeMap::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_instances_of_one_kind_use_their_own_config() {
+        let config = BackendConfig {
+            mayastor: MayastorConfig {
+                default_replicas: 3,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let primary = BackendInstanceConfig::new("mayastor-a", "mayastor");
+        let dr = BackendInstanceConfig {
+            mayastor: Some(MayastorConfig {
+                api_endpoint: Some("http://mayastor-dr:8080".into()),
+                default_replicas: 1,
+                ..Default::default()
+            }),
+            ..BackendInstanceConfig::new("mayastor-dr", "mayastor")
+        };
+
+        // Without its own section an instance keeps the kind default
+        assert_eq!(primary.backend_config(&config).mayastor.api_endpoint, None);
+        assert_eq!(
+            dr.backend_config(&config).mayastor.api_endpoint.as_deref(),
+            Some("http://mayastor-dr:8080")
+        );
+
+        let replicas = |instance: &BackendInstanceConfig| {
+            let backend = BackendFactory::create_instance(instance, config.clone()).unwrap();
+            async move {
+                let response = backend.provision(request("vol")).await.unwrap();
+                response.platform_details["replicas"].clone()
+            }
+        };
+        assert_eq!(replicas(&primary).await, "3");
+        assert_eq!(replicas(&dr).await, "1");
+    }
 }
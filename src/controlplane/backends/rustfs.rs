@@ -2,7 +2,10 @@
 //!
 //! Provides S3-compatible object storage provisioning via RustFS.
 
-use crate::domain::ports::{ProvisionRequest, ProvisionResponse, StorageProvisioner, StorageType};
+use crate::domain::ports::{
+    parse_ec, BackendCapabilities, ProvisionRequest, ProvisionResponse, StorageProvisioner,
+    StorageTier, StorageType, PARAM_ENCRYPTED, PARAM_ERASURE_CODING, PARAM_VERSIONING,
+};
 use crate::error::{Error, Result};
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
@@ -15,7 +18,8 @@ use tracing::{debug, info, warn};
 // =============================================================================
 
 /// Configuration for RustFS adapter
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "camelCase")]
 pub struct RustFSConfig {
     /// RustFS API endpoint
     pub api_endpoint: String,
@@ -56,9 +60,15 @@ struct BucketState {
     capacity_bytes: u64,
     ec_policy: String,
     versioning: bool,
+    /// Default server-side encryption (SSE-S3) enabled
+    #[serde(default)]
+    encrypted: bool,
     created_at: chrono::DateTime<chrono::Utc>,
     objects_count: u64,
     used_bytes: u64,
+    /// S3 bucket tags
+    #[serde(default)]
+    tags: BTreeMap<String, String>,
 }
 
 // =============================================================================
@@ -87,6 +97,8 @@ impl RustFSAdapter {
         name: &str,
         capacity_bytes: u64,
         versioning: bool,
+        encrypted: bool,
+        ec_shards: (u32, u32),
     ) -> Result<String> {
         // Validate bucket name (S3 rules)
         if !is_valid_bucket_name(name) {
@@ -97,11 +109,11 @@ impl RustFSAdapter {
         }
 
         let bucket_id = format!("bucket-{}", generate_id());
-        let ec_policy = format!("EC:{}:{}", self.config.ec_data_shards, self.config.ec_parity_shards);
+        let ec_policy = format!("EC:{}:{}", ec_shards.0, ec_shards.1);
 
         info!(
-            "Creating RustFS bucket: {} ({} bytes, EC: {}, versioning: {})",
-            name, capacity_bytes, ec_policy, versioning
+            "Creating RustFS bucket: {} ({} bytes, EC: {}, versioning: {}, encrypted: {})",
+            name, capacity_bytes, ec_policy, versioning, encrypted
         );
 
         // In a real implementation, this would:
@@ -109,6 +121,7 @@ impl RustFSAdapter {
         // 2. Configure erasure coding policy
         // 3. Set up lifecycle rules
         // 4. Enable versioning if requested
+        // 5. Set default bucket encryption (PutBucketEncryption) if requested
 
         let state = BucketState {
             id: bucket_id.clone(),
@@ -116,9 +129,11 @@ impl RustFSAdapter {
             capacity_bytes,
             ec_policy,
             versioning,
+            encrypted,
             created_at: chrono::Utc::now(),
             objects_count: 0,
             used_bytes: 0,
+            tags: BTreeMap::new(),
         };
 
         self.buckets.write().await.insert(bucket_id.clone(), state);
@@ -185,15 +200,37 @@ impl StorageProvisioner for RustFSAdapter {
             });
         }
 
-        // Determine versioning based on tier
-        let versioning = match request.tier {
-            Some(crate::domain::ports::StorageTier::Cold) => false, // Archive doesn't need versioning
-            _ => self.config.default_versioning,
+        // An explicit request wins; otherwise determine versioning based on tier
+        let versioning = match request.platform_params.get(PARAM_VERSIONING) {
+            Some(v) => v.eq_ignore_ascii_case("true"),
+            None => match request.tier {
+                Some(StorageTier::Cold) => false, // Archive doesn't need versioning
+                _ => self.config.default_versioning,
+            },
         };
 
+        let ec_shards = request
+            .platform_params
+            .get(PARAM_ERASURE_CODING)
+            .and_then(|spec| parse_ec(spec))
+            .unwrap_or((self.config.ec_data_shards, self.config.ec_parity_shards));
+
+        let encrypted = request
+            .platform_params
+            .get(PARAM_ENCRYPTED)
+            .is_some_and(|v| v.eq_ignore_ascii_case("true"));
+
+        validate_bucket_tags(&request.labels)?;
         let bucket_id = self
-            .create_bucket(&request.name, request.capacity_bytes, versioning)
+            .create_bucket(
+                &request.name,
+                request.capacity_bytes,
+                versioning,
+                encrypted,
+                ec_shards,
+            )
             .await?;
+        self.set_labels(&bucket_id, &request.labels).await?;
 
         let state = self.get_bucket(&bucket_id).await.ok_or_else(|| {
             Error::BackendOperationFailed {
@@ -208,6 +245,7 @@ impl StorageProvisioner for RustFSAdapter {
         platform_details.insert("bucket_name".to_string(), state.name.clone());
         platform_details.insert("ec_policy".to_string(), state.ec_policy.clone());
         platform_details.insert("versioning".to_string(), state.versioning.to_string());
+        platform_details.insert("encrypted".to_string(), state.encrypted.to_string());
         platform_details.insert("endpoint".to_string(), self.config.api_endpoint.clone());
 
         Ok(ProvisionResponse {
@@ -218,6 +256,7 @@ impl StorageProvisioner for RustFSAdapter {
             pool_name: "rustfs-default".to_string(),
             primary_node: None,
             platform_details,
+            resolution: None,
         })
     }
 
@@ -225,6 +264,46 @@ impl StorageProvisioner for RustFSAdapter {
         self.delete_bucket(storage_id).await
     }
 
+    async fn resize(&self, storage_id: &str, capacity_bytes: u64) -> Result<ProvisionResponse> {
+        {
+            let mut buckets = self.buckets.write().await;
+            let state = buckets.get_mut(storage_id).ok_or_else(|| Error::ResourceNotFound {
+                kind: "Bucket".into(),
+                name: storage_id.into(),
+            })?;
+            // The bucket quota may shrink, but not below what is stored
+            if capacity_bytes < state.used_bytes {
+                return Err(Error::ApiValidation(format!(
+                    "Bucket {} holds {} bytes, cannot set quota to {}",
+                    storage_id, state.used_bytes, capacity_bytes
+                )));
+            }
+            info!("Resizing RustFS bucket {} quota to {} bytes", storage_id, capacity_bytes);
+            state.capacity_bytes = capacity_bytes;
+        }
+
+        self.get(storage_id).await?.ok_or_else(|| Error::ResourceNotFound {
+            kind: "Bucket".into(),
+            name: storage_id.into(),
+        })
+    }
+
+    async fn set_labels(&self, storage_id: &str, labels: &BTreeMap<String, String>) -> Result<()> {
+        validate_bucket_tags(labels)?;
+
+        // In a real implementation, this would call PutBucketTagging
+        match self.buckets.write().await.get_mut(storage_id) {
+            Some(state) => {
+                state.tags = labels.clone();
+                Ok(())
+            }
+            None => Err(Error::ResourceNotFound {
+                kind: "Bucket".into(),
+                name: storage_id.into(),
+            }),
+        }
+    }
+
     async fn get(&self, storage_id: &str) -> Result<Option<ProvisionResponse>> {
         let state = match self.get_bucket(storage_id).await {
             Some(s) => s,
@@ -235,6 +314,8 @@ impl StorageProvisioner for RustFSAdapter {
         platform_details.insert("backend".to_string(), "rustfs".to_string());
         platform_details.insert("bucket_name".to_string(), state.name.clone());
         platform_details.insert("ec_policy".to_string(), state.ec_policy.clone());
+        platform_details.insert("versioning".to_string(), state.versioning.to_string());
+        platform_details.insert("encrypted".to_string(), state.encrypted.to_string());
         platform_details.insert("objects_count".to_string(), state.objects_count.to_string());
         platform_details.insert("used_bytes".to_string(), state.used_bytes.to_string());
 
@@ -246,6 +327,7 @@ impl StorageProvisioner for RustFSAdapter {
             pool_name: "rustfs-default".to_string(),
             primary_node: None,
             platform_details,
+            resolution: None,
         }))
     }
 
@@ -267,12 +349,49 @@ impl StorageProvisioner for RustFSAdapter {
                 pool_name: "rustfs-default".to_string(),
                 primary_node: None,
                 platform_details,
+                resolution: None,
             });
         }
 
         Ok(responses)
     }
 
+    async fn migrate_tier(&self, storage_id: &str, tier: StorageTier) -> Result<ProvisionResponse> {
+        info!("Moving RustFS bucket {} to {}", storage_id, tier);
+
+        // In a real implementation, this would install a lifecycle transition
+        // rule moving the bucket's objects to the tier's storage class
+        let state = {
+            let mut buckets = self.buckets.write().await;
+            let state = buckets.get_mut(storage_id).ok_or_else(|| Error::ResourceNotFound {
+                kind: "Bucket".into(),
+                name: storage_id.into(),
+            })?;
+            if tier == StorageTier::Cold {
+                state.versioning = false; // Archive doesn't need versioning
+            }
+            state.clone()
+        };
+
+        let mut platform_details = BTreeMap::new();
+        platform_details.insert("backend".to_string(), "rustfs".to_string());
+        platform_details.insert("bucket_name".to_string(), state.name.clone());
+        platform_details.insert("ec_policy".to_string(), state.ec_policy.clone());
+        platform_details.insert("versioning".to_string(), state.versioning.to_string());
+        platform_details.insert("tier".to_string(), tier.to_string());
+
+        Ok(ProvisionResponse {
+            storage_id: state.id,
+            name: state.name,
+            storage_type: StorageType::Object,
+            capacity_bytes: state.capacity_bytes,
+            pool_name: "rustfs-default".to_string(),
+            primary_node: None,
+            platform_details,
+            resolution: None,
+        })
+    }
+
     async fn health_check(&self) -> Result<bool> {
         // In a real implementation, check RustFS service status
         Ok(true)
@@ -285,6 +404,36 @@ impl StorageProvisioner for RustFSAdapter {
     fn supported_types(&self) -> Vec<StorageType> {
         vec![StorageType::Object]
     }
+
+    fn capabilities(&self) -> BackendCapabilities {
+        BackendCapabilities {
+            resize: true,
+            erasure_coding: true,
+            encryption: true,
+            versioning: true,
+            tagging: true,
+            ..Default::default()
+        }
+    }
+}
+
+/// Validate labels against S3 bucket tagging limits
+fn validate_bucket_tags(tags: &BTreeMap<String, String>) -> Result<()> {
+    if tags.len() > 50 {
+        return Err(Error::ApiValidation(format!(
+            "S3 buckets allow at most 50 tags, got {}",
+            tags.len()
+        )));
+    }
+    for (key, value) in tags {
+        if key.is_empty() || key.len() > 128 || value.len() > 256 {
+            return Err(Error::ApiValidation(format!(
+                "Invalid bucket tag {}={}: keys must be 1-128 and values at most 256 characters",
+                key, value
+            )));
+        }
+    }
+    Ok(())
 }
 
 /// Validate S3-compatible bucket name
@@ -368,6 +517,8 @@ mod tests {
             tier: None,
             max_iops: None,
             labels: BTreeMap::new(),
+            annotations: BTreeMap::new(),
+            tenant: None,
             platform_params: BTreeMap::new(),
         };
 
@@ -391,6 +542,8 @@ mod tests {
             tier: None,
             max_iops: None,
             labels: BTreeMap::new(),
+            annotations: BTreeMap::new(),
+            tenant: None,
             platform_params: BTreeMap::new(),
         };
 
@@ -401,4 +554,65 @@ mod tests {
         let result = adapter.provision(request).await;
         assert!(result.is_err());
     }
+
+    #[tokio::test]
+    async fn test_bucket_tagging() {
+        let adapter = RustFSAdapter::new(RustFSConfig::default());
+
+        let request = ProvisionRequest {
+            request_id: "test-req".into(),
+            name: "tagged-bucket".into(),
+            storage_type: StorageType::Object,
+            capacity_bytes: 1024 * 1024 * 1024,
+            tier: None,
+            max_iops: None,
+            labels: BTreeMap::from([("team".to_string(), "data".to_string())]),
+            annotations: BTreeMap::new(),
+            tenant: None,
+            platform_params: BTreeMap::new(),
+        };
+        let response = adapter.provision(request).await.unwrap();
+        let tags = |adapter: &RustFSAdapter| {
+            let buckets = adapter.buckets.try_read().unwrap();
+            buckets[&response.storage_id].tags.clone()
+        };
+        assert_eq!(tags(&adapter)["team"], "data");
+
+        let relabelled = BTreeMap::from([("env".to_string(), "prod".to_string())]);
+        adapter.set_labels(&response.storage_id, &relabelled).await.unwrap();
+        assert_eq!(tags(&adapter), relabelled);
+
+        let too_many = (0..51).map(|i| (format!("k{}", i), "v".to_string())).collect();
+        assert!(adapter.set_labels(&response.storage_id, &too_many).await.is_err());
+        assert!(adapter.set_labels("missing", &relabelled).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_advertised_features_take_effect() {
+        let adapter = RustFSAdapter::new(RustFSConfig::default());
+        let request = ProvisionRequest {
+            request_id: "test-req".into(),
+            name: "featured-bucket".into(),
+            storage_type: StorageType::Object,
+            capacity_bytes: 1024 * 1024 * 1024,
+            tier: Some(StorageTier::Cold),
+            max_iops: None,
+            labels: BTreeMap::new(),
+            annotations: BTreeMap::new(),
+            tenant: None,
+            platform_params: BTreeMap::from([
+                (PARAM_ERASURE_CODING.to_string(), "8+3".to_string()),
+                (PARAM_ENCRYPTED.to_string(), "true".to_string()),
+                (PARAM_VERSIONING.to_string(), "true".to_string()),
+            ]),
+        };
+        adapter.capabilities().validate("rustfs", &request).unwrap();
+
+        let response = adapter.provision(request).await.unwrap();
+        let fetched = adapter.get(&response.storage_id).await.unwrap().unwrap();
+        assert_eq!(fetched.platform_details["ec_policy"], "EC:8:3");
+        assert_eq!(fetched.platform_details["encrypted"], "true");
+        // An explicit request wins over the cold tier's default
+        assert_eq!(fetched.platform_details["versioning"], "true");
+    }
 }
@@ -2,7 +2,10 @@
 //!
 //! Provides file storage provisioning via SeaweedFS.
 
-use crate::domain::ports::{ProvisionRequest, ProvisionResponse, StorageProvisioner, StorageType};
+use crate::domain::ports::{
+    BackendCapabilities, ProvisionRequest, ProvisionResponse, StorageProvisioner, StorageTier,
+    StorageType,
+};
 use crate::error::{Error, Result};
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
@@ -15,7 +18,8 @@ use tracing::{debug, info, warn};
 // =============================================================================
 
 /// Configuration for SeaweedFS adapter
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "camelCase")]
 pub struct SeaweedFSConfig {
     /// Master server endpoint
     pub master_endpoint: String,
@@ -131,17 +135,22 @@ impl SeaweedFSAdapter {
     async fn get_volume(&self, volume_id: &str) -> Option<FileVolumeState> {
         self.volumes.read().await.get(volume_id).cloned()
     }
+
+    /// Replication scheme used for a tier
+    fn replication_for(&self, tier: Option<StorageTier>) -> &str {
+        match tier {
+            Some(StorageTier::Hot) => "010", // 1 replica on different server
+            Some(StorageTier::Cold) => "001", // 1 replica on different rack
+            _ => &self.config.default_replication,
+        }
+    }
 }
 
 #[async_trait]
 impl StorageProvisioner for SeaweedFSAdapter {
     async fn provision(&self, request: ProvisionRequest) -> Result<ProvisionResponse> {
         // Determine replication based on tier
-        let replication = match request.tier {
-            Some(crate::domain::ports::StorageTier::Hot) => "010", // 1 replica on different server
-            Some(crate::domain::ports::StorageTier::Cold) => "001", // 1 replica on different rack
-            _ => &self.config.default_replication,
-        };
+        let replication = self.replication_for(request.tier);
 
         let volume_id = self
             .create_volume(&request.name, request.capacity_bytes, replication)
@@ -169,6 +178,7 @@ impl StorageProvisioner for SeaweedFSAdapter {
             pool_name: "seaweedfs-default".to_string(),
             primary_node: None,
             platform_details,
+            resolution: None,
         })
     }
 
@@ -176,6 +186,27 @@ impl StorageProvisioner for SeaweedFSAdapter {
         self.delete_volume(storage_id).await
     }
 
+    async fn resize(&self, storage_id: &str, capacity_bytes: u64) -> Result<ProvisionResponse> {
+        // Capacity is a directory quota, so it can grow or shrink
+        match self.volumes.write().await.get_mut(storage_id) {
+            Some(state) => {
+                info!("Resizing SeaweedFS volume {} to {} bytes", storage_id, capacity_bytes);
+                state.capacity_bytes = capacity_bytes;
+            }
+            None => {
+                return Err(Error::ResourceNotFound {
+                    kind: "SeaweedFSVolume".into(),
+                    name: storage_id.into(),
+                })
+            }
+        }
+
+        self.get(storage_id).await?.ok_or_else(|| Error::ResourceNotFound {
+            kind: "SeaweedFSVolume".into(),
+            name: storage_id.into(),
+        })
+    }
+
     async fn get(&self, storage_id: &str) -> Result<Option<ProvisionResponse>> {
         let state = match self.get_volume(storage_id).await {
             Some(s) => s,
@@ -195,6 +226,7 @@ impl StorageProvisioner for SeaweedFSAdapter {
             pool_name: "seaweedfs-default".to_string(),
             primary_node: None,
             platform_details,
+            resolution: None,
         }))
     }
 
@@ -215,12 +247,51 @@ impl StorageProvisioner for SeaweedFSAdapter {
                 pool_name: "seaweedfs-default".to_string(),
                 primary_node: None,
                 platform_details,
+                resolution: None,
             });
         }
 
         Ok(responses)
     }
 
+    async fn migrate_tier(&self, storage_id: &str, tier: StorageTier) -> Result<ProvisionResponse> {
+        let replication = self.replication_for(Some(tier)).to_string();
+
+        info!(
+            "Moving SeaweedFS volume {} to {} (replication {})",
+            storage_id, tier, replication
+        );
+
+        // In a real implementation, this would update the collection's
+        // replication and let volume.fix.replication rebalance the data
+        let state = {
+            let mut volumes = self.volumes.write().await;
+            let state = volumes.get_mut(storage_id).ok_or_else(|| Error::ResourceNotFound {
+                kind: "SeaweedFSVolume".into(),
+                name: storage_id.into(),
+            })?;
+            state.replication = replication;
+            state.clone()
+        };
+
+        let mut platform_details = BTreeMap::new();
+        platform_details.insert("backend".to_string(), "seaweedfs".to_string());
+        platform_details.insert("path".to_string(), state.path.clone());
+        platform_details.insert("replication".to_string(), state.replication.clone());
+        platform_details.insert("tier".to_string(), tier.to_string());
+
+        Ok(ProvisionResponse {
+            storage_id: state.id,
+            name: state.name,
+            storage_type: StorageType::File,
+            capacity_bytes: state.capacity_bytes,
+            pool_name: "seaweedfs-default".to_string(),
+            primary_node: None,
+            platform_details,
+            resolution: None,
+        })
+    }
+
     async fn health_check(&self) -> Result<bool> {
         // In a real implementation, check SeaweedFS master/filer status
         Ok(true)
@@ -233,6 +304,15 @@ impl StorageProvisioner for SeaweedFSAdapter {
     fn supported_types(&self) -> Vec<StorageType> {
         vec![StorageType::File]
     }
+
+    fn capabilities(&self) -> BackendCapabilities {
+        // Erasure coding and encryption are cluster-wide settings in
+        // SeaweedFS, not something a single volume can ask for
+        BackendCapabilities {
+            resize: true,
+            ..Default::default()
+        }
+    }
 }
 
 /// Generate a simple unique ID
@@ -261,6 +341,8 @@ mod tests {
             tier: None,
             max_iops: None,
             labels: BTreeMap::new(),
+            annotations: BTreeMap::new(),
+            tenant: None,
             platform_params: BTreeMap::new(),
         };
 
@@ -271,4 +353,26 @@ mod tests {
         assert_eq!(response.storage_type, StorageType::File);
         assert!(response.platform_details.contains_key("path"));
     }
+
+    #[test]
+    fn test_unsupported_features_are_rejected() {
+        use crate::domain::ports::{PARAM_ENCRYPTED, PARAM_ERASURE_CODING};
+
+        let caps = SeaweedFSAdapter::new(SeaweedFSConfig::default()).capabilities();
+        for (key, value) in [(PARAM_ERASURE_CODING, "4+2"), (PARAM_ENCRYPTED, "true")] {
+            let request = ProvisionRequest {
+                request_id: "test-req".into(),
+                name: "test-share".into(),
+                storage_type: StorageType::File,
+                capacity_bytes: 1024 * 1024 * 1024,
+                tier: None,
+                max_iops: None,
+                labels: BTreeMap::new(),
+                annotations: BTreeMap::new(),
+                tenant: None,
+                platform_params: BTreeMap::from([(key.to_string(), value.to_string())]),
+            };
+            assert!(caps.validate("seaweedfs", &request).is_err(), "{}", key);
+        }
+    }
 }
@@ -0,0 +1,493 @@
+//! Event Streaming
+//!
+//! Collects registry, cache and orchestrator lifecycle events into one
+//! sequence so they can be streamed out of the process (`GET /v1/events`).
+//! Every event gets an increasing ID and is kept in a bounded replay
+//! buffer, so a client reconnecting with `Last-Event-ID` receives what it
+//! missed as long as it is still buffered.
+
+use crate::domain::ports::{StorageTier, StorageType};
+use chrono::{DateTime, Utc};
+use futures::Stream;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, VecDeque};
+use std::sync::Arc;
+use tokio::sync::broadcast;
+use tokio::task::JoinHandle;
+use tracing::{debug, warn};
+
+// =============================================================================
+// Constants
+// =============================================================================
+
+/// Events kept for `Last-Event-ID` replay by default
+pub const DEFAULT_REPLAY_CAPACITY: usize = 1024;
+
+/// Capacity of the live broadcast channel to stream subscribers
+const LIVE_CHANNEL_CAPACITY: usize = 1024;
+
+// =============================================================================
+// Orchestrator Events
+// =============================================================================
+
+/// Lifecycle events emitted by the orchestrator
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum OrchestratorEvent {
+    /// Storage was provisioned
+    StorageProvisioned {
+        storage_id: String,
+        name: String,
+        storage_type: StorageType,
+        tier: Option<StorageTier>,
+        backend: String,
+    },
+
+    /// Storage was deleted
+    StorageDeleted { storage_id: String },
+
+    /// Storage capacity changed (resize or drift correction)
+    StorageResized {
+        storage_id: String,
+        capacity_bytes: u64,
+    },
+
+    /// Storage moved to another tier
+    StorageMigrated {
+        storage_id: String,
+        from_tier: Option<StorageTier>,
+        to_tier: Option<StorageTier>,
+    },
+
+    /// Labels or annotations changed
+    StorageMetadataUpdated { storage_id: String },
+
+    /// Existing storage started being tracked (import or adoption)
+    StorageImported { storage_id: String, backend: String },
+
+    /// Storage disappeared from its backend
+    StorageLost { storage_id: String },
+
+    /// An operation on a resource failed or was denied
+    OperationFailed {
+        action: String,
+        resource: String,
+        reason: String,
+    },
+
+    /// Drives on a node were classified
+    DrivesClassified { node_id: String, drive_count: usize },
+
+    /// A pool's backend became unhealthy
+    PoolDegraded {
+        pool: String,
+        backend: String,
+        reason: String,
+    },
+
+    /// All backends serving a pool are healthy again
+    PoolRecovered { pool: String, backend: String },
+}
+
+impl OrchestratorEvent {
+    /// Node the event concerns, if any
+    pub fn node_id(&self) -> Option<&str> {
+        match self {
+            OrchestratorEvent::DrivesClassified { node_id, .. } => Some(node_id),
+            _ => None,
+        }
+    }
+}
+
+// =============================================================================
+// Stream Events
+// =============================================================================
+
+/// Component an event came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum EventKind {
+    Registry,
+    Cache,
+    Orchestrator,
+}
+
+impl std::fmt::Display for EventKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EventKind::Registry => write!(f, "registry"),
+            EventKind::Cache => write!(f, "cache"),
+            EventKind::Orchestrator => write!(f, "orchestrator"),
+        }
+    }
+}
+
+/// An event as streamed to clients
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StreamEvent {
+    /// Position in the stream (used as the SSE event ID)
+    pub id: u64,
+    pub kind: EventKind,
+    /// Event variant, e.g. `NodeWentOffline`
+    pub event_type: String,
+    /// Node the event concerns, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub node: Option<String>,
+    pub timestamp: DateTime<Utc>,
+    /// Event fields
+    pub data: serde_json::Value,
+}
+
+/// Which events a subscriber wants
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EventFilter {
+    /// Event kinds (`registry`, `cache`, `orchestrator`) or event types
+    /// (`NodeWentOffline`); empty means all
+    pub kinds: BTreeSet<String>,
+    /// Only events concerning this node
+    pub node: Option<String>,
+}
+
+impl EventFilter {
+    /// Parse a comma-separated kind list and an optional node
+    pub fn new(kinds: Option<&str>, node: Option<String>) -> Self {
+        Self {
+            kinds: kinds
+                .unwrap_or_default()
+                .split(',')
+                .map(str::trim)
+                .filter(|k| !k.is_empty())
+                .map(str::to_string)
+                .collect(),
+            node,
+        }
+    }
+
+    pub fn matches(&self, event: &StreamEvent) -> bool {
+        (self.kinds.is_empty()
+            || self.kinds.contains(&event.kind.to_string())
+            || self.kinds.contains(&event.event_type))
+            && self
+                .node
+                .as_deref()
+                .is_none_or(|node| event.node.as_deref() == Some(node))
+    }
+}
+
+/// Item delivered to a stream subscriber
+#[derive(Debug, Clone, PartialEq)]
+pub enum StreamItem {
+    Event(StreamEvent),
+    /// Events were dropped (replay buffer overrun or slow consumer)
+    Gap { reason: String },
+}
+
+// =============================================================================
+// Event Hub
+// =============================================================================
+
+struct HubState {
+    next_id: u64,
+    buffer: VecDeque<StreamEvent>,
+}
+
+/// Sequences events from all sources and fans them out to subscribers
+pub struct EventHub {
+    state: Mutex<HubState>,
+    capacity: usize,
+    live: broadcast::Sender<StreamEvent>,
+}
+
+impl EventHub {
+    /// Create a hub keeping the last `capacity` events for replay (0 keeps
+    /// none, so reconnecting clients only get a gap and live events)
+    pub fn new(capacity: usize) -> Arc<Self> {
+        let (live, _) = broadcast::channel(LIVE_CHANNEL_CAPACITY);
+        Arc::new(Self {
+            state: Mutex::new(HubState {
+                next_id: 1,
+                buffer: VecDeque::with_capacity(capacity),
+            }),
+            capacity,
+            live,
+        })
+    }
+
+    /// Assign an ID to an event, buffer it and send it to live subscribers
+    ///
+    /// `payload` is an externally tagged enum (`{"Variant": {...}}`); the
+    /// variant name becomes the event type.
+    pub fn publish<E: Serialize>(&self, kind: EventKind, node: Option<String>, payload: &E) {
+        let (event_type, data) = match serde_json::to_value(payload) {
+            Ok(serde_json::Value::Object(map)) if map.len() == 1 => {
+                let (variant, fields) = map.into_iter().next().unwrap_or_default();
+                (variant, fields)
+            }
+            Ok(serde_json::Value::String(variant)) => (variant, serde_json::Value::Null),
+            Ok(other) => ("Event".to_string(), other),
+            Err(e) => {
+                warn!("Dropping unserializable {} event: {}", kind, e);
+                return;
+            }
+        };
+
+        let mut state = self.state.lock();
+        let event = StreamEvent {
+            id: state.next_id,
+            kind,
+            event_type,
+            node,
+            timestamp: Utc::now(),
+            data,
+        };
+        state.next_id += 1;
+        if self.capacity > 0 {
+            if state.buffer.len() == self.capacity {
+                state.buffer.pop_front();
+            }
+            state.buffer.push_back(event.clone());
+        }
+        // Sent under the lock so subscribers never see a gap between
+        // their replay and live events
+        let _ = self.live.send(event);
+    }
+
+    /// Forward every event from a broadcast receiver until it closes
+    pub fn attach<E, F>(
+        self: &Arc<Self>,
+        kind: EventKind,
+        mut receiver: broadcast::Receiver<E>,
+        node_of: F,
+    ) -> JoinHandle<()>
+    where
+        E: Serialize + Clone + Send + 'static,
+        F: Fn(&E) -> Option<String> + Send + 'static,
+    {
+        let hub = self.clone();
+        tokio::spawn(async move {
+            loop {
+                match receiver.recv().await {
+                    Ok(event) => hub.publish(kind, node_of(&event), &event),
+                    Err(broadcast::error::RecvError::Lagged(missed)) => {
+                        warn!("Event hub fell behind on {} events; {} dropped", kind, missed)
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break,
+                }
+            }
+            debug!("{} event source closed", kind);
+        })
+    }
+
+    /// ID of the newest event (0 if none yet)
+    pub fn last_id(&self) -> u64 {
+        self.state.lock().next_id - 1
+    }
+
+    /// Subscribe to events after `last_event_id` (None for live events only)
+    pub fn subscribe(&self, last_event_id: Option<u64>, filter: EventFilter) -> Subscription {
+        let state = self.state.lock();
+        let live = self.live.subscribe();
+        let mut pending = VecDeque::new();
+
+        if let Some(after) = last_event_id {
+            let oldest = state.buffer.front().map_or(state.next_id, |e| e.id);
+            if after >= state.next_id {
+                pending.push_back(StreamItem::Gap {
+                    reason: format!("unknown event ID {}; replaying buffered events", after),
+                });
+                pending.extend(state.buffer.iter().cloned().map(StreamItem::Event));
+            } else {
+                if after + 1 < oldest {
+                    pending.push_back(StreamItem::Gap {
+                        reason: format!("events {} to {} are no longer buffered", after + 1, oldest - 1),
+                    });
+                }
+                pending.extend(
+                    state
+                        .buffer
+                        .iter()
+                        .filter(|e| e.id > after)
+                        .cloned()
+                        .map(StreamItem::Event),
+                );
+            }
+        }
+
+        Subscription {
+            pending,
+            live,
+            filter,
+        }
+    }
+}
+
+/// Replayed and live events for one subscriber
+pub struct Subscription {
+    pending: VecDeque<StreamItem>,
+    live: broadcast::Receiver<StreamEvent>,
+    filter: EventFilter,
+}
+
+impl Subscription {
+    /// Next item matching the filter; None once the hub is gone
+    pub async fn next(&mut self) -> Option<StreamItem> {
+        loop {
+            let item = match self.pending.pop_front() {
+                Some(item) => item,
+                None => match self.live.recv().await {
+                    Ok(event) => StreamItem::Event(event),
+                    Err(broadcast::error::RecvError::Lagged(missed)) => StreamItem::Gap {
+                        reason: format!("subscriber fell behind; {} events dropped", missed),
+                    },
+                    Err(broadcast::error::RecvError::Closed) => return None,
+                },
+            };
+
+            match &item {
+                StreamItem::Event(event) if !self.filter.matches(event) => continue,
+                _ => return Some(item),
+            }
+        }
+    }
+
+    /// The subscription as a stream
+    pub fn into_stream(self) -> impl Stream<Item = StreamItem> + Send {
+        futures::stream::unfold(self, |mut subscription| async move {
+            subscription.next().await.map(|item| (item, subscription))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hardware::registry::RegistryEvent;
+
+    fn offline(node: &str) -> RegistryEvent {
+        RegistryEvent::NodeWentOffline {
+            node_id: node.into(),
+        }
+    }
+
+    fn event_ids(items: &[StreamItem]) -> Vec<u64> {
+        items
+            .iter()
+            .filter_map(|i| match i {
+                StreamItem::Event(e) => Some(e.id),
+                StreamItem::Gap { .. } => None,
+            })
+            .collect()
+    }
+
+    async fn drain(subscription: &mut Subscription, count: usize) -> Vec<StreamItem> {
+        let mut items = Vec::new();
+        for _ in 0..count {
+            items.push(subscription.next().await.unwrap());
+        }
+        items
+    }
+
+    #[tokio::test]
+    async fn test_replay_after_last_event_id() {
+        let hub = EventHub::new(3);
+        for node in ["n1", "n2", "n3", "n4"] {
+            hub.publish(EventKind::Registry, Some(node.into()), &offline(node));
+        }
+        assert_eq!(hub.last_id(), 4);
+
+        // Still buffered: replay 3 and 4, then live
+        let mut subscription = hub.subscribe(Some(2), EventFilter::default());
+        hub.publish(EventKind::Registry, Some("n5".into()), &offline("n5"));
+        let items = drain(&mut subscription, 3).await;
+        assert_eq!(event_ids(&items), vec![3, 4, 5]);
+        match &items[0] {
+            StreamItem::Event(e) => {
+                assert_eq!(e.event_type, "NodeWentOffline");
+                assert_eq!(e.data["node_id"], "n3");
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+
+        // Event 1 fell out of the buffer
+        let mut subscription = hub.subscribe(Some(0), EventFilter::default());
+        let items = drain(&mut subscription, 4).await;
+        assert!(matches!(items[0], StreamItem::Gap { .. }));
+        assert_eq!(event_ids(&items), vec![3, 4, 5]);
+    }
+
+    #[tokio::test]
+    async fn test_zero_capacity_buffers_nothing() {
+        let hub = EventHub::new(0);
+        for node in ["n1", "n2"] {
+            hub.publish(EventKind::Registry, Some(node.into()), &offline(node));
+        }
+        assert_eq!(hub.last_id(), 2);
+        assert!(hub.state.lock().buffer.is_empty());
+
+        let mut subscription = hub.subscribe(Some(1), EventFilter::default());
+        hub.publish(EventKind::Registry, Some("n3".into()), &offline("n3"));
+        let items = drain(&mut subscription, 2).await;
+        assert!(matches!(items[0], StreamItem::Gap { .. }));
+        assert_eq!(event_ids(&items), vec![3]);
+    }
+
+    #[tokio::test]
+    async fn test_attached_cache_publishes_cache_events() {
+        use crate::cache::{CacheKey, MultiTierCache, MultiTierCacheConfig, StorageCache};
+
+        let tmp = tempfile::TempDir::new().unwrap();
+        let cache = MultiTierCache::with_config(MultiTierCacheConfig {
+            l2_path: Some(tmp.path().to_string_lossy().to_string()),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+        let hub = EventHub::new(16);
+        hub.attach(EventKind::Cache, cache.subscribe(), |_| None);
+        let mut subscription = hub.subscribe(None, EventFilter::new(Some("cache"), None));
+
+        let key = CacheKey::new("volumes", "vol-1/block-0");
+        cache.put(key, bytes::Bytes::from_static(b"data")).await.unwrap();
+        match subscription.next().await.unwrap() {
+            StreamItem::Event(e) => {
+                assert_eq!(e.kind, EventKind::Cache);
+                assert_eq!(e.event_type, "Put");
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_filter_by_kind_and_node() {
+        let hub = EventHub::new(16);
+        let registry = broadcast::channel::<RegistryEvent>(16);
+        hub.attach(EventKind::Registry, registry.0.subscribe(), |e| Some(e.node_id().to_string()));
+
+        let mut by_node = hub.subscribe(None, EventFilter::new(None, Some("n2".into())));
+        let mut by_type = hub.subscribe(None, EventFilter::new(Some("StorageDeleted"), None));
+
+        registry.0.send(offline("n1")).unwrap();
+        registry.0.send(offline("n2")).unwrap();
+        tokio::task::yield_now().await;
+        hub.publish(
+            EventKind::Orchestrator,
+            None,
+            &OrchestratorEvent::StorageDeleted {
+                storage_id: "vol-1".into(),
+            },
+        );
+
+        match by_node.next().await.unwrap() {
+            StreamItem::Event(e) => assert_eq!(e.node.as_deref(), Some("n2")),
+            other => panic!("unexpected {:?}", other),
+        }
+        match by_type.next().await.unwrap() {
+            StreamItem::Event(e) => {
+                assert_eq!(e.kind, EventKind::Orchestrator);
+                assert_eq!(e.data["storage_id"], "vol-1");
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,414 @@
+//! Storage Import
+//!
+//! Brings pre-existing volumes, buckets and shares under management. Each
+//! registered backend (`StorageProvisioner::list()`) and platform
+//! (`PlatformAdapter::list_storage()`) is enumerated, anything without an
+//! orchestrator record becomes an import candidate with a proposed
+//! `StorageRecord`, and selected candidates are imported by inserting that
+//! record. Import never touches the data itself.
+
+use crate::controlplane::orchestrator::{Orchestrator, StorageRecord, PLATFORM_BACKEND};
+use crate::controlplane::resolver::{STORAGE_TIER_LABEL, STORAGE_TYPE_LABEL};
+use crate::domain::ports::{
+    Platform, PlatformStorage, ProvisionResponse, StorageTier, StorageType,
+};
+use crate::error::{Error, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::Arc;
+use tracing::{info, warn};
+
+// =============================================================================
+// Constants
+// =============================================================================
+
+/// Label recording where imported storage was found
+pub const IMPORTED_FROM_LABEL: &str = "storage.billyronks.io/imported-from";
+
+/// Name fragments that hint at a tier, checked in order
+const TIER_HINTS: [(StorageTier, &[&str]); 3] = [
+    (StorageTier::Hot, &["hot", "nvme", "high-iops", "high-performance", "premium"]),
+    (StorageTier::Warm, &["warm", "ssd", "standard", "general"]),
+    (StorageTier::Cold, &["cold", "hdd", "archive", "backup", "erasure"]),
+];
+
+// =============================================================================
+// Types
+// =============================================================================
+
+/// Where unmanaged storage was found
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum ImportSource {
+    /// A registered backend instance
+    Backend { name: String },
+    /// A platform adapter
+    Platform { platform: Platform },
+}
+
+/// Unmanaged storage with the record it would be imported as
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportCandidate {
+    /// Storage ID on the source
+    pub storage_id: String,
+    /// Where the storage was found
+    pub source: ImportSource,
+    /// Record that importing would create
+    pub proposed: StorageRecord,
+    /// How the tier, pool and labels were inferred
+    pub reasons: Vec<String>,
+}
+
+/// Which candidates to import
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportSelection {
+    /// Storage IDs to import
+    #[serde(default)]
+    pub storage_ids: Vec<String>,
+    /// Import every candidate (`storage_ids` is ignored)
+    #[serde(default)]
+    pub all: bool,
+    /// Extra labels applied to every imported record
+    #[serde(default)]
+    pub labels: BTreeMap<String, String>,
+}
+
+/// Outcome of an import
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportResult {
+    /// Records that were created
+    pub imported: Vec<StorageRecord>,
+    /// Requested storage IDs that were not imported, with the reason
+    pub skipped: BTreeMap<String, String>,
+}
+
+// =============================================================================
+// Importer
+// =============================================================================
+
+/// Discovers and imports unmanaged storage
+pub struct Importer {
+    orchestrator: Arc<Orchestrator>,
+}
+
+impl Importer {
+    /// Create an importer over the orchestrator's backends and platforms
+    pub fn new(orchestrator: Arc<Orchestrator>) -> Self {
+        Self { orchestrator }
+    }
+
+    /// List storage that exists on a backend or platform but has no record
+    ///
+    /// Sources that fail to list are skipped with a warning so one
+    /// unreachable backend does not block importing from the others.
+    pub async fn discover(&self) -> Result<Vec<ImportCandidate>> {
+        let managed: BTreeSet<String> = self
+            .orchestrator
+            .list_storage_records()
+            .await
+            .into_iter()
+            .map(|r| r.id)
+            .collect();
+        let in_flight = self.orchestrator.in_flight_storage().await?;
+        let unmanaged = |id: &str| !managed.contains(id) && !in_flight.contains(id);
+
+        let mut candidates = Vec::new();
+
+        for (backend, provisioner) in self.orchestrator.backend_provisioners().await {
+            match provisioner.list().await {
+                Ok(list) => candidates.extend(
+                    list.iter()
+                        .filter(|s| unmanaged(&s.storage_id))
+                        .map(|s| self.backend_candidate(&backend, s)),
+                ),
+                Err(e) => warn!("Import: could not list backend {}: {}", backend, e),
+            }
+        }
+
+        for adapter in self.orchestrator.platform_adapters().await {
+            let platform = adapter.platform();
+            match adapter.list_storage().await {
+                Ok(list) => candidates.extend(
+                    list.iter()
+                        .filter(|s| unmanaged(&s.storage_id))
+                        .map(|s| platform_candidate(platform, s)),
+                ),
+                Err(e) => warn!("Import: could not list platform {:?}: {}", platform, e),
+            }
+        }
+
+        candidates.sort_by(|a, b| a.storage_id.cmp(&b.storage_id));
+        Ok(candidates)
+    }
+
+    /// Import the selected candidates
+    ///
+    /// Requested IDs that are not (or no longer) candidates are reported
+    /// as skipped rather than failing the whole import.
+    pub async fn import(&self, selection: &ImportSelection) -> Result<ImportResult> {
+        if !selection.all && selection.storage_ids.is_empty() {
+            return Err(Error::ApiValidation(
+                "select storage to import with storageIds or all".into(),
+            ));
+        }
+
+        let mut candidates: BTreeMap<String, ImportCandidate> = self
+            .discover()
+            .await?
+            .into_iter()
+            .map(|c| (c.storage_id.clone(), c))
+            .collect();
+
+        let selected: Vec<String> = if selection.all {
+            candidates.keys().cloned().collect()
+        } else {
+            selection.storage_ids.clone()
+        };
+
+        let mut result = ImportResult::default();
+        for id in selected {
+            let Some(candidate) = candidates.remove(&id) else {
+                result.skipped.insert(id, "not an unmanaged storage candidate".into());
+                continue;
+            };
+
+            let mut record = candidate.proposed;
+            record.labels.extend(selection.labels.clone());
+            match self.orchestrator.import_storage(record).await {
+                Ok(record) => result.imported.push(record),
+                Err(e) => {
+                    result.skipped.insert(id, e.to_string());
+                }
+            }
+        }
+
+        info!(
+            "Imported {} storage resources ({} skipped)",
+            result.imported.len(),
+            result.skipped.len()
+        );
+        Ok(result)
+    }
+
+    /// Candidate for storage found on a backend instance
+    pub fn backend_candidate(&self, backend: &str, storage: &ProvisionResponse) -> ImportCandidate {
+        let mut reasons = vec![format!("pool {} reported by backend", storage.pool_name)];
+        let tier = infer_tier(
+            storage.platform_details.get("tier").map(String::as_str),
+            &storage.pool_name,
+            &mut reasons,
+        );
+
+        let record = StorageRecord {
+            id: storage.storage_id.clone(),
+            name: storage.name.clone(),
+            storage_type: storage.storage_type,
+            capacity_bytes: storage.capacity_bytes,
+            backend: backend.to_string(),
+            pool_name: storage.pool_name.clone(),
+            tier,
+            platform: self.orchestrator.default_platform(),
+            storage_class: None,
+            allocations: Vec::new(),
+            lost: false,
+            labels: import_labels(backend, storage.storage_type, tier),
+            annotations: BTreeMap::new(),
+            tenant: None,
+            created_at: chrono::Utc::now(),
+        };
+
+        ImportCandidate {
+            storage_id: record.id.clone(),
+            source: ImportSource::Backend { name: backend.to_string() },
+            proposed: record,
+            reasons,
+        }
+    }
+}
+
+/// Candidate for storage found on a platform
+///
+/// The platform serves the storage itself, so the record's backend is
+/// `PLATFORM_BACKEND` and its pool is the platform's class or volume type.
+/// The class is not recorded as `storage_class`, which would delete the
+/// shared class along with the storage.
+fn platform_candidate(platform: Platform, storage: &PlatformStorage) -> ImportCandidate {
+    let mut reasons = vec![format!("pool {} is the platform storage class", storage.storage_class)];
+    let tier = infer_tier(
+        storage.details.get("tier").map(String::as_str),
+        &storage.storage_class,
+        &mut reasons,
+    );
+    let source = format!("{:?}", platform).to_lowercase();
+
+    let record = StorageRecord {
+        id: storage.storage_id.clone(),
+        name: storage.name.clone(),
+        storage_type: storage.storage_type,
+        capacity_bytes: storage.capacity_bytes,
+        backend: PLATFORM_BACKEND.to_string(),
+        pool_name: storage.storage_class.clone(),
+        tier,
+        platform,
+        storage_class: None,
+        allocations: Vec::new(),
+        lost: false,
+        labels: import_labels(&source, storage.storage_type, tier),
+        annotations: BTreeMap::new(),
+        tenant: None,
+        created_at: chrono::Utc::now(),
+    };
+
+    ImportCandidate {
+        storage_id: record.id.clone(),
+        source: ImportSource::Platform { platform },
+        proposed: record,
+        reasons,
+    }
+}
+
+/// Infer a tier from an explicit detail, falling back to name hints
+fn infer_tier(detail: Option<&str>, name: &str, reasons: &mut Vec<String>) -> Option<StorageTier> {
+    if let Some(tier) = detail.and_then(|t| t.parse::<StorageTier>().ok()) {
+        reasons.push(format!("tier {} reported by source", tier));
+        return Some(tier);
+    }
+
+    let lower = name.to_lowercase();
+    for (tier, hints) in TIER_HINTS {
+        if let Some(hint) = hints.iter().find(|h| lower.contains(*h)) {
+            reasons.push(format!("tier {} inferred from '{}' in {}", tier, hint, name));
+            return Some(tier);
+        }
+    }
+
+    reasons.push(format!("no tier hint in {}; backend default assumed", name));
+    None
+}
+
+/// Labels stamped on every imported record
+fn import_labels(
+    source: &str,
+    storage_type: StorageType,
+    tier: Option<StorageTier>,
+) -> BTreeMap<String, String> {
+    let mut labels = BTreeMap::from([
+        (IMPORTED_FROM_LABEL.to_string(), source.to_string()),
+        (STORAGE_TYPE_LABEL.to_string(), storage_type.to_string()),
+    ]);
+    if let Some(tier) = tier {
+        labels.insert(STORAGE_TIER_LABEL.to_string(), tier.to_string());
+    }
+    labels
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::controlplane::orchestrator::OrchestratorConfig;
+    use crate::domain::ports::ProvisionRequest;
+    use crate::hardware::registry::NodeRegistry;
+
+    fn request(name: &str, storage_type: StorageType) -> ProvisionRequest {
+        ProvisionRequest {
+            request_id: format!("req-{}", name),
+            name: name.into(),
+            storage_type,
+            capacity_bytes: 10 * 1024 * 1024 * 1024,
+            tier: Some(StorageTier::Hot),
+            max_iops: None,
+            labels: BTreeMap::new(),
+            annotations: BTreeMap::new(),
+            tenant: None,
+            platform_params: BTreeMap::new(),
+        }
+    }
+
+    async fn setup() -> Arc<Orchestrator> {
+        let orchestrator = Orchestrator::new(OrchestratorConfig::default(), NodeRegistry::new());
+        orchestrator.initialize().await.unwrap();
+        orchestrator
+    }
+
+    #[tokio::test]
+    async fn test_discover_candidates() {
+        let orchestrator = setup().await;
+        let mayastor = orchestrator
+            .backend_provisioners()
+            .await
+            .into_iter()
+            .find(|(name, _)| name == "mayastor")
+            .unwrap()
+            .1;
+        let harvester = orchestrator
+            .platform_adapters()
+            .await
+            .into_iter()
+            .find(|a| a.platform() == Platform::Harvester)
+            .unwrap();
+
+        orchestrator.provision(request("managed", StorageType::Block)).await.unwrap();
+        let volume = mayastor.provision(request("legacy", StorageType::Block)).await.unwrap();
+        let pvc = harvester
+            .provision("legacy-pvc", StorageType::Block, 1 << 30, "longhorn-ssd")
+            .await
+            .unwrap();
+
+        let candidates = Importer::new(orchestrator.clone()).discover().await.unwrap();
+        assert_eq!(candidates.len(), 2);
+
+        let from_backend = candidates.iter().find(|c| c.storage_id == volume.storage_id).unwrap();
+        assert_eq!(from_backend.source, ImportSource::Backend { name: "mayastor".into() });
+        assert_eq!(from_backend.proposed.tier, Some(StorageTier::Hot));
+        assert_eq!(from_backend.proposed.pool_name, volume.pool_name);
+        assert_eq!(from_backend.proposed.labels[IMPORTED_FROM_LABEL], "mayastor");
+
+        let from_platform = candidates.iter().find(|c| c.storage_id == pvc).unwrap();
+        assert_eq!(from_platform.source, ImportSource::Platform { platform: Platform::Harvester });
+        assert_eq!(from_platform.proposed.backend, PLATFORM_BACKEND);
+        assert_eq!(from_platform.proposed.tier, Some(StorageTier::Warm));
+        assert_eq!(from_platform.proposed.labels[STORAGE_TIER_LABEL], "warm");
+    }
+
+    #[tokio::test]
+    async fn test_import_selected_and_all() {
+        let orchestrator = setup().await;
+        let rustfs = orchestrator
+            .backend_provisioners()
+            .await
+            .into_iter()
+            .find(|(name, _)| name == "rustfs")
+            .unwrap()
+            .1;
+        let first = rustfs.provision(request("bucket-a", StorageType::Object)).await.unwrap();
+        let second = rustfs.provision(request("bucket-b", StorageType::Object)).await.unwrap();
+        let importer = Importer::new(orchestrator.clone());
+
+        assert!(importer.import(&ImportSelection::default()).await.is_err());
+
+        let selection = ImportSelection {
+            storage_ids: vec![first.storage_id.clone(), "missing".into()],
+            labels: BTreeMap::from([("team".to_string(), "data".to_string())]),
+            ..Default::default()
+        };
+        let result = importer.import(&selection).await.unwrap();
+        assert_eq!(result.imported.len(), 1);
+        assert!(result.skipped.contains_key("missing"));
+
+        let record = orchestrator.get_storage_record(&first.storage_id).await.unwrap();
+        assert_eq!(record.backend, "rustfs");
+        assert_eq!(record.labels["team"], "data");
+
+        let all = ImportSelection { all: true, ..Default::default() };
+        let result = importer.import(&all).await.unwrap();
+        assert_eq!(result.imported.len(), 1);
+        assert_eq!(result.imported[0].id, second.storage_id);
+
+        // Importing did not touch the buckets themselves
+        assert_eq!(rustfs.list().await.unwrap().len(), 2);
+        assert!(importer.discover().await.unwrap().is_empty());
+    }
+}
@@ -0,0 +1,523 @@
+//! Storage Inventory
+//!
+//! Holds the orchestrator's `StorageRecord`s together with the indexes used
+//! to list them: a creation-time index that gives a stable `(created_at, id)`
+//! ordering for cursor pagination, and a label index that narrows
+//! label-selector queries before the remaining filters are applied. Label
+//! and annotation updates use JSON merge-patch (RFC 7386) semantics.
+
+use crate::controlplane::orchestrator::StorageRecord;
+use crate::domain::ports::{StorageTier, StorageType};
+use crate::error::{Error, Result};
+use chrono::{DateTime, Utc};
+use std::collections::{BTreeMap, BTreeSet};
+use std::ops::Bound;
+use std::str::FromStr;
+
+// =============================================================================
+// Constants
+// =============================================================================
+
+/// Page size used when a query does not set one
+pub const DEFAULT_PAGE_SIZE: usize = 100;
+
+/// Largest page a query may request
+pub const MAX_PAGE_SIZE: usize = 1000;
+
+// =============================================================================
+// Label Selector
+// =============================================================================
+
+/// A single label selector requirement
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LabelRequirement {
+    /// `key=value` or `key==value`
+    Equals(String, String),
+    /// `key!=value` (also matches when the key is absent)
+    NotEquals(String, String),
+    /// `key`
+    Exists(String),
+    /// `!key`
+    NotExists(String),
+}
+
+impl LabelRequirement {
+    fn matches(&self, labels: &BTreeMap<String, String>) -> bool {
+        match self {
+            LabelRequirement::Equals(k, v) => labels.get(k) == Some(v),
+            LabelRequirement::NotEquals(k, v) => labels.get(k) != Some(v),
+            LabelRequirement::Exists(k) => labels.contains_key(k),
+            LabelRequirement::NotExists(k) => !labels.contains_key(k),
+        }
+    }
+}
+
+/// Kubernetes-style equality-based label selector (`a=b,c!=d,e,!f`)
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LabelSelector {
+    pub requirements: Vec<LabelRequirement>,
+}
+
+impl LabelSelector {
+    /// Whether all requirements hold for `labels`
+    pub fn matches(&self, labels: &BTreeMap<String, String>) -> bool {
+        self.requirements.iter().all(|r| r.matches(labels))
+    }
+}
+
+impl FromStr for LabelSelector {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let invalid = |term: &str| {
+            Error::ApiValidation(format!("Invalid label selector term: '{}'", term))
+        };
+
+        let mut requirements = Vec::new();
+        for term in s.split(',').map(str::trim).filter(|t| !t.is_empty()) {
+            let requirement = if let Some((k, v)) = term.split_once("!=") {
+                LabelRequirement::NotEquals(k.trim().into(), v.trim().into())
+            } else if let Some((k, v)) = term.split_once("==") {
+                LabelRequirement::Equals(k.trim().into(), v.trim().into())
+            } else if let Some((k, v)) = term.split_once('=') {
+                LabelRequirement::Equals(k.trim().into(), v.trim().into())
+            } else if let Some(k) = term.strip_prefix('!') {
+                LabelRequirement::NotExists(k.trim().into())
+            } else {
+                LabelRequirement::Exists(term.into())
+            };
+
+            let key = match &requirement {
+                LabelRequirement::Equals(k, _)
+                | LabelRequirement::NotEquals(k, _)
+                | LabelRequirement::Exists(k)
+                | LabelRequirement::NotExists(k) => k,
+            };
+            if key.is_empty() || key.contains(['=', '!', ' ']) {
+                return Err(invalid(term));
+            }
+            requirements.push(requirement);
+        }
+
+        Ok(Self { requirements })
+    }
+}
+
+/// Check a label against Kubernetes syntax: `[prefix/]name`, where the name
+/// and value are at most 63 alphanumeric, `-`, `_` or `.` characters
+pub fn validate_label(key: &str, value: &str) -> Result<()> {
+    let valid_segment = |s: &str, allow_empty: bool| {
+        (allow_empty || !s.is_empty())
+            && s.len() <= 63
+            && s.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+    };
+
+    let (prefix, name) = match key.rsplit_once('/') {
+        Some((prefix, name)) => (Some(prefix), name),
+        None => (None, key),
+    };
+    let prefix_ok = prefix.is_none_or(|p| {
+        !p.is_empty() && p.len() <= 253 && p.split('.').all(|part| valid_segment(part, false))
+    });
+
+    if !prefix_ok || !valid_segment(name, false) {
+        return Err(Error::ApiValidation(format!("Invalid label key: '{}'", key)));
+    }
+    if !valid_segment(value, true) {
+        return Err(Error::ApiValidation(format!(
+            "Invalid value for label {}: '{}'",
+            key, value
+        )));
+    }
+    Ok(())
+}
+
+// =============================================================================
+// Merge Patch
+// =============================================================================
+
+/// Apply a JSON merge-patch (RFC 7386) to `target`
+pub fn merge_patch(target: &mut serde_json::Value, patch: &serde_json::Value) {
+    let serde_json::Value::Object(patch) = patch else {
+        *target = patch.clone();
+        return;
+    };
+    if !target.is_object() {
+        *target = serde_json::Value::Object(Default::default());
+    }
+    let target = target.as_object_mut().expect("target is an object");
+
+    for (key, value) in patch {
+        if value.is_null() {
+            target.remove(key);
+        } else {
+            merge_patch(target.entry(key.clone()).or_insert(serde_json::Value::Null), value);
+        }
+    }
+}
+
+/// Labels and annotations after applying a merge-patch document of the form
+/// `{"labels": {...}, "annotations": {...}}`
+pub fn patch_metadata(
+    labels: &BTreeMap<String, String>,
+    annotations: &BTreeMap<String, String>,
+    patch: &serde_json::Value,
+) -> Result<(BTreeMap<String, String>, BTreeMap<String, String>)> {
+    let Some(fields) = patch.as_object() else {
+        return Err(Error::ApiValidation("metadata patch must be a JSON object".into()));
+    };
+    if let Some(field) = fields.keys().find(|k| *k != "labels" && *k != "annotations") {
+        return Err(Error::ApiValidation(format!(
+            "Unknown metadata field '{}': only labels and annotations can be patched",
+            field
+        )));
+    }
+
+    let mut document = serde_json::json!({ "labels": labels, "annotations": annotations });
+    merge_patch(&mut document, patch);
+
+    let take = |field: &str| -> Result<BTreeMap<String, String>> {
+        match document.get(field) {
+            None | Some(serde_json::Value::Null) => Ok(BTreeMap::new()),
+            Some(value) => serde_json::from_value(value.clone()).map_err(|_| {
+                Error::ApiValidation(format!("{} must map strings to strings", field))
+            }),
+        }
+    };
+    let labels = take("labels")?;
+    for (key, value) in &labels {
+        validate_label(key, value)?;
+    }
+
+    Ok((labels, take("annotations")?))
+}
+
+// =============================================================================
+// Query
+// =============================================================================
+
+/// Filters and paging for listing storage
+#[derive(Debug, Clone, Default)]
+pub struct StorageQuery {
+    pub storage_type: Option<StorageType>,
+    pub tier: Option<StorageTier>,
+    pub backend: Option<String>,
+    pub pool: Option<String>,
+    pub selector: LabelSelector,
+    pub name_prefix: Option<String>,
+    /// Only records created at or after this time
+    pub created_after: Option<DateTime<Utc>>,
+    /// Only records created before this time
+    pub created_before: Option<DateTime<Utc>>,
+    /// Opaque cursor from a previous page
+    pub cursor: Option<String>,
+    /// Page size (defaults to `DEFAULT_PAGE_SIZE`, capped at `MAX_PAGE_SIZE`)
+    pub limit: Option<usize>,
+}
+
+impl StorageQuery {
+    fn matches(&self, record: &StorageRecord) -> bool {
+        self.storage_type.is_none_or(|t| record.storage_type == t)
+            && self.tier.is_none_or(|t| record.tier == Some(t))
+            && self.backend.as_ref().is_none_or(|b| &record.backend == b)
+            && self.pool.as_ref().is_none_or(|p| &record.pool_name == p)
+            && self.name_prefix.as_ref().is_none_or(|p| record.name.starts_with(p.as_str()))
+            && self.created_after.is_none_or(|t| record.created_at >= t)
+            && self.created_before.is_none_or(|t| record.created_at < t)
+            && self.selector.matches(&record.labels)
+    }
+}
+
+/// One page of query results
+#[derive(Debug, Clone)]
+pub struct StoragePage {
+    pub items: Vec<StorageRecord>,
+    /// Cursor for the next page (None on the last page)
+    pub next_cursor: Option<String>,
+}
+
+/// Position in the `(created_at, id)` ordering
+type SortKey = (DateTime<Utc>, String);
+
+fn encode_cursor(key: &SortKey) -> String {
+    let raw = format!("{}|{}", key.0.to_rfc3339(), key.1);
+    raw.bytes().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn decode_cursor(cursor: &str) -> Result<SortKey> {
+    let invalid = || Error::ApiValidation(format!("Invalid cursor: {}", cursor));
+
+    if !cursor.len().is_multiple_of(2) {
+        return Err(invalid());
+    }
+    let bytes = (0..cursor.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&cursor[i..i + 2], 16))
+        .collect::<std::result::Result<Vec<u8>, _>>()
+        .map_err(|_| invalid())?;
+    let raw = String::from_utf8(bytes).map_err(|_| invalid())?;
+    let (time, id) = raw.split_once('|').ok_or_else(invalid)?;
+    let time = DateTime::parse_from_rfc3339(time).map_err(|_| invalid())?;
+
+    Ok((time.with_timezone(&Utc), id.to_string()))
+}
+
+// =============================================================================
+// Inventory
+// =============================================================================
+
+/// Indexed store of storage records
+#[derive(Debug, Default)]
+pub struct StorageInventory {
+    records: BTreeMap<String, StorageRecord>,
+    by_created: BTreeSet<SortKey>,
+    /// label key -> label value -> record IDs
+    by_label: BTreeMap<String, BTreeMap<String, BTreeSet<String>>>,
+}
+
+impl StorageInventory {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get(&self, id: &str) -> Option<&StorageRecord> {
+        self.records.get(id)
+    }
+
+    /// Mutable access for fields that are not indexed
+    ///
+    /// `id`, `created_at` and `labels` must not be changed through this;
+    /// use `insert` to replace a record with different labels.
+    pub fn get_mut(&mut self, id: &str) -> Option<&mut StorageRecord> {
+        self.records.get_mut(id)
+    }
+
+    pub fn contains_key(&self, id: &str) -> bool {
+        self.records.contains_key(id)
+    }
+
+    pub fn values(&self) -> impl Iterator<Item = &StorageRecord> {
+        self.records.values()
+    }
+
+    pub fn len(&self) -> usize {
+        self.records.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+
+    /// Insert or replace a record, returning the previous one
+    pub fn insert(&mut self, record: StorageRecord) -> Option<StorageRecord> {
+        let previous = self.remove(&record.id);
+
+        self.by_created.insert((record.created_at, record.id.clone()));
+        for (key, value) in &record.labels {
+            self.by_label
+                .entry(key.clone())
+                .or_default()
+                .entry(value.clone())
+                .or_default()
+                .insert(record.id.clone());
+        }
+        self.records.insert(record.id.clone(), record);
+
+        previous
+    }
+
+    /// Remove a record and its index entries
+    pub fn remove(&mut self, id: &str) -> Option<StorageRecord> {
+        let record = self.records.remove(id)?;
+
+        self.by_created.remove(&(record.created_at, record.id.clone()));
+        for (key, value) in &record.labels {
+            if let Some(values) = self.by_label.get_mut(key) {
+                if let Some(ids) = values.get_mut(value) {
+                    ids.remove(id);
+                    if ids.is_empty() {
+                        values.remove(value);
+                    }
+                }
+                if values.is_empty() {
+                    self.by_label.remove(key);
+                }
+            }
+        }
+
+        Some(record)
+    }
+
+    /// Records matching the query, one page at a time
+    pub fn query(&self, query: &StorageQuery) -> Result<StoragePage> {
+        let limit = query.limit.unwrap_or(DEFAULT_PAGE_SIZE).clamp(1, MAX_PAGE_SIZE);
+        let start = match &query.cursor {
+            Some(cursor) => Bound::Excluded(decode_cursor(cursor)?),
+            None => Bound::Unbounded,
+        };
+        let candidates = self.label_candidates(&query.selector);
+
+        let mut items = Vec::new();
+        let mut last_key = None;
+        let mut next_cursor = None;
+        for key in self.by_created.range((start, Bound::Unbounded)) {
+            if candidates.as_ref().is_some_and(|ids| !ids.contains(&key.1)) {
+                continue;
+            }
+            let record = &self.records[&key.1];
+            if !query.matches(record) {
+                continue;
+            }
+            // A further match exists, so the page ends at the previous key
+            if items.len() == limit {
+                next_cursor = last_key.map(encode_cursor);
+                break;
+            }
+            items.push(record.clone());
+            last_key = Some(key);
+        }
+
+        Ok(StoragePage { items, next_cursor })
+    }
+
+    /// IDs allowed by the selector's equality terms (None if it has none)
+    fn label_candidates(&self, selector: &LabelSelector) -> Option<BTreeSet<String>> {
+        let mut candidates: Option<BTreeSet<String>> = None;
+
+        for requirement in &selector.requirements {
+            if let LabelRequirement::Equals(key, value) = requirement {
+                let ids = self
+                    .by_label
+                    .get(key)
+                    .and_then(|values| values.get(value))
+                    .cloned()
+                    .unwrap_or_default();
+                candidates = Some(match candidates {
+                    Some(current) => current.intersection(&ids).cloned().collect(),
+                    None => ids,
+                });
+            }
+        }
+
+        candidates
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::ports::Platform;
+    use chrono::Duration;
+
+    fn record(id: &str, minutes: i64, labels: &[(&str, &str)]) -> StorageRecord {
+        StorageRecord {
+            id: id.into(),
+            name: format!("vol-{}", id),
+            storage_type: StorageType::Block,
+            capacity_bytes: 1 << 30,
+            backend: "mayastor".into(),
+            pool_name: "pool-a".into(),
+            tier: Some(StorageTier::Hot),
+            platform: Platform::Kubernetes,
+            storage_class: None,
+            allocations: Vec::new(),
+            lost: false,
+            labels: labels.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect(),
+            annotations: BTreeMap::new(),
+            tenant: None,
+            created_at: DateTime::<Utc>::from_timestamp(1_700_000_000, 0).unwrap()
+                + Duration::minutes(minutes),
+        }
+    }
+
+    #[test]
+    fn test_label_selector_parse() {
+        let selector: LabelSelector = "app=db, env!=prod,team,!legacy".parse().unwrap();
+        assert_eq!(selector.requirements.len(), 4);
+
+        let labels = BTreeMap::from([
+            ("app".to_string(), "db".to_string()),
+            ("team".to_string(), "data".to_string()),
+        ]);
+        assert!(selector.matches(&labels));
+
+        assert!("=x".parse::<LabelSelector>().is_err());
+        assert!("".parse::<LabelSelector>().unwrap().requirements.is_empty());
+    }
+
+    #[test]
+    fn test_patch_metadata() {
+        let labels = BTreeMap::from([
+            ("app".to_string(), "db".to_string()),
+            ("env".to_string(), "dev".to_string()),
+        ]);
+        let annotations = BTreeMap::from([("owner".to_string(), "alice".to_string())]);
+
+        let patch = serde_json::json!({
+            "labels": { "env": "prod", "app": null, "example.com/team": "data" },
+        });
+        let (labels, kept) = patch_metadata(&labels, &annotations, &patch).unwrap();
+        assert_eq!(labels.len(), 2);
+        assert_eq!(labels["env"], "prod");
+        assert_eq!(labels["example.com/team"], "data");
+        assert_eq!(kept, annotations);
+
+        let cleared = patch_metadata(&labels, &annotations, &serde_json::json!({ "annotations": null }))
+            .unwrap();
+        assert!(cleared.1.is_empty());
+
+        for bad in [
+            serde_json::json!({ "labels": { "bad key": "x" } }),
+            serde_json::json!({ "labels": { "n": 1 } }),
+            serde_json::json!({ "owner": "bob" }),
+            serde_json::json!(["labels"]),
+        ] {
+            assert!(patch_metadata(&labels, &annotations, &bad).is_err());
+        }
+    }
+
+    #[test]
+    fn test_query_filters_and_pages() {
+        let mut inventory = StorageInventory::new();
+        for i in 0..5 {
+            let env = if i % 2 == 0 { "prod" } else { "dev" };
+            inventory.insert(record(&format!("r{}", i), i, &[("env", env)]));
+        }
+
+        let prod = StorageQuery {
+            selector: "env=prod".parse().unwrap(),
+            limit: Some(2),
+            ..Default::default()
+        };
+        let first = inventory.query(&prod).unwrap();
+        let ids: Vec<_> = first.items.iter().map(|r| r.id.as_str()).collect();
+        assert_eq!(ids, ["r0", "r2"]);
+
+        let second = inventory
+            .query(&StorageQuery { cursor: first.next_cursor, ..prod.clone() })
+            .unwrap();
+        assert_eq!(second.items.len(), 1);
+        assert_eq!(second.items[0].id, "r4");
+        assert!(second.next_cursor.is_none());
+
+        let base = record("r0", 0, &[]).created_at;
+        let window = StorageQuery {
+            created_after: Some(base + Duration::minutes(1)),
+            created_before: Some(base + Duration::minutes(3)),
+            selector: "env!=prod".parse().unwrap(),
+            ..Default::default()
+        };
+        let ids: Vec<_> = inventory.query(&window).unwrap().items.into_iter().map(|r| r.id).collect();
+        assert_eq!(ids, ["r1"]);
+
+        // Relabelling keeps the index consistent
+        inventory.insert(record("r1", 1, &[("env", "prod")]));
+        assert_eq!(inventory.query(&prod).unwrap().items[1].id, "r1");
+        inventory.remove("r0");
+        assert_eq!(inventory.query(&prod).unwrap().items[0].id, "r1");
+
+        assert!(inventory
+            .query(&StorageQuery { cursor: Some("zz".into()), ..Default::default() })
+            .is_err());
+    }
+}
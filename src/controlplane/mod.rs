@@ -5,10 +5,32 @@
 
 pub mod orchestrator;
 pub mod api;
+pub mod audit;
 pub mod backends;
+pub mod events;
+pub mod import;
+pub mod inventory;
 pub mod platform;
+pub mod policy;
+pub mod quota;
+pub mod reconciler;
+pub mod resolver;
+pub mod saga;
+pub mod tiering;
+pub mod webhooks;
 
 pub use orchestrator::*;
 pub use api::*;
+pub use audit::*;
 pub use backends::*;
+pub use events::*;
+pub use import::*;
+pub use inventory::*;
 pub use platform::*;
+pub use policy::*;
+pub use quota::*;
+pub use reconciler::*;
+pub use resolver::*;
+pub use saga::*;
+pub use tiering::*;
+pub use webhooks::*;
@@ -6,20 +6,35 @@
 //! - Platform adapter management
 //! - Pool lifecycle management
 
-use crate::controlplane::backends::{BackendConfig, BackendFactory};
+use crate::controlplane::backends::{BackendConfig, BackendFactory, BackendInstanceConfig};
+use crate::controlplane::inventory::{
+    patch_metadata, StorageInventory, StoragePage, StorageQuery,
+};
 use crate::controlplane::platform::{PlatformConfig, PlatformFactory};
+use crate::controlplane::audit::{AuditAction, AuditEvent, AuditLog};
+use crate::controlplane::events::OrchestratorEvent;
+use crate::controlplane::policy::{CallerIdentity, PolicyEngine};
+use crate::controlplane::quota::{QuotaCharge, QuotaManager};
+use crate::controlplane::resolver::{
+    BackendCandidate, ResolverConfig, StorageIntent, StorageResolver,
+};
+use crate::controlplane::saga::{
+    InMemorySagaStore, SagaExecutor, SagaHandler, SagaKind, SagaRecord, SagaStore,
+};
 use crate::domain::ports::{
-    Platform, PlatformAdapter, ProvisionRequest, ProvisionResponse, StorageProvisioner,
-    StorageTier, StorageType,
+    BackendCapabilities, Platform, PlatformAdapter, PlatformStorageClass, ProvisionRequest,
+    ProvisionResponse, Resolution, StorageProvisioner, StorageTier, StorageType,
 };
 use crate::error::{Error, Result};
 use crate::hardware::allocation::{AllocationPolicy, DriveAllocator};
-use crate::hardware::classification::DeviceClassifier;
+use async_trait::async_trait;
+use crate::hardware::classification::{DeviceClassifier, FingerprintDatabase, FingerprintDatabaseRef};
 use crate::hardware::registry::NodeRegistry;
 use serde::{Deserialize, Serialize};
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
-use tokio::sync::RwLock;
+use tokio::sync::{broadcast, RwLock};
 use tracing::{debug, error, info, warn};
 
 // =============================================================================
@@ -39,12 +54,29 @@ pub struct PoolInfo {
     pub utilization_percent: u32,
 }
 
+// =============================================================================
+// Backend Info
+// =============================================================================
+
+/// Information about a registered backend instance
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackendInfo {
+    pub name: String,
+    pub kind: String,
+    pub weight: u32,
+    pub tags: BTreeSet<String>,
+    pub healthy: bool,
+    pub supported_types: Vec<StorageType>,
+    pub capabilities: BackendCapabilities,
+}
+
 // =============================================================================
 // Orchestrator Configuration
 // =============================================================================
 
 /// Configuration for the orchestrator
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "camelCase")]
 pub struct OrchestratorConfig {
     /// Backend configurations
     pub backends: BackendConfig,
@@ -56,6 +88,8 @@ pub struct OrchestratorConfig {
     pub auto_classify: bool,
     /// Classification interval in seconds
     pub classify_interval_secs: u64,
+    /// Type/tier/backend resolution settings
+    pub resolver: ResolverConfig,
 }
 
 impl Default for OrchestratorConfig {
@@ -66,6 +100,7 @@ impl Default for OrchestratorConfig {
             default_platform: Platform::Kubernetes,
             auto_classify: true,
             classify_interval_secs: 300,
+            resolver: ResolverConfig::default(),
         }
     }
 }
@@ -76,17 +111,108 @@ impl Default for OrchestratorConfig {
 
 /// Record of provisioned storage
 #[derive(Debug, Clone, Serialize, Deserialize)]
-struct StorageRecord {
-    id: String,
-    name: String,
-    storage_type: StorageType,
-    capacity_bytes: u64,
-    backend: String,
-    pool_name: String,
-    platform: Platform,
-    created_at: chrono::DateTime<chrono::Utc>,
+pub struct StorageRecord {
+    pub id: String,
+    pub name: String,
+    pub storage_type: StorageType,
+    pub capacity_bytes: u64,
+    pub backend: String,
+    pub pool_name: String,
+    /// Current tier (None if the backend default was used)
+    pub tier: Option<StorageTier>,
+    pub platform: Platform,
+    /// Platform storage class created for this storage (if any)
+    #[serde(default)]
+    pub storage_class: Option<String>,
+    /// Dedicated drive allocations (if any)
+    #[serde(default)]
+    pub allocations: Vec<String>,
+    /// Set when the backend no longer reports this storage
+    #[serde(default)]
+    pub lost: bool,
+    /// User and system labels
+    #[serde(default)]
+    pub labels: BTreeMap<String, String>,
+    /// Free-form annotations (not pushed to backends)
+    #[serde(default)]
+    pub annotations: BTreeMap<String, String>,
+    /// Tenant charged for the storage (None means the default tenant)
+    #[serde(default)]
+    pub tenant: Option<String>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
 }
 
+// =============================================================================
+// Registered Backend
+// =============================================================================
+
+/// A backend instance with its selection metadata
+pub struct RegisteredBackend {
+    /// Instance configuration (name, weight, tags)
+    pub instance: BackendInstanceConfig,
+    /// Adapter serving the instance
+    pub provisioner: Arc<dyn StorageProvisioner>,
+    /// Last observed health
+    healthy: AtomicBool,
+}
+
+impl StorageRecord {
+    /// Quota charge held by this storage
+    pub fn quota_charge(&self) -> QuotaCharge {
+        QuotaCharge::new(self.tenant.as_deref(), self.tier, self.capacity_bytes, 1)
+    }
+}
+
+impl RegisteredBackend {
+    fn new(instance: BackendInstanceConfig, provisioner: Arc<dyn StorageProvisioner>) -> Self {
+        Self {
+            instance,
+            provisioner,
+            healthy: AtomicBool::new(true),
+        }
+    }
+
+    /// Whether the backend was healthy when last checked
+    pub fn is_healthy(&self) -> bool {
+        self.healthy.load(Ordering::Relaxed)
+    }
+
+    fn set_healthy(&self, healthy: bool) {
+        self.healthy.store(healthy, Ordering::Relaxed);
+    }
+}
+
+// =============================================================================
+// Saga Plans
+// =============================================================================
+
+/// `platform_params` key requesting N dedicated drives for the storage
+pub const PARAM_DEDICATED_DRIVES: &str = "dedicatedDrives";
+
+/// `platform_params` key naming the platform to create a storage class on
+pub const PARAM_PLATFORM: &str = "platform";
+
+/// `StorageRecord::backend` value for storage served by the platform itself
+pub const PLATFORM_BACKEND: &str = "platform";
+
+const PROVISION_STEPS: [&str; 5] = [
+    "reserve-quota",
+    "allocate-drives",
+    "provision-backend",
+    "create-storage-class",
+    "record",
+];
+
+const DELETE_STEPS: [&str; 5] = [
+    "detach-record",
+    "delete-storage-class",
+    "delete-backend",
+    "release-drives",
+    "release-quota",
+];
+
+const MIGRATE_STEPS: [&str; 3] = ["reserve-quota", "migrate-backend", "update-record"];
+
 // =============================================================================
 // Orchestrator
 // =============================================================================
@@ -100,33 +226,65 @@ pub struct Orchestrator {
     allocator: Arc<DriveAllocator>,
     /// Device classifier
     classifier: DeviceClassifier,
-    /// Backend adapters by name
-    backends: RwLock<BTreeMap<String, Arc<dyn StorageProvisioner>>>,
+    /// Storage type/tier/backend resolver
+    resolver: StorageResolver,
+    /// Backend instances by name
+    backends: RwLock<BTreeMap<String, Arc<RegisteredBackend>>>,
     /// Platform adapters by name
     platforms: RwLock<BTreeMap<Platform, Arc<dyn PlatformAdapter>>>,
     /// Provisioned storage records
-    storage_records: RwLock<BTreeMap<String, StorageRecord>>,
+    storage_records: RwLock<StorageInventory>,
     /// Pool records
     pools: RwLock<BTreeMap<String, PoolInfo>>,
+    /// Storage IDs with a tier migration in flight
+    migrations: RwLock<BTreeSet<String>>,
+    /// Saga executor for multi-step operations
+    sagas: SagaExecutor,
+    /// Tenant quota limits and usage
+    quotas: Arc<QuotaManager>,
+    /// Admission policies
+    policies: Arc<PolicyEngine>,
+    /// Audit trail of mutating operations
+    audit: AuditLog,
+    /// Lifecycle event broadcaster
+    event_sender: broadcast::Sender<OrchestratorEvent>,
 }
 
 impl Orchestrator {
-    /// Create a new orchestrator
+    /// Create a new orchestrator (saga progress is kept in memory)
     pub fn new(
         config: OrchestratorConfig,
         registry: Arc<NodeRegistry>,
     ) -> Arc<Self> {
-        let allocator = DriveAllocator::new(registry.clone());
+        Self::with_saga_store(config, registry, Arc::new(InMemorySagaStore::new()))
+    }
+
+    /// Create a new orchestrator that persists saga progress to `saga_store`
+    pub fn with_saga_store(
+        config: OrchestratorConfig,
+        registry: Arc<NodeRegistry>,
+        saga_store: Arc<dyn SagaStore>,
+    ) -> Arc<Self> {
+        let fingerprints = FingerprintDatabase::shared();
+        let allocator = DriveAllocator::with_fingerprints(registry.clone(), fingerprints.clone());
+        let resolver = StorageResolver::new(config.resolver.clone());
 
         Arc::new(Self {
             config,
             registry,
             allocator,
-            classifier: DeviceClassifier::new(),
+            classifier: DeviceClassifier::new().with_fingerprints(fingerprints),
+            resolver,
             backends: RwLock::new(BTreeMap::new()),
             platforms: RwLock::new(BTreeMap::new()),
-            storage_records: RwLock::new(BTreeMap::new()),
+            storage_records: RwLock::new(StorageInventory::new()),
             pools: RwLock::new(BTreeMap::new()),
+            migrations: RwLock::new(BTreeSet::new()),
+            sagas: SagaExecutor::new(saga_store),
+            quotas: Arc::new(QuotaManager::new()),
+            policies: Arc::new(PolicyEngine::new()),
+            audit: AuditLog::default(),
+            event_sender: broadcast::channel(1024).0,
         })
     }
 
@@ -135,9 +293,10 @@ impl Orchestrator {
         info!("Initializing orchestrator");
 
         // Initialize backends
-        self.register_backend("mayastor", &self.config.backends).await?;
-        self.register_backend("seaweedfs", &self.config.backends).await?;
-        self.register_backend("rustfs", &self.config.backends).await?;
+        for instance in self.config.backends.instances() {
+            let backend = BackendFactory::create_instance(&instance, self.config.backends.clone())?;
+            self.register_backend(instance, backend).await;
+        }
 
         // Initialize platforms
         self.register_platform(Platform::Harvester, &self.config.platforms).await?;
@@ -146,18 +305,42 @@ impl Orchestrator {
         // Create default pools
         self.create_default_pools().await?;
 
+        // Finish or roll back work interrupted by a restart
+        let resumed = self.resume_sagas().await?;
+        if resumed > 0 {
+            info!("Resumed {} interrupted saga(s)", resumed);
+        }
+
         info!("Orchestrator initialized successfully");
         Ok(())
     }
 
-    /// Register a storage backend
-    async fn register_backend(&self, name: &str, config: &BackendConfig) -> Result<()> {
-        info!("Registering backend: {}", name);
+    /// Register a named backend instance, replacing any with the same name
+    pub async fn register_backend(
+        &self,
+        instance: BackendInstanceConfig,
+        provisioner: Arc<dyn StorageProvisioner>,
+    ) {
+        info!(
+            "Registering backend: {} ({}, weight {})",
+            instance.name, instance.kind, instance.weight
+        );
 
-        let backend = BackendFactory::create(name, config.clone())?;
-        self.backends.write().await.insert(name.to_string(), backend);
+        let name = instance.name.clone();
+        let backend = Arc::new(RegisteredBackend::new(instance, provisioner));
+        self.backends.write().await.insert(name, backend);
+    }
 
-        Ok(())
+    /// Look up a registered backend's adapter by instance name
+    async fn backend(&self, name: &str) -> Result<Arc<dyn StorageProvisioner>> {
+        self.backends
+            .read()
+            .await
+            .get(name)
+            .map(|b| b.provisioner.clone())
+            .ok_or_else(|| Error::BackendUnavailable {
+                backend: name.to_string(),
+            })
     }
 
     /// Register a platform adapter
@@ -208,55 +391,117 @@ impl Orchestrator {
             utilization_percent: 0,
         };
 
-        let mut pools = self.pools.write().await;
-        pools.insert(hot_pool.name.clone(), hot_pool);
-        pools.insert(object_pool.name.clone(), object_pool);
-        pools.insert(file_pool.name.clone(), file_pool);
+        for pool in [hot_pool, object_pool, file_pool] {
+            let event = AuditEvent::new(AuditAction::CreatePool, format!("pool/{}", pool.name))
+                .after(&pool);
+            self.pools.write().await.insert(pool.name.clone(), pool);
+            self.audit.record(event).await;
+        }
 
         Ok(())
     }
 
     /// Provision storage
     pub async fn provision(&self, request: ProvisionRequest) -> Result<ProvisionResponse> {
-        info!(
-            "Provisioning storage: {} ({:?}, {} bytes)",
-            request.name, request.storage_type, request.capacity_bytes
-        );
+        self.provision_intent(StorageIntent::from(&request)).await
+    }
 
-        // Select backend based on storage type
-        let backend_name = match request.storage_type {
-            StorageType::Block => "mayastor",
-            StorageType::File => "seaweedfs",
-            StorageType::Object => "rustfs",
-        };
+    /// Provision storage from an intent that may leave type, tier or backend open
+    pub async fn provision_intent(&self, intent: StorageIntent) -> Result<ProvisionResponse> {
+        self.provision_intent_as(intent, &CallerIdentity::default())
+            .await
+    }
 
-        let backends = self.backends.read().await;
-        let backend = backends.get(backend_name).ok_or_else(|| {
-            Error::BackendUnavailable {
-                backend: backend_name.to_string(),
-            }
-        })?;
+    /// Provision storage from an intent on behalf of `caller`
+    pub async fn provision_intent_as(
+        &self,
+        intent: StorageIntent,
+        caller: &CallerIdentity,
+    ) -> Result<ProvisionResponse> {
+        let resolution = self.resolve(&intent).await?;
+        let request = intent.into_request(&resolution);
+        self.provision_resolved(request, resolution, caller).await
+    }
 
-        // Provision via backend
-        let response = backend.provision(request.clone()).await?;
+    /// Resolve an intent against the registered backends and pools
+    pub async fn resolve(&self, intent: &StorageIntent) -> Result<Resolution> {
+        let candidates: Vec<BackendCandidate> = self
+            .backends
+            .read()
+            .await
+            .iter()
+            .map(|(name, backend)| BackendCandidate {
+                name: name.clone(),
+                kind: backend.instance.kind.clone(),
+                supported_types: backend.provisioner.supported_types(),
+                weight: backend.instance.weight,
+                tags: backend.instance.tags.clone(),
+                capabilities: backend.provisioner.capabilities(),
+                healthy: backend.is_healthy(),
+            })
+            .collect();
+        let pools: Vec<PoolInfo> = self.pools.read().await.values().cloned().collect();
+
+        let resolution = self.resolver.resolve(intent, &candidates, &pools)?;
+        debug!("Resolved {}: {:?}", intent.name, resolution);
+        Ok(resolution)
+    }
 
-        // Record the provisioning
-        let record = StorageRecord {
-            id: response.storage_id.clone(),
-            name: response.name.clone(),
-            storage_type: request.storage_type,
-            capacity_bytes: response.capacity_bytes,
-            backend: backend_name.to_string(),
-            pool_name: response.pool_name.clone(),
-            platform: self.config.default_platform,
-            created_at: chrono::Utc::now(),
+    /// Provision a request on the backend chosen by the resolver
+    ///
+    /// `request` must already carry the resolved type and tier (see
+    /// [`StorageIntent::into_request`]) so that admission, the quota charge,
+    /// the platform storage class and the record all agree on them.
+    /// Admission policies are checked first. Provisioning then runs as a saga: dedicated
+    /// drives, backend volume, platform storage class and the orchestrator
+    /// record are each undone if a later step fails.
+    async fn provision_resolved(
+        &self,
+        request: ProvisionRequest,
+        mut resolution: Resolution,
+        caller: &CallerIdentity,
+    ) -> Result<ProvisionResponse> {
+        let name = request.name.clone();
+        let result = async {
+            let warnings = self.policies.admit(&request, caller)?;
+            resolution.warnings.extend(warnings.iter().map(|w| w.to_string()));
+
+            let mut saga = SagaRecord::new(SagaKind::Provision, &PROVISION_STEPS);
+            saga.put("request", &request)?;
+            saga.put("resolution", &resolution)?;
+
+            let saga = self.sagas.run(self, saga).await?;
+            saga.require::<ProvisionResponse>("response")
+        }
+        .await;
+
+        let event = match &result {
+            Ok(response) => {
+                let record = self.get_storage_record(&response.storage_id).await;
+                if let Some(record) = &record {
+                    self.emit(OrchestratorEvent::StorageProvisioned {
+                        storage_id: record.id.clone(),
+                        name: record.name.clone(),
+                        storage_type: record.storage_type,
+                        tier: record.tier,
+                        backend: record.backend.clone(),
+                    });
+                }
+                AuditEvent::new(AuditAction::Provision, format!("storage/{}", response.storage_id))
+                    .after(&record)
+            }
+            Err(e) => {
+                self.emit(OrchestratorEvent::OperationFailed {
+                    action: AuditAction::Provision.to_string(),
+                    resource: format!("storage/{}", name),
+                    reason: e.to_string(),
+                });
+                AuditEvent::new(AuditAction::Provision, format!("storage/{}", name)).before(&request)
+            }
         };
+        self.audit.record(event.actor(caller).outcome(&result)).await;
 
-        self.storage_records.write().await.insert(response.storage_id.clone(), record);
-
-        info!("Provisioned storage: {} -> {}", request.name, response.storage_id);
-
-        Ok(response)
+        result
     }
 
     /// Get storage by ID
@@ -270,12 +515,7 @@ impl Orchestrator {
         drop(records);
 
         // Get from backend
-        let backends = self.backends.read().await;
-        let backend = backends.get(&record.backend).ok_or_else(|| {
-            Error::BackendUnavailable {
-                backend: record.backend.clone(),
-            }
-        })?;
+        let backend = self.backend(&record.backend).await?;
 
         backend.get(storage_id).await
     }
@@ -285,31 +525,394 @@ impl Orchestrator {
         info!("Deleting storage: {}", storage_id);
 
         // Get record
-        let records = self.storage_records.read().await;
-        let record = records.get(storage_id).cloned().ok_or_else(|| {
+        let record = self.get_storage_record(storage_id).await.ok_or_else(|| {
             Error::ResourceNotFound {
                 kind: "Storage".into(),
                 name: storage_id.into(),
             }
         })?;
-        drop(records);
 
-        // Delete from backend
-        let backends = self.backends.read().await;
-        let backend = backends.get(&record.backend).ok_or_else(|| {
-            Error::BackendUnavailable {
-                backend: record.backend.clone(),
+        let result = async {
+            let mut saga = SagaRecord::new(SagaKind::Delete, &DELETE_STEPS);
+            saga.put("record", &record)?;
+            self.sagas.run(self, saga).await.map(|_| ())
+        }
+        .await;
+        self.audit_storage(AuditAction::Delete, storage_id, Some(&record), &result)
+            .await;
+        result?;
+
+        info!("Deleted storage: {}", storage_id);
+
+        Ok(())
+    }
+
+    /// Get the orchestrator's record for provisioned storage
+    pub async fn get_storage_record(&self, storage_id: &str) -> Option<StorageRecord> {
+        self.storage_records.read().await.get(storage_id).cloned()
+    }
+
+    /// List the orchestrator's records for all provisioned storage
+    pub async fn list_storage_records(&self) -> Vec<StorageRecord> {
+        self.storage_records.read().await.values().cloned().collect()
+    }
+
+    /// Apply a merge-patch to a record's labels and annotations
+    ///
+    /// Label changes are pushed to the backend or platform holding the
+    /// storage when it supports tags; the record is only updated once that
+    /// succeeds.
+    pub async fn patch_storage_metadata(
+        &self,
+        storage_id: &str,
+        patch: &serde_json::Value,
+    ) -> Result<StorageRecord> {
+        let before = self.get_storage_record(storage_id).await;
+        let result = self.apply_metadata_patch(storage_id, patch).await;
+        self.audit_storage(AuditAction::UpdateLabels, storage_id, before.as_ref(), &result)
+            .await;
+        result
+    }
+
+    async fn apply_metadata_patch(
+        &self,
+        storage_id: &str,
+        patch: &serde_json::Value,
+    ) -> Result<StorageRecord> {
+        let not_found = || Error::ResourceNotFound {
+            kind: "Storage".into(),
+            name: storage_id.into(),
+        };
+        let record = self.get_storage_record(storage_id).await.ok_or_else(not_found)?;
+        let (labels, annotations) = patch_metadata(&record.labels, &record.annotations, patch)?;
+
+        if labels != record.labels {
+            self.push_labels(&record, &labels).await?;
+        }
+
+        let mut records = self.storage_records.write().await;
+        let mut updated = records.get(storage_id).cloned().ok_or_else(not_found)?;
+        updated.labels = labels;
+        updated.annotations = annotations;
+        records.insert(updated.clone());
+
+        info!("Updated metadata of storage {}", storage_id);
+        Ok(updated)
+    }
+
+    /// List records matching `query`, one page at a time
+    pub async fn query_storage(&self, query: &StorageQuery) -> Result<StoragePage> {
+        self.storage_records.read().await.query(query)
+    }
+
+    /// Registered backend adapters by instance name
+    pub async fn backend_provisioners(&self) -> Vec<(String, Arc<dyn StorageProvisioner>)> {
+        self.backends
+            .read()
+            .await
+            .iter()
+            .map(|(name, b)| (name.clone(), b.provisioner.clone()))
+            .collect()
+    }
+
+    /// Start tracking existing storage under a prepared record
+    ///
+    /// Fails if a record with the same ID already exists. The backing
+    /// storage is not touched.
+    pub async fn import_storage(&self, record: StorageRecord) -> Result<StorageRecord> {
+        let id = record.id.clone();
+        let result = {
+            let mut records = self.storage_records.write().await;
+            if records.contains_key(&record.id) {
+                Err(Error::ResourceExists {
+                    kind: "StorageRecord".into(),
+                    name: record.id,
+                })
+            } else {
+                info!("Imported storage {} from {}", record.id, record.backend);
+                self.quotas.charge(&record.quota_charge());
+                records.insert(record.clone());
+                Ok(record)
+            }
+        };
+
+        self.audit_storage(AuditAction::Import, &id, None, &result).await;
+        result
+    }
+
+    /// Storage IDs touched by sagas that have not finished
+    pub async fn in_flight_storage(&self) -> Result<BTreeSet<String>> {
+        let mut ids = BTreeSet::new();
+
+        for saga in self.pending_sagas().await? {
+            if let Some(response) = saga.get::<ProvisionResponse>("response")? {
+                ids.insert(response.storage_id);
+            }
+            if let Some(record) = saga.get::<StorageRecord>("record")? {
+                ids.insert(record.id);
+            }
+        }
+
+        Ok(ids)
+    }
+
+    /// Platform new records default to
+    pub fn default_platform(&self) -> Platform {
+        self.config.default_platform
+    }
+
+    /// Registered platform adapters
+    pub async fn platform_adapters(&self) -> Vec<Arc<dyn PlatformAdapter>> {
+        self.platforms.read().await.values().cloned().collect()
+    }
+
+    /// Flag a record whose backend storage has disappeared
+    pub async fn mark_storage_lost(&self, storage_id: &str) -> bool {
+        let before = self.get_storage_record(storage_id).await;
+        let marked = match self.storage_records.write().await.get_mut(storage_id) {
+            Some(record) => {
+                record.lost = true;
+                true
+            }
+            None => false,
+        };
+
+        if marked {
+            self.audit_storage(AuditAction::MarkLost, storage_id, before.as_ref(), &Ok(()))
+                .await;
+        }
+        marked
+    }
+
+    /// Clear the lost flag of a record whose storage has reappeared
+    pub async fn clear_storage_lost(&self, storage_id: &str) -> bool {
+        let before = self.get_storage_record(storage_id).await;
+        let cleared = match self.storage_records.write().await.get_mut(storage_id) {
+            Some(record) if record.lost => {
+                record.lost = false;
+                true
+            }
+            _ => false,
+        };
+
+        if cleared {
+            self.audit_storage(AuditAction::ClearLost, storage_id, before.as_ref(), &Ok(()))
+                .await;
+        }
+        cleared
+    }
+
+    /// Correct the recorded capacity of provisioned storage
+    pub async fn update_storage_capacity(&self, storage_id: &str, capacity_bytes: u64) -> bool {
+        let before = self.get_storage_record(storage_id).await;
+        let updated = match self.storage_records.write().await.get_mut(storage_id) {
+            Some(record) => {
+                self.quotas.release(&record.quota_charge());
+                record.capacity_bytes = capacity_bytes;
+                self.quotas.charge(&record.quota_charge());
+                true
+            }
+            None => false,
+        };
+
+        if updated {
+            self.audit_storage(AuditAction::UpdateCapacity, storage_id, before.as_ref(), &Ok(()))
+                .await;
+        }
+        updated
+    }
+
+    /// Grow or shrink provisioned storage
+    ///
+    /// Growth is checked against the tenant's quota before the backend is
+    /// asked to resize; the reserved bytes are returned if the backend fails.
+    pub async fn resize_storage(&self, storage_id: &str, capacity_bytes: u64) -> Result<StorageRecord> {
+        let before = self.get_storage_record(storage_id).await;
+        let result = self.resize_record(storage_id, capacity_bytes).await;
+        self.audit_storage(AuditAction::Resize, storage_id, before.as_ref(), &result)
+            .await;
+        result
+    }
+
+    async fn resize_record(&self, storage_id: &str, capacity_bytes: u64) -> Result<StorageRecord> {
+        let record = self.get_storage_record(storage_id).await.ok_or_else(|| {
+            Error::ResourceNotFound {
+                kind: "Storage".into(),
+                name: storage_id.into(),
             }
         })?;
+        if record.backend == PLATFORM_BACKEND {
+            return Err(Error::ApiValidation(format!(
+                "Storage {} is served by the platform and cannot be resized here",
+                storage_id
+            )));
+        }
+        if capacity_bytes == 0 {
+            return Err(Error::ApiValidation("capacity must be greater than zero".into()));
+        }
 
-        backend.delete(storage_id).await?;
+        let growth = QuotaCharge::new(
+            record.tenant.as_deref(),
+            record.tier,
+            capacity_bytes.saturating_sub(record.capacity_bytes),
+            0,
+        );
+        if growth.bytes > 0 {
+            self.quotas.reserve(&growth, capacity_bytes)?;
+        }
 
-        // Remove record
-        self.storage_records.write().await.remove(storage_id);
+        info!(
+            "Resizing storage {} from {} to {} bytes",
+            storage_id, record.capacity_bytes, capacity_bytes
+        );
+        let response = match self.backend(&record.backend).await {
+            Ok(backend) => backend.resize(storage_id, capacity_bytes).await,
+            Err(e) => Err(e),
+        };
+        let response = match response {
+            Ok(response) => response,
+            Err(e) => {
+                self.quotas.release(&growth);
+                return Err(e);
+            }
+        };
 
-        info!("Deleted storage: {}", storage_id);
+        // Settle the charge on what the backend actually allocated. The
+        // reservation is dropped first so it cannot outlive a record that
+        // was deleted while the backend was resizing.
+        let mut records = self.storage_records.write().await;
+        self.quotas.release(&growth);
+        let stored = records.get_mut(storage_id).ok_or_else(|| Error::ResourceNotFound {
+            kind: "Storage".into(),
+            name: storage_id.into(),
+        })?;
+        self.quotas.release(&QuotaCharge { volumes: 0, ..stored.quota_charge() });
+        stored.capacity_bytes = response.capacity_bytes;
+        self.quotas.charge(&QuotaCharge { volumes: 0, ..stored.quota_charge() });
 
-        Ok(())
+        Ok(stored.clone())
+    }
+
+    /// Tenant quota limits and usage
+    pub fn quotas(&self) -> &Arc<QuotaManager> {
+        &self.quotas
+    }
+
+    /// Admission policies applied before provisioning
+    pub fn policies(&self) -> &Arc<PolicyEngine> {
+        &self.policies
+    }
+
+    /// Drive fingerprints shared by classification and allocation
+    pub fn fingerprints(&self) -> &FingerprintDatabaseRef {
+        self.classifier.fingerprints()
+    }
+
+    /// Audit trail of mutating operations
+    pub fn audit(&self) -> &AuditLog {
+        &self.audit
+    }
+
+    /// Subscribe to storage lifecycle events
+    pub fn subscribe(&self) -> broadcast::Receiver<OrchestratorEvent> {
+        self.event_sender.subscribe()
+    }
+
+    /// Send a lifecycle event to subscribers (if any)
+    fn emit(&self, event: OrchestratorEvent) {
+        let _ = self.event_sender.send(event);
+    }
+
+    /// Move provisioned storage to a different tier
+    pub async fn migrate_storage(
+        &self,
+        storage_id: &str,
+        target_tier: StorageTier,
+    ) -> Result<ProvisionResponse> {
+        let record = self.get_storage_record(storage_id).await.ok_or_else(|| {
+            Error::ResourceNotFound {
+                kind: "Storage".into(),
+                name: storage_id.into(),
+            }
+        })?;
+
+        if !self.migrations.write().await.insert(storage_id.to_string()) {
+            return Err(Error::MigrationInProgress {
+                volume_name: record.name,
+            });
+        }
+
+        info!(
+            "Migrating storage {} from {:?} to {}",
+            storage_id, record.tier, target_tier
+        );
+
+        let result = self.run_migration(&record, target_tier).await;
+        self.migrations.write().await.remove(storage_id);
+        self.audit_storage(AuditAction::Migrate, storage_id, Some(&record), &result)
+            .await;
+
+        match &result {
+            Ok(response) => info!(
+                "Migrated storage {} to {} (pool {})",
+                storage_id, target_tier, response.pool_name
+            ),
+            Err(e) => warn!("Migration of {} to {} failed: {}", storage_id, target_tier, e),
+        }
+
+        result
+    }
+
+    /// Move data via the backend and update the record
+    async fn run_migration(
+        &self,
+        record: &StorageRecord,
+        target_tier: StorageTier,
+    ) -> Result<ProvisionResponse> {
+        let mut saga = SagaRecord::new(SagaKind::Migrate, &MIGRATE_STEPS);
+        saga.put("record", record)?;
+        saga.put("targetTier", &target_tier)?;
+
+        let result = match self.sagas.run(self, saga).await {
+            Ok(saga) => saga.require("response"),
+            Err(e) => Err(e),
+        };
+
+        result.map_err(|e| match e {
+            Error::MigrationFailed { .. } | Error::SagaCompensationFailed { .. } => e,
+            other => Error::MigrationFailed {
+                volume_name: record.name.clone(),
+                reason: other.to_string(),
+            },
+        })
+    }
+
+    /// Resume provisioning, deletion and migration sagas interrupted by a restart
+    pub async fn resume_sagas(&self) -> Result<usize> {
+        self.sagas.resume_all(self).await
+    }
+
+    /// Sagas that are in flight or stuck awaiting manual intervention
+    pub async fn pending_sagas(&self) -> Result<Vec<SagaRecord>> {
+        self.sagas.pending().await
+    }
+
+    /// List registered backend instances with their capabilities
+    pub async fn list_backends(&self) -> Vec<BackendInfo> {
+        self.backends
+            .read()
+            .await
+            .values()
+            .map(|b| BackendInfo {
+                name: b.instance.name.clone(),
+                kind: b.instance.kind.clone(),
+                weight: b.instance.weight,
+                tags: b.instance.tags.clone(),
+                healthy: b.is_healthy(),
+                supported_types: b.provisioner.supported_types(),
+                capabilities: b.provisioner.capabilities(),
+            })
+            .collect()
     }
 
     /// List all pools
@@ -334,6 +937,7 @@ impl Orchestrator {
             }
         })?;
 
+        let mut classified = BTreeMap::new();
         for drive in &entry.status.drives {
             // Create a DriveInfo from DriveStatus for classification
             let drive_info = crate::domain::ports::DriveInfo {
@@ -353,6 +957,11 @@ impl Orchestrator {
                 zns_supported: drive.namespaces.iter().any(|ns| ns.is_zns),
                 nvme_namespaces: vec![],
                 smart_data: None,
+                health: None,
+                in_use: Vec::new(),
+                wwid: drive.wwid.clone(),
+                paths: drive.paths.clone(),
+                location: Default::default(),
             };
 
             let classification = self.classifier.classify(&drive_info);
@@ -363,19 +972,73 @@ impl Orchestrator {
                 classification.performance,
                 classification.confidence_percent()
             );
+            classified.insert(drive.id.clone(), format!("{:?}", classification.performance));
         }
 
+        self.emit(OrchestratorEvent::DrivesClassified {
+            node_id: node_id.to_string(),
+            drive_count: classified.len(),
+        });
+        self.audit
+            .record(
+                AuditEvent::new(AuditAction::Classify, format!("node/{}", node_id))
+                    .after(&classified),
+            )
+            .await;
+
         Ok(())
     }
 
-    /// Get backend health status
+    /// Get backend health status, refreshing the health used for selection
+    ///
+    /// Pools whose backend changes health emit `PoolDegraded` or
+    /// `PoolRecovered`.
     pub async fn backends_health(&self) -> BTreeMap<String, bool> {
-        let backends = self.backends.read().await;
+        let backends: Vec<_> = self.backends.read().await.values().cloned().collect();
         let mut health = BTreeMap::new();
+        let mut was_unhealthy = BTreeSet::new();
+        let mut unhealthy = BTreeSet::new();
 
-        for (name, backend) in backends.iter() {
-            let is_healthy = backend.health_check().await.unwrap_or(false);
-            health.insert(name.clone(), is_healthy);
+        for backend in &backends {
+            if !backend.is_healthy() {
+                was_unhealthy.insert(backend.instance.name.clone());
+            }
+            let is_healthy = backend.provisioner.health_check().await.unwrap_or(false);
+            backend.set_healthy(is_healthy);
+            if !is_healthy {
+                unhealthy.insert(backend.instance.name.clone());
+            }
+            health.insert(backend.instance.name.clone(), is_healthy);
+        }
+
+        if was_unhealthy != unhealthy {
+            // A pool names its backend by kind or instance name
+            let serving = |pool: &PoolInfo, names: &BTreeSet<String>| -> Vec<String> {
+                backends
+                    .iter()
+                    .filter(|b| b.instance.kind == pool.backend || b.instance.name == pool.backend)
+                    .filter(|b| names.contains(&b.instance.name))
+                    .map(|b| b.instance.name.clone())
+                    .collect()
+            };
+            for pool in self.pools.read().await.values() {
+                let before = serving(pool, &was_unhealthy);
+                let after = serving(pool, &unhealthy);
+                if before.is_empty() && !after.is_empty() {
+                    warn!("Pool {} degraded: backend {} unhealthy", pool.name, after.join(", "));
+                    self.emit(OrchestratorEvent::PoolDegraded {
+                        pool: pool.name.clone(),
+                        backend: pool.backend.clone(),
+                        reason: format!("backend {} failed its health check", after.join(", ")),
+                    });
+                } else if !before.is_empty() && after.is_empty() {
+                    info!("Pool {} recovered", pool.name);
+                    self.emit(OrchestratorEvent::PoolRecovered {
+                        pool: pool.name.clone(),
+                        backend: pool.backend.clone(),
+                    });
+                }
+            }
         }
 
         health
@@ -417,6 +1080,483 @@ impl Orchestrator {
     }
 }
 
+// =============================================================================
+// Saga Steps
+// =============================================================================
+
+#[async_trait]
+impl SagaHandler for Orchestrator {
+    async fn execute_step(&self, saga: &mut SagaRecord, step: &str) -> Result<()> {
+        match (saga.kind, step) {
+            (SagaKind::Provision, "reserve-quota") => {
+                if saga.get::<QuotaCharge>("quotaCharge")?.is_some() {
+                    return Ok(());
+                }
+                let request: ProvisionRequest = saga.require("request")?;
+                let charge = QuotaCharge::new(
+                    request.tenant.as_deref(),
+                    request.tier,
+                    request.capacity_bytes,
+                    1,
+                );
+                self.quotas.reserve(&charge, request.capacity_bytes)?;
+                saga.put("quotaCharge", &charge)
+            }
+            (SagaKind::Provision, "allocate-drives") => self.step_allocate_drives(saga).await,
+            (SagaKind::Provision, "provision-backend") => self.step_provision_backend(saga).await,
+            (SagaKind::Provision, "create-storage-class") => {
+                self.step_create_storage_class(saga).await
+            }
+            (SagaKind::Provision, "record") => self.step_record(saga).await,
+            (SagaKind::Delete, "detach-record") => {
+                let record: StorageRecord = saga.require("record")?;
+                self.storage_records.write().await.remove(&record.id);
+                Ok(())
+            }
+            (SagaKind::Delete, "delete-storage-class") => {
+                let record: StorageRecord = saga.require("record")?;
+                let Some(class) = &record.storage_class else {
+                    return Ok(());
+                };
+
+                // Keep the class as it is now so compensation can restore it
+                if saga.get::<PlatformStorageClass>("deletedStorageClass")?.is_none() {
+                    let adapter = self.platform_adapter(record.platform).await?;
+                    let existing = adapter.list_storage_classes().await?;
+                    if let Some(existing) = existing.into_iter().find(|c| &c.name == class) {
+                        saga.put("deletedStorageClass", &existing)?;
+                    }
+                }
+                self.delete_storage_class(record.platform, class).await
+            }
+            (SagaKind::Delete, "delete-backend") => {
+                let record: StorageRecord = saga.require("record")?;
+                let result = if record.backend == PLATFORM_BACKEND {
+                    let adapter = self.platform_adapter(record.platform).await?;
+                    adapter.delete_storage(&record.id).await
+                } else {
+                    self.backend(&record.backend).await?.delete(&record.id).await
+                };
+                match result {
+                    // Already gone (e.g. the step is re-run after a restart)
+                    Ok(()) | Err(Error::ResourceNotFound { .. }) => Ok(()),
+                    Err(e) => Err(e),
+                }
+            }
+            (SagaKind::Delete, "release-drives") => {
+                let record: StorageRecord = saga.require("record")?;
+                self.release_drives(&record.allocations).await;
+                Ok(())
+            }
+            (SagaKind::Delete, "release-quota") => {
+                let record: StorageRecord = saga.require("record")?;
+                self.quotas.release(&record.quota_charge());
+                Ok(())
+            }
+            (SagaKind::Migrate, "reserve-quota") => {
+                let record: StorageRecord = saga.require("record")?;
+                let target_tier: StorageTier = saga.require("targetTier")?;
+                self.quotas.retier(&record.quota_charge(), Some(target_tier))
+            }
+            (SagaKind::Migrate, "migrate-backend") => {
+                let record: StorageRecord = saga.require("record")?;
+                let target_tier: StorageTier = saga.require("targetTier")?;
+                let backend = self.backend(&record.backend).await?;
+                let response = backend.migrate_tier(&record.id, target_tier).await?;
+                saga.put("response", &response)
+            }
+            (SagaKind::Migrate, "update-record") => {
+                let record: StorageRecord = saga.require("record")?;
+                let target_tier: StorageTier = saga.require("targetTier")?;
+                let response: ProvisionResponse = saga.require("response")?;
+                let mut records = self.storage_records.write().await;
+                let stored = records.get_mut(&record.id).ok_or_else(|| {
+                    Error::ResourceNotFound {
+                        kind: "Storage".into(),
+                        name: record.id.clone(),
+                    }
+                })?;
+                stored.tier = Some(target_tier);
+                stored.pool_name = response.pool_name;
+                Ok(())
+            }
+            _ => Err(Error::Internal(format!(
+                "unknown {} saga step: {}",
+                saga.kind, step
+            ))),
+        }
+    }
+
+    async fn compensate_step(&self, saga: &mut SagaRecord, step: &str) -> Result<()> {
+        match (saga.kind, step) {
+            (SagaKind::Provision, "reserve-quota") => {
+                if let Some(charge) = saga.get::<QuotaCharge>("quotaCharge")? {
+                    self.quotas.release(&charge);
+                    saga.take("quotaCharge");
+                }
+                Ok(())
+            }
+            (SagaKind::Provision, "allocate-drives") => {
+                let allocations: Vec<String> = saga.get("allocations")?.unwrap_or_default();
+                self.release_drives(&allocations).await;
+                saga.take("allocations");
+                Ok(())
+            }
+            (SagaKind::Provision, "provision-backend") => {
+                if let Some(response) = saga.get::<ProvisionResponse>("response")? {
+                    let backend_name: String = saga.require("backend")?;
+                    let backend = self.backend(&backend_name).await?;
+                    match backend.delete(&response.storage_id).await {
+                        Ok(()) | Err(Error::ResourceNotFound { .. }) => {}
+                        Err(e) => return Err(e),
+                    }
+                    saga.take("response");
+                }
+                Ok(())
+            }
+            (SagaKind::Provision, "create-storage-class") => {
+                if let Some(class) = saga.get::<String>("storageClass")? {
+                    let platform: Platform = saga.require("platform")?;
+                    self.delete_storage_class(platform, &class).await?;
+                    saga.take("storageClass");
+                }
+                Ok(())
+            }
+            (SagaKind::Provision, "record") => {
+                if let Some(response) = saga.get::<ProvisionResponse>("response")? {
+                    self.storage_records.write().await.remove(&response.storage_id);
+                }
+                Ok(())
+            }
+            (SagaKind::Delete, "detach-record") => {
+                let record: StorageRecord = saga.require("record")?;
+                self.storage_records.write().await.insert(record);
+                Ok(())
+            }
+            (SagaKind::Delete, "delete-storage-class") => {
+                let record: StorageRecord = saga.require("record")?;
+                // Nothing saved: the class did not exist when the step ran
+                if let Some(class) = saga.get::<PlatformStorageClass>("deletedStorageClass")? {
+                    let adapter = self.platform_adapter(record.platform).await?;
+                    adapter
+                        .restore_storage_class(
+                            &class,
+                            record.storage_type,
+                            record.tier.unwrap_or(StorageTier::Warm),
+                        )
+                        .await?;
+                    debug!("Restored storage class {}", class.name);
+                }
+                Ok(())
+            }
+            // Deleting data and releasing drives cannot be undone; both are
+            // the final steps, so nothing after them can fail
+            (SagaKind::Delete, _) => Ok(()),
+            (SagaKind::Migrate, "reserve-quota") => {
+                let record: StorageRecord = saga.require("record")?;
+                let target_tier: StorageTier = saga.require("targetTier")?;
+                let moved = QuotaCharge {
+                    tier: Some(target_tier),
+                    ..record.quota_charge()
+                };
+                self.quotas.release(&moved);
+                self.quotas.charge(&record.quota_charge());
+                Ok(())
+            }
+            (SagaKind::Migrate, "migrate-backend") => {
+                let record: StorageRecord = saga.require("record")?;
+                match record.tier {
+                    Some(original) => {
+                        let backend = self.backend(&record.backend).await?;
+                        backend.migrate_tier(&record.id, original).await?;
+                    }
+                    None => warn!(
+                        "Storage {} had no recorded tier; leaving it on the target tier",
+                        record.id
+                    ),
+                }
+                Ok(())
+            }
+            (SagaKind::Migrate, "update-record") => {
+                let record: StorageRecord = saga.require("record")?;
+                if let Some(stored) = self.storage_records.write().await.get_mut(&record.id) {
+                    stored.tier = record.tier;
+                    stored.pool_name = record.pool_name;
+                }
+                Ok(())
+            }
+            _ => Err(Error::Internal(format!(
+                "unknown {} saga step: {}",
+                saga.kind, step
+            ))),
+        }
+    }
+}
+
+impl Orchestrator {
+    /// Allocate dedicated drives if the request asks for them
+    async fn step_allocate_drives(&self, saga: &mut SagaRecord) -> Result<()> {
+        let request: ProvisionRequest = saga.require("request")?;
+        if saga.get::<Vec<String>>("allocations")?.is_some() {
+            return Ok(());
+        }
+
+        let count = match request.platform_params.get(PARAM_DEDICATED_DRIVES) {
+            Some(n) => n.parse::<usize>().map_err(|_| {
+                Error::ApiValidation(format!("{} must be a number: {}", PARAM_DEDICATED_DRIVES, n))
+            })?,
+            None => 0,
+        };
+        if count == 0 {
+            return saga.put("allocations", &Vec::<String>::new());
+        }
+
+        let policy = match request.storage_type {
+            StorageType::Block => AllocationPolicy::for_block(),
+            StorageType::File => AllocationPolicy::for_file(),
+            StorageType::Object => AllocationPolicy::for_object(),
+        };
+        let allocations: Vec<String> = self
+            .allocator
+            .allocate_with_policy(&policy, count)
+            .await?
+            .into_iter()
+            .map(|a| a.allocation_id)
+            .collect();
+
+        saga.put("allocations", &allocations)
+    }
+
+    /// Provision on the resolved backend, failing over to the next candidate
+    /// when a backend reports itself unavailable
+    async fn step_provision_backend(&self, saga: &mut SagaRecord) -> Result<()> {
+        if saga.get::<ProvisionResponse>("response")?.is_some() {
+            return Ok(());
+        }
+        let request: ProvisionRequest = saga.require("request")?;
+        let mut resolution: Resolution = saga.require("resolution")?;
+
+        let order: Vec<String> = std::iter::once(resolution.backend.clone())
+            .chain(resolution.fallbacks.drain(..))
+            .collect();
+        let mut last_error = None;
+
+        for backend_name in order {
+            let backend = match self.backends.read().await.get(&backend_name).cloned() {
+                Some(backend) => backend,
+                None => continue,
+            };
+
+            info!(
+                "Provisioning storage: {} ({:?}, {} bytes) on {}",
+                request.name, request.storage_type, request.capacity_bytes, backend_name
+            );
+
+            let mut response = match backend.provisioner.provision(request.clone()).await {
+                Ok(response) => response,
+                Err(e @ Error::BackendUnavailable { .. }) => {
+                    warn!("Backend {} unavailable, trying next candidate: {}", backend_name, e);
+                    backend.set_healthy(false);
+                    resolution
+                        .reasons
+                        .push(format!("backend {} unavailable, failed over", backend_name));
+                    last_error = Some(e);
+                    continue;
+                }
+                Err(e) => return Err(e),
+            };
+            backend.set_healthy(true);
+
+            if resolution.backend != backend_name {
+                resolution.backend = backend_name.clone();
+                resolution.pool_name = None;
+            }
+            response.resolution = Some(resolution);
+
+            saga.put("backend", &backend_name)?;
+            return saga.put("response", &response);
+        }
+
+        Err(last_error.unwrap_or(Error::BackendUnavailable {
+            backend: resolution.backend,
+        }))
+    }
+
+    /// Create a platform storage class if the request names a platform
+    async fn step_create_storage_class(&self, saga: &mut SagaRecord) -> Result<()> {
+        let request: ProvisionRequest = saga.require("request")?;
+
+        let platform = match request.platform_params.get(PARAM_PLATFORM) {
+            Some(name) => match name.to_lowercase().as_str() {
+                "kubernetes" => Platform::Kubernetes,
+                "harvester" => Platform::Harvester,
+                "openstack" => Platform::OpenStack,
+                _ => {
+                    return Err(Error::ApiValidation(format!("Unknown platform: {}", name)));
+                }
+            },
+            None => return saga.put("platform", &self.config.default_platform),
+        };
+        saga.put("platform", &platform)?;
+
+        if saga.get::<String>("storageClass")?.is_some() {
+            return Ok(());
+        }
+
+        let adapter = self.platform_adapter(platform).await?;
+        let class = adapter
+            .create_storage_class(
+                &request.name,
+                request.storage_type,
+                request.tier.unwrap_or(StorageTier::Warm),
+                BTreeMap::new(),
+            )
+            .await?;
+
+        saga.put("storageClass", &class.name)
+    }
+
+    /// Insert the orchestrator record
+    async fn step_record(&self, saga: &mut SagaRecord) -> Result<()> {
+        let request: ProvisionRequest = saga.require("request")?;
+        let response: ProvisionResponse = saga.require("response")?;
+
+        let record = StorageRecord {
+            id: response.storage_id.clone(),
+            name: response.name.clone(),
+            storage_type: request.storage_type,
+            capacity_bytes: response.capacity_bytes,
+            backend: saga.require("backend")?,
+            pool_name: response.pool_name.clone(),
+            tier: request.tier,
+            platform: saga.require("platform")?,
+            storage_class: saga.get("storageClass")?,
+            allocations: saga.get("allocations")?.unwrap_or_default(),
+            lost: false,
+            labels: request.labels.clone(),
+            annotations: request.annotations.clone(),
+            tenant: request.tenant.clone(),
+            created_at: chrono::Utc::now(),
+        };
+
+        self.storage_records.write().await.insert(record);
+
+        info!("Provisioned storage: {} -> {}", request.name, response.storage_id);
+
+        Ok(())
+    }
+
+    /// Push labels to whatever holds the storage, if it supports tags
+    async fn push_labels(&self, record: &StorageRecord, labels: &BTreeMap<String, String>) -> Result<()> {
+        if record.backend == PLATFORM_BACKEND {
+            let adapter = self.platform_adapter(record.platform).await?;
+            if adapter.supports_labels() {
+                return adapter.set_labels(&record.id, labels).await;
+            }
+        } else {
+            let backend = self.backend(&record.backend).await?;
+            if backend.capabilities().tagging {
+                return backend.set_labels(&record.id, labels).await;
+            }
+        }
+
+        debug!("{} does not support tags; labels kept on the record only", record.backend);
+        Ok(())
+    }
+
+    /// Record an audit event for an operation on a storage record
+    async fn audit_storage<T>(
+        &self,
+        action: AuditAction,
+        storage_id: &str,
+        before: Option<&StorageRecord>,
+        result: &Result<T>,
+    ) {
+        let after = match result {
+            Ok(_) => self.get_storage_record(storage_id).await,
+            Err(_) => None,
+        };
+
+        let id = storage_id.to_string();
+        let event = match (result, &after) {
+            (Err(e), _) => Some(OrchestratorEvent::OperationFailed {
+                action: action.to_string(),
+                resource: format!("storage/{}", id),
+                reason: e.to_string(),
+            }),
+            (Ok(_), _) if action == AuditAction::Delete => {
+                Some(OrchestratorEvent::StorageDeleted { storage_id: id })
+            }
+            (Ok(_), Some(after)) => match action {
+                AuditAction::Resize | AuditAction::UpdateCapacity => {
+                    Some(OrchestratorEvent::StorageResized {
+                        storage_id: id,
+                        capacity_bytes: after.capacity_bytes,
+                    })
+                }
+                AuditAction::Migrate => Some(OrchestratorEvent::StorageMigrated {
+                    storage_id: id,
+                    from_tier: before.and_then(|b| b.tier),
+                    to_tier: after.tier,
+                }),
+                AuditAction::UpdateLabels => {
+                    Some(OrchestratorEvent::StorageMetadataUpdated { storage_id: id })
+                }
+                AuditAction::Import | AuditAction::Adopt => Some(OrchestratorEvent::StorageImported {
+                    storage_id: id,
+                    backend: after.backend.clone(),
+                }),
+                AuditAction::MarkLost => Some(OrchestratorEvent::StorageLost { storage_id: id }),
+                _ => None,
+            },
+            (Ok(_), None) => None,
+        };
+        if let Some(event) = event {
+            self.emit(event);
+        }
+
+        self.audit
+            .record(
+                AuditEvent::new(action, format!("storage/{}", storage_id))
+                    .before(&before)
+                    .after(&after)
+                    .outcome(result),
+            )
+            .await;
+    }
+
+    /// Look up a registered platform adapter
+    async fn platform_adapter(&self, platform: Platform) -> Result<Arc<dyn PlatformAdapter>> {
+        self.platforms
+            .read()
+            .await
+            .get(&platform)
+            .cloned()
+            .ok_or_else(|| Error::PlatformAdapter {
+                platform: format!("{:?}", platform),
+                reason: "adapter not registered".into(),
+            })
+    }
+
+    /// Delete a platform storage class, treating "already gone" as success
+    async fn delete_storage_class(&self, platform: Platform, class: &str) -> Result<()> {
+        let adapter = self.platform_adapter(platform).await?;
+        match adapter.delete_storage_class(class).await {
+            Ok(()) | Err(Error::ResourceNotFound { .. }) => Ok(()),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Release drive allocations, logging (not failing on) unknown IDs
+    async fn release_drives(&self, allocations: &[String]) {
+        for id in allocations {
+            if let Err(e) = self.allocator.release_allocation(id).await {
+                warn!("Could not release allocation {}: {}", id, e);
+            }
+        }
+    }
+}
+
 /// Orchestrator status summary
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct OrchestratorStatus {
@@ -435,6 +1575,9 @@ pub struct OrchestratorStatus {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::controlplane::backends::MayastorAdapter;
+    use crate::controlplane::quota::QuotaLimits;
+    use crate::crd::{PolicyEffect, PolicyRule};
 
     #[tokio::test]
     async fn test_orchestrator_creation() {
@@ -465,6 +1608,8 @@ mod tests {
             tier: Some(StorageTier::Hot),
             max_iops: None,
             labels: BTreeMap::new(),
+            annotations: BTreeMap::new(),
+            tenant: None,
             platform_params: BTreeMap::new(),
         };
 
@@ -479,6 +1624,313 @@ mod tests {
         assert!(fetched.is_some());
     }
 
+    #[tokio::test]
+    async fn test_tenant_quota_enforced() {
+        let registry = NodeRegistry::new();
+        let orchestrator = Orchestrator::new(OrchestratorConfig::default(), registry);
+        orchestrator.initialize().await.unwrap();
+
+        const GI: u64 = 1024 * 1024 * 1024;
+        orchestrator.quotas().set_limits(
+            "team-a",
+            QuotaLimits {
+                tier_bytes: BTreeMap::from([(StorageTier::Hot, 15 * GI)]),
+                ..Default::default()
+            },
+        );
+
+        let request = |name: &str, capacity_bytes| ProvisionRequest {
+            request_id: name.into(),
+            name: name.into(),
+            storage_type: StorageType::Block,
+            capacity_bytes,
+            tier: Some(StorageTier::Hot),
+            max_iops: None,
+            labels: BTreeMap::new(),
+            annotations: BTreeMap::new(),
+            tenant: Some("team-a".into()),
+            platform_params: BTreeMap::new(),
+        };
+
+        let first = orchestrator.provision(request("vol-1", 10 * GI)).await.unwrap();
+        let err = orchestrator.provision(request("vol-2", 10 * GI)).await.unwrap_err();
+        assert!(matches!(err, Error::QuotaExceeded { .. }));
+        // The failed provision left no charge behind
+        assert_eq!(orchestrator.quotas().tenant("team-a").usage.total_bytes, 10 * GI);
+
+        // Growing past the limit is refused, shrinking frees quota
+        assert!(orchestrator.resize_storage(&first.storage_id, 20 * GI).await.is_err());
+        let record = orchestrator.resize_storage(&first.storage_id, 12 * GI).await.unwrap();
+        assert_eq!(record.capacity_bytes, 12 * GI);
+        assert_eq!(orchestrator.quotas().tenant("team-a").usage.total_bytes, 12 * GI);
+
+        orchestrator.delete_storage(&first.storage_id).await.unwrap();
+        let usage = orchestrator.quotas().tenant("team-a").usage;
+        assert_eq!(usage.total_bytes, 0);
+        assert_eq!(usage.volumes, 0);
+        orchestrator.provision(request("vol-3", 15 * GI)).await.unwrap();
+    }
+
+    /// Resizes through a Mayastor adapter, then holds the result until released
+    struct GatedResizeBackend {
+        inner: MayastorAdapter,
+        resized: tokio::sync::Notify,
+        release: tokio::sync::Notify,
+    }
+
+    #[async_trait::async_trait]
+    impl StorageProvisioner for GatedResizeBackend {
+        async fn provision(&self, request: ProvisionRequest) -> Result<ProvisionResponse> {
+            self.inner.provision(request).await
+        }
+        async fn delete(&self, storage_id: &str) -> Result<()> {
+            self.inner.delete(storage_id).await
+        }
+        async fn get(&self, storage_id: &str) -> Result<Option<ProvisionResponse>> {
+            self.inner.get(storage_id).await
+        }
+        async fn list(&self) -> Result<Vec<ProvisionResponse>> {
+            self.inner.list().await
+        }
+        async fn migrate_tier(&self, storage_id: &str, tier: StorageTier) -> Result<ProvisionResponse> {
+            self.inner.migrate_tier(storage_id, tier).await
+        }
+        async fn resize(&self, storage_id: &str, capacity_bytes: u64) -> Result<ProvisionResponse> {
+            let response = self.inner.resize(storage_id, capacity_bytes).await;
+            self.resized.notify_one();
+            self.release.notified().await;
+            response
+        }
+        async fn health_check(&self) -> Result<bool> {
+            Ok(true)
+        }
+        fn backend_name(&self) -> &str {
+            "gated"
+        }
+        fn supported_types(&self) -> Vec<StorageType> {
+            vec![StorageType::Block]
+        }
+        fn capabilities(&self) -> BackendCapabilities {
+            self.inner.capabilities()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_resize_of_deleted_storage_releases_reservation() {
+        let registry = NodeRegistry::new();
+        let orchestrator = Orchestrator::new(OrchestratorConfig::default(), registry);
+        orchestrator.initialize().await.unwrap();
+        let backend = Arc::new(GatedResizeBackend {
+            inner: MayastorAdapter::new(Default::default()),
+            resized: tokio::sync::Notify::new(),
+            release: tokio::sync::Notify::new(),
+        });
+        orchestrator
+            .register_backend(
+                BackendInstanceConfig::new("mayastor-gated", "mayastor").with_weight(500),
+                backend.clone(),
+            )
+            .await;
+
+        const GI: u64 = 1024 * 1024 * 1024;
+        let request = ProvisionRequest {
+            request_id: "vol-1".into(),
+            name: "vol-1".into(),
+            storage_type: StorageType::Block,
+            capacity_bytes: 10 * GI,
+            tier: Some(StorageTier::Hot),
+            max_iops: None,
+            labels: BTreeMap::new(),
+            annotations: BTreeMap::new(),
+            tenant: Some("team-a".into()),
+            platform_params: BTreeMap::new(),
+        };
+        let id = orchestrator.provision(request).await.unwrap().storage_id;
+
+        let resize = tokio::spawn({
+            let orchestrator = orchestrator.clone();
+            let id = id.clone();
+            async move { orchestrator.resize_storage(&id, 20 * GI).await }
+        });
+        backend.resized.notified().await;
+        orchestrator.delete_storage(&id).await.unwrap();
+        backend.release.notify_one();
+
+        assert!(resize.await.unwrap().is_err());
+        assert_eq!(orchestrator.quotas().tenant("team-a").usage.total_bytes, 0);
+    }
+
+    #[tokio::test]
+    async fn test_provision_records_and_charges_resolved_tier() {
+        let registry = NodeRegistry::new();
+        let orchestrator = Orchestrator::new(OrchestratorConfig::default(), registry);
+        orchestrator.initialize().await.unwrap();
+
+        const GI: u64 = 1024 * 1024 * 1024;
+        orchestrator.quotas().set_limits(
+            "team-a",
+            QuotaLimits {
+                tier_bytes: BTreeMap::from([(StorageTier::Hot, 15 * GI)]),
+                ..Default::default()
+            },
+        );
+
+        // No explicit tier: the resolver picks hot from the tier label
+        let request = |name: &str| ProvisionRequest {
+            request_id: name.into(),
+            name: name.into(),
+            storage_type: StorageType::Block,
+            capacity_bytes: 10 * GI,
+            tier: None,
+            max_iops: None,
+            labels: BTreeMap::from([(
+                crate::controlplane::resolver::STORAGE_TIER_LABEL.to_string(),
+                "hot".to_string(),
+            )]),
+            annotations: BTreeMap::new(),
+            tenant: Some("team-a".into()),
+            platform_params: BTreeMap::new(),
+        };
+
+        let response = orchestrator.provision(request("vol-1")).await.unwrap();
+        let record = orchestrator.get_storage_record(&response.storage_id).await.unwrap();
+        assert_eq!(record.tier, Some(StorageTier::Hot));
+        let usage = orchestrator.quotas().tenant("team-a").usage;
+        assert_eq!(usage.tier_bytes.get(&StorageTier::Hot), Some(&(10 * GI)));
+
+        // The per-tier limit applies even though the request named no tier
+        let err = orchestrator.provision(request("vol-2")).await.unwrap_err();
+        assert!(matches!(err, Error::QuotaExceeded { .. }));
+    }
+
+    #[tokio::test]
+    async fn test_admission_policy_checked_before_provisioning() {
+        let registry = NodeRegistry::new();
+        let orchestrator = Orchestrator::new(OrchestratorConfig::default(), registry);
+        orchestrator.initialize().await.unwrap();
+
+        orchestrator
+            .policies()
+            .set_policy(
+                "tiers",
+                &[
+                    PolicyRule {
+                        name: "dev-cold-only".into(),
+                        effect: PolicyEffect::Deny,
+                        when: vec!["labels.env == dev".into(), "tier != cold".into()],
+                        message: Some("dev storage must use the cold tier".into()),
+                    },
+                    PolicyRule {
+                        name: "owner".into(),
+                        effect: PolicyEffect::Warn,
+                        when: vec!["labels.owner missing".into()],
+                        message: None,
+                    },
+                ],
+            )
+            .unwrap();
+
+        let mut request = ProvisionRequest {
+            request_id: "dev-1".into(),
+            name: "dev-volume".into(),
+            storage_type: StorageType::Block,
+            capacity_bytes: 1024 * 1024 * 1024,
+            tier: Some(StorageTier::Hot),
+            max_iops: None,
+            labels: BTreeMap::from([("env".to_string(), "dev".to_string())]),
+            annotations: BTreeMap::new(),
+            tenant: None,
+            platform_params: BTreeMap::new(),
+        };
+
+        let err = orchestrator.provision(request.clone()).await.unwrap_err();
+        assert!(matches!(err, Error::PolicyDenied { ref rule, .. } if rule == "dev-cold-only"));
+        assert!(orchestrator.list_storage_records().await.is_empty());
+        assert_eq!(orchestrator.quotas().tenant("default").usage.volumes, 0);
+
+        request.tier = Some(StorageTier::Cold);
+        let response = orchestrator.provision(request).await.unwrap();
+        let warnings = response.resolution.unwrap().warnings;
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].starts_with("tiers/owner"));
+    }
+
+    #[tokio::test]
+    async fn test_patch_storage_metadata() {
+        let registry = NodeRegistry::new();
+        let orchestrator = Orchestrator::new(OrchestratorConfig::default(), registry);
+        orchestrator.initialize().await.unwrap();
+
+        let request = ProvisionRequest {
+            request_id: "test-1".into(),
+            name: "test-volume".into(),
+            storage_type: StorageType::Block,
+            capacity_bytes: 10 * 1024 * 1024 * 1024,
+            tier: Some(StorageTier::Hot),
+            max_iops: None,
+            labels: BTreeMap::from([("app".to_string(), "db".to_string())]),
+            annotations: BTreeMap::from([("owner".to_string(), "alice".to_string())]),
+            tenant: None,
+            platform_params: BTreeMap::new(),
+        };
+        let response = orchestrator.provision(request).await.unwrap();
+        let id = response.storage_id.as_str();
+
+        let record = orchestrator.get_storage_record(id).await.unwrap();
+        assert_eq!(record.labels["app"], "db");
+        assert_eq!(record.annotations["owner"], "alice");
+
+        let patch = serde_json::json!({ "labels": { "app": null, "env": "prod" } });
+        let updated = orchestrator.patch_storage_metadata(id, &patch).await.unwrap();
+        assert_eq!(updated.labels, BTreeMap::from([("env".to_string(), "prod".to_string())]));
+        assert_eq!(updated.annotations["owner"], "alice");
+
+        // The label index follows the patch
+        let by_env = StorageQuery { selector: "env=prod".parse().unwrap(), ..Default::default() };
+        assert_eq!(orchestrator.query_storage(&by_env).await.unwrap().items.len(), 1);
+        let by_app = StorageQuery { selector: "app=db".parse().unwrap(), ..Default::default() };
+        assert!(orchestrator.query_storage(&by_app).await.unwrap().items.is_empty());
+
+        // Invalid patches leave the record untouched
+        let bad = serde_json::json!({ "labels": { "bad key": "x" } });
+        assert!(orchestrator.patch_storage_metadata(id, &bad).await.is_err());
+        assert_eq!(orchestrator.get_storage_record(id).await.unwrap().labels, updated.labels);
+        assert!(orchestrator.patch_storage_metadata("missing", &patch).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_migrate_storage() {
+        let registry = NodeRegistry::new();
+        let orchestrator = Orchestrator::new(OrchestratorConfig::default(), registry);
+        orchestrator.initialize().await.unwrap();
+
+        let request = ProvisionRequest {
+            request_id: "test-1".into(),
+            name: "test-volume".into(),
+            storage_type: StorageType::Block,
+            capacity_bytes: 10 * 1024 * 1024 * 1024,
+            tier: Some(StorageTier::Hot),
+            max_iops: None,
+            labels: BTreeMap::new(),
+            annotations: BTreeMap::new(),
+            tenant: None,
+            platform_params: BTreeMap::new(),
+        };
+        let response = orchestrator.provision(request).await.unwrap();
+
+        orchestrator
+            .migrate_storage(&response.storage_id, StorageTier::Cold)
+            .await
+            .unwrap();
+
+        let record = orchestrator.get_storage_record(&response.storage_id).await.unwrap();
+        assert_eq!(record.tier, Some(StorageTier::Cold));
+        assert_eq!(record.pool_name, "pool-tier-cold");
+
+        let missing = orchestrator.migrate_storage("vol-missing", StorageTier::Cold).await;
+        assert!(matches!(missing, Err(Error::ResourceNotFound { .. })));
+    }
+
     #[tokio::test]
     async fn test_list_pools() {
         let registry = NodeRegistry::new();
@@ -490,4 +1942,224 @@ mod tests {
         let pools = orchestrator.list_pools().await.unwrap();
         assert!(pools.len() >= 3); // hot, object, file pools
     }
+
+    /// Block backend that is always unreachable
+    struct UnreachableBackend;
+
+    #[async_trait::async_trait]
+    impl StorageProvisioner for UnreachableBackend {
+        async fn provision(&self, _request: ProvisionRequest) -> Result<ProvisionResponse> {
+            Err(Error::BackendUnavailable {
+                backend: "unreachable".into(),
+            })
+        }
+        async fn delete(&self, _storage_id: &str) -> Result<()> {
+            Ok(())
+        }
+        async fn get(&self, _storage_id: &str) -> Result<Option<ProvisionResponse>> {
+            Ok(None)
+        }
+        async fn list(&self) -> Result<Vec<ProvisionResponse>> {
+            Ok(Vec::new())
+        }
+        async fn health_check(&self) -> Result<bool> {
+            Ok(false)
+        }
+        fn backend_name(&self) -> &str {
+            "unreachable"
+        }
+        fn supported_types(&self) -> Vec<StorageType> {
+            vec![StorageType::Block]
+        }
+    }
+
+    #[tokio::test]
+    async fn test_backend_failover() {
+        let registry = NodeRegistry::new();
+        let orchestrator = Orchestrator::new(OrchestratorConfig::default(), registry);
+        orchestrator.initialize().await.unwrap();
+
+        // Heavier weight makes the broken instance the first choice
+        orchestrator
+            .register_backend(
+                BackendInstanceConfig::new("mayastor-dr", "mayastor").with_weight(500),
+                Arc::new(UnreachableBackend),
+            )
+            .await;
+
+        let request = ProvisionRequest {
+            request_id: "test-1".into(),
+            name: "test-volume".into(),
+            storage_type: StorageType::Block,
+            capacity_bytes: 10 * 1024 * 1024 * 1024,
+            tier: Some(StorageTier::Hot),
+            max_iops: None,
+            labels: BTreeMap::new(),
+            annotations: BTreeMap::new(),
+            tenant: None,
+            platform_params: BTreeMap::new(),
+        };
+        let response = orchestrator.provision(request.clone()).await.unwrap();
+        let resolution = response.resolution.unwrap();
+        assert_eq!(resolution.backend, "mayastor");
+        assert!(resolution.reasons.iter().any(|r| r.contains("failed over")));
+
+        let record = orchestrator.get_storage_record(&response.storage_id).await.unwrap();
+        assert_eq!(record.backend, "mayastor");
+
+        // The failed instance is now skipped up front
+        let response = orchestrator.provision(request).await.unwrap();
+        let resolution = response.resolution.unwrap();
+        assert_eq!(resolution.backend, "mayastor");
+        assert!(!resolution.reasons.iter().any(|r| r.contains("failed over")));
+    }
+
+    #[tokio::test]
+    async fn test_unhealthy_backend_degrades_pool() {
+        let registry = NodeRegistry::new();
+        let orchestrator = Orchestrator::new(OrchestratorConfig::default(), registry);
+        orchestrator.initialize().await.unwrap();
+        let mut events = orchestrator.subscribe();
+
+        orchestrator
+            .register_backend(
+                BackendInstanceConfig::new("mayastor-dr", "mayastor"),
+                Arc::new(UnreachableBackend),
+            )
+            .await;
+        let health = orchestrator.backends_health().await;
+        assert_eq!(health.get("mayastor-dr"), Some(&false));
+
+        match events.try_recv().unwrap() {
+            OrchestratorEvent::PoolDegraded { pool, reason, .. } => {
+                assert_eq!(pool, "hot-nvme-pool");
+                assert!(reason.contains("mayastor-dr"));
+            }
+            other => panic!("unexpected event: {:?}", other),
+        }
+
+        // No new event while the backend stays down
+        orchestrator.backends_health().await;
+        assert!(events.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_provision_saga_compensates() {
+        let registry = NodeRegistry::new();
+        let orchestrator = Orchestrator::new(OrchestratorConfig::default(), registry);
+        orchestrator.initialize().await.unwrap();
+
+        // Unknown platform fails after the backend volume was created
+        let mut request = ProvisionRequest {
+            request_id: "test-1".into(),
+            name: "test-volume".into(),
+            storage_type: StorageType::Block,
+            capacity_bytes: 10 * 1024 * 1024 * 1024,
+            tier: Some(StorageTier::Hot),
+            max_iops: None,
+            labels: BTreeMap::new(),
+            annotations: BTreeMap::new(),
+            tenant: None,
+            platform_params: BTreeMap::new(),
+        };
+        request.platform_params.insert(PARAM_PLATFORM.into(), "vmware".into());
+
+        let result = orchestrator.provision(request.clone()).await;
+        assert!(matches!(result, Err(Error::ApiValidation(_))));
+
+        let backend = orchestrator.backend("mayastor").await.unwrap();
+        assert!(backend.list().await.unwrap().is_empty());
+        assert!(orchestrator.list_storage_records().await.is_empty());
+        assert!(orchestrator.pending_sagas().await.unwrap().is_empty());
+
+        // With a registered platform the storage class is created and later removed
+        request.platform_params.insert(PARAM_PLATFORM.into(), "harvester".into());
+        let response = orchestrator.provision(request).await.unwrap();
+        let record = orchestrator.get_storage_record(&response.storage_id).await.unwrap();
+        assert_eq!(record.platform, Platform::Harvester);
+        let class = record.storage_class.clone().unwrap();
+
+        let harvester = orchestrator.platform_adapter(Platform::Harvester).await.unwrap();
+        let classes = harvester.list_storage_classes().await.unwrap();
+        assert!(classes.iter().any(|c| c.name == class));
+
+        orchestrator.delete_storage(&response.storage_id).await.unwrap();
+        let classes = harvester.list_storage_classes().await.unwrap();
+        assert!(!classes.iter().any(|c| c.name == class));
+        assert!(backend.list().await.unwrap().is_empty());
+    }
+
+    /// Block backend that provisions through Mayastor but cannot delete
+    struct UndeletableBackend(MayastorAdapter);
+
+    #[async_trait::async_trait]
+    impl StorageProvisioner for UndeletableBackend {
+        async fn provision(&self, request: ProvisionRequest) -> Result<ProvisionResponse> {
+            self.0.provision(request).await
+        }
+        async fn delete(&self, _storage_id: &str) -> Result<()> {
+            Err(Error::BackendUnavailable {
+                backend: "undeletable".into(),
+            })
+        }
+        async fn get(&self, storage_id: &str) -> Result<Option<ProvisionResponse>> {
+            self.0.get(storage_id).await
+        }
+        async fn list(&self) -> Result<Vec<ProvisionResponse>> {
+            self.0.list().await
+        }
+        async fn migrate_tier(&self, storage_id: &str, tier: StorageTier) -> Result<ProvisionResponse> {
+            self.0.migrate_tier(storage_id, tier).await
+        }
+        async fn health_check(&self) -> Result<bool> {
+            Ok(true)
+        }
+        fn backend_name(&self) -> &str {
+            "undeletable"
+        }
+        fn supported_types(&self) -> Vec<StorageType> {
+            vec![StorageType::Block]
+        }
+    }
+
+    #[tokio::test]
+    async fn test_failed_delete_restores_storage_class() {
+        let registry = NodeRegistry::new();
+        let orchestrator = Orchestrator::new(OrchestratorConfig::default(), registry);
+        orchestrator.initialize().await.unwrap();
+        orchestrator
+            .register_backend(
+                BackendInstanceConfig::new("mayastor-locked", "mayastor").with_weight(500),
+                Arc::new(UndeletableBackend(MayastorAdapter::new(Default::default()))),
+            )
+            .await;
+
+        let request = ProvisionRequest {
+            request_id: "test-1".into(),
+            name: "test-volume".into(),
+            storage_type: StorageType::Block,
+            capacity_bytes: 10 * 1024 * 1024 * 1024,
+            tier: Some(StorageTier::Hot),
+            max_iops: None,
+            labels: BTreeMap::new(),
+            annotations: BTreeMap::new(),
+            tenant: None,
+            platform_params: BTreeMap::from([(PARAM_PLATFORM.into(), "harvester".into())]),
+        };
+        let response = orchestrator.provision(request).await.unwrap();
+        let record = orchestrator.get_storage_record(&response.storage_id).await.unwrap();
+        assert_eq!(record.backend, "mayastor-locked");
+        let harvester = orchestrator.platform_adapter(Platform::Harvester).await.unwrap();
+        let original = harvester.list_storage_classes().await.unwrap();
+
+        // The class was made for the hot tier; the record no longer is
+        orchestrator
+            .migrate_storage(&response.storage_id, StorageTier::Cold)
+            .await
+            .unwrap();
+
+        assert!(orchestrator.delete_storage(&response.storage_id).await.is_err());
+        assert_eq!(harvester.list_storage_classes().await.unwrap(), original);
+        assert!(orchestrator.get_storage_record(&response.storage_id).await.is_some());
+    }
 }
@@ -3,7 +3,9 @@
 //! Provides integration with Harvester HCI using Longhorn CSI
 //! for block storage provisioning.
 
-use crate::domain::ports::{Platform, PlatformAdapter, PlatformStorageClass, StorageTier, StorageType};
+use crate::domain::ports::{
+    Platform, PlatformAdapter, PlatformStorage, PlatformStorageClass, StorageTier, StorageType,
+};
 use crate::error::{Error, Result};
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
@@ -16,7 +18,8 @@ use tracing::{debug, info, warn};
 // =============================================================================
 
 /// Configuration for Harvester adapter
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "camelCase")]
 pub struct HarvesterConfig {
     /// Harvester API endpoint
     pub api_endpoint: Option<String>,
@@ -76,6 +79,8 @@ struct VolumeState {
     name: String,
     storage_class: String,
     capacity_bytes: u64,
+    /// Kubernetes labels on the PVC
+    labels: BTreeMap<String, String>,
 }
 
 impl HarvesterAdapter {
@@ -117,6 +122,7 @@ impl HarvesterAdapter {
             name: name.to_string(),
             storage_class: storage_class.to_string(),
             capacity_bytes,
+            labels: BTreeMap::new(),
         };
 
         self.volumes.write().await.insert(volume_id.clone(), state);
@@ -196,6 +202,32 @@ impl PlatformAdapter for HarvesterAdapter {
         }
     }
 
+    async fn restore_storage_class(
+        &self,
+        class: &PlatformStorageClass,
+        storage_type: StorageType,
+        tier: StorageTier,
+    ) -> Result<()> {
+        info!("Restoring Harvester storage class: {}", class.name);
+
+        let replicas = class
+            .parameters
+            .get("numberOfReplicas")
+            .and_then(|r| r.parse().ok())
+            .unwrap_or(self.config.default_replicas);
+        let state = StorageClassState {
+            name: class.name.clone(),
+            storage_type: format!("{:?}", storage_type).to_lowercase(),
+            tier: format!("{:?}", tier).to_lowercase(),
+            replicas,
+            is_default: class.is_default,
+            parameters: class.parameters.clone(),
+        };
+
+        self.storage_classes.write().await.insert(class.name.clone(), state);
+        Ok(())
+    }
+
     async fn list_storage_classes(&self) -> Result<Vec<PlatformStorageClass>> {
         let classes = self.storage_classes.read().await;
 
@@ -242,6 +274,41 @@ impl PlatformAdapter for HarvesterAdapter {
         }
     }
 
+    async fn list_storage(&self) -> Result<Vec<PlatformStorage>> {
+        Ok(self
+            .volumes
+            .read()
+            .await
+            .values()
+            .map(|v| PlatformStorage {
+                storage_id: v.id.clone(),
+                name: v.name.clone(),
+                storage_type: StorageType::Block,
+                capacity_bytes: v.capacity_bytes,
+                storage_class: v.storage_class.clone(),
+                details: BTreeMap::from([("kind".to_string(), "PersistentVolumeClaim".to_string())]),
+            })
+            .collect())
+    }
+
+    fn supports_labels(&self) -> bool {
+        true
+    }
+
+    async fn set_labels(&self, storage_id: &str, labels: &BTreeMap<String, String>) -> Result<()> {
+        // In a real implementation, this would patch metadata.labels on the PVC
+        match self.volumes.write().await.get_mut(storage_id) {
+            Some(volume) => {
+                volume.labels = labels.clone();
+                Ok(())
+            }
+            None => Err(Error::ResourceNotFound {
+                kind: "PersistentVolumeClaim".into(),
+                name: storage_id.into(),
+            }),
+        }
+    }
+
     async fn health_check(&self) -> Result<bool> {
         // In a real implementation, check Longhorn manager status
         Ok(true)
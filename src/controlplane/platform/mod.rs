@@ -12,6 +12,7 @@ pub use openstack::*;
 
 use crate::domain::ports::{Platform, PlatformAdapter, PlatformStorageClass, StorageTier, StorageType};
 use crate::error::Result;
+use serde::Deserialize;
 use std::sync::Arc;
 
 /// Factory for creating platform adapters
@@ -32,7 +33,8 @@ impl PlatformFactory {
 }
 
 /// Combined platform configuration
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "camelCase")]
 pub struct PlatformConfig {
     pub harvester: HarvesterConfig,
     pub openstack: OpenStackConfig,
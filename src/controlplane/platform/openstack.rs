@@ -5,7 +5,9 @@
 //! - Manila: File storage (shares)
 //! - Swift: Object storage
 
-use crate::domain::ports::{Platform, PlatformAdapter, PlatformStorageClass, StorageTier, StorageType};
+use crate::domain::ports::{
+    Platform, PlatformAdapter, PlatformStorage, PlatformStorageClass, StorageTier, StorageType,
+};
 use crate::error::{Error, Result};
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
@@ -18,7 +20,8 @@ use tracing::{debug, info, warn};
 // =============================================================================
 
 /// Configuration for OpenStack adapter
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "camelCase")]
 pub struct OpenStackConfig {
     /// Keystone auth URL
     pub auth_url: String,
@@ -66,6 +69,8 @@ struct CinderVolume {
     volume_type: String,
     availability_zone: String,
     status: String,
+    #[serde(default)]
+    metadata: BTreeMap<String, String>,
 }
 
 /// Manila share state
@@ -78,6 +83,8 @@ struct ManilaShare {
     share_protocol: String,
     export_location: Option<String>,
     status: String,
+    #[serde(default)]
+    metadata: BTreeMap<String, String>,
 }
 
 /// Swift container state
@@ -87,6 +94,9 @@ struct SwiftContainer {
     storage_policy: String,
     object_count: u64,
     bytes_used: u64,
+    /// `X-Container-Meta-*` headers
+    #[serde(default)]
+    metadata: BTreeMap<String, String>,
 }
 
 // =============================================================================
@@ -153,6 +163,7 @@ impl OpenStackAdapter {
             volume_type: volume_type.to_string(),
             availability_zone: self.config.availability_zone.clone().unwrap_or_else(|| "nova".to_string()),
             status: "available".to_string(),
+            metadata: BTreeMap::new(),
         };
 
         self.cinder_volumes.write().await.insert(volume_id.clone(), volume);
@@ -183,6 +194,7 @@ impl OpenStackAdapter {
             share_protocol: "NFS".to_string(),
             export_location: Some(format!("10.0.0.1:/shares/{}", share_id)),
             status: "available".to_string(),
+            metadata: BTreeMap::new(),
         };
 
         self.manila_shares.write().await.insert(share_id.clone(), share);
@@ -207,6 +219,7 @@ impl OpenStackAdapter {
             storage_policy: storage_policy.to_string(),
             object_count: 0,
             bytes_used: 0,
+            metadata: BTreeMap::new(),
         };
 
         self.swift_containers.write().await.insert(name.to_string(), container);
@@ -365,6 +378,25 @@ impl PlatformAdapter for OpenStackAdapter {
         }
     }
 
+    async fn restore_storage_class(
+        &self,
+        class: &PlatformStorageClass,
+        storage_type: StorageType,
+        tier: StorageTier,
+    ) -> Result<()> {
+        info!("Restoring OpenStack volume type: {}", class.name);
+
+        let volume_type = VolumeType {
+            name: class.name.clone(),
+            description: format!("{:?} storage, {:?} tier", storage_type, tier),
+            is_public: true,
+            extra_specs: class.parameters.clone(),
+        };
+
+        self.volume_types.write().await.insert(class.name.clone(), volume_type);
+        Ok(())
+    }
+
     async fn list_storage_classes(&self) -> Result<Vec<PlatformStorageClass>> {
         let types = self.volume_types.read().await;
 
@@ -425,6 +457,84 @@ impl PlatformAdapter for OpenStackAdapter {
         })
     }
 
+    async fn list_storage(&self) -> Result<Vec<PlatformStorage>> {
+        const GIB: u64 = 1024 * 1024 * 1024;
+        let mut storage = Vec::new();
+
+        for v in self.cinder_volumes.read().await.values() {
+            storage.push(PlatformStorage {
+                storage_id: v.id.clone(),
+                name: v.name.clone(),
+                storage_type: StorageType::Block,
+                capacity_bytes: v.size_gb * GIB,
+                storage_class: v.volume_type.clone(),
+                details: BTreeMap::from([
+                    ("service".to_string(), "cinder".to_string()),
+                    ("availabilityZone".to_string(), v.availability_zone.clone()),
+                ]),
+            });
+        }
+
+        for s in self.manila_shares.read().await.values() {
+            let mut details = BTreeMap::from([
+                ("service".to_string(), "manila".to_string()),
+                ("protocol".to_string(), s.share_protocol.clone()),
+            ]);
+            if let Some(export) = &s.export_location {
+                details.insert("exportLocation".to_string(), export.clone());
+            }
+            storage.push(PlatformStorage {
+                storage_id: s.id.clone(),
+                name: s.name.clone(),
+                storage_type: StorageType::File,
+                capacity_bytes: s.size_gb * GIB,
+                storage_class: s.share_type.clone(),
+                details,
+            });
+        }
+
+        for c in self.swift_containers.read().await.values() {
+            storage.push(PlatformStorage {
+                storage_id: c.name.clone(),
+                name: c.name.clone(),
+                storage_type: StorageType::Object,
+                capacity_bytes: c.bytes_used,
+                storage_class: c.storage_policy.clone(),
+                details: BTreeMap::from([
+                    ("service".to_string(), "swift".to_string()),
+                    ("objectCount".to_string(), c.object_count.to_string()),
+                ]),
+            });
+        }
+
+        Ok(storage)
+    }
+
+    fn supports_labels(&self) -> bool {
+        true
+    }
+
+    async fn set_labels(&self, storage_id: &str, labels: &BTreeMap<String, String>) -> Result<()> {
+        // Cinder and Manila metadata, or Swift container metadata
+        if let Some(volume) = self.cinder_volumes.write().await.get_mut(storage_id) {
+            volume.metadata = labels.clone();
+            return Ok(());
+        }
+        if let Some(share) = self.manila_shares.write().await.get_mut(storage_id) {
+            share.metadata = labels.clone();
+            return Ok(());
+        }
+        if let Some(container) = self.swift_containers.write().await.get_mut(storage_id) {
+            container.metadata = labels.clone();
+            return Ok(());
+        }
+
+        Err(Error::ResourceNotFound {
+            kind: "Storage".into(),
+            name: storage_id.into(),
+        })
+    }
+
     async fn health_check(&self) -> Result<bool> {
         // In a real implementation, check Keystone and service endpoints
         Ok(true)
@@ -0,0 +1,753 @@
+//! Admission Policies
+//!
+//! Evaluates `StoragePolicy` rules against a provisioning request and the
+//! identity of the caller before any resources are reserved. A rule matches
+//! when all of its conditions hold; a matching `deny` rule rejects the
+//! request and matching `warn` rules are returned alongside the response.
+//!
+//! Conditions are written as `<field> <operator> [value]`, separated by
+//! spaces:
+//!
+//! - fields: `name`, `storageType`, `tier`, `capacity`, `maxIops`, `tenant`,
+//!   `user`, `group`, `labels.<key>`, `annotations.<key>`, `params.<key>`
+//! - operators: `==`, `!=`, `<`, `<=`, `>`, `>=` (capacity and maxIops only),
+//!   `in (a, b)`, `notin (a, b)`, `exists`, `missing`
+//!
+//! A missing field never equals anything, so `!=` and `notin` match it.
+//!
+//! Policies are loaded from manifest files at startup and from
+//! `StoragePolicy` resources followed through a Kubernetes watch.
+
+use crate::crd::{PolicyEffect, PolicyRule, StoragePolicy};
+use crate::domain::capacity::parse_capacity;
+use crate::domain::ports::{ProvisionRequest, StorageTier, StorageType};
+use crate::error::{Error, Result};
+use futures::StreamExt;
+use kube::runtime::watcher::{self, Event};
+use kube::Api;
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::Arc;
+use tokio::sync::broadcast;
+use tracing::{debug, info, warn};
+
+// =============================================================================
+// Caller Identity
+// =============================================================================
+
+/// Who is making a request
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CallerIdentity {
+    /// Authenticated user (None if anonymous)
+    pub user: Option<String>,
+    /// Groups the user belongs to
+    #[serde(default)]
+    pub groups: Vec<String>,
+}
+
+impl std::fmt::Display for CallerIdentity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.user.as_deref().unwrap_or("anonymous"))
+    }
+}
+
+// =============================================================================
+// Conditions
+// =============================================================================
+
+/// Request attribute a condition inspects
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Field {
+    Name,
+    StorageType,
+    Tier,
+    Capacity,
+    MaxIops,
+    Tenant,
+    User,
+    Group,
+    Label(String),
+    Annotation(String),
+    Param(String),
+}
+
+impl FromStr for Field {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let keyed = |prefix: &str| {
+            s.strip_prefix(prefix)
+                .filter(|key| !key.is_empty())
+                .map(str::to_string)
+        };
+
+        Ok(match s {
+            "name" => Field::Name,
+            "storageType" => Field::StorageType,
+            "tier" => Field::Tier,
+            "capacity" => Field::Capacity,
+            "maxIops" => Field::MaxIops,
+            "tenant" => Field::Tenant,
+            "user" => Field::User,
+            "group" => Field::Group,
+            _ => {
+                if let Some(key) = keyed("labels.") {
+                    Field::Label(key)
+                } else if let Some(key) = keyed("annotations.") {
+                    Field::Annotation(key)
+                } else if let Some(key) = keyed("params.") {
+                    Field::Param(key)
+                } else {
+                    return Err(Error::Configuration(format!("unknown policy field: {}", s)));
+                }
+            }
+        })
+    }
+}
+
+impl Field {
+    /// Values of the field for a request (empty if missing)
+    fn values(&self, request: &ProvisionRequest, caller: &CallerIdentity) -> Vec<String> {
+        match self {
+            Field::Name => vec![request.name.clone()],
+            Field::StorageType => vec![request.storage_type.to_string()],
+            Field::Tier => request.tier.iter().map(|t| t.to_string()).collect(),
+            Field::Capacity => vec![request.capacity_bytes.to_string()],
+            Field::MaxIops => request.max_iops.iter().map(|i| i.to_string()).collect(),
+            Field::Tenant => request.tenant.iter().cloned().collect(),
+            Field::User => caller.user.iter().cloned().collect(),
+            Field::Group => caller.groups.clone(),
+            Field::Label(key) => request.labels.get(key).cloned().into_iter().collect(),
+            Field::Annotation(key) => request.annotations.get(key).cloned().into_iter().collect(),
+            Field::Param(key) => request.platform_params.get(key).cloned().into_iter().collect(),
+        }
+    }
+
+    fn is_numeric(&self) -> bool {
+        matches!(self, Field::Capacity | Field::MaxIops)
+    }
+
+    /// Normalize a literal from a rule so it compares equal to `values`
+    fn normalize(&self, literal: &str) -> Result<String> {
+        Ok(match self {
+            Field::StorageType => literal.parse::<StorageType>()?.to_string(),
+            Field::Tier => literal.parse::<StorageTier>()?.to_string(),
+            Field::Capacity => parse_capacity(literal)?.to_string(),
+            Field::MaxIops => literal
+                .parse::<u64>()
+                .map_err(|_| Error::Configuration(format!("maxIops must be a number: {}", literal)))?
+                .to_string(),
+            _ => literal.to_string(),
+        })
+    }
+}
+
+/// Comparison a condition applies
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Operator {
+    Equals,
+    NotEquals,
+    LessThan,
+    LessOrEqual,
+    GreaterThan,
+    GreaterOrEqual,
+    In,
+    NotIn,
+    Exists,
+    Missing,
+}
+
+impl FromStr for Operator {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Ok(match s {
+            "==" => Operator::Equals,
+            "!=" => Operator::NotEquals,
+            "<" => Operator::LessThan,
+            "<=" => Operator::LessOrEqual,
+            ">" => Operator::GreaterThan,
+            ">=" => Operator::GreaterOrEqual,
+            "in" => Operator::In,
+            "notin" => Operator::NotIn,
+            "exists" => Operator::Exists,
+            "missing" => Operator::Missing,
+            _ => return Err(Error::Configuration(format!("unknown policy operator: {}", s))),
+        })
+    }
+}
+
+/// One parsed `when` entry
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Condition {
+    field: Field,
+    operator: Operator,
+    operands: Vec<String>,
+}
+
+impl FromStr for Condition {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let invalid = |reason: &str| Error::Configuration(format!("invalid condition '{}': {}", s, reason));
+
+        let mut parts = s.trim().splitn(3, char::is_whitespace);
+        let field: Field = parts.next().filter(|f| !f.is_empty()).ok_or_else(|| invalid("empty"))?.parse()?;
+        let operator: Operator = parts.next().ok_or_else(|| invalid("missing operator"))?.parse()?;
+        let rest = parts.next().map(str::trim).unwrap_or("");
+
+        let literals: Vec<&str> = match operator {
+            Operator::Exists | Operator::Missing => {
+                if !rest.is_empty() {
+                    return Err(invalid("exists/missing take no value"));
+                }
+                Vec::new()
+            }
+            Operator::In | Operator::NotIn => rest
+                .strip_prefix('(')
+                .and_then(|r| r.strip_suffix(')'))
+                .ok_or_else(|| invalid("expected a list like (a, b)"))?
+                .split(',')
+                .map(unquote)
+                .filter(|v| !v.is_empty())
+                .collect(),
+            _ => {
+                let value = unquote(rest);
+                if value.is_empty() {
+                    return Err(invalid("missing value"));
+                }
+                vec![value]
+            }
+        };
+
+        if matches!(
+            operator,
+            Operator::LessThan | Operator::LessOrEqual | Operator::GreaterThan | Operator::GreaterOrEqual
+        ) && !field.is_numeric()
+        {
+            return Err(invalid("ordering operators apply to capacity and maxIops only"));
+        }
+
+        let operands = literals
+            .into_iter()
+            .map(|l| field.normalize(l))
+            .collect::<Result<Vec<_>>>()
+            .map_err(|e| invalid(&e.to_string()))?;
+
+        Ok(Self { field, operator, operands })
+    }
+}
+
+impl Condition {
+    fn matches(&self, request: &ProvisionRequest, caller: &CallerIdentity) -> bool {
+        let values = self.field.values(request, caller);
+        let any_equal = |operand: &String| values.iter().any(|v| v == operand);
+        let compare = |cmp: fn(u64, u64) -> bool| {
+            let operand = self.operands[0].parse::<u64>().unwrap_or(0);
+            values
+                .iter()
+                .filter_map(|v| v.parse::<u64>().ok())
+                .any(|v| cmp(v, operand))
+        };
+
+        match self.operator {
+            Operator::Equals => any_equal(&self.operands[0]),
+            Operator::NotEquals => !any_equal(&self.operands[0]),
+            Operator::In => self.operands.iter().any(any_equal),
+            Operator::NotIn => !self.operands.iter().any(any_equal),
+            Operator::LessThan => compare(|v, o| v < o),
+            Operator::LessOrEqual => compare(|v, o| v <= o),
+            Operator::GreaterThan => compare(|v, o| v > o),
+            Operator::GreaterOrEqual => compare(|v, o| v >= o),
+            Operator::Exists => !values.is_empty(),
+            Operator::Missing => values.is_empty(),
+        }
+    }
+}
+
+fn unquote(s: &str) -> &str {
+    let s = s.trim();
+    s.strip_prefix('"')
+        .and_then(|s| s.strip_suffix('"'))
+        .unwrap_or(s)
+}
+
+// =============================================================================
+// Decisions
+// =============================================================================
+
+/// A rule that matched a request
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PolicyViolation {
+    pub policy: String,
+    pub rule: String,
+    pub effect: PolicyEffect,
+    pub message: String,
+}
+
+impl std::fmt::Display for PolicyViolation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}/{}: {}", self.policy, self.rule, self.message)
+    }
+}
+
+/// Outcome of evaluating all policies against a request
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AdmissionDecision {
+    /// First deny rule that matched (None if admitted)
+    pub denied_by: Option<PolicyViolation>,
+    /// Warn rules that matched
+    pub warnings: Vec<PolicyViolation>,
+}
+
+impl AdmissionDecision {
+    pub fn allowed(&self) -> bool {
+        self.denied_by.is_none()
+    }
+}
+
+// =============================================================================
+// Policy Engine
+// =============================================================================
+
+#[derive(Debug, Clone)]
+struct CompiledRule {
+    name: String,
+    effect: PolicyEffect,
+    conditions: Vec<Condition>,
+    message: String,
+}
+
+impl CompiledRule {
+    fn compile(policy: &str, rule: &PolicyRule) -> Result<Self> {
+        let conditions = rule
+            .when
+            .iter()
+            .map(|c| c.parse::<Condition>())
+            .collect::<Result<Vec<_>>>()
+            .map_err(|e| Error::Configuration(format!("policy {} rule {}: {}", policy, rule.name, e)))?;
+
+        Ok(Self {
+            name: rule.name.clone(),
+            effect: rule.effect,
+            message: rule
+                .message
+                .clone()
+                .unwrap_or_else(|| format!("matched rule {}", rule.name)),
+            conditions,
+        })
+    }
+}
+
+/// Holds compiled policies and evaluates requests against them
+#[derive(Debug, Default)]
+pub struct PolicyEngine {
+    /// Compiled rules by policy name
+    policies: RwLock<BTreeMap<String, Vec<CompiledRule>>>,
+}
+
+impl PolicyEngine {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Compile and install (or replace) a policy
+    pub fn set_policy(&self, name: &str, rules: &[PolicyRule]) -> Result<()> {
+        let compiled = rules
+            .iter()
+            .map(|rule| CompiledRule::compile(name, rule))
+            .collect::<Result<Vec<_>>>()?;
+
+        info!("Loaded storage policy {} ({} rules)", name, compiled.len());
+        self.policies.write().insert(name.to_string(), compiled);
+        Ok(())
+    }
+
+    /// Install a `StoragePolicy` resource, returning its name
+    pub fn apply(&self, policy: &StoragePolicy) -> Result<String> {
+        let name = policy
+            .metadata
+            .name
+            .clone()
+            .ok_or_else(|| Error::Configuration("StoragePolicy has no name".into()))?;
+        self.set_policy(&name, &policy.spec.rules)?;
+        Ok(name)
+    }
+
+    /// Remove a policy
+    pub fn remove_policy(&self, name: &str) -> bool {
+        self.policies.write().remove(name).is_some()
+    }
+
+    /// Names of installed policies
+    pub fn policy_names(&self) -> Vec<String> {
+        self.policies.read().keys().cloned().collect()
+    }
+
+    /// Install every `StoragePolicy` in a YAML file, or in every `.yaml`/`.yml`
+    /// file of a directory
+    pub fn load_manifests(&self, path: &Path) -> Result<Vec<String>> {
+        let mut files = Vec::new();
+        if path.is_dir() {
+            for entry in std::fs::read_dir(path)? {
+                let file = entry?.path();
+                if matches!(file.extension().and_then(|e| e.to_str()), Some("yaml" | "yml")) {
+                    files.push(file);
+                }
+            }
+            files.sort();
+        } else {
+            files.push(path.to_path_buf());
+        }
+
+        let mut names = Vec::new();
+        for file in files {
+            let content = std::fs::read_to_string(&file)?;
+            for document in serde_yaml::Deserializer::from_str(&content) {
+                let value = serde_yaml::Value::deserialize(document).map_err(|e| {
+                    Error::Configuration(format!("{}: {}", file.display(), e))
+                })?;
+                if value.is_null() {
+                    continue;
+                }
+                let policy: StoragePolicy = serde_yaml::from_value(value).map_err(|e| {
+                    Error::Configuration(format!("{}: invalid StoragePolicy: {}", file.display(), e))
+                })?;
+                names.push(self.apply(&policy)?);
+            }
+        }
+
+        Ok(names)
+    }
+
+    /// Evaluate every rule against a request
+    pub fn evaluate(&self, request: &ProvisionRequest, caller: &CallerIdentity) -> AdmissionDecision {
+        let mut decision = AdmissionDecision::default();
+
+        for (policy, rules) in self.policies.read().iter() {
+            for rule in rules {
+                if !rule.conditions.iter().all(|c| c.matches(request, caller)) {
+                    continue;
+                }
+                let violation = PolicyViolation {
+                    policy: policy.clone(),
+                    rule: rule.name.clone(),
+                    effect: rule.effect,
+                    message: rule.message.clone(),
+                };
+                match rule.effect {
+                    PolicyEffect::Deny if decision.denied_by.is_none() => {
+                        decision.denied_by = Some(violation)
+                    }
+                    PolicyEffect::Deny => {}
+                    PolicyEffect::Warn => decision.warnings.push(violation),
+                }
+            }
+        }
+
+        decision
+    }
+
+    /// Evaluate a request, log the decision and fail if it is denied
+    ///
+    /// Returns the warnings of an admitted request.
+    pub fn admit(&self, request: &ProvisionRequest, caller: &CallerIdentity) -> Result<Vec<PolicyViolation>> {
+        let decision = self.evaluate(request, caller);
+
+        for warning in &decision.warnings {
+            warn!(
+                "Admission warning for {} by {}: {}",
+                request.name, caller, warning
+            );
+        }
+
+        match decision.denied_by {
+            Some(denial) => {
+                warn!("Admission denied for {} by {}: {}", request.name, caller, denial);
+                Err(Error::PolicyDenied {
+                    policy: denial.policy,
+                    rule: denial.rule,
+                    message: denial.message,
+                })
+            }
+            None => {
+                info!(
+                    "Admission allowed for {} by {} ({} warnings)",
+                    request.name,
+                    caller,
+                    decision.warnings.len()
+                );
+                Ok(decision.warnings)
+            }
+        }
+    }
+}
+
+// =============================================================================
+// Resource Watcher
+// =============================================================================
+
+/// `StoragePolicy` resources installed in a policy engine
+///
+/// A resource replaces a file-loaded policy of the same name. A resource
+/// whose rules fail to compile leaves the previous rules in place.
+pub struct PolicyResources {
+    policies: Arc<PolicyEngine>,
+    names: BTreeSet<String>,
+}
+
+impl PolicyResources {
+    pub fn new(policies: Arc<PolicyEngine>) -> Self {
+        Self {
+            policies,
+            names: BTreeSet::new(),
+        }
+    }
+
+    /// Install an added or changed resource
+    pub fn apply(&mut self, policy: &StoragePolicy) -> Result<String> {
+        let name = self.policies.apply(policy)?;
+        self.names.insert(name.clone());
+        Ok(name)
+    }
+
+    /// Remove a deleted resource's policy
+    pub fn remove(&mut self, policy: &StoragePolicy) {
+        if let Some(name) = &policy.metadata.name {
+            if self.names.remove(name) {
+                info!("Removing storage policy {}", name);
+                self.policies.remove_policy(name);
+            }
+        }
+    }
+
+    /// Replace every watched resource with `policies` (after a watch restart)
+    pub fn replace(&mut self, policies: &[StoragePolicy]) {
+        let current: BTreeSet<&str> = policies
+            .iter()
+            .filter_map(|p| p.metadata.name.as_deref())
+            .collect();
+        let gone: Vec<String> = self
+            .names
+            .iter()
+            .filter(|name| !current.contains(name.as_str()))
+            .cloned()
+            .collect();
+        for name in gone {
+            self.names.remove(&name);
+            self.policies.remove_policy(&name);
+        }
+        for policy in policies {
+            if let Err(e) = self.apply(policy) {
+                warn!("Ignoring StoragePolicy: {}", e);
+            }
+        }
+    }
+}
+
+/// Follow `StoragePolicy` resources in the cluster until shutdown
+pub async fn watch_policy_resources(
+    policies: Arc<PolicyEngine>,
+    client: kube::Client,
+    mut shutdown: broadcast::Receiver<()>,
+) {
+    let api: Api<StoragePolicy> = Api::all(client);
+    let mut events = watcher::watcher(api, watcher::Config::default()).boxed();
+    let mut resources = PolicyResources::new(policies);
+    info!("Watching StoragePolicy resources");
+
+    loop {
+        tokio::select! {
+            event = events.next() => match event {
+                Some(Ok(Event::Applied(policy))) => {
+                    if let Err(e) = resources.apply(&policy) {
+                        warn!("Ignoring StoragePolicy: {}", e);
+                    }
+                }
+                Some(Ok(Event::Deleted(policy))) => resources.remove(&policy),
+                Some(Ok(Event::Restarted(policies))) => {
+                    info!("Loaded {} StoragePolicy resources", policies.len());
+                    resources.replace(&policies);
+                }
+                // The watcher retries on its own
+                Some(Err(e)) => warn!("StoragePolicy watch error: {}", e),
+                None => break,
+            },
+            _ = shutdown.recv() => {
+                debug!("StoragePolicy watcher shutting down");
+                break;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::ports::PARAM_VERSIONING;
+
+    fn rule(name: &str, effect: PolicyEffect, when: &[&str]) -> PolicyRule {
+        PolicyRule {
+            name: name.into(),
+            effect,
+            when: when.iter().map(|c| c.to_string()).collect(),
+            message: None,
+        }
+    }
+
+    fn request(storage_type: StorageType, tier: StorageTier, capacity: &str, env: &str) -> ProvisionRequest {
+        ProvisionRequest {
+            request_id: "req".into(),
+            name: "vol".into(),
+            storage_type,
+            capacity_bytes: parse_capacity(capacity).unwrap(),
+            tier: Some(tier),
+            max_iops: None,
+            labels: BTreeMap::from([("env".to_string(), env.to_string())]),
+            annotations: BTreeMap::new(),
+            tenant: None,
+            platform_params: BTreeMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_parse_conditions() {
+        assert!("labels.env == prod".parse::<Condition>().is_ok());
+        assert!("tier in (hot, \"warm\")".parse::<Condition>().is_ok());
+        assert!("params.versioning missing".parse::<Condition>().is_ok());
+        assert!("capacity > 2Ti".parse::<Condition>().is_ok());
+
+        assert!("tier > hot".parse::<Condition>().is_err());
+        assert!("tier == lukewarm".parse::<Condition>().is_err());
+        assert!("colour == red".parse::<Condition>().is_err());
+        assert!("labels.env ==".parse::<Condition>().is_err());
+        assert!("tier in hot".parse::<Condition>().is_err());
+        assert!("labels.env exists prod".parse::<Condition>().is_err());
+    }
+
+    #[test]
+    fn test_evaluate_rules() {
+        let engine = PolicyEngine::new();
+        engine
+            .set_policy(
+                "platform",
+                &[
+                    rule("dev-cold-only", PolicyEffect::Deny, &["labels.env == dev", "tier != cold"]),
+                    rule(
+                        "prod-versioning",
+                        PolicyEffect::Deny,
+                        &["labels.env == prod", "storageType == object", "params.versioning != true"],
+                    ),
+                    rule("hot-max", PolicyEffect::Deny, &["tier == hot", "capacity > 2Ti"]),
+                    rule("big-volume", PolicyEffect::Warn, &["capacity >= 1Ti", "group notin (storage-admins)"]),
+                ],
+            )
+            .unwrap();
+        let anonymous = CallerIdentity::default();
+
+        let denied = |r: &ProvisionRequest, caller: &CallerIdentity| {
+            engine.evaluate(r, caller).denied_by.map(|d| d.rule)
+        };
+
+        let dev_hot = request(StorageType::Block, StorageTier::Hot, "10Gi", "dev");
+        assert_eq!(denied(&dev_hot, &anonymous).as_deref(), Some("dev-cold-only"));
+        let dev_cold = request(StorageType::Block, StorageTier::Cold, "10Gi", "dev");
+        assert!(engine.evaluate(&dev_cold, &anonymous).allowed());
+
+        let mut bucket = request(StorageType::Object, StorageTier::Warm, "10Gi", "prod");
+        assert_eq!(denied(&bucket, &anonymous).as_deref(), Some("prod-versioning"));
+        bucket.platform_params.insert(PARAM_VERSIONING.into(), "true".into());
+        assert!(engine.evaluate(&bucket, &anonymous).allowed());
+
+        let huge = request(StorageType::Block, StorageTier::Hot, "3Ti", "prod");
+        let err = engine.admit(&huge, &anonymous).unwrap_err();
+        assert!(matches!(err, Error::PolicyDenied { ref rule, .. } if rule == "hot-max"));
+
+        let big = request(StorageType::Block, StorageTier::Hot, "1Ti", "prod");
+        assert_eq!(engine.admit(&big, &anonymous).unwrap().len(), 1);
+        let admin = CallerIdentity {
+            user: Some("alice".into()),
+            groups: vec!["storage-admins".into()],
+        };
+        assert!(engine.admit(&big, &admin).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_load_manifests() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("policies.yaml"),
+            r#"
+apiVersion: storage.billyronks.io/v1
+kind: StoragePolicy
+metadata:
+  name: limits
+spec:
+  rules:
+    - name: hot-max
+      when: ["tier == hot", "capacity > 2Ti"]
+      message: hot volumes are limited to 2Ti
+---
+apiVersion: storage.billyronks.io/v1
+kind: StoragePolicy
+metadata:
+  name: hygiene
+spec:
+  rules:
+    - name: owner-label
+      effect: warn
+      when: ["labels.owner missing"]
+"#,
+        )
+        .unwrap();
+        std::fs::write(dir.path().join("README.txt"), "ignored").unwrap();
+
+        let engine = PolicyEngine::new();
+        let names = engine.load_manifests(dir.path()).unwrap();
+        assert_eq!(names, vec!["limits", "hygiene"]);
+
+        let decision = engine.evaluate(
+            &request(StorageType::Block, StorageTier::Hot, "4Ti", "prod"),
+            &CallerIdentity::default(),
+        );
+        assert_eq!(decision.denied_by.unwrap().message, "hot volumes are limited to 2Ti");
+        assert_eq!(decision.warnings[0].rule, "owner-label");
+    }
+
+    #[test]
+    fn test_watched_resources_follow_changes() {
+        let policy = |name: &str, when: &[&str]| {
+            StoragePolicy::new(
+                name,
+                crate::crd::StoragePolicySpec {
+                    rules: vec![rule("r", PolicyEffect::Deny, when)],
+                },
+            )
+        };
+        let engine = Arc::new(PolicyEngine::new());
+        engine.set_policy("from-file", &[]).unwrap();
+        let mut resources = PolicyResources::new(engine.clone());
+
+        resources.apply(&policy("dev", &["labels.env == dev"])).unwrap();
+        resources.apply(&policy("prod", &["labels.env == prod"])).unwrap();
+        let dev = request(StorageType::Block, StorageTier::Hot, "1Gi", "dev");
+        assert!(!engine.evaluate(&dev, &CallerIdentity::default()).allowed());
+
+        // A broken edit keeps the previous rules
+        assert!(resources.apply(&policy("dev", &["bogus ~~ x"])).is_err());
+        assert!(!engine.evaluate(&dev, &CallerIdentity::default()).allowed());
+
+        resources.remove(&policy("dev", &[]));
+        assert!(engine.evaluate(&dev, &CallerIdentity::default()).allowed());
+
+        // A restart drops resources that no longer exist, not file policies
+        resources.replace(&[policy("test", &["labels.env == test"])]);
+        assert_eq!(engine.policy_names(), vec!["from-file", "test"]);
+    }
+}
@@ -0,0 +1,637 @@
+//! Tenant Quotas
+//!
+//! Limits what each tenant (namespace) may provision: bytes per tier, total
+//! bytes, volume count and maximum single-volume size. Usage is charged when
+//! capacity is reserved, before any backend work, and checked and charged
+//! under a single lock so concurrent requests cannot overshoot a limit.
+//! Failed or compensated operations release their charge.
+//!
+//! Limits come from `StorageQuota` manifests loaded at startup and from
+//! `StorageQuota` resources followed through a Kubernetes watch, which
+//! also writes each tenant's usage back to the resource status.
+
+use crate::crd::{StorageQuota, StorageQuotaStatus};
+use crate::domain::capacity::parse_capacity;
+use crate::domain::ports::StorageTier;
+use crate::error::{Error, Result};
+use futures::StreamExt;
+use kube::api::{Patch, PatchParams};
+use kube::runtime::watcher::{self, Event};
+use kube::Api;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::broadcast;
+use tracing::{debug, info, warn};
+
+// =============================================================================
+// Constants
+// =============================================================================
+
+/// Tenant charged for requests that do not name one
+pub const DEFAULT_TENANT: &str = "default";
+
+/// How often usage is written to watched `StorageQuota` resources
+pub const QUOTA_STATUS_INTERVAL: Duration = Duration::from_secs(30);
+
+// =============================================================================
+// Types
+// =============================================================================
+
+/// Limits for one tenant (unset limits are unbounded)
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QuotaLimits {
+    /// Bytes allowed per tier
+    #[serde(default)]
+    pub tier_bytes: BTreeMap<StorageTier, u64>,
+    /// Bytes allowed across all tiers
+    #[serde(default)]
+    pub total_bytes: Option<u64>,
+    /// Number of volumes allowed
+    #[serde(default)]
+    pub max_volumes: Option<u64>,
+    /// Largest single volume allowed
+    #[serde(default)]
+    pub max_volume_bytes: Option<u64>,
+}
+
+impl QuotaLimits {
+    /// Convert a `StorageQuota` spec
+    pub fn from_spec(spec: &crate::crd::StorageQuotaSpec) -> Result<Self> {
+        let mut tier_bytes = BTreeMap::new();
+        for (tier, capacity) in &spec.tier_capacity {
+            tier_bytes.insert(tier.parse::<StorageTier>()?, parse_capacity(capacity)?);
+        }
+
+        Ok(Self {
+            tier_bytes,
+            total_bytes: spec.total_capacity.as_deref().map(parse_capacity).transpose()?,
+            max_volumes: spec.max_volumes,
+            max_volume_bytes: spec.max_volume_size.as_deref().map(parse_capacity).transpose()?,
+        })
+    }
+}
+
+/// What a tenant currently has charged
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QuotaUsage {
+    /// Bytes charged per tier
+    pub tier_bytes: BTreeMap<StorageTier, u64>,
+    /// Bytes charged across all tiers
+    pub total_bytes: u64,
+    /// Volumes charged
+    pub volumes: u64,
+}
+
+impl From<&QuotaUsage> for StorageQuotaStatus {
+    fn from(usage: &QuotaUsage) -> Self {
+        Self {
+            used_by_tier: usage
+                .tier_bytes
+                .iter()
+                .map(|(tier, bytes)| (tier.to_string(), *bytes))
+                .collect(),
+            used_bytes: usage.total_bytes,
+            volume_count: usage.volumes,
+            last_updated: Some(chrono::Utc::now()),
+        }
+    }
+}
+
+/// Limits and usage for one tenant
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TenantQuota {
+    pub tenant: String,
+    /// None if the tenant has no quota
+    pub limits: Option<QuotaLimits>,
+    pub usage: QuotaUsage,
+}
+
+/// An amount charged to a tenant, kept so it can be released later
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QuotaCharge {
+    pub tenant: String,
+    /// Tier charged (None counts toward the total only)
+    pub tier: Option<StorageTier>,
+    pub bytes: u64,
+    pub volumes: u64,
+}
+
+impl QuotaCharge {
+    /// Charge for `bytes` of storage; `tenant` None means `DEFAULT_TENANT`
+    pub fn new(tenant: Option<&str>, tier: Option<StorageTier>, bytes: u64, volumes: u64) -> Self {
+        Self {
+            tenant: tenant.unwrap_or(DEFAULT_TENANT).to_string(),
+            tier,
+            bytes,
+            volumes,
+        }
+    }
+}
+
+// =============================================================================
+// Quota Manager
+// =============================================================================
+
+#[derive(Debug, Default)]
+struct QuotaState {
+    limits: BTreeMap<String, QuotaLimits>,
+    usage: BTreeMap<String, QuotaUsage>,
+}
+
+/// Tracks tenant limits and usage
+#[derive(Debug, Default)]
+pub struct QuotaManager {
+    state: Mutex<QuotaState>,
+}
+
+impl QuotaManager {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set (or replace) a tenant's limits
+    pub fn set_limits(&self, tenant: &str, limits: QuotaLimits) {
+        info!("Setting storage quota for tenant {}", tenant);
+        self.state.lock().limits.insert(tenant.to_string(), limits);
+    }
+
+    /// Remove a tenant's limits; usage keeps being tracked
+    pub fn remove_limits(&self, tenant: &str) -> bool {
+        self.state.lock().limits.remove(tenant).is_some()
+    }
+
+    /// Apply a `StorageQuota` resource, returning the tenant it applies to
+    pub fn apply(&self, quota: &StorageQuota) -> Result<String> {
+        let tenant = quota
+            .spec
+            .tenant
+            .clone()
+            .or_else(|| quota.metadata.namespace.clone())
+            .ok_or_else(|| {
+                Error::Configuration(format!(
+                    "StorageQuota {} names no tenant or namespace",
+                    quota.metadata.name.as_deref().unwrap_or("<unnamed>")
+                ))
+            })?;
+        self.set_limits(&tenant, QuotaLimits::from_spec(&quota.spec)?);
+        Ok(tenant)
+    }
+
+    /// Apply every `StorageQuota` in a (multi-document) YAML file
+    pub fn load_manifests(&self, path: &Path) -> Result<Vec<String>> {
+        let content = std::fs::read_to_string(path)?;
+        let mut tenants = Vec::new();
+
+        for document in serde_yaml::Deserializer::from_str(&content) {
+            let value = serde_yaml::Value::deserialize(document).map_err(|e| {
+                Error::Configuration(format!("{}: {}", path.display(), e))
+            })?;
+            if value.is_null() {
+                continue;
+            }
+            let quota: StorageQuota = serde_yaml::from_value(value).map_err(|e| {
+                Error::Configuration(format!("{}: invalid StorageQuota: {}", path.display(), e))
+            })?;
+            tenants.push(self.apply(&quota)?);
+        }
+
+        Ok(tenants)
+    }
+
+    /// Check a charge against the tenant's limits and take it atomically
+    ///
+    /// `volume_bytes` is the resulting size of the single volume involved
+    /// and is checked against `max_volume_bytes`.
+    pub fn reserve(&self, charge: &QuotaCharge, volume_bytes: u64) -> Result<()> {
+        let mut state = self.state.lock();
+        let usage = state.usage.get(&charge.tenant).cloned().unwrap_or_default();
+
+        if let Some(limits) = state.limits.get(&charge.tenant) {
+            let exceeded = |reason: String| {
+                Err(Error::QuotaExceeded {
+                    tenant: charge.tenant.clone(),
+                    reason,
+                })
+            };
+
+            if let Some(max) = limits.max_volume_bytes {
+                if volume_bytes > max {
+                    return exceeded(format!(
+                        "volume size {} bytes exceeds the {} byte maximum",
+                        volume_bytes, max
+                    ));
+                }
+            }
+            if let Some(max) = limits.max_volumes {
+                if usage.volumes + charge.volumes > max {
+                    return exceeded(format!("{} of {} volumes already used", usage.volumes, max));
+                }
+            }
+            if let Some(max) = limits.total_bytes {
+                if usage.total_bytes + charge.bytes > max {
+                    return exceeded(format!(
+                        "{} of {} bytes already used, {} more requested",
+                        usage.total_bytes, max, charge.bytes
+                    ));
+                }
+            }
+            if let Some(tier) = charge.tier {
+                if let Some(max) = limits.tier_bytes.get(&tier) {
+                    let used = usage.tier_bytes.get(&tier).copied().unwrap_or(0);
+                    if used + charge.bytes > *max {
+                        return exceeded(format!(
+                            "{} of {} {} bytes already used, {} more requested",
+                            used, max, tier, charge.bytes
+                        ));
+                    }
+                }
+            }
+        }
+
+        Self::add(&mut state, charge);
+        Ok(())
+    }
+
+    /// Move a charge to another tier, checking only the target tier's limit
+    pub fn retier(&self, charge: &QuotaCharge, tier: Option<StorageTier>) -> Result<()> {
+        if tier == charge.tier {
+            return Ok(());
+        }
+        let mut state = self.state.lock();
+
+        if let (Some(target), Some(limits)) = (tier, state.limits.get(&charge.tenant)) {
+            if let Some(max) = limits.tier_bytes.get(&target) {
+                let used = state
+                    .usage
+                    .get(&charge.tenant)
+                    .and_then(|u| u.tier_bytes.get(&target))
+                    .copied()
+                    .unwrap_or(0);
+                if used + charge.bytes > *max {
+                    return Err(Error::QuotaExceeded {
+                        tenant: charge.tenant.clone(),
+                        reason: format!(
+                            "{} of {} {} bytes already used, {} more requested",
+                            used, max, target, charge.bytes
+                        ),
+                    });
+                }
+            }
+        }
+
+        Self::remove(&mut state, charge);
+        Self::add(&mut state, &QuotaCharge { tier, ..charge.clone() });
+        Ok(())
+    }
+
+    /// Take a charge without checking limits (e.g. for imported storage)
+    pub fn charge(&self, charge: &QuotaCharge) {
+        Self::add(&mut self.state.lock(), charge);
+    }
+
+    /// Return a previously taken charge
+    pub fn release(&self, charge: &QuotaCharge) {
+        Self::remove(&mut self.state.lock(), charge);
+    }
+
+    /// Limits and usage for one tenant
+    pub fn tenant(&self, tenant: &str) -> TenantQuota {
+        let state = self.state.lock();
+        TenantQuota {
+            tenant: tenant.to_string(),
+            limits: state.limits.get(tenant).cloned(),
+            usage: state.usage.get(tenant).cloned().unwrap_or_default(),
+        }
+    }
+
+    /// Limits and usage for every tenant with either
+    pub fn list(&self) -> Vec<TenantQuota> {
+        let tenants: BTreeSet<String> = {
+            let state = self.state.lock();
+            state.limits.keys().chain(state.usage.keys()).cloned().collect()
+        };
+        tenants.iter().map(|t| self.tenant(t)).collect()
+    }
+
+    fn remove(state: &mut QuotaState, charge: &QuotaCharge) {
+        let Some(usage) = state.usage.get_mut(&charge.tenant) else {
+            return;
+        };
+
+        usage.total_bytes = usage.total_bytes.saturating_sub(charge.bytes);
+        usage.volumes = usage.volumes.saturating_sub(charge.volumes);
+        if let Some(tier) = charge.tier {
+            if let Some(bytes) = usage.tier_bytes.get_mut(&tier) {
+                *bytes = bytes.saturating_sub(charge.bytes);
+                if *bytes == 0 {
+                    usage.tier_bytes.remove(&tier);
+                }
+            }
+        }
+    }
+
+    fn add(state: &mut QuotaState, charge: &QuotaCharge) {
+        let usage = state.usage.entry(charge.tenant.clone()).or_default();
+        usage.total_bytes += charge.bytes;
+        usage.volumes += charge.volumes;
+        if let Some(tier) = charge.tier {
+            *usage.tier_bytes.entry(tier).or_default() += charge.bytes;
+        }
+    }
+}
+
+// =============================================================================
+// Resource Watcher
+// =============================================================================
+
+/// Namespace and name of a `StorageQuota` resource
+type ResourceKey = (String, String);
+
+/// A watched resource and the usage last written to its status
+#[derive(Debug)]
+struct WatchedQuota {
+    tenant: String,
+    written: Option<QuotaUsage>,
+}
+
+/// `StorageQuota` resources applied to a quota manager
+///
+/// Deleting the last resource for a tenant removes the tenant's limits;
+/// usage keeps being tracked.
+pub struct QuotaResources {
+    quotas: Arc<QuotaManager>,
+    watched: BTreeMap<ResourceKey, WatchedQuota>,
+}
+
+impl QuotaResources {
+    pub fn new(quotas: Arc<QuotaManager>) -> Self {
+        Self {
+            quotas,
+            watched: BTreeMap::new(),
+        }
+    }
+
+    fn key(quota: &StorageQuota) -> ResourceKey {
+        (
+            quota.metadata.namespace.clone().unwrap_or_default(),
+            quota.metadata.name.clone().unwrap_or_default(),
+        )
+    }
+
+    /// Apply an added or changed resource, returning its tenant
+    pub fn apply(&mut self, quota: &StorageQuota) -> Result<String> {
+        let tenant = self.quotas.apply(quota)?;
+        let key = Self::key(quota);
+        if let Some(previous) = self.watched.get(&key) {
+            if previous.tenant != tenant {
+                let previous = previous.tenant.clone();
+                self.watched.remove(&key);
+                self.release_tenant(&previous);
+            }
+        }
+        self.watched.insert(
+            key,
+            WatchedQuota {
+                tenant: tenant.clone(),
+                written: None,
+            },
+        );
+        Ok(tenant)
+    }
+
+    /// Forget a deleted resource
+    pub fn remove(&mut self, quota: &StorageQuota) {
+        if let Some(watched) = self.watched.remove(&Self::key(quota)) {
+            self.release_tenant(&watched.tenant);
+        }
+    }
+
+    /// Replace every watched resource with `quotas` (after a watch restart)
+    pub fn replace(&mut self, quotas: &[StorageQuota]) {
+        let current: BTreeSet<ResourceKey> = quotas.iter().map(Self::key).collect();
+        let gone: Vec<ResourceKey> = self
+            .watched
+            .keys()
+            .filter(|key| !current.contains(*key))
+            .cloned()
+            .collect();
+        for key in gone {
+            if let Some(watched) = self.watched.remove(&key) {
+                self.release_tenant(&watched.tenant);
+            }
+        }
+        for quota in quotas {
+            if let Err(e) = self.apply(quota) {
+                warn!("Ignoring StorageQuota: {}", e);
+            }
+        }
+    }
+
+    /// Remove a tenant's limits once no watched resource names it
+    fn release_tenant(&self, tenant: &str) {
+        if !self.watched.values().any(|w| w.tenant == tenant) {
+            info!("Removing storage quota for tenant {}", tenant);
+            self.quotas.remove_limits(tenant);
+        }
+    }
+
+    /// Resources whose tenant usage changed since their status was written
+    pub fn stale_statuses(&self) -> Vec<(ResourceKey, QuotaUsage)> {
+        self.watched
+            .iter()
+            .filter_map(|(key, watched)| {
+                let usage = self.quotas.tenant(&watched.tenant).usage;
+                (watched.written.as_ref() != Some(&usage)).then(|| (key.clone(), usage))
+            })
+            .collect()
+    }
+
+    /// Note that `usage` was written to a resource's status
+    pub fn mark_written(&mut self, key: &ResourceKey, usage: QuotaUsage) {
+        if let Some(watched) = self.watched.get_mut(key) {
+            watched.written = Some(usage);
+        }
+    }
+}
+
+/// Follow `StorageQuota` resources in the cluster until shutdown, writing
+/// tenant usage to their status every [`QUOTA_STATUS_INTERVAL`]
+pub async fn watch_quota_resources(
+    quotas: Arc<QuotaManager>,
+    client: kube::Client,
+    mut shutdown: broadcast::Receiver<()>,
+) {
+    let api: Api<StorageQuota> = Api::all(client.clone());
+    let mut events = watcher::watcher(api, watcher::Config::default()).boxed();
+    let mut resources = QuotaResources::new(quotas);
+    let mut status_interval = tokio::time::interval(QUOTA_STATUS_INTERVAL);
+    info!("Watching StorageQuota resources");
+
+    loop {
+        tokio::select! {
+            event = events.next() => match event {
+                Some(Ok(Event::Applied(quota))) => {
+                    if let Err(e) = resources.apply(&quota) {
+                        warn!("Ignoring StorageQuota: {}", e);
+                    }
+                }
+                Some(Ok(Event::Deleted(quota))) => resources.remove(&quota),
+                Some(Ok(Event::Restarted(quotas))) => {
+                    info!("Loaded {} StorageQuota resources", quotas.len());
+                    resources.replace(&quotas);
+                }
+                // The watcher retries on its own
+                Some(Err(e)) => warn!("StorageQuota watch error: {}", e),
+                None => break,
+            },
+            _ = status_interval.tick() => {
+                for ((namespace, name), usage) in resources.stale_statuses() {
+                    let api: Api<StorageQuota> = Api::namespaced(client.clone(), &namespace);
+                    let status = StorageQuotaStatus::from(&usage);
+                    let patch = Patch::Merge(serde_json::json!({ "status": status }));
+                    match api.patch_status(&name, &PatchParams::default(), &patch).await {
+                        Ok(_) => resources.mark_written(&(namespace, name), usage),
+                        Err(e) => warn!("Failed to update StorageQuota {}/{} status: {}", namespace, name, e),
+                    }
+                }
+            }
+            _ = shutdown.recv() => {
+                debug!("StorageQuota watcher shutting down");
+                break;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crd::StorageQuotaSpec;
+
+    const GI: u64 = 1024 * 1024 * 1024;
+
+    #[test]
+    fn test_reserve_enforces_limits() {
+        let quotas = QuotaManager::new();
+        quotas.set_limits(
+            "team-a",
+            QuotaLimits {
+                tier_bytes: BTreeMap::from([(StorageTier::Hot, 100 * GI)]),
+                max_volumes: Some(2),
+                max_volume_bytes: Some(80 * GI),
+                ..Default::default()
+            },
+        );
+
+        let hot = |bytes| QuotaCharge::new(Some("team-a"), Some(StorageTier::Hot), bytes, 1);
+        quotas.reserve(&hot(60 * GI), 60 * GI).unwrap();
+
+        // Tier limit
+        let err = quotas.reserve(&hot(60 * GI), 60 * GI).unwrap_err();
+        assert!(matches!(err, Error::QuotaExceeded { .. }));
+        // Single-volume limit
+        assert!(quotas.reserve(&hot(90 * GI), 90 * GI).is_err());
+        // Other tiers are not limited
+        let warm = QuotaCharge::new(Some("team-a"), Some(StorageTier::Warm), 500 * GI, 1);
+        assert!(quotas.reserve(&warm, 60 * GI).is_ok());
+        // Volume count
+        assert!(quotas.reserve(&hot(GI), GI).is_err());
+
+        quotas.release(&warm);
+        quotas.reserve(&hot(40 * GI), 40 * GI).unwrap();
+
+        let usage = quotas.tenant("team-a").usage;
+        assert_eq!(usage.volumes, 2);
+        assert_eq!(usage.tier_bytes[&StorageTier::Hot], 100 * GI);
+        assert!(!usage.tier_bytes.contains_key(&StorageTier::Warm));
+
+        // Tenants without limits are tracked but not restricted
+        quotas.reserve(&QuotaCharge::new(None, None, 10_000 * GI, 1), 10_000 * GI).unwrap();
+        assert_eq!(quotas.list().len(), 2);
+    }
+
+    #[test]
+    fn test_apply_storage_quota() {
+        let mut quota = StorageQuota::new(
+            "limits",
+            StorageQuotaSpec {
+                tenant: None,
+                tier_capacity: BTreeMap::from([("hot".to_string(), "500Gi".to_string())]),
+                total_capacity: Some("2Ti".into()),
+                max_volumes: Some(10),
+                max_volume_size: Some("100Gi".into()),
+            },
+        );
+        let quotas = QuotaManager::new();
+        assert!(quotas.apply(&quota).is_err());
+
+        quota.metadata.namespace = Some("team-b".into());
+        assert_eq!(quotas.apply(&quota).unwrap(), "team-b");
+
+        let limits = quotas.tenant("team-b").limits.unwrap();
+        assert_eq!(limits.tier_bytes[&StorageTier::Hot], 500 * GI);
+        assert_eq!(limits.total_bytes, Some(2048 * GI));
+        assert_eq!(limits.max_volume_bytes, Some(100 * GI));
+
+        quota.spec.tier_capacity.insert("lukewarm".into(), "1Gi".into());
+        assert!(quotas.apply(&quota).is_err());
+    }
+
+    #[test]
+    fn test_watched_resources_follow_changes() {
+        let quota = |namespace: &str, name: &str, hot: &str| {
+            let mut quota = StorageQuota::new(
+                name,
+                StorageQuotaSpec {
+                    tenant: None,
+                    tier_capacity: BTreeMap::from([("hot".to_string(), hot.to_string())]),
+                    total_capacity: None,
+                    max_volumes: None,
+                    max_volume_size: None,
+                },
+            );
+            quota.metadata.namespace = Some(namespace.into());
+            quota
+        };
+        let quotas = Arc::new(QuotaManager::new());
+        let mut resources = QuotaResources::new(quotas.clone());
+
+        resources.apply(&quota("team-a", "limits", "10Gi")).unwrap();
+        resources.apply(&quota("team-b", "limits", "20Gi")).unwrap();
+        assert_eq!(
+            quotas.tenant("team-a").limits.unwrap().tier_bytes[&StorageTier::Hot],
+            10 * GI
+        );
+
+        // Every resource needs its status written once, then only on change
+        let stale = resources.stale_statuses();
+        assert_eq!(stale.len(), 2);
+        for (key, usage) in stale {
+            resources.mark_written(&key, usage);
+        }
+        assert!(resources.stale_statuses().is_empty());
+        quotas.charge(&QuotaCharge::new(Some("team-a"), Some(StorageTier::Hot), GI, 1));
+        let stale = resources.stale_statuses();
+        assert_eq!(stale.len(), 1);
+        let ((namespace, _), usage) = &stale[0];
+        assert_eq!(namespace, "team-a");
+        assert_eq!(StorageQuotaStatus::from(usage).used_bytes, GI);
+
+        resources.remove(&quota("team-a", "limits", "10Gi"));
+        assert!(quotas.tenant("team-a").limits.is_none());
+        assert_eq!(quotas.tenant("team-a").usage.volumes, 1);
+
+        // A restart drops resources that no longer exist
+        resources.replace(&[quota("team-c", "limits", "5Gi")]);
+        assert!(quotas.tenant("team-b").limits.is_none());
+        assert!(quotas.tenant("team-c").limits.is_some());
+    }
+}
@@ -0,0 +1,601 @@
+//! Drift Reconciler
+//!
+//! Periodically compares the orchestrator's storage records with what each
+//! backend reports through `StorageProvisioner::list()` and produces:
+//! - Orphans: storage a backend has but the orchestrator does not know about
+//! - Ghosts: records whose storage the backend no longer reports
+//! - Capacity drift: records whose capacity differs from the backend's
+//! - Recovered: records flagged lost whose storage the backend reports again
+//!
+//! Repair is opt-in per finding type. Storage that belongs to an in-flight
+//! saga is never treated as an orphan, and an orphan is only repaired once it
+//! has been seen in two consecutive passes.
+
+use crate::controlplane::import::Importer;
+use crate::controlplane::orchestrator::{Orchestrator, StorageRecord};
+use crate::domain::ports::{ProvisionResponse, StorageType};
+use crate::error::Result;
+use chrono::{DateTime, Utc};
+use parking_lot::RwLock;
+use prometheus::{IntCounter, IntCounterVec, Opts};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+use tokio::sync::broadcast;
+use tracing::{info, warn};
+
+// =============================================================================
+// Configuration
+// =============================================================================
+
+/// What to do with storage a backend has but the orchestrator does not
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum OrphanAction {
+    /// Report only
+    #[default]
+    Report,
+    /// Create an orchestrator record for it
+    Adopt,
+    /// Delete it from the backend
+    Delete,
+}
+
+/// What to do with records whose storage has disappeared
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum GhostAction {
+    /// Report only
+    #[default]
+    Report,
+    /// Flag the record as lost, and clear the flag once the storage is back
+    MarkLost,
+}
+
+/// What to do with capacity drift
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum DriftAction {
+    /// Report only
+    #[default]
+    Report,
+    /// Update the record to the backend's capacity
+    Update,
+}
+
+/// Configuration for the reconciler
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReconcilerConfig {
+    /// Seconds between passes
+    pub interval_secs: u64,
+    /// Repair for orphans
+    pub orphan_action: OrphanAction,
+    /// Repair for ghosts
+    pub ghost_action: GhostAction,
+    /// Repair for capacity drift
+    pub drift_action: DriftAction,
+}
+
+impl Default for ReconcilerConfig {
+    fn default() -> Self {
+        Self {
+            interval_secs: 300,
+            orphan_action: OrphanAction::Report,
+            ghost_action: GhostAction::Report,
+            drift_action: DriftAction::Report,
+        }
+    }
+}
+
+// =============================================================================
+// Report
+// =============================================================================
+
+/// Storage present on a backend but unknown to the orchestrator
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OrphanStorage {
+    pub storage_id: String,
+    pub name: String,
+    pub backend: String,
+    pub storage_type: StorageType,
+    pub capacity_bytes: u64,
+    /// Repair applied, if any
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub repair: Option<String>,
+}
+
+/// Record whose storage the backend no longer reports
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GhostRecord {
+    pub storage_id: String,
+    pub name: String,
+    pub backend: String,
+    /// Whether the record was already flagged lost before this pass
+    pub already_lost: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub repair: Option<String>,
+}
+
+/// Record whose capacity differs from the backend's
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CapacityDrift {
+    pub storage_id: String,
+    pub name: String,
+    pub backend: String,
+    pub recorded_bytes: u64,
+    pub actual_bytes: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub repair: Option<String>,
+}
+
+/// Record flagged lost whose storage the backend reports again
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecoveredRecord {
+    pub storage_id: String,
+    pub name: String,
+    pub backend: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub repair: Option<String>,
+}
+
+/// Result of one reconciliation pass
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReconcileReport {
+    pub generated_at: DateTime<Utc>,
+    /// Backends that were compared
+    pub backends_checked: Vec<String>,
+    /// Backends whose listing failed (their records are not judged)
+    pub backends_failed: BTreeMap<String, String>,
+    pub orphans: Vec<OrphanStorage>,
+    pub ghosts: Vec<GhostRecord>,
+    pub drift: Vec<CapacityDrift>,
+    #[serde(default)]
+    pub recovered: Vec<RecoveredRecord>,
+}
+
+impl ReconcileReport {
+    /// Whether the pass found nothing out of place
+    pub fn is_clean(&self) -> bool {
+        self.orphans.is_empty()
+            && self.ghosts.is_empty()
+            && self.drift.is_empty()
+            && self.recovered.is_empty()
+    }
+}
+
+// =============================================================================
+// Metrics
+// =============================================================================
+
+struct ReconcileMetrics {
+    runs: IntCounter,
+    findings: IntCounterVec,
+    repairs: IntCounterVec,
+}
+
+/// Process-wide reconciler counters, registered with the default registry
+fn metrics() -> &'static ReconcileMetrics {
+    static METRICS: OnceLock<ReconcileMetrics> = OnceLock::new();
+
+    METRICS.get_or_init(|| {
+        let runs = IntCounter::new(
+            "unified_control_plane_reconcile_runs_total",
+            "Reconciliation passes completed",
+        )
+        .unwrap();
+        let findings = IntCounterVec::new(
+            Opts::new(
+                "unified_control_plane_reconcile_findings_total",
+                "Orphans, ghosts and capacity drift found by reconciliation",
+            ),
+            &["kind", "backend"],
+        )
+        .unwrap();
+        let repairs = IntCounterVec::new(
+            Opts::new(
+                "unified_control_plane_reconcile_repairs_total",
+                "Repairs applied by reconciliation",
+            ),
+            &["action"],
+        )
+        .unwrap();
+
+        let registry = prometheus::default_registry();
+        let _ = registry.register(Box::new(runs.clone()));
+        let _ = registry.register(Box::new(findings.clone()));
+        let _ = registry.register(Box::new(repairs.clone()));
+
+        ReconcileMetrics {
+            runs,
+            findings,
+            repairs,
+        }
+    })
+}
+
+// =============================================================================
+// Reconciler
+// =============================================================================
+
+/// Compares orchestrator records with backend state
+pub struct Reconciler {
+    config: ReconcilerConfig,
+    orchestrator: Arc<Orchestrator>,
+    /// Most recent report
+    last_report: RwLock<Option<ReconcileReport>>,
+    /// Orphans seen in the previous pass (repair candidates)
+    seen_orphans: RwLock<BTreeSet<String>>,
+}
+
+impl Reconciler {
+    /// Create a new reconciler
+    pub fn new(config: ReconcilerConfig, orchestrator: Arc<Orchestrator>) -> Arc<Self> {
+        Arc::new(Self {
+            config,
+            orchestrator,
+            last_report: RwLock::new(None),
+            seen_orphans: RwLock::new(BTreeSet::new()),
+        })
+    }
+
+    /// Compare records with backends without repairing anything
+    pub async fn scan(&self) -> Result<ReconcileReport> {
+        let records: BTreeMap<String, StorageRecord> = self
+            .orchestrator
+            .list_storage_records()
+            .await
+            .into_iter()
+            .map(|r| (r.id.clone(), r))
+            .collect();
+        let in_flight = self.orchestrator.in_flight_storage().await?;
+
+        let mut report = ReconcileReport {
+            generated_at: Utc::now(),
+            backends_checked: Vec::new(),
+            backends_failed: BTreeMap::new(),
+            orphans: Vec::new(),
+            ghosts: Vec::new(),
+            drift: Vec::new(),
+            recovered: Vec::new(),
+        };
+
+        for (backend_name, backend) in self.orchestrator.backend_provisioners().await {
+            let listed: BTreeMap<String, ProvisionResponse> = match backend.list().await {
+                Ok(list) => list.into_iter().map(|s| (s.storage_id.clone(), s)).collect(),
+                Err(e) => {
+                    warn!("Reconcile: could not list backend {}: {}", backend_name, e);
+                    report.backends_failed.insert(backend_name, e.to_string());
+                    continue;
+                }
+            };
+
+            for (id, storage) in &listed {
+                match records.get(id) {
+                    None if in_flight.contains(id) => {}
+                    None => report.orphans.push(OrphanStorage {
+                        storage_id: id.clone(),
+                        name: storage.name.clone(),
+                        backend: backend_name.clone(),
+                        storage_type: storage.storage_type,
+                        capacity_bytes: storage.capacity_bytes,
+                        repair: None,
+                    }),
+                    Some(record) if record.capacity_bytes != storage.capacity_bytes => {
+                        report.drift.push(CapacityDrift {
+                            storage_id: id.clone(),
+                            name: record.name.clone(),
+                            backend: backend_name.clone(),
+                            recorded_bytes: record.capacity_bytes,
+                            actual_bytes: storage.capacity_bytes,
+                            repair: None,
+                        })
+                    }
+                    Some(_) => {}
+                }
+                if let Some(record) = records.get(id).filter(|r| r.lost) {
+                    report.recovered.push(RecoveredRecord {
+                        storage_id: id.clone(),
+                        name: record.name.clone(),
+                        backend: backend_name.clone(),
+                        repair: None,
+                    });
+                }
+            }
+
+            for record in records.values().filter(|r| r.backend == backend_name) {
+                if !listed.contains_key(&record.id) && !in_flight.contains(&record.id) {
+                    report.ghosts.push(GhostRecord {
+                        storage_id: record.id.clone(),
+                        name: record.name.clone(),
+                        backend: backend_name.clone(),
+                        already_lost: record.lost,
+                        repair: None,
+                    });
+                }
+            }
+
+            report.backends_checked.push(backend_name);
+        }
+
+        Ok(report)
+    }
+
+    /// Run one pass: scan, repair as configured, record metrics and the report
+    pub async fn run_once(&self) -> Result<ReconcileReport> {
+        let mut report = self.scan().await?;
+        self.repair(&mut report).await;
+
+        let m = metrics();
+        m.runs.inc();
+        for o in &report.orphans {
+            m.findings.with_label_values(&["orphan", &o.backend]).inc();
+        }
+        for g in &report.ghosts {
+            m.findings.with_label_values(&["ghost", &g.backend]).inc();
+        }
+        for d in &report.drift {
+            m.findings.with_label_values(&["drift", &d.backend]).inc();
+        }
+        for r in &report.recovered {
+            m.findings.with_label_values(&["recovered", &r.backend]).inc();
+        }
+
+        if report.is_clean() {
+            info!("Reconcile: {} backend(s) consistent", report.backends_checked.len());
+        } else {
+            warn!(
+                "Reconcile: {} orphan(s), {} ghost(s), {} capacity drift(s), {} recovered",
+                report.orphans.len(),
+                report.ghosts.len(),
+                report.drift.len(),
+                report.recovered.len()
+            );
+        }
+
+        *self.last_report.write() = Some(report.clone());
+        Ok(report)
+    }
+
+    /// Most recent report, if a pass has run
+    pub fn last_report(&self) -> Option<ReconcileReport> {
+        self.last_report.read().clone()
+    }
+
+    /// Run passes periodically until shutdown
+    pub async fn run(self: Arc<Self>, mut shutdown: broadcast::Receiver<()>) {
+        let mut interval =
+            tokio::time::interval(Duration::from_secs(self.config.interval_secs.max(1)));
+
+        info!(
+            "Reconciler started (every {}s, orphans: {:?}, ghosts: {:?}, drift: {:?})",
+            self.config.interval_secs,
+            self.config.orphan_action,
+            self.config.ghost_action,
+            self.config.drift_action
+        );
+
+        loop {
+            tokio::select! {
+                _ = interval.tick() => {
+                    if let Err(e) = self.run_once().await {
+                        warn!("Reconcile pass failed: {}", e);
+                    }
+                }
+                _ = shutdown.recv() => {
+                    info!("Reconciler shutting down");
+                    break;
+                }
+            }
+        }
+    }
+
+    /// Apply configured repairs, annotating the report
+    async fn repair(&self, report: &mut ReconcileReport) {
+        let previous = std::mem::replace(
+            &mut *self.seen_orphans.write(),
+            report.orphans.iter().map(|o| o.storage_id.clone()).collect(),
+        );
+
+        for orphan in &mut report.orphans {
+            if self.config.orphan_action == OrphanAction::Report
+                || !previous.contains(&orphan.storage_id)
+            {
+                continue;
+            }
+            orphan.repair = self.repair_orphan(orphan).await;
+        }
+
+        if self.config.ghost_action == GhostAction::MarkLost {
+            for ghost in report.ghosts.iter_mut().filter(|g| !g.already_lost) {
+                if self.orchestrator.mark_storage_lost(&ghost.storage_id).await {
+                    ghost.repair = Some("marked-lost".into());
+                    metrics().repairs.with_label_values(&["mark-lost"]).inc();
+                }
+            }
+            for recovered in &mut report.recovered {
+                if self.orchestrator.clear_storage_lost(&recovered.storage_id).await {
+                    recovered.repair = Some("cleared-lost".into());
+                    metrics().repairs.with_label_values(&["clear-lost"]).inc();
+                }
+            }
+        }
+
+        if self.config.drift_action == DriftAction::Update {
+            for drift in &mut report.drift {
+                if self
+                    .orchestrator
+                    .update_storage_capacity(&drift.storage_id, drift.actual_bytes)
+                    .await
+                {
+                    drift.repair = Some("updated".into());
+                    metrics().repairs.with_label_values(&["update"]).inc();
+                }
+            }
+        }
+    }
+
+    async fn repair_orphan(&self, orphan: &OrphanStorage) -> Option<String> {
+        let backend = self
+            .orchestrator
+            .backend_provisioners()
+            .await
+            .into_iter()
+            .find(|(name, _)| name == &orphan.backend)
+            .map(|(_, b)| b)?;
+
+        match self.config.orphan_action {
+            OrphanAction::Report => None,
+            OrphanAction::Adopt => {
+                let storage = match backend.get(&orphan.storage_id).await {
+                    Ok(Some(storage)) => storage,
+                    Ok(None) => return None,
+                    Err(e) => {
+                        warn!("Reconcile: could not fetch orphan {}: {}", orphan.storage_id, e);
+                        return None;
+                    }
+                };
+                // Adopted like an import, which refuses IDs recorded since the scan
+                let candidate = Importer::new(self.orchestrator.clone())
+                    .backend_candidate(&orphan.backend, &storage);
+                match self.orchestrator.import_storage(candidate.proposed).await {
+                    Ok(_) => {
+                        metrics().repairs.with_label_values(&["adopt"]).inc();
+                        Some("adopted".into())
+                    }
+                    Err(e) => {
+                        warn!("Reconcile: could not adopt orphan {}: {}", orphan.storage_id, e);
+                        None
+                    }
+                }
+            }
+            OrphanAction::Delete => match backend.delete(&orphan.storage_id).await {
+                Ok(()) => {
+                    info!(
+                        "Reconcile: deleted orphan {} from {}",
+                        orphan.storage_id, orphan.backend
+                    );
+                    metrics().repairs.with_label_values(&["delete"]).inc();
+                    Some("deleted".into())
+                }
+                Err(e) => {
+                    warn!("Reconcile: could not delete orphan {}: {}", orphan.storage_id, e);
+                    None
+                }
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::controlplane::import::IMPORTED_FROM_LABEL;
+    use crate::controlplane::orchestrator::OrchestratorConfig;
+    use crate::domain::ports::{ProvisionRequest, StorageTier};
+    use crate::hardware::registry::NodeRegistry;
+
+    fn request(name: &str) -> ProvisionRequest {
+        ProvisionRequest {
+            request_id: format!("req-{}", name),
+            name: name.into(),
+            storage_type: StorageType::Block,
+            capacity_bytes: 10 * 1024 * 1024 * 1024,
+            tier: Some(StorageTier::Hot),
+            max_iops: None,
+            labels: BTreeMap::new(),
+            annotations: BTreeMap::new(),
+            tenant: None,
+            platform_params: BTreeMap::new(),
+        }
+    }
+
+    async fn setup() -> (Arc<Orchestrator>, Arc<dyn crate::domain::ports::StorageProvisioner>) {
+        let orchestrator = Orchestrator::new(OrchestratorConfig::default(), NodeRegistry::new());
+        orchestrator.initialize().await.unwrap();
+        let mayastor = orchestrator
+            .backend_provisioners()
+            .await
+            .into_iter()
+            .find(|(name, _)| name == "mayastor")
+            .unwrap()
+            .1;
+        (orchestrator, mayastor)
+    }
+
+    #[tokio::test]
+    async fn test_detects_orphans_and_ghosts() {
+        let (orchestrator, mayastor) = setup().await;
+
+        // Tracked volume
+        let tracked = orchestrator.provision(request("tracked")).await.unwrap();
+        // Orphan: created directly on the backend
+        let orphan = mayastor.provision(request("orphan")).await.unwrap();
+        // Ghost: deleted behind the orchestrator's back
+        let ghost = orchestrator.provision(request("ghost")).await.unwrap();
+        mayastor.delete(&ghost.storage_id).await.unwrap();
+
+        let reconciler = Reconciler::new(ReconcilerConfig::default(), orchestrator.clone());
+        let report = reconciler.run_once().await.unwrap();
+
+        assert_eq!(report.orphans.len(), 1);
+        assert_eq!(report.orphans[0].storage_id, orphan.storage_id);
+        assert_eq!(report.ghosts.len(), 1);
+        assert_eq!(report.ghosts[0].storage_id, ghost.storage_id);
+        assert!(report.drift.is_empty());
+        assert!(report.orphans[0].repair.is_none());
+
+        // Report-only mode changes nothing
+        assert!(orchestrator.get_storage_record(&tracked.storage_id).await.is_some());
+        assert!(!orchestrator.get_storage_record(&ghost.storage_id).await.unwrap().lost);
+        assert!(reconciler.last_report().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_repairs() {
+        let (orchestrator, mayastor) = setup().await;
+        let orphan = mayastor.provision(request("orphan")).await.unwrap();
+        let ghost = orchestrator.provision(request("ghost")).await.unwrap();
+        mayastor.delete(&ghost.storage_id).await.unwrap();
+
+        let config = ReconcilerConfig {
+            orphan_action: OrphanAction::Adopt,
+            ghost_action: GhostAction::MarkLost,
+            ..Default::default()
+        };
+        let reconciler = Reconciler::new(config, orchestrator.clone());
+
+        // Orphans are only repaired once seen twice
+        let first = reconciler.run_once().await.unwrap();
+        assert!(first.orphans[0].repair.is_none());
+        assert_eq!(first.ghosts[0].repair.as_deref(), Some("marked-lost"));
+        assert!(orchestrator.get_storage_record(&ghost.storage_id).await.unwrap().lost);
+
+        let second = reconciler.run_once().await.unwrap();
+        assert_eq!(second.orphans[0].repair.as_deref(), Some("adopted"));
+        assert!(second.ghosts[0].already_lost);
+
+        let adopted = orchestrator.get_storage_record(&orphan.storage_id).await.unwrap();
+        assert_eq!(adopted.backend, "mayastor");
+        assert_eq!(adopted.labels[IMPORTED_FROM_LABEL], "mayastor");
+
+        let third = reconciler.run_once().await.unwrap();
+        assert!(third.orphans.is_empty());
+        assert!(third.recovered.is_empty());
+
+        // A volume flagged lost in an earlier pass that the backend reports
+        // again is no longer lost
+        orchestrator.mark_storage_lost(&orphan.storage_id).await;
+        let fourth = reconciler.run_once().await.unwrap();
+        assert_eq!(fourth.recovered.len(), 1);
+        assert_eq!(fourth.recovered[0].repair.as_deref(), Some("cleared-lost"));
+        assert!(!orchestrator.get_storage_record(&orphan.storage_id).await.unwrap().lost);
+        assert!(reconciler.run_once().await.unwrap().recovered.is_empty());
+    }
+}
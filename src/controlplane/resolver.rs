@@ -0,0 +1,728 @@
+//! Storage Resolver
+//!
+//! Turns a possibly-`Auto` storage intent into a concrete storage type,
+//! tier and backend. Decisions are driven by access-mode hints, IOPS and
+//! capacity requirements, labels and current pool headroom, and every
+//! choice is recorded with a reason on the provisioning response.
+
+use crate::controlplane::orchestrator::PoolInfo;
+use crate::crd::{UnifiedStorageType, UnifiedTier};
+use crate::domain::ports::{
+    AccessMode, BackendCapabilities, ProvisionRequest, Resolution, StorageTier, StorageType,
+};
+use crate::error::{Error, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+
+// =============================================================================
+// Constants
+// =============================================================================
+
+/// Label that pins the storage type
+pub const STORAGE_TYPE_LABEL: &str = "storage.billyronks.io/type";
+
+/// Label that pins the tier
+pub const STORAGE_TIER_LABEL: &str = "storage.billyronks.io/tier";
+
+// =============================================================================
+// Configuration
+// =============================================================================
+
+/// Configuration for the storage resolver
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "camelCase")]
+pub struct ResolverConfig {
+    /// IOPS requirement at or above which the hot tier is chosen
+    pub hot_iops_threshold: u64,
+    /// IOPS requirement at or above which the warm tier is chosen
+    pub warm_iops_threshold: u64,
+    /// Capacity at or above which `Auto` without access hints resolves to object
+    pub object_capacity_threshold_bytes: u64,
+    /// Tier used when nothing else decides
+    pub default_tier: StorageTier,
+    /// Free space (percent of pool capacity) that must remain after placement
+    pub min_headroom_percent: u32,
+}
+
+impl Default for ResolverConfig {
+    fn default() -> Self {
+        Self {
+            hot_iops_threshold: 10_000,
+            warm_iops_threshold: 1_000,
+            object_capacity_threshold_bytes: 10 * 1024 * 1024 * 1024 * 1024, // 10Ti
+            default_tier: StorageTier::Warm,
+            min_headroom_percent: 10,
+        }
+    }
+}
+
+// =============================================================================
+// Storage Intent
+// =============================================================================
+
+/// What the caller asked for, before resolution
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageIntent {
+    /// Unique identifier for this request
+    pub request_id: String,
+    /// Name for the storage resource
+    pub name: String,
+    /// Requested storage type (may be `Auto`)
+    pub storage_type: UnifiedStorageType,
+    /// Requested tier (may be `Auto`)
+    pub tier: UnifiedTier,
+    /// Explicit backend, if the caller pinned one
+    #[serde(default)]
+    pub backend: Option<String>,
+    /// Capability tags the backend instance must carry
+    #[serde(default)]
+    pub backend_tags: Vec<String>,
+    /// Access modes the workload needs
+    #[serde(default)]
+    pub access_modes: Vec<AccessMode>,
+    /// Desired capacity in bytes
+    pub capacity_bytes: u64,
+    /// Optional IOPS requirement
+    #[serde(default)]
+    pub max_iops: Option<u64>,
+    /// Labels for the provisioned resource
+    #[serde(default)]
+    pub labels: BTreeMap<String, String>,
+    /// Annotations kept on the storage record
+    #[serde(default)]
+    pub annotations: BTreeMap<String, String>,
+    /// Tenant (namespace) the storage is charged to
+    #[serde(default)]
+    pub tenant: Option<String>,
+    /// Platform-specific parameters
+    #[serde(default)]
+    pub platform_params: BTreeMap<String, String>,
+}
+
+impl StorageIntent {
+    /// Build the concrete provision request for a resolution
+    pub fn into_request(self, resolution: &Resolution) -> ProvisionRequest {
+        ProvisionRequest {
+            request_id: self.request_id,
+            name: self.name,
+            storage_type: resolution.storage_type,
+            capacity_bytes: self.capacity_bytes,
+            tier: Some(resolution.tier),
+            max_iops: self.max_iops,
+            labels: self.labels,
+            annotations: self.annotations,
+            tenant: self.tenant,
+            platform_params: self.platform_params,
+        }
+    }
+}
+
+impl From<&ProvisionRequest> for StorageIntent {
+    fn from(request: &ProvisionRequest) -> Self {
+        Self {
+            request_id: request.request_id.clone(),
+            name: request.name.clone(),
+            storage_type: match request.storage_type {
+                StorageType::Block => UnifiedStorageType::Block,
+                StorageType::File => UnifiedStorageType::File,
+                StorageType::Object => UnifiedStorageType::Object,
+            },
+            tier: match request.tier {
+                Some(StorageTier::Hot) => UnifiedTier::Hot,
+                Some(StorageTier::Warm) => UnifiedTier::Warm,
+                Some(StorageTier::Cold) => UnifiedTier::Cold,
+                None => UnifiedTier::Auto,
+            },
+            backend: None,
+            backend_tags: Vec::new(),
+            access_modes: Vec::new(),
+            capacity_bytes: request.capacity_bytes,
+            max_iops: request.max_iops,
+            labels: request.labels.clone(),
+            annotations: request.annotations.clone(),
+            tenant: request.tenant.clone(),
+            platform_params: request.platform_params.clone(),
+        }
+    }
+}
+
+/// A registered backend that could serve a request
+#[derive(Debug, Clone)]
+pub struct BackendCandidate {
+    /// Backend instance name
+    pub name: String,
+    /// Backend kind, e.g. `mayastor`
+    pub kind: String,
+    /// Storage types the backend supports
+    pub supported_types: Vec<StorageType>,
+    /// Relative preference among instances serving the same type
+    pub weight: u32,
+    /// Capability tags
+    pub tags: BTreeSet<String>,
+    /// Optional features the backend honours
+    pub capabilities: BackendCapabilities,
+    /// Whether the backend was healthy when last checked
+    pub healthy: bool,
+}
+
+impl BackendCandidate {
+    /// Create a healthy candidate with the default weight and no tags,
+    /// whose kind is its name
+    pub fn new(name: impl Into<String>, supported_types: Vec<StorageType>) -> Self {
+        let name = name.into();
+        Self {
+            kind: name.clone(),
+            name,
+            supported_types,
+            weight: 100,
+            tags: BTreeSet::new(),
+            capabilities: BackendCapabilities::default(),
+            healthy: true,
+        }
+    }
+}
+
+/// A candidate with its best pool and score
+struct ScoredBackend<'a> {
+    candidate: &'a BackendCandidate,
+    pool: Option<&'a PoolInfo>,
+    headroom: Option<u64>,
+    score: f64,
+}
+
+// =============================================================================
+// Storage Resolver
+// =============================================================================
+
+/// Resolves storage type, tier and backend for provisioning requests
+#[derive(Debug, Clone, Default)]
+pub struct StorageResolver {
+    config: ResolverConfig,
+}
+
+impl StorageResolver {
+    /// Create a new resolver
+    pub fn new(config: ResolverConfig) -> Self {
+        Self { config }
+    }
+
+    /// Resolve an intent against the registered backends and known pools
+    pub fn resolve(
+        &self,
+        intent: &StorageIntent,
+        backends: &[BackendCandidate],
+        pools: &[PoolInfo],
+    ) -> Result<Resolution> {
+        let mut reasons = Vec::new();
+
+        let (storage_type, auto_type) = self.resolve_type(intent, &mut reasons)?;
+        let (tier, auto_tier) = self.resolve_tier(intent, &mut reasons)?;
+        let (backend, pool_name, fallbacks) =
+            self.resolve_backend(intent, storage_type, backends, pools, &mut reasons)?;
+
+        Ok(Resolution {
+            storage_type,
+            tier,
+            backend,
+            pool_name,
+            fallbacks,
+            auto_type,
+            auto_tier,
+            reasons,
+            warnings: Vec::new(),
+        })
+    }
+
+    /// Resolve the storage type
+    fn resolve_type(
+        &self,
+        intent: &StorageIntent,
+        reasons: &mut Vec<String>,
+    ) -> Result<(StorageType, bool)> {
+        let explicit = match intent.storage_type {
+            UnifiedStorageType::Block => Some(StorageType::Block),
+            UnifiedStorageType::File => Some(StorageType::File),
+            UnifiedStorageType::Object => Some(StorageType::Object),
+            UnifiedStorageType::Auto => None,
+        };
+
+        if let Some(storage_type) = explicit {
+            check_access_modes(storage_type, &intent.access_modes)?;
+            reasons.push(format!("storage type {} requested explicitly", storage_type));
+            return Ok((storage_type, false));
+        }
+
+        let modes = &intent.access_modes;
+        let (storage_type, reason) = if modes.contains(&AccessMode::S3) {
+            (StorageType::Object, "access mode S3 requires object storage".to_string())
+        } else if let Some(mode) = modes
+            .iter()
+            .find(|m| matches!(m, AccessMode::ReadWriteMany | AccessMode::ReadOnlyMany))
+        {
+            (StorageType::File, format!("access mode {} requires shared file storage", mode))
+        } else if modes.contains(&AccessMode::ReadWriteOnce) {
+            (StorageType::Block, "access mode RWO maps to block storage".to_string())
+        } else if let Some(value) = intent.labels.get(STORAGE_TYPE_LABEL) {
+            let storage_type: StorageType = value.parse().map_err(|_| {
+                Error::ApiValidation(format!(
+                    "Invalid {} label: {}. Use 'block', 'file', or 'object'",
+                    STORAGE_TYPE_LABEL, value
+                ))
+            })?;
+            (storage_type, format!("label {}={}", STORAGE_TYPE_LABEL, value))
+        } else if let Some(iops) = intent.max_iops {
+            (StorageType::Block, format!("IOPS requirement ({}) maps to block storage", iops))
+        } else if intent.capacity_bytes >= self.config.object_capacity_threshold_bytes {
+            (
+                StorageType::Object,
+                format!(
+                    "capacity {} bytes without access hints maps to object storage",
+                    intent.capacity_bytes
+                ),
+            )
+        } else {
+            (StorageType::Block, "no hints, defaulting to block storage".to_string())
+        };
+
+        check_access_modes(storage_type, modes)?;
+        reasons.push(reason);
+        Ok((storage_type, true))
+    }
+
+    /// Resolve the tier
+    fn resolve_tier(
+        &self,
+        intent: &StorageIntent,
+        reasons: &mut Vec<String>,
+    ) -> Result<(StorageTier, bool)> {
+        let explicit = match intent.tier {
+            UnifiedTier::Hot => Some(StorageTier::Hot),
+            UnifiedTier::Warm => Some(StorageTier::Warm),
+            UnifiedTier::Cold => Some(StorageTier::Cold),
+            UnifiedTier::Auto => None,
+        };
+
+        if let Some(tier) = explicit {
+            reasons.push(format!("tier {} requested explicitly", tier));
+            return Ok((tier, false));
+        }
+
+        let (tier, reason) = if let Some(value) = intent.labels.get(STORAGE_TIER_LABEL) {
+            let tier: StorageTier = value.parse().map_err(|_| {
+                Error::ApiValidation(format!(
+                    "Invalid {} label: {}. Use 'hot', 'warm', or 'cold'",
+                    STORAGE_TIER_LABEL, value
+                ))
+            })?;
+            (tier, format!("label {}={}", STORAGE_TIER_LABEL, value))
+        } else if let Some(iops) = intent.max_iops {
+            let tier = if iops >= self.config.hot_iops_threshold {
+                StorageTier::Hot
+            } else if iops >= self.config.warm_iops_threshold {
+                StorageTier::Warm
+            } else {
+                StorageTier::Cold
+            };
+            (tier, format!("IOPS requirement ({}) maps to tier {}", iops, tier))
+        } else {
+            (
+                self.config.default_tier,
+                format!("no tier hints, using default tier {}", self.config.default_tier),
+            )
+        };
+
+        reasons.push(reason);
+        Ok((tier, true))
+    }
+
+    /// Rank the backend instances that can serve the request
+    ///
+    /// Candidates must support the storage type, carry every required tag,
+    /// advertise the capabilities the request needs and be healthy. Each is scored as `weight * (1 + free_ratio)`, where
+    /// `free_ratio` is the fraction of its best pool left free after placement
+    /// (0 while pool capacity is unknown). The best candidate is chosen and
+    /// the rest become ordered fallbacks.
+    fn resolve_backend(
+        &self,
+        intent: &StorageIntent,
+        storage_type: StorageType,
+        backends: &[BackendCandidate],
+        pools: &[PoolInfo],
+        reasons: &mut Vec<String>,
+    ) -> Result<(String, Option<String>, Vec<String>)> {
+        let candidates: Vec<&BackendCandidate> = backends
+            .iter()
+            .filter(|b| b.supported_types.contains(&storage_type))
+            .filter(|b| intent.backend.as_ref().map(|name| &b.name == name).unwrap_or(true))
+            .filter(|b| intent.backend_tags.iter().all(|tag| b.tags.contains(tag)))
+            .collect();
+
+        if candidates.is_empty() {
+            return Err(match &intent.backend {
+                Some(name) => Error::ApiValidation(format!(
+                    "Backend {} is not registered or does not support {} storage with tags {:?}",
+                    name, storage_type, intent.backend_tags
+                )),
+                None => Error::BackendUnavailable {
+                    backend: storage_type.to_string(),
+                },
+            });
+        }
+
+        // Drop backends that cannot honour the request; if none can, report why
+        let probe = ProvisionRequest {
+            request_id: intent.request_id.clone(),
+            name: intent.name.clone(),
+            storage_type,
+            capacity_bytes: intent.capacity_bytes,
+            tier: None,
+            max_iops: intent.max_iops,
+            labels: BTreeMap::new(),
+            annotations: BTreeMap::new(),
+            tenant: None,
+            platform_params: intent.platform_params.clone(),
+        };
+        let mut rejection = None;
+        let candidates: Vec<&BackendCandidate> = candidates
+            .into_iter()
+            .filter(|b| match b.capabilities.validate(&b.name, &probe) {
+                Ok(()) => true,
+                Err(e) => {
+                    rejection.get_or_insert(e);
+                    false
+                }
+            })
+            .collect();
+        if let (true, Some(e)) = (candidates.is_empty(), rejection) {
+            return Err(e);
+        }
+
+        // A pinned backend is tried even if it was last seen unhealthy
+        let healthy: Vec<&BackendCandidate> = candidates
+            .iter()
+            .copied()
+            .filter(|b| b.healthy || intent.backend.is_some())
+            .collect();
+        if healthy.is_empty() {
+            return Err(Error::BackendUnavailable {
+                backend: candidates
+                    .iter()
+                    .map(|b| b.name.as_str())
+                    .collect::<Vec<_>>()
+                    .join(","),
+            });
+        }
+
+        let mut scored: Vec<ScoredBackend<'_>> = Vec::new();
+        let mut shortfall = None;
+
+        for candidate in healthy {
+            // A pool names its backend by kind or instance name
+            let backend_pools: Vec<&PoolInfo> = pools
+                .iter()
+                .filter(|p| p.backend == candidate.kind || p.backend == candidate.name)
+                .collect();
+            let known: Vec<&PoolInfo> = backend_pools
+                .iter()
+                .copied()
+                .filter(|p| p.total_capacity_bytes > 0)
+                .collect();
+
+            let (pool, headroom) = if known.is_empty() {
+                (backend_pools.first().copied(), None)
+            } else {
+                let fitting = known
+                    .iter()
+                    .filter_map(|p| Some((*p, self.headroom_after(p, intent.capacity_bytes)?)))
+                    .max_by_key(|(_, headroom)| *headroom);
+
+                match fitting {
+                    Some((pool, headroom)) => (Some(pool), Some(headroom)),
+                    None => {
+                        let available = known.iter().map(|p| p.available_capacity_bytes).max();
+                        shortfall = shortfall.max(available);
+                        continue;
+                    }
+                }
+            };
+
+            let free_ratio = match (pool, headroom) {
+                (Some(pool), Some(headroom)) => headroom as f64 / pool.total_capacity_bytes as f64,
+                _ => 0.0,
+            };
+
+            scored.push(ScoredBackend {
+                candidate,
+                pool,
+                headroom,
+                score: candidate.weight as f64 * (1.0 + free_ratio),
+            });
+        }
+
+        scored.sort_by(|a, b| {
+            b.score
+                .total_cmp(&a.score)
+                .then_with(|| a.candidate.name.cmp(&b.candidate.name))
+        });
+
+        let best = scored.first().ok_or(Error::InsufficientCapacity {
+            requested: intent.capacity_bytes,
+            available: shortfall.unwrap_or(0),
+        })?;
+
+        let source = if intent.backend.is_some() {
+            "requested explicitly".to_string()
+        } else {
+            format!("scored {:.1} of {} candidate(s)", best.score, scored.len())
+        };
+        match (best.pool, best.headroom) {
+            (Some(pool), Some(headroom)) => reasons.push(format!(
+                "backend {} {}; pool {} keeps {} bytes free after placement",
+                best.candidate.name, source, pool.name, headroom
+            )),
+            (Some(pool), None) => reasons.push(format!(
+                "backend {} {}; pool {} capacity not yet reported",
+                best.candidate.name, source, pool.name
+            )),
+            _ => reasons.push(format!("backend {} {}", best.candidate.name, source)),
+        }
+
+        let fallbacks = scored[1..]
+            .iter()
+            .map(|s| s.candidate.name.clone())
+            .collect();
+
+        Ok((
+            best.candidate.name.clone(),
+            best.pool.map(|p| p.name.clone()),
+            fallbacks,
+        ))
+    }
+
+    /// Free bytes left in a pool after placing a request, if it keeps the minimum headroom
+    fn headroom_after(&self, pool: &PoolInfo, capacity_bytes: u64) -> Option<u64> {
+        let reserve = pool.total_capacity_bytes / 100 * self.config.min_headroom_percent as u64;
+        let remaining = pool.available_capacity_bytes.checked_sub(capacity_bytes)?;
+        (remaining >= reserve).then_some(remaining)
+    }
+}
+
+/// Reject access modes the storage type cannot serve
+fn check_access_modes(storage_type: StorageType, modes: &[AccessMode]) -> Result<()> {
+    let conflict = modes.iter().find(|mode| match storage_type {
+        StorageType::Block => matches!(mode, AccessMode::ReadWriteMany | AccessMode::S3),
+        StorageType::File => matches!(mode, AccessMode::S3),
+        StorageType::Object => !matches!(mode, AccessMode::S3),
+    });
+
+    match conflict {
+        Some(mode) => Err(Error::ApiValidation(format!(
+            "Access mode {} is not supported by {} storage",
+            mode, storage_type
+        ))),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GI: u64 = 1024 * 1024 * 1024;
+
+    fn intent(storage_type: UnifiedStorageType) -> StorageIntent {
+        StorageIntent {
+            request_id: "req-1".into(),
+            name: "test".into(),
+            storage_type,
+            tier: UnifiedTier::Auto,
+            backend: None,
+            backend_tags: Vec::new(),
+            access_modes: Vec::new(),
+            capacity_bytes: 10 * GI,
+            max_iops: None,
+            labels: BTreeMap::new(),
+            annotations: BTreeMap::new(),
+            tenant: None,
+            platform_params: BTreeMap::new(),
+        }
+    }
+
+    fn backends() -> Vec<BackendCandidate> {
+        let mut mayastor = BackendCandidate::new("mayastor", vec![StorageType::Block]);
+        mayastor.capabilities.qos = true;
+        vec![
+            mayastor,
+            BackendCandidate::new("seaweedfs", vec![StorageType::File]),
+            BackendCandidate::new("rustfs", vec![StorageType::Object]),
+        ]
+    }
+
+    fn pool(name: &str, backend: &str, total: u64, available: u64) -> PoolInfo {
+        PoolInfo {
+            name: name.into(),
+            pool_type: "block".into(),
+            backend: backend.into(),
+            drive_count: 0,
+            node_count: 0,
+            total_capacity_bytes: total,
+            available_capacity_bytes: available,
+            utilization_percent: 0,
+        }
+    }
+
+    #[test]
+    fn test_access_modes_pick_type() {
+        let resolver = StorageResolver::default();
+
+        let mut rwx = intent(UnifiedStorageType::Auto);
+        rwx.access_modes = vec![AccessMode::ReadWriteMany];
+        let resolution = resolver.resolve(&rwx, &backends(), &[]).unwrap();
+        assert_eq!(resolution.storage_type, StorageType::File);
+        assert_eq!(resolution.backend, "seaweedfs");
+        assert!(resolution.auto_type);
+
+        let mut s3 = intent(UnifiedStorageType::Auto);
+        s3.access_modes = vec![AccessMode::S3];
+        let resolution = resolver.resolve(&s3, &backends(), &[]).unwrap();
+        assert_eq!(resolution.storage_type, StorageType::Object);
+        assert_eq!(resolution.backend, "rustfs");
+    }
+
+    #[test]
+    fn test_iops_picks_block_and_tier() {
+        let resolver = StorageResolver::default();
+        let mut request = intent(UnifiedStorageType::Auto);
+        request.max_iops = Some(50_000);
+
+        let resolution = resolver.resolve(&request, &backends(), &[]).unwrap();
+        assert_eq!(resolution.storage_type, StorageType::Block);
+        assert_eq!(resolution.tier, StorageTier::Hot);
+        assert!(resolution.auto_tier);
+        assert_eq!(resolution.reasons.len(), 3);
+    }
+
+    #[test]
+    fn test_labels_and_capacity() {
+        let resolver = StorageResolver::default();
+
+        let mut labeled = intent(UnifiedStorageType::Auto);
+        labeled.labels.insert(STORAGE_TIER_LABEL.into(), "cold".into());
+        labeled.labels.insert(STORAGE_TYPE_LABEL.into(), "file".into());
+        let resolution = resolver.resolve(&labeled, &backends(), &[]).unwrap();
+        assert_eq!(resolution.storage_type, StorageType::File);
+        assert_eq!(resolution.tier, StorageTier::Cold);
+
+        let mut huge = intent(UnifiedStorageType::Auto);
+        huge.capacity_bytes = 50 * 1024 * GI;
+        let resolution = resolver.resolve(&huge, &backends(), &[]).unwrap();
+        assert_eq!(resolution.storage_type, StorageType::Object);
+    }
+
+    #[test]
+    fn test_conflicting_access_mode_rejected() {
+        let resolver = StorageResolver::default();
+        let mut request = intent(UnifiedStorageType::Block);
+        request.access_modes = vec![AccessMode::ReadWriteMany];
+
+        let result = resolver.resolve(&request, &backends(), &[]);
+        assert!(matches!(result, Err(Error::ApiValidation(_))));
+    }
+
+    #[test]
+    fn test_headroom_selects_backend() {
+        let resolver = StorageResolver::default();
+        let mut candidates = backends();
+        candidates.push(BackendCandidate::new("mayastor-b", vec![StorageType::Block]));
+        let pools = vec![
+            pool("pool-a", "mayastor", 100 * GI, 15 * GI),
+            pool("pool-b", "mayastor-b", 100 * GI, 80 * GI),
+        ];
+
+        let request = intent(UnifiedStorageType::Block);
+        let resolution = resolver.resolve(&request, &candidates, &pools).unwrap();
+        assert_eq!(resolution.backend, "mayastor-b");
+        assert_eq!(resolution.pool_name.as_deref(), Some("pool-b"));
+
+        // Nothing fits once the reserve is accounted for
+        let mut big = intent(UnifiedStorageType::Block);
+        big.capacity_bytes = 75 * GI;
+        let result = resolver.resolve(&big, &candidates[..1], &pools);
+        assert!(matches!(result, Err(Error::InsufficientCapacity { .. })));
+    }
+
+    #[test]
+    fn test_named_instance_uses_pools_of_its_kind() {
+        let resolver = StorageResolver::default();
+        let mut east = BackendCandidate::new("mayastor-east", vec![StorageType::Block]);
+        east.kind = "mayastor".into();
+        let candidates = vec![east];
+        let pools = vec![pool("pool-east", "mayastor", 100 * GI, 80 * GI)];
+
+        let request = intent(UnifiedStorageType::Block);
+        let resolution = resolver.resolve(&request, &candidates, &pools).unwrap();
+        assert_eq!(resolution.backend, "mayastor-east");
+        assert_eq!(resolution.pool_name.as_deref(), Some("pool-east"));
+
+        let mut big = intent(UnifiedStorageType::Block);
+        big.capacity_bytes = 95 * GI;
+        let result = resolver.resolve(&big, &candidates, &pools);
+        assert!(matches!(result, Err(Error::InsufficientCapacity { .. })));
+    }
+
+    #[test]
+    fn test_weights_tags_and_health() {
+        let resolver = StorageResolver::default();
+        let mut primary = BackendCandidate::new("mayastor-a", vec![StorageType::Block]);
+        primary.weight = 200;
+        let mut local = BackendCandidate::new("local-nvme", vec![StorageType::Block]);
+        local.tags.insert("nvme".into());
+        let candidates = vec![primary, local];
+
+        let request = intent(UnifiedStorageType::Block);
+        let resolution = resolver.resolve(&request, &candidates, &[]).unwrap();
+        assert_eq!(resolution.backend, "mayastor-a");
+        assert_eq!(resolution.fallbacks, vec!["local-nvme".to_string()]);
+
+        // Required tags narrow the candidates
+        let mut tagged = intent(UnifiedStorageType::Block);
+        tagged.backend_tags = vec!["nvme".into()];
+        let resolution = resolver.resolve(&tagged, &candidates, &[]).unwrap();
+        assert_eq!(resolution.backend, "local-nvme");
+        assert!(resolution.fallbacks.is_empty());
+
+        // Unhealthy instances are skipped
+        let mut degraded = candidates.clone();
+        degraded[0].healthy = false;
+        let resolution = resolver.resolve(&request, &degraded, &[]).unwrap();
+        assert_eq!(resolution.backend, "local-nvme");
+
+        degraded[1].healthy = false;
+        let result = resolver.resolve(&request, &degraded, &[]);
+        assert!(matches!(result, Err(Error::BackendUnavailable { .. })));
+    }
+
+    #[test]
+    fn test_capabilities_filter_candidates() {
+        let resolver = StorageResolver::default();
+
+        // File storage with an IOPS limit: seaweedfs cannot enforce QoS
+        let mut request = intent(UnifiedStorageType::File);
+        request.max_iops = Some(500);
+        let err = resolver.resolve(&request, &backends(), &[]).unwrap_err();
+        assert!(matches!(err, Error::ApiValidation(_)));
+        assert!(err.to_string().contains("QoS"));
+
+        // A second instance that supports versioning takes the request
+        let mut versioned = BackendCandidate::new("rustfs-b", vec![StorageType::Object]);
+        versioned.capabilities.versioning = true;
+        let mut candidates = backends();
+        candidates.push(versioned);
+
+        let mut request = intent(UnifiedStorageType::Object);
+        request.platform_params.insert("versioning".into(), "true".into());
+        let resolution = resolver.resolve(&request, &candidates, &[]).unwrap();
+        assert_eq!(resolution.backend, "rustfs-b");
+    }
+}
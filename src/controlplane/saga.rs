@@ -0,0 +1,510 @@
+//! Provisioning Sagas
+//!
+//! Multi-step operations (provision, delete, migrate) run as sagas: an
+//! ordered list of named steps, each with a compensating action. Progress
+//! is persisted after every step so that an interrupted saga can be resumed
+//! after a restart, either rolling forward or finishing its compensation.
+//!
+//! The saga itself is plain data ([`SagaRecord`]); the work for each step is
+//! supplied by a [`SagaHandler`]. Steps must be idempotent, because a step
+//! that was running when the process died is executed again on resume.
+
+use crate::error::{Error, Result};
+use async_trait::async_trait;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use tracing::{debug, error, info, warn};
+
+// =============================================================================
+// Saga Record
+// =============================================================================
+
+/// Kind of operation a saga performs
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SagaKind {
+    Provision,
+    Delete,
+    Migrate,
+}
+
+impl std::fmt::Display for SagaKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SagaKind::Provision => write!(f, "provision"),
+            SagaKind::Delete => write!(f, "delete"),
+            SagaKind::Migrate => write!(f, "migrate"),
+        }
+    }
+}
+
+/// Where a saga is in its lifecycle
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "state", rename_all = "camelCase")]
+pub enum SagaStatus {
+    /// Executing steps forward
+    Running,
+    /// A step failed; undoing completed steps in reverse
+    Compensating { error: String },
+    /// All steps completed
+    Completed,
+    /// Failure was fully compensated
+    Compensated { error: String },
+    /// Compensation itself failed; needs manual intervention
+    Stuck { error: String },
+}
+
+impl SagaStatus {
+    /// Whether the saga has finished (successfully or not)
+    pub fn is_terminal(&self) -> bool {
+        matches!(
+            self,
+            SagaStatus::Completed | SagaStatus::Compensated { .. } | SagaStatus::Stuck { .. }
+        )
+    }
+}
+
+/// Persistent state of a saga
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SagaRecord {
+    /// Unique saga ID
+    pub id: String,
+    /// Operation being performed
+    pub kind: SagaKind,
+    /// Planned steps, in execution order
+    pub steps: Vec<String>,
+    /// Number of steps completed (and not yet compensated)
+    pub completed: usize,
+    /// Lifecycle state
+    pub status: SagaStatus,
+    /// Step inputs and outputs, keyed by name
+    pub context: BTreeMap<String, serde_json::Value>,
+    /// When the saga started
+    pub started_at: chrono::DateTime<chrono::Utc>,
+    /// When the saga last made progress
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl SagaRecord {
+    /// Create a new saga with the given steps
+    pub fn new(kind: SagaKind, steps: &[&str]) -> Self {
+        let now = chrono::Utc::now();
+        Self {
+            id: generate_saga_id(),
+            kind,
+            steps: steps.iter().map(|s| s.to_string()).collect(),
+            completed: 0,
+            status: SagaStatus::Running,
+            context: BTreeMap::new(),
+            started_at: now,
+            updated_at: now,
+        }
+    }
+
+    /// Store a value in the saga context
+    pub fn put<T: Serialize>(&mut self, key: &str, value: &T) -> Result<()> {
+        self.context.insert(key.to_string(), serde_json::to_value(value)?);
+        Ok(())
+    }
+
+    /// Read a value from the saga context
+    pub fn get<T: DeserializeOwned>(&self, key: &str) -> Result<Option<T>> {
+        self.context
+            .get(key)
+            .map(|v| serde_json::from_value(v.clone()))
+            .transpose()
+            .map_err(Error::from)
+    }
+
+    /// Read a value that an earlier step must have stored
+    pub fn require<T: DeserializeOwned>(&self, key: &str) -> Result<T> {
+        self.get(key)?.ok_or_else(|| {
+            Error::Internal(format!("saga {} is missing context key {}", self.id, key))
+        })
+    }
+
+    /// Remove a value from the saga context
+    pub fn take(&mut self, key: &str) {
+        self.context.remove(key);
+    }
+}
+
+fn generate_saga_id() -> String {
+    use std::time::{SystemTime, UNIX_EPOCH};
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_nanos();
+    let seq = COUNTER.fetch_add(1, Ordering::Relaxed);
+    format!("saga-{:016x}-{:04x}", now, seq & 0xffff)
+}
+
+// =============================================================================
+// Saga Store
+// =============================================================================
+
+/// Persistence for in-flight sagas
+#[async_trait]
+pub trait SagaStore: Send + Sync {
+    /// Save (insert or replace) a saga
+    async fn save(&self, saga: &SagaRecord) -> Result<()>;
+
+    /// Remove a saga
+    async fn remove(&self, saga_id: &str) -> Result<()>;
+
+    /// Load all stored sagas
+    async fn load_all(&self) -> Result<Vec<SagaRecord>>;
+}
+
+/// Saga store that keeps state in memory (lost on restart)
+#[derive(Default)]
+pub struct InMemorySagaStore {
+    sagas: RwLock<BTreeMap<String, SagaRecord>>,
+}
+
+impl InMemorySagaStore {
+    /// Create an empty store
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl SagaStore for InMemorySagaStore {
+    async fn save(&self, saga: &SagaRecord) -> Result<()> {
+        self.sagas.write().await.insert(saga.id.clone(), saga.clone());
+        Ok(())
+    }
+
+    async fn remove(&self, saga_id: &str) -> Result<()> {
+        self.sagas.write().await.remove(saga_id);
+        Ok(())
+    }
+
+    async fn load_all(&self) -> Result<Vec<SagaRecord>> {
+        Ok(self.sagas.read().await.values().cloned().collect())
+    }
+}
+
+/// Saga store that writes one JSON file per saga into a directory
+pub struct FileSagaStore {
+    dir: PathBuf,
+}
+
+impl FileSagaStore {
+    /// Create a store rooted at `dir`, creating the directory if needed
+    pub async fn new(dir: impl Into<PathBuf>) -> Result<Self> {
+        let dir = dir.into();
+        tokio::fs::create_dir_all(&dir).await?;
+        Ok(Self { dir })
+    }
+
+    fn path_for(&self, saga_id: &str) -> PathBuf {
+        self.dir.join(format!("{}.json", saga_id))
+    }
+}
+
+#[async_trait]
+impl SagaStore for FileSagaStore {
+    async fn save(&self, saga: &SagaRecord) -> Result<()> {
+        // Write then rename so a crash never leaves a truncated file behind
+        let path = self.path_for(&saga.id);
+        let tmp = path.with_extension("json.tmp");
+        tokio::fs::write(&tmp, serde_json::to_vec_pretty(saga)?).await?;
+        tokio::fs::rename(&tmp, &path).await?;
+        Ok(())
+    }
+
+    async fn remove(&self, saga_id: &str) -> Result<()> {
+        match tokio::fs::remove_file(self.path_for(saga_id)).await {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    async fn load_all(&self) -> Result<Vec<SagaRecord>> {
+        let mut sagas = Vec::new();
+        let mut entries = tokio::fs::read_dir(&self.dir).await?;
+
+        while let Some(entry) = entries.next_entry().await? {
+            let path = entry.path();
+            if path.extension().and_then(|e| e.to_str()) != Some("json") {
+                continue;
+            }
+            match serde_json::from_slice(&tokio::fs::read(&path).await?) {
+                Ok(saga) => sagas.push(saga),
+                Err(e) => warn!("Skipping unreadable saga file {}: {}", path.display(), e),
+            }
+        }
+
+        Ok(sagas)
+    }
+}
+
+// =============================================================================
+// Saga Executor
+// =============================================================================
+
+/// Performs and undoes the steps of a saga
+#[async_trait]
+pub trait SagaHandler: Send + Sync {
+    /// Execute a step; outputs for later steps go into the saga context
+    async fn execute_step(&self, saga: &mut SagaRecord, step: &str) -> Result<()>;
+
+    /// Undo a completed step
+    async fn compensate_step(&self, saga: &mut SagaRecord, step: &str) -> Result<()>;
+}
+
+/// Drives sagas forward and compensates them on failure
+pub struct SagaExecutor {
+    store: Arc<dyn SagaStore>,
+}
+
+impl SagaExecutor {
+    /// Create an executor persisting to `store`
+    pub fn new(store: Arc<dyn SagaStore>) -> Self {
+        Self { store }
+    }
+
+    /// Run (or resume) a saga to a terminal state
+    ///
+    /// Returns the finished saga on success. If a step fails and all
+    /// completed steps are compensated, the step's error is returned; if
+    /// compensation fails too, `Error::SagaCompensationFailed` is returned
+    /// and the saga is kept in the store as `Stuck`.
+    pub async fn run<H: SagaHandler + ?Sized>(
+        &self,
+        handler: &H,
+        mut saga: SagaRecord,
+    ) -> Result<SagaRecord> {
+        self.persist(&mut saga).await?;
+
+        // Forward phase
+        let mut failure = None;
+        if saga.status == SagaStatus::Running {
+            while saga.completed < saga.steps.len() {
+                let step = saga.steps[saga.completed].clone();
+                debug!("Saga {} ({}) executing step {}", saga.id, saga.kind, step);
+
+                match handler.execute_step(&mut saga, &step).await {
+                    Ok(()) => {
+                        saga.completed += 1;
+                        self.persist(&mut saga).await?;
+                    }
+                    Err(e) => {
+                        warn!("Saga {} step {} failed, compensating: {}", saga.id, step, e);
+                        saga.status = SagaStatus::Compensating {
+                            error: e.to_string(),
+                        };
+                        self.persist(&mut saga).await?;
+                        failure = Some(e);
+                        break;
+                    }
+                }
+            }
+        }
+
+        if saga.status == SagaStatus::Running {
+            saga.status = SagaStatus::Completed;
+            self.store.remove(&saga.id).await?;
+            info!("Saga {} ({}) completed", saga.id, saga.kind);
+            return Ok(saga);
+        }
+
+        // Compensation phase
+        let error = match &saga.status {
+            SagaStatus::Compensating { error } => error.clone(),
+            _ => return Ok(saga),
+        };
+
+        while saga.completed > 0 {
+            let step = saga.steps[saga.completed - 1].clone();
+            debug!("Saga {} ({}) compensating step {}", saga.id, saga.kind, step);
+
+            if let Err(e) = handler.compensate_step(&mut saga, &step).await {
+                error!("Saga {} could not compensate step {}: {}", saga.id, step, e);
+                saga.status = SagaStatus::Stuck {
+                    error: format!("{}; compensating {}: {}", error, step, e),
+                };
+                self.persist(&mut saga).await?;
+                return Err(Error::SagaCompensationFailed {
+                    saga_id: saga.id,
+                    step,
+                    reason: e.to_string(),
+                });
+            }
+
+            saga.completed -= 1;
+            self.persist(&mut saga).await?;
+        }
+
+        saga.status = SagaStatus::Compensated {
+            error: error.clone(),
+        };
+        self.store.remove(&saga.id).await?;
+        info!("Saga {} ({}) compensated after: {}", saga.id, saga.kind, error);
+
+        Err(failure.unwrap_or(Error::Internal(error)))
+    }
+
+    /// Resume every unfinished saga in the store
+    ///
+    /// Returns the number of sagas resumed. Individual failures are logged;
+    /// stuck sagas are left in place for an operator to inspect.
+    pub async fn resume_all<H: SagaHandler + ?Sized>(&self, handler: &H) -> Result<usize> {
+        let mut resumed = 0;
+
+        for saga in self.store.load_all().await? {
+            if saga.status.is_terminal() {
+                continue;
+            }
+
+            info!(
+                "Resuming saga {} ({}) at step {}/{}",
+                saga.id,
+                saga.kind,
+                saga.completed,
+                saga.steps.len()
+            );
+            resumed += 1;
+
+            let id = saga.id.clone();
+            if let Err(e) = self.run(handler, saga).await {
+                warn!("Resumed saga {} did not complete: {}", id, e);
+            }
+        }
+
+        Ok(resumed)
+    }
+
+    /// Sagas that are still in flight or stuck
+    pub async fn pending(&self) -> Result<Vec<SagaRecord>> {
+        self.store.load_all().await
+    }
+
+    async fn persist(&self, saga: &mut SagaRecord) -> Result<()> {
+        saga.updated_at = chrono::Utc::now();
+        self.store.save(saga).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use parking_lot::Mutex;
+
+    /// Records calls and fails on a chosen step
+    #[derive(Default)]
+    struct ScriptedHandler {
+        fail_step: Option<&'static str>,
+        fail_compensation: Option<&'static str>,
+        log: Mutex<Vec<String>>,
+    }
+
+    #[async_trait]
+    impl SagaHandler for ScriptedHandler {
+        async fn execute_step(&self, saga: &mut SagaRecord, step: &str) -> Result<()> {
+            self.log.lock().push(format!("do:{}", step));
+            if self.fail_step == Some(step) {
+                return Err(Error::BackendUnavailable {
+                    backend: step.into(),
+                });
+            }
+            saga.put(step, &true)
+        }
+
+        async fn compensate_step(&self, saga: &mut SagaRecord, step: &str) -> Result<()> {
+            self.log.lock().push(format!("undo:{}", step));
+            if self.fail_compensation == Some(step) {
+                return Err(Error::Internal("cannot undo".into()));
+            }
+            saga.take(step);
+            Ok(())
+        }
+    }
+
+    fn executor() -> (SagaExecutor, Arc<InMemorySagaStore>) {
+        let store = Arc::new(InMemorySagaStore::new());
+        (SagaExecutor::new(store.clone()), store)
+    }
+
+    #[tokio::test]
+    async fn test_saga_completes() {
+        let (executor, store) = executor();
+        let handler = ScriptedHandler::default();
+
+        let saga = SagaRecord::new(SagaKind::Provision, &["a", "b", "c"]);
+        let done = executor.run(&handler, saga).await.unwrap();
+
+        assert_eq!(done.status, SagaStatus::Completed);
+        assert_eq!(done.completed, 3);
+        assert_eq!(*handler.log.lock(), vec!["do:a", "do:b", "do:c"]);
+        assert!(store.load_all().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_saga_compensates_in_reverse() {
+        let (executor, store) = executor();
+        let handler = ScriptedHandler {
+            fail_step: Some("c"),
+            ..Default::default()
+        };
+
+        let saga = SagaRecord::new(SagaKind::Provision, &["a", "b", "c"]);
+        let err = executor.run(&handler, saga).await.unwrap_err();
+
+        assert!(matches!(err, Error::BackendUnavailable { .. }));
+        assert_eq!(
+            *handler.log.lock(),
+            vec!["do:a", "do:b", "do:c", "undo:b", "undo:a"]
+        );
+        assert!(store.load_all().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_stuck_saga_is_kept() {
+        let (executor, store) = executor();
+        let handler = ScriptedHandler {
+            fail_step: Some("c"),
+            fail_compensation: Some("a"),
+            ..Default::default()
+        };
+
+        let saga = SagaRecord::new(SagaKind::Delete, &["a", "b", "c"]);
+        let err = executor.run(&handler, saga).await.unwrap_err();
+        assert!(matches!(err, Error::SagaCompensationFailed { .. }));
+
+        let pending = store.load_all().await.unwrap();
+        assert_eq!(pending.len(), 1);
+        assert!(matches!(pending[0].status, SagaStatus::Stuck { .. }));
+        assert_eq!(pending[0].completed, 1);
+    }
+
+    #[tokio::test]
+    async fn test_file_store_resume() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = Arc::new(FileSagaStore::new(dir.path()).await.unwrap());
+
+        // Simulate a crash after the first of three steps
+        let mut saga = SagaRecord::new(SagaKind::Migrate, &["a", "b", "c"]);
+        saga.put("a", &true).unwrap();
+        saga.completed = 1;
+        store.save(&saga).await.unwrap();
+
+        let executor = SagaExecutor::new(store.clone());
+        let handler = ScriptedHandler::default();
+        assert_eq!(executor.resume_all(&handler).await.unwrap(), 1);
+
+        assert_eq!(*handler.log.lock(), vec!["do:b", "do:c"]);
+        assert!(store.load_all().await.unwrap().is_empty());
+    }
+}
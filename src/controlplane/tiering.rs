@@ -0,0 +1,873 @@
+//! Heat-based Tiering Policy Engine
+//!
+//! Tracks per-volume access heat and recommends (or performs) tier changes
+//! using ordered rules such as "no I/O for 14 days -> cold". Inputs are:
+//! - Per-volume I/O and cache hits/misses reported by data-path agents
+//! - Per-drive metrics of the drives backing the volume's pool
+//!
+//! Volumes the engine has no statistics for are reported as unknown and
+//! never moved: absent telemetry is not evidence of an idle volume.
+//!
+//! In dry-run mode the engine only produces a report; in enforce mode it
+//! drives `Orchestrator::migrate_storage` with a cap on concurrent moves.
+
+use crate::controlplane::orchestrator::{Orchestrator, StorageRecord};
+use crate::domain::ports::StorageTier;
+use crate::hardware::registry::NodeRegistry;
+use chrono::{DateTime, Utc};
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{broadcast, Semaphore};
+use tracing::{info, warn};
+
+// =============================================================================
+// Constants
+// =============================================================================
+
+/// Drive metrics older than this are ignored when computing pool heat
+const METRICS_MAX_AGE_SECS: u64 = 300;
+
+const SECS_PER_DAY: u64 = 24 * 60 * 60;
+
+// =============================================================================
+// Configuration
+// =============================================================================
+
+/// Whether the engine only reports or also moves data
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum TieringMode {
+    /// Produce recommendations without moving anything
+    #[default]
+    DryRun,
+    /// Execute recommendations via the migration path
+    Enforce,
+}
+
+/// Condition evaluated against a volume's heat
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum TieringCondition {
+    /// No I/O observed for at least this long
+    IdleFor { secs: u64 },
+    /// Observed volume IOPS at or above the threshold
+    IopsAbove { iops: f64 },
+    /// Observed volume IOPS below the threshold
+    IopsBelow { iops: f64 },
+    /// Cache hit ratio at or above the threshold (0.0-1.0)
+    CacheHitRatioAbove { ratio: f64 },
+    /// Average utilization of the pool's drives at or above the threshold
+    PoolUtilizationAbove { percent: f32 },
+    /// Aggregate IOPS of the pool's drives at or above the threshold
+    PoolIopsAbove { iops: u64 },
+    /// All nested conditions must hold
+    All { conditions: Vec<TieringCondition> },
+}
+
+impl TieringCondition {
+    /// Check the condition against a heat snapshot
+    pub fn matches(&self, heat: &VolumeHeat) -> bool {
+        match self {
+            TieringCondition::IdleFor { secs } => heat.idle_secs >= *secs,
+            TieringCondition::IopsAbove { iops } => heat.iops >= *iops,
+            TieringCondition::IopsBelow { iops } => heat.iops < *iops,
+            TieringCondition::CacheHitRatioAbove { ratio } => {
+                heat.cache_hit_ratio.map(|r| r >= *ratio).unwrap_or(false)
+            }
+            TieringCondition::PoolUtilizationAbove { percent } => heat
+                .pool_utilization_percent
+                .map(|u| u >= *percent)
+                .unwrap_or(false),
+            TieringCondition::PoolIopsAbove { iops } => {
+                heat.pool_iops.map(|p| p >= *iops).unwrap_or(false)
+            }
+            TieringCondition::All { conditions } => conditions.iter().all(|c| c.matches(heat)),
+        }
+    }
+
+    /// Human-readable description used in recommendation reasons
+    pub fn describe(&self) -> String {
+        match self {
+            TieringCondition::IdleFor { secs } => format!("no I/O for {}", format_secs(*secs)),
+            TieringCondition::IopsAbove { iops } => format!("IOPS >= {}", iops),
+            TieringCondition::IopsBelow { iops } => format!("IOPS < {}", iops),
+            TieringCondition::CacheHitRatioAbove { ratio } => {
+                format!("cache hit ratio >= {:.0}%", ratio * 100.0)
+            }
+            TieringCondition::PoolUtilizationAbove { percent } => {
+                format!("pool utilization >= {}%", percent)
+            }
+            TieringCondition::PoolIopsAbove { iops } => format!("pool IOPS >= {}", iops),
+            TieringCondition::All { conditions } => conditions
+                .iter()
+                .map(|c| c.describe())
+                .collect::<Vec<_>>()
+                .join(" and "),
+        }
+    }
+}
+
+/// A single tiering rule; rules are evaluated in order and the first match wins
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TieringRule {
+    /// Rule name (reported with each recommendation)
+    pub name: String,
+    /// Tiers the rule applies to (empty = any tier)
+    #[serde(default)]
+    pub from_tiers: Vec<StorageTier>,
+    /// Tier to move matching volumes to
+    pub target: StorageTier,
+    /// Condition that triggers the move
+    pub condition: TieringCondition,
+}
+
+impl TieringRule {
+    /// Check whether the rule applies to a volume in the given tier
+    fn applies_to(&self, tier: Option<StorageTier>) -> bool {
+        if tier == Some(self.target) {
+            return false;
+        }
+        self.from_tiers.is_empty() || tier.map(|t| self.from_tiers.contains(&t)).unwrap_or(false)
+    }
+}
+
+/// Configuration for the tiering engine
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TieringConfig {
+    /// Report-only or enforcing
+    #[serde(default)]
+    pub mode: TieringMode,
+    /// Ordered tiering rules
+    #[serde(default = "default_rules")]
+    pub rules: Vec<TieringRule>,
+    /// Maximum number of migrations running at the same time
+    #[serde(default = "default_max_concurrent_moves")]
+    pub max_concurrent_moves: usize,
+    /// Maximum number of migrations started per evaluation cycle
+    #[serde(default = "default_max_moves_per_cycle")]
+    pub max_moves_per_cycle: usize,
+    /// Evaluation interval in seconds
+    #[serde(default = "default_evaluation_interval")]
+    pub evaluation_interval_secs: u64,
+    /// Volumes younger than this are never moved
+    #[serde(default = "default_min_age")]
+    pub min_age_secs: u64,
+}
+
+impl Default for TieringConfig {
+    fn default() -> Self {
+        Self {
+            mode: TieringMode::default(),
+            rules: default_rules(),
+            max_concurrent_moves: default_max_concurrent_moves(),
+            max_moves_per_cycle: default_max_moves_per_cycle(),
+            evaluation_interval_secs: default_evaluation_interval(),
+            min_age_secs: default_min_age(),
+        }
+    }
+}
+
+fn default_rules() -> Vec<TieringRule> {
+    vec![
+        TieringRule {
+            name: "idle-14d-to-cold".into(),
+            from_tiers: vec![StorageTier::Hot, StorageTier::Warm],
+            target: StorageTier::Cold,
+            condition: TieringCondition::IdleFor {
+                secs: 14 * SECS_PER_DAY,
+            },
+        },
+        TieringRule {
+            name: "idle-3d-hot-to-warm".into(),
+            from_tiers: vec![StorageTier::Hot],
+            target: StorageTier::Warm,
+            condition: TieringCondition::IdleFor {
+                secs: 3 * SECS_PER_DAY,
+            },
+        },
+        TieringRule {
+            name: "busy-to-hot".into(),
+            from_tiers: vec![StorageTier::Warm, StorageTier::Cold],
+            target: StorageTier::Hot,
+            condition: TieringCondition::IopsAbove { iops: 500.0 },
+        },
+    ]
+}
+
+fn default_max_concurrent_moves() -> usize {
+    2
+}
+
+fn default_max_moves_per_cycle() -> usize {
+    10
+}
+
+fn default_evaluation_interval() -> u64 {
+    3600
+}
+
+fn default_min_age() -> u64 {
+    SECS_PER_DAY
+}
+
+// =============================================================================
+// Access Statistics
+// =============================================================================
+
+/// Access statistics for a single volume
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VolumeAccessStats {
+    pub read_ops: u64,
+    pub write_ops: u64,
+    pub bytes_read: u64,
+    pub bytes_written: u64,
+    pub cache_hits: u64,
+    pub cache_misses: u64,
+    /// Last time any I/O or cache access was observed
+    pub last_access: Option<DateTime<Utc>>,
+    /// When statistics were first reported for the volume
+    pub tracked_since: DateTime<Utc>,
+    /// Start of the current counting window
+    pub window_start: DateTime<Utc>,
+}
+
+impl VolumeAccessStats {
+    fn new(now: DateTime<Utc>) -> Self {
+        Self {
+            read_ops: 0,
+            write_ops: 0,
+            bytes_read: 0,
+            bytes_written: 0,
+            cache_hits: 0,
+            cache_misses: 0,
+            last_access: None,
+            tracked_since: now,
+            window_start: now,
+        }
+    }
+
+    /// Average IOPS over the current window
+    pub fn iops(&self, now: DateTime<Utc>) -> f64 {
+        let elapsed = now.signed_duration_since(self.window_start).num_seconds().max(1);
+        (self.read_ops + self.write_ops) as f64 / elapsed as f64
+    }
+
+    /// Cache hit ratio, if the volume saw any cache lookups
+    pub fn cache_hit_ratio(&self) -> Option<f64> {
+        let total = self.cache_hits + self.cache_misses;
+        if total == 0 {
+            None
+        } else {
+            Some(self.cache_hits as f64 / total as f64)
+        }
+    }
+
+    /// Start a new counting window, keeping the last access time
+    fn reset_window(&mut self, now: DateTime<Utc>) {
+        *self = Self {
+            last_access: self.last_access,
+            tracked_since: self.tracked_since,
+            ..Self::new(now)
+        };
+    }
+}
+
+/// Heat snapshot of a volume used for rule evaluation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VolumeHeat {
+    pub storage_id: String,
+    pub current_tier: Option<StorageTier>,
+    /// Volume IOPS over the current window
+    pub iops: f64,
+    /// Seconds since the last observed access (or since tracking began)
+    pub idle_secs: u64,
+    pub cache_hit_ratio: Option<f64>,
+    /// Average utilization of the drives backing the volume's pool
+    pub pool_utilization_percent: Option<f32>,
+    /// Aggregate IOPS of the drives backing the volume's pool
+    pub pool_iops: Option<u64>,
+}
+
+// =============================================================================
+// Recommendations & Reports
+// =============================================================================
+
+/// A recommended tier change
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TierRecommendation {
+    pub storage_id: String,
+    pub name: String,
+    pub from: Option<StorageTier>,
+    pub to: StorageTier,
+    /// Name of the matching rule
+    pub rule: String,
+    pub reason: String,
+    pub heat: VolumeHeat,
+}
+
+/// What happened to a recommendation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "camelCase")]
+pub enum MoveOutcome {
+    /// Dry-run: would have been moved
+    Planned,
+    /// Moved successfully
+    Migrated,
+    /// Over the per-cycle limit; will be retried next cycle
+    Deferred,
+    /// Migration failed
+    Failed { error: String },
+}
+
+/// A recommendation and its outcome
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TierDecision {
+    pub recommendation: TierRecommendation,
+    pub outcome: MoveOutcome,
+}
+
+/// Result of one evaluation cycle
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TieringReport {
+    pub generated_at: DateTime<Utc>,
+    pub mode: TieringMode,
+    /// Number of volumes evaluated
+    pub evaluated: usize,
+    /// Volumes without access statistics, left where they are
+    #[serde(default)]
+    pub unknown: Vec<String>,
+    pub decisions: Vec<TierDecision>,
+}
+
+impl TieringReport {
+    /// Count decisions with a given outcome
+    pub fn count(&self, matches: impl Fn(&MoveOutcome) -> bool) -> usize {
+        self.decisions.iter().filter(|d| matches(&d.outcome)).count()
+    }
+}
+
+// =============================================================================
+// Tiering Engine
+// =============================================================================
+
+/// Heat-based tiering policy engine
+pub struct TieringEngine {
+    config: TieringConfig,
+    orchestrator: Arc<Orchestrator>,
+    registry: Arc<NodeRegistry>,
+    /// Access statistics by storage ID
+    stats: RwLock<HashMap<String, VolumeAccessStats>>,
+    /// Limits concurrent migrations
+    move_slots: Arc<Semaphore>,
+    /// Most recent report
+    last_report: RwLock<Option<TieringReport>>,
+}
+
+impl TieringEngine {
+    /// Create a new tiering engine
+    pub fn new(
+        config: TieringConfig,
+        orchestrator: Arc<Orchestrator>,
+        registry: Arc<NodeRegistry>,
+    ) -> Arc<Self> {
+        let move_slots = Arc::new(Semaphore::new(config.max_concurrent_moves.max(1)));
+
+        Arc::new(Self {
+            config,
+            orchestrator,
+            registry,
+            stats: RwLock::new(HashMap::new()),
+            move_slots,
+            last_report: RwLock::new(None),
+        })
+    }
+
+    /// Get the engine configuration
+    pub fn config(&self) -> &TieringConfig {
+        &self.config
+    }
+
+    /// Record I/O on a volume
+    pub fn record_access(
+        &self,
+        storage_id: &str,
+        read_ops: u64,
+        write_ops: u64,
+        bytes_read: u64,
+        bytes_written: u64,
+    ) {
+        self.record_access_at(storage_id, read_ops, write_ops, bytes_read, bytes_written, Utc::now());
+    }
+
+    /// Record I/O on a volume at a specific time
+    pub fn record_access_at(
+        &self,
+        storage_id: &str,
+        read_ops: u64,
+        write_ops: u64,
+        bytes_read: u64,
+        bytes_written: u64,
+        at: DateTime<Utc>,
+    ) {
+        let mut stats = self.stats.write();
+        let entry = stats
+            .entry(storage_id.to_string())
+            .or_insert_with(|| VolumeAccessStats::new(at));
+
+        entry.read_ops += read_ops;
+        entry.write_ops += write_ops;
+        entry.bytes_read += bytes_read;
+        entry.bytes_written += bytes_written;
+        if read_ops + write_ops > 0 && entry.last_access.map(|t| t < at).unwrap_or(true) {
+            entry.last_access = Some(at);
+        }
+    }
+
+    /// Record cache lookups for a volume
+    pub fn record_cache_lookups(&self, storage_id: &str, hits: u64, misses: u64) {
+        self.record_cache_lookups_at(storage_id, hits, misses, Utc::now());
+    }
+
+    /// Record cache lookups for a volume at a specific time
+    pub fn record_cache_lookups_at(
+        &self,
+        storage_id: &str,
+        hits: u64,
+        misses: u64,
+        at: DateTime<Utc>,
+    ) {
+        let mut stats = self.stats.write();
+        let entry = stats
+            .entry(storage_id.to_string())
+            .or_insert_with(|| VolumeAccessStats::new(at));
+
+        entry.cache_hits += hits;
+        entry.cache_misses += misses;
+        if hits + misses > 0 && entry.last_access.map(|t| t < at).unwrap_or(true) {
+            entry.last_access = Some(at);
+        }
+    }
+
+    /// Get access statistics for a volume
+    pub fn access_stats(&self, storage_id: &str) -> Option<VolumeAccessStats> {
+        self.stats.read().get(storage_id).cloned()
+    }
+
+    /// Compute the heat snapshot of a volume, or `None` if nothing has been
+    /// reported for it
+    pub fn heat(&self, record: &StorageRecord, now: DateTime<Utc>) -> Option<VolumeHeat> {
+        let stats = self.stats.read().get(&record.id).cloned()?;
+        let last_access = stats.last_access.unwrap_or(stats.tracked_since);
+        let (pool_utilization_percent, pool_iops) = self.pool_heat(&record.pool_name);
+
+        Some(VolumeHeat {
+            storage_id: record.id.clone(),
+            current_tier: record.tier,
+            iops: stats.iops(now),
+            idle_secs: now.signed_duration_since(last_access).num_seconds().max(0) as u64,
+            cache_hit_ratio: stats.cache_hit_ratio(),
+            pool_utilization_percent,
+            pool_iops,
+        })
+    }
+
+    /// Average utilization and total IOPS of the drives assigned to a pool
+    fn pool_heat(&self, pool_name: &str) -> (Option<f32>, Option<u64>) {
+        let mut utilization_sum = 0.0f32;
+        let mut iops_sum = 0u64;
+        let mut samples = 0u32;
+
+        for node_id in self.registry.online_node_ids() {
+            let Some(entry) = self.registry.get(node_id) else {
+                continue;
+            };
+            for drive in entry.drives() {
+                if drive.pool_ref.as_deref() != Some(pool_name) {
+                    continue;
+                }
+                if let Some(metrics) = entry.get_drive_metrics(&drive.id) {
+                    if metrics.is_stale(METRICS_MAX_AGE_SECS) {
+                        continue;
+                    }
+                    utilization_sum += metrics.get_utilization_percent();
+                    iops_sum += metrics.get_iops();
+                    samples += 1;
+                }
+            }
+        }
+
+        if samples == 0 {
+            (None, None)
+        } else {
+            (Some(utilization_sum / samples as f32), Some(iops_sum))
+        }
+    }
+
+    /// Evaluate all volumes and return recommendations
+    pub async fn evaluate(&self) -> Vec<TierRecommendation> {
+        self.evaluate_at(Utc::now()).await
+    }
+
+    /// Evaluate all volumes as of a specific time
+    pub async fn evaluate_at(&self, now: DateTime<Utc>) -> Vec<TierRecommendation> {
+        let records = self.orchestrator.list_storage_records().await;
+        self.recommend(&records, now).0
+    }
+
+    /// Match rules against each record, returning the recommendations and
+    /// the IDs of volumes without statistics
+    fn recommend(
+        &self,
+        records: &[StorageRecord],
+        now: DateTime<Utc>,
+    ) -> (Vec<TierRecommendation>, Vec<String>) {
+        let mut recommendations = Vec::new();
+        let mut unknown = Vec::new();
+
+        for record in records {
+            let age = now.signed_duration_since(record.created_at).num_seconds();
+            if age < self.config.min_age_secs as i64 {
+                continue;
+            }
+
+            let Some(heat) = self.heat(record, now) else {
+                unknown.push(record.id.clone());
+                continue;
+            };
+            let Some(rule) = self
+                .config
+                .rules
+                .iter()
+                .find(|r| r.applies_to(record.tier) && r.condition.matches(&heat))
+            else {
+                continue;
+            };
+
+            recommendations.push(TierRecommendation {
+                storage_id: record.id.clone(),
+                name: record.name.clone(),
+                from: record.tier,
+                to: rule.target,
+                rule: rule.name.clone(),
+                reason: rule.condition.describe(),
+                heat,
+            });
+        }
+
+        (recommendations, unknown)
+    }
+
+    /// Run one evaluation cycle
+    pub async fn run_once(&self) -> TieringReport {
+        self.run_once_at(Utc::now()).await
+    }
+
+    /// Run one evaluation cycle as of a specific time
+    pub async fn run_once_at(&self, now: DateTime<Utc>) -> TieringReport {
+        let records = self.orchestrator.list_storage_records().await;
+        let (recommendations, unknown) = self.recommend(&records, now);
+
+        let decisions = match self.config.mode {
+            TieringMode::DryRun => recommendations
+                .into_iter()
+                .map(|recommendation| TierDecision {
+                    recommendation,
+                    outcome: MoveOutcome::Planned,
+                })
+                .collect(),
+            TieringMode::Enforce => self.execute(recommendations).await,
+        };
+
+        // Start a fresh counting window so IOPS reflects recent activity
+        for stats in self.stats.write().values_mut() {
+            stats.reset_window(now);
+        }
+
+        let report = TieringReport {
+            generated_at: now,
+            mode: self.config.mode,
+            evaluated: records.len(),
+            unknown,
+            decisions,
+        };
+
+        info!(
+            "Tiering cycle: {} volumes ({} without statistics), {} recommendations, \
+             {} migrated, {} failed, {} deferred",
+            report.evaluated,
+            report.unknown.len(),
+            report.decisions.len(),
+            report.count(|o| matches!(o, MoveOutcome::Migrated)),
+            report.count(|o| matches!(o, MoveOutcome::Failed { .. })),
+            report.count(|o| matches!(o, MoveOutcome::Deferred)),
+        );
+
+        *self.last_report.write() = Some(report.clone());
+        report
+    }
+
+    /// Execute recommendations with the concurrency and per-cycle limits
+    async fn execute(&self, recommendations: Vec<TierRecommendation>) -> Vec<TierDecision> {
+        let limit = self.config.max_moves_per_cycle;
+        let mut deferred = Vec::new();
+        let mut moves = Vec::new();
+
+        for (idx, recommendation) in recommendations.into_iter().enumerate() {
+            if idx >= limit {
+                deferred.push(TierDecision {
+                    recommendation,
+                    outcome: MoveOutcome::Deferred,
+                });
+                continue;
+            }
+
+            let slots = self.move_slots.clone();
+            let orchestrator = self.orchestrator.clone();
+            moves.push(async move {
+                let outcome = match slots.acquire_owned().await {
+                    Ok(_permit) => {
+                        match orchestrator
+                            .migrate_storage(&recommendation.storage_id, recommendation.to)
+                            .await
+                        {
+                            Ok(_) => MoveOutcome::Migrated,
+                            Err(e) => {
+                                warn!(
+                                    "Tiering move of {} ({}) failed: {}",
+                                    recommendation.storage_id, recommendation.rule, e
+                                );
+                                MoveOutcome::Failed {
+                                    error: e.to_string(),
+                                }
+                            }
+                        }
+                    }
+                    Err(e) => MoveOutcome::Failed {
+                        error: e.to_string(),
+                    },
+                };
+                TierDecision {
+                    recommendation,
+                    outcome,
+                }
+            });
+        }
+
+        let mut decisions = futures::future::join_all(moves).await;
+        decisions.extend(deferred);
+        decisions
+    }
+
+    /// Get the most recent report
+    pub fn last_report(&self) -> Option<TieringReport> {
+        self.last_report.read().clone()
+    }
+
+    /// What a cycle would do now, without moving anything or keeping the
+    /// report
+    pub async fn plan(&self) -> TieringReport {
+        let now = Utc::now();
+        let records = self.orchestrator.list_storage_records().await;
+        let (recommendations, unknown) = self.recommend(&records, now);
+
+        TieringReport {
+            generated_at: now,
+            mode: TieringMode::DryRun,
+            evaluated: records.len(),
+            unknown,
+            decisions: recommendations
+                .into_iter()
+                .map(|recommendation| TierDecision {
+                    recommendation,
+                    outcome: MoveOutcome::Planned,
+                })
+                .collect(),
+        }
+    }
+
+    /// Run evaluation cycles until shutdown
+    pub async fn run(self: Arc<Self>, mut shutdown: broadcast::Receiver<()>) {
+        let mut interval =
+            tokio::time::interval(Duration::from_secs(self.config.evaluation_interval_secs.max(1)));
+
+        info!(
+            "Tiering engine started ({:?}, every {}s, max {} concurrent moves)",
+            self.config.mode, self.config.evaluation_interval_secs, self.config.max_concurrent_moves
+        );
+
+        loop {
+            tokio::select! {
+                _ = interval.tick() => {
+                    self.run_once().await;
+                }
+                _ = shutdown.recv() => {
+                    info!("Tiering engine shutting down");
+                    break;
+                }
+            }
+        }
+    }
+}
+
+// =============================================================================
+// Utility Functions
+// =============================================================================
+
+/// Format a duration in seconds compactly (e.g. "14d", "6h")
+fn format_secs(secs: u64) -> String {
+    if secs >= SECS_PER_DAY && secs.is_multiple_of(SECS_PER_DAY) {
+        format!("{}d", secs / SECS_PER_DAY)
+    } else if secs >= 3600 && secs.is_multiple_of(3600) {
+        format!("{}h", secs / 3600)
+    } else {
+        format!("{}s", secs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::controlplane::OrchestratorConfig;
+    use crate::domain::ports::{ProvisionRequest, StorageType};
+    use std::collections::BTreeMap;
+
+    async fn setup(config: TieringConfig) -> (Arc<Orchestrator>, Arc<TieringEngine>) {
+        let registry = NodeRegistry::new();
+        let orchestrator = Orchestrator::new(OrchestratorConfig::default(), registry.clone());
+        orchestrator.initialize().await.unwrap();
+        let engine = TieringEngine::new(config, orchestrator.clone(), registry);
+        (orchestrator, engine)
+    }
+
+    async fn provision(orchestrator: &Orchestrator, name: &str, tier: StorageTier) -> String {
+        let request = ProvisionRequest {
+            request_id: format!("req-{}", name),
+            name: name.into(),
+            storage_type: StorageType::Block,
+            capacity_bytes: 10 * 1024 * 1024 * 1024,
+            tier: Some(tier),
+            max_iops: None,
+            labels: BTreeMap::new(),
+            annotations: BTreeMap::new(),
+            tenant: None,
+            platform_params: BTreeMap::new(),
+        };
+        orchestrator.provision(request).await.unwrap().storage_id
+    }
+
+    #[test]
+    fn test_condition_describe() {
+        let condition = TieringCondition::All {
+            conditions: vec![
+                TieringCondition::IdleFor {
+                    secs: 14 * SECS_PER_DAY,
+                },
+                TieringCondition::PoolUtilizationAbove { percent: 80.0 },
+            ],
+        };
+        assert_eq!(condition.describe(), "no I/O for 14d and pool utilization >= 80%");
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_recommends_without_moving() {
+        let (orchestrator, engine) = setup(TieringConfig::default()).await;
+        let idle = provision(&orchestrator, "idle-volume", StorageTier::Hot).await;
+        let busy = provision(&orchestrator, "busy-volume", StorageTier::Hot).await;
+
+        // The idle volume's agent reports, but no I/O
+        engine.record_access(&idle, 0, 0, 0, 0);
+        let now = Utc::now() + chrono::Duration::days(20);
+        engine.record_access_at(&busy, 1000, 1000, 0, 0, now - chrono::Duration::hours(1));
+
+        let report = engine.run_once_at(now).await;
+
+        assert_eq!(report.evaluated, 2);
+        assert_eq!(report.decisions.len(), 1);
+        let decision = &report.decisions[0];
+        assert_eq!(decision.recommendation.storage_id, idle);
+        assert_eq!(decision.recommendation.to, StorageTier::Cold);
+        assert_eq!(decision.recommendation.rule, "idle-14d-to-cold");
+        assert!(matches!(decision.outcome, MoveOutcome::Planned));
+
+        // Nothing moved in dry-run mode
+        let record = orchestrator.get_storage_record(&idle).await.unwrap();
+        assert_eq!(record.tier, Some(StorageTier::Hot));
+        assert!(engine.last_report().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_enforce_respects_per_cycle_limit() {
+        let config = TieringConfig {
+            mode: TieringMode::Enforce,
+            max_concurrent_moves: 1,
+            max_moves_per_cycle: 2,
+            ..Default::default()
+        };
+        let (orchestrator, engine) = setup(config).await;
+        for i in 0..3 {
+            let id = provision(&orchestrator, &format!("volume-{}", i), StorageTier::Hot).await;
+            engine.record_access(&id, 0, 0, 0, 0);
+        }
+
+        let now = Utc::now() + chrono::Duration::days(20);
+        let report = engine.run_once_at(now).await;
+
+        assert_eq!(report.count(|o| matches!(o, MoveOutcome::Migrated)), 2);
+        assert_eq!(report.count(|o| matches!(o, MoveOutcome::Deferred)), 1);
+
+        let cold = orchestrator
+            .list_storage_records()
+            .await
+            .into_iter()
+            .filter(|r| r.tier == Some(StorageTier::Cold))
+            .count();
+        assert_eq!(cold, 2);
+    }
+
+    #[tokio::test]
+    async fn test_cache_hits_keep_volume_warm() {
+        let (orchestrator, engine) = setup(TieringConfig::default()).await;
+        let id = provision(&orchestrator, "cached-volume", StorageTier::Hot).await;
+
+        let now = Utc::now();
+        engine.record_cache_lookups_at(&id, 1, 1, now);
+        let stats = engine.access_stats(&id).unwrap();
+        assert_eq!(stats.cache_hit_ratio(), Some(0.5));
+
+        // Idle for 4 days after the first lookups, past the 3-day hot-to-warm rule
+        let later = now + chrono::Duration::days(4);
+        assert_eq!(engine.evaluate_at(later).await.len(), 1);
+
+        // A hit 2 days in leaves it idle for only 2 days
+        engine.record_cache_lookups_at(&id, 1, 0, now + chrono::Duration::days(2));
+        assert!(engine.evaluate_at(later).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_volumes_without_statistics_are_left_alone() {
+        let config = TieringConfig {
+            mode: TieringMode::Enforce,
+            ..Default::default()
+        };
+        let (orchestrator, engine) = setup(config).await;
+        let id = provision(&orchestrator, "unobserved", StorageTier::Hot).await;
+
+        let now = Utc::now() + chrono::Duration::days(30);
+        let report = engine.run_once_at(now).await;
+
+        assert!(report.decisions.is_empty());
+        assert_eq!(report.unknown, vec![id.clone()]);
+        let record = orchestrator.get_storage_record(&id).await.unwrap();
+        assert_eq!(record.tier, Some(StorageTier::Hot));
+    }
+}
@@ -0,0 +1,742 @@
+//! Webhook Notifications
+//!
+//! Pushes events from the event hub (drive failures, nodes going offline,
+//! degraded pools, failed provisions, ...) to HTTP endpoints. Each
+//! subscription selects events by kind or type and node, has its own
+//! delivery queue and rate limit, and optionally signs payloads with
+//! HMAC-SHA256. Failed deliveries are retried with exponential backoff and
+//! parked in a dead-letter list once retries are exhausted.
+//!
+//! Each request is a `POST` of the `StreamEvent` as JSON with headers:
+//!
+//! - `X-Webhook-Event`: event type, e.g. `NodeWentOffline`
+//! - `X-Webhook-Id`: event ID (stable across retries)
+//! - `X-Webhook-Timestamp`: Unix seconds when the attempt was signed
+//! - `X-Webhook-Signature`: `sha256=<hex HMAC of "{timestamp}.{body}">`,
+//!   only when the subscription has a secret
+
+use crate::controlplane::events::{EventFilter, EventHub, StreamEvent, StreamItem};
+use crate::error::{Error, Result};
+use chrono::{DateTime, Utc};
+use parking_lot::{Mutex, RwLock};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, VecDeque};
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use tokio::time::Instant;
+use tracing::{debug, info, warn};
+
+// =============================================================================
+// Constants
+// =============================================================================
+
+/// Header carrying the event type
+pub const WEBHOOK_EVENT_HEADER: &str = "x-webhook-event";
+
+/// Header carrying the event ID
+pub const WEBHOOK_ID_HEADER: &str = "x-webhook-id";
+
+/// Header carrying the signing timestamp (Unix seconds)
+pub const WEBHOOK_TIMESTAMP_HEADER: &str = "x-webhook-timestamp";
+
+/// Header carrying the HMAC-SHA256 signature
+pub const WEBHOOK_SIGNATURE_HEADER: &str = "x-webhook-signature";
+
+/// Deliveries per minute per endpoint when a subscription sets no limit
+pub const DEFAULT_MAX_PER_MINUTE: u32 = 60;
+
+// =============================================================================
+// Configuration
+// =============================================================================
+
+/// An endpoint and the events it receives
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookSubscription {
+    /// Unique subscription name
+    pub name: String,
+
+    /// Endpoint receiving `POST` requests
+    pub url: String,
+
+    /// Event kinds (`registry`, `orchestrator`, `cache`) or types
+    /// (`DriveHealthChanged`, `PoolDegraded`, ...); empty means all
+    #[serde(default)]
+    pub events: Vec<String>,
+
+    /// Only events concerning this node
+    #[serde(default)]
+    pub node: Option<String>,
+
+    /// Key used to sign payloads (never returned by the API)
+    #[serde(default, skip_serializing)]
+    pub secret: Option<String>,
+
+    /// Maximum requests per minute to this endpoint, retries included
+    #[serde(default)]
+    pub max_per_minute: Option<u32>,
+}
+
+impl WebhookSubscription {
+    /// Check the name, URL and rate limit
+    pub fn validate(&self) -> Result<()> {
+        if self.name.trim().is_empty() {
+            return Err(Error::ApiValidation("webhook name must not be empty".into()));
+        }
+        let url = reqwest::Url::parse(&self.url).map_err(|e| {
+            Error::ApiValidation(format!("webhook {}: invalid URL {}: {}", self.name, self.url, e))
+        })?;
+        if !matches!(url.scheme(), "http" | "https") {
+            return Err(Error::ApiValidation(format!(
+                "webhook {}: URL must be http or https",
+                self.name
+            )));
+        }
+        if self.max_per_minute == Some(0) {
+            return Err(Error::ApiValidation(format!(
+                "webhook {}: maxPerMinute must be positive",
+                self.name
+            )));
+        }
+        Ok(())
+    }
+
+    fn filter(&self) -> EventFilter {
+        EventFilter::new(Some(&self.events.join(",")), self.node.clone())
+    }
+
+    /// Minimum spacing between requests to the endpoint
+    fn spacing(&self) -> Duration {
+        let per_minute = self.max_per_minute.unwrap_or(DEFAULT_MAX_PER_MINUTE).max(1);
+        Duration::from_secs(60) / per_minute
+    }
+}
+
+/// Retry and queueing settings shared by all subscriptions
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct WebhookDeliveryConfig {
+    /// Attempts per event before it is dead-lettered
+    pub max_attempts: u32,
+    /// Delay before the first retry; doubled after each failure
+    pub initial_backoff_ms: u64,
+    /// Upper bound on the retry delay
+    pub max_backoff_ms: u64,
+    /// Timeout for a single request
+    pub timeout_secs: u64,
+    /// Events queued per endpoint before new ones are dead-lettered
+    pub queue_capacity: usize,
+    /// Dead letters kept (oldest are dropped first)
+    pub dead_letter_capacity: usize,
+}
+
+impl Default for WebhookDeliveryConfig {
+    fn default() -> Self {
+        Self {
+            max_attempts: 5,
+            initial_backoff_ms: 1000,
+            max_backoff_ms: 60_000,
+            timeout_secs: 10,
+            queue_capacity: 1000,
+            dead_letter_capacity: 1000,
+        }
+    }
+}
+
+/// Webhook configuration file
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhooksFile {
+    #[serde(default)]
+    pub delivery: WebhookDeliveryConfig,
+    #[serde(default)]
+    pub subscriptions: Vec<WebhookSubscription>,
+}
+
+impl WebhooksFile {
+    /// Read a YAML (or JSON) webhook configuration file
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)?;
+        serde_yaml::from_str(&content)
+            .map_err(|e| Error::Configuration(format!("{}: {}", path.display(), e)))
+    }
+}
+
+// =============================================================================
+// Signing
+// =============================================================================
+
+const HMAC_BLOCK_SIZE: usize = 64;
+
+/// HMAC-SHA256 of `message` (RFC 2104)
+pub fn hmac_sha256(key: &[u8], message: &[u8]) -> [u8; 32] {
+    let mut block = [0u8; HMAC_BLOCK_SIZE];
+    if key.len() > HMAC_BLOCK_SIZE {
+        block[..32].copy_from_slice(&Sha256::digest(key));
+    } else {
+        block[..key.len()].copy_from_slice(key);
+    }
+
+    let mut inner = Sha256::new();
+    inner.update(block.map(|b| b ^ 0x36));
+    inner.update(message);
+    let mut outer = Sha256::new();
+    outer.update(block.map(|b| b ^ 0x5c));
+    outer.update(inner.finalize());
+    outer.finalize().into()
+}
+
+/// Value of the signature header for a payload signed at `timestamp`
+pub fn webhook_signature(secret: &str, timestamp: i64, body: &[u8]) -> String {
+    let mut message = format!("{}.", timestamp).into_bytes();
+    message.extend_from_slice(body);
+    let digest = hmac_sha256(secret.as_bytes(), &message);
+    let hex: String = digest.iter().map(|b| format!("{:02x}", b)).collect();
+    format!("sha256={}", hex)
+}
+
+// =============================================================================
+// Delivery State
+// =============================================================================
+
+/// An event that could not be delivered
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeadLetter {
+    pub id: u64,
+    pub subscription: String,
+    pub url: String,
+    pub event: StreamEvent,
+    pub attempts: u32,
+    pub last_error: String,
+    pub failed_at: DateTime<Utc>,
+}
+
+/// Delivery counters for a subscription
+#[derive(Debug, Default)]
+struct DeliveryStats {
+    delivered: AtomicU64,
+    retried: AtomicU64,
+    dead_lettered: AtomicU64,
+}
+
+/// A subscription as reported by the API
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookStatus {
+    #[serde(flatten)]
+    pub subscription: WebhookSubscription,
+    /// Whether payloads are signed
+    pub signed: bool,
+    pub delivered: u64,
+    pub retried: u64,
+    pub dead_lettered: u64,
+    /// Events waiting to be sent
+    pub queued: usize,
+}
+
+/// Why an attempt failed and whether another attempt may succeed
+struct AttemptFailure {
+    reason: String,
+    retryable: bool,
+}
+
+struct Shared {
+    config: WebhookDeliveryConfig,
+    client: reqwest::Client,
+    dead_letters: Mutex<VecDeque<DeadLetter>>,
+    next_dead_letter_id: AtomicU64,
+}
+
+impl Shared {
+    fn dead_letter(
+        &self,
+        subscription: &WebhookSubscription,
+        stats: &DeliveryStats,
+        event: StreamEvent,
+        attempts: u32,
+        reason: String,
+    ) {
+        warn!(
+            "Webhook {}: giving up on event {} ({}) after {} attempt(s): {}",
+            subscription.name, event.id, event.event_type, attempts, reason
+        );
+        stats.dead_lettered.fetch_add(1, Ordering::Relaxed);
+
+        let mut dead_letters = self.dead_letters.lock();
+        if dead_letters.len() >= self.config.dead_letter_capacity.max(1) {
+            dead_letters.pop_front();
+        }
+        dead_letters.push_back(DeadLetter {
+            id: self.next_dead_letter_id.fetch_add(1, Ordering::Relaxed),
+            subscription: subscription.name.clone(),
+            url: subscription.url.clone(),
+            event,
+            attempts,
+            last_error: reason,
+            failed_at: Utc::now(),
+        });
+    }
+
+    async fn attempt(
+        &self,
+        subscription: &WebhookSubscription,
+        event: &StreamEvent,
+        body: &[u8],
+    ) -> std::result::Result<(), AttemptFailure> {
+        let timestamp = Utc::now().timestamp();
+        let mut request = self
+            .client
+            .post(&subscription.url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .header(WEBHOOK_EVENT_HEADER, &event.event_type)
+            .header(WEBHOOK_ID_HEADER, event.id.to_string())
+            .header(WEBHOOK_TIMESTAMP_HEADER, timestamp.to_string())
+            .body(body.to_vec());
+        if let Some(secret) = &subscription.secret {
+            request = request.header(
+                WEBHOOK_SIGNATURE_HEADER,
+                webhook_signature(secret, timestamp, body),
+            );
+        }
+
+        match request.send().await {
+            Ok(response) if response.status().is_success() => Ok(()),
+            Ok(response) => {
+                let status = response.status();
+                Err(AttemptFailure {
+                    reason: format!("endpoint returned {}", status),
+                    // Other client errors will not change on retry
+                    retryable: status.is_server_error()
+                        || status == reqwest::StatusCode::REQUEST_TIMEOUT
+                        || status == reqwest::StatusCode::TOO_MANY_REQUESTS,
+                })
+            }
+            Err(e) => Err(AttemptFailure {
+                reason: e.to_string(),
+                retryable: true,
+            }),
+        }
+    }
+}
+
+/// A subscription with its queue and delivery worker
+struct Endpoint {
+    subscription: WebhookSubscription,
+    filter: EventFilter,
+    sender: mpsc::Sender<StreamEvent>,
+    stats: Arc<DeliveryStats>,
+    worker: JoinHandle<()>,
+}
+
+impl Drop for Endpoint {
+    fn drop(&mut self) {
+        self.worker.abort();
+    }
+}
+
+/// Deliver queued events in order, spacing requests by the rate limit
+async fn delivery_worker(
+    shared: Arc<Shared>,
+    subscription: WebhookSubscription,
+    stats: Arc<DeliveryStats>,
+    mut queue: mpsc::Receiver<StreamEvent>,
+) {
+    let spacing = subscription.spacing();
+    let max_backoff = Duration::from_millis(shared.config.max_backoff_ms);
+    let mut next_slot = Instant::now();
+
+    while let Some(event) = queue.recv().await {
+        let body = match serde_json::to_vec(&event) {
+            Ok(body) => body,
+            Err(e) => {
+                shared.dead_letter(&subscription, &stats, event, 0, e.to_string());
+                continue;
+            }
+        };
+
+        let mut backoff = Duration::from_millis(shared.config.initial_backoff_ms);
+        let mut attempts = 0;
+        loop {
+            tokio::time::sleep_until(next_slot).await;
+            next_slot = Instant::now() + spacing;
+            attempts += 1;
+
+            match shared.attempt(&subscription, &event, &body).await {
+                Ok(()) => {
+                    debug!("Webhook {}: delivered event {}", subscription.name, event.id);
+                    stats.delivered.fetch_add(1, Ordering::Relaxed);
+                    break;
+                }
+                Err(failure)
+                    if failure.retryable && attempts < shared.config.max_attempts.max(1) =>
+                {
+                    debug!(
+                        "Webhook {}: attempt {} for event {} failed ({}); retrying in {:?}",
+                        subscription.name, attempts, event.id, failure.reason, backoff
+                    );
+                    stats.retried.fetch_add(1, Ordering::Relaxed);
+                    tokio::time::sleep(backoff).await;
+                    backoff = (backoff * 2).min(max_backoff);
+                }
+                Err(failure) => {
+                    shared.dead_letter(&subscription, &stats, event, attempts, failure.reason);
+                    break;
+                }
+            }
+        }
+    }
+}
+
+// =============================================================================
+// Webhook Dispatcher
+// =============================================================================
+
+/// Delivers hub events to webhook subscriptions
+pub struct WebhookDispatcher {
+    shared: Arc<Shared>,
+    endpoints: RwLock<BTreeMap<String, Endpoint>>,
+}
+
+impl WebhookDispatcher {
+    /// Create a dispatcher with no subscriptions
+    pub fn new(config: WebhookDeliveryConfig) -> Result<Arc<Self>> {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(config.timeout_secs.max(1)))
+            .build()
+            .map_err(|e| Error::Configuration(format!("webhook HTTP client: {}", e)))?;
+        Ok(Arc::new(Self {
+            shared: Arc::new(Shared {
+                config,
+                client,
+                dead_letters: Mutex::new(VecDeque::new()),
+                next_dead_letter_id: AtomicU64::new(1),
+            }),
+            endpoints: RwLock::new(BTreeMap::new()),
+        }))
+    }
+
+    /// Create a dispatcher from a configuration file
+    pub fn from_file(path: &Path) -> Result<Arc<Self>> {
+        let file = WebhooksFile::load(path)?;
+        let dispatcher = Self::new(file.delivery)?;
+        for subscription in file.subscriptions {
+            dispatcher
+                .set_subscription(subscription)
+                .map_err(|e| Error::Configuration(format!("{}: {}", path.display(), e)))?;
+        }
+        Ok(dispatcher)
+    }
+
+    /// Add or replace a subscription
+    ///
+    /// Replacing a subscription drops events still queued for the old one.
+    pub fn set_subscription(&self, subscription: WebhookSubscription) -> Result<()> {
+        subscription.validate()?;
+
+        let (sender, receiver) = mpsc::channel(self.shared.config.queue_capacity.max(1));
+        let stats = Arc::new(DeliveryStats::default());
+        let worker = tokio::spawn(delivery_worker(
+            self.shared.clone(),
+            subscription.clone(),
+            stats.clone(),
+            receiver,
+        ));
+
+        info!("Webhook {} delivering to {}", subscription.name, subscription.url);
+        self.endpoints.write().insert(
+            subscription.name.clone(),
+            Endpoint {
+                filter: subscription.filter(),
+                subscription,
+                sender,
+                stats,
+                worker,
+            },
+        );
+        Ok(())
+    }
+
+    /// Remove a subscription; false if it did not exist
+    pub fn remove_subscription(&self, name: &str) -> bool {
+        self.endpoints.write().remove(name).is_some()
+    }
+
+    /// Subscriptions with their delivery counters
+    pub fn list(&self) -> Vec<WebhookStatus> {
+        self.endpoints.read().values().map(Self::status).collect()
+    }
+
+    /// One subscription with its delivery counters
+    pub fn get(&self, name: &str) -> Option<WebhookStatus> {
+        self.endpoints.read().get(name).map(Self::status)
+    }
+
+    fn status(endpoint: &Endpoint) -> WebhookStatus {
+        WebhookStatus {
+            subscription: endpoint.subscription.clone(),
+            signed: endpoint.subscription.secret.is_some(),
+            delivered: endpoint.stats.delivered.load(Ordering::Relaxed),
+            retried: endpoint.stats.retried.load(Ordering::Relaxed),
+            dead_lettered: endpoint.stats.dead_lettered.load(Ordering::Relaxed),
+            queued: endpoint.sender.max_capacity() - endpoint.sender.capacity(),
+        }
+    }
+
+    /// Queue an event for every subscription it matches
+    pub fn dispatch(&self, event: &StreamEvent) {
+        for endpoint in self.endpoints.read().values() {
+            if !endpoint.filter.matches(event) {
+                continue;
+            }
+            self.enqueue(endpoint, event.clone());
+        }
+    }
+
+    fn enqueue(&self, endpoint: &Endpoint, event: StreamEvent) {
+        if let Err(e) = endpoint.sender.try_send(event) {
+            let event = match e {
+                mpsc::error::TrySendError::Full(event) | mpsc::error::TrySendError::Closed(event) => {
+                    event
+                }
+            };
+            self.shared.dead_letter(
+                &endpoint.subscription,
+                &endpoint.stats,
+                event,
+                0,
+                "delivery queue full".into(),
+            );
+        }
+    }
+
+    /// Events that could not be delivered, oldest first
+    pub fn dead_letters(&self) -> Vec<DeadLetter> {
+        self.shared.dead_letters.lock().iter().cloned().collect()
+    }
+
+    /// Drop all dead letters, returning how many there were
+    pub fn clear_dead_letters(&self) -> usize {
+        let mut dead_letters = self.shared.dead_letters.lock();
+        let count = dead_letters.len();
+        dead_letters.clear();
+        count
+    }
+
+    /// Queue a dead letter for another round of delivery
+    pub fn redeliver(&self, id: u64) -> Result<()> {
+        let mut dead_letters = self.shared.dead_letters.lock();
+        let position = dead_letters
+            .iter()
+            .position(|d| d.id == id)
+            .ok_or_else(|| Error::ResourceNotFound {
+                kind: "DeadLetter".into(),
+                name: id.to_string(),
+            })?;
+
+        let endpoints = self.endpoints.read();
+        let endpoint = endpoints
+            .get(&dead_letters[position].subscription)
+            .ok_or_else(|| Error::ResourceNotFound {
+                kind: "WebhookSubscription".into(),
+                name: dead_letters[position].subscription.clone(),
+            })?;
+        let letter = dead_letters.remove(position).expect("position is in range");
+        drop(dead_letters);
+
+        self.enqueue(endpoint, letter.event);
+        Ok(())
+    }
+
+    /// Forward hub events to subscriptions until the hub goes away
+    pub fn start(self: &Arc<Self>, hub: &EventHub) -> JoinHandle<()> {
+        let dispatcher = self.clone();
+        let mut subscription = hub.subscribe(None, EventFilter::default());
+        tokio::spawn(async move {
+            while let Some(item) = subscription.next().await {
+                match item {
+                    StreamItem::Event(event) => dispatcher.dispatch(&event),
+                    StreamItem::Gap { reason } => {
+                        warn!("Webhook dispatcher missed events: {}", reason)
+                    }
+                }
+            }
+            debug!("Webhook dispatcher stopped");
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::controlplane::events::EventKind;
+    use axum::{extract::State, http::HeaderMap, http::StatusCode, routing::post, Router};
+    use std::sync::atomic::AtomicUsize;
+
+    #[test]
+    fn test_hmac_sha256_rfc4231() {
+        // RFC 4231 test case 2
+        let digest = hmac_sha256(b"Jefe", b"what do ya want for nothing?");
+        let hex: String = digest.iter().map(|b| format!("{:02x}", b)).collect();
+        assert_eq!(
+            hex,
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+
+        let signature = webhook_signature("Jefe", 1700000000, b"{}");
+        assert!(signature.starts_with("sha256="));
+        assert_eq!(signature.len(), "sha256=".len() + 64);
+    }
+
+    #[derive(Clone, Default)]
+    struct Receiver {
+        /// Requests to fail with 503 before succeeding
+        failures: Arc<AtomicUsize>,
+        received: Arc<Mutex<Vec<(HeaderMap, String)>>>,
+    }
+
+    async fn receive(State(receiver): State<Receiver>, headers: HeaderMap, body: String) -> StatusCode {
+        receiver.received.lock().push((headers, body));
+        let failures = receiver.failures.load(Ordering::SeqCst);
+        if failures > 0 {
+            receiver.failures.store(failures - 1, Ordering::SeqCst);
+            StatusCode::SERVICE_UNAVAILABLE
+        } else {
+            StatusCode::OK
+        }
+    }
+
+    async fn local_receiver(failures: usize) -> (String, Receiver) {
+        let receiver = Receiver::default();
+        receiver.failures.store(failures, Ordering::SeqCst);
+        let app = Router::new()
+            .route("/hook", post(receive))
+            .with_state(receiver.clone());
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/hook", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        (url, receiver)
+    }
+
+    fn fast_delivery(max_attempts: u32) -> WebhookDeliveryConfig {
+        WebhookDeliveryConfig {
+            max_attempts,
+            initial_backoff_ms: 1,
+            max_backoff_ms: 5,
+            ..Default::default()
+        }
+    }
+
+    fn subscription(url: &str, events: &[&str]) -> WebhookSubscription {
+        WebhookSubscription {
+            name: "oncall".into(),
+            url: url.into(),
+            events: events.iter().map(|e| e.to_string()).collect(),
+            node: None,
+            secret: Some("s3cret".into()),
+            max_per_minute: Some(6000),
+        }
+    }
+
+    async fn wait_for(mut done: impl FnMut() -> bool) {
+        for _ in 0..200 {
+            if done() {
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        panic!("condition not reached");
+    }
+
+    #[tokio::test]
+    async fn test_signed_delivery_with_retry() {
+        let (url, receiver) = local_receiver(1).await;
+        let dispatcher = WebhookDispatcher::new(fast_delivery(3)).unwrap();
+        dispatcher
+            .set_subscription(subscription(&url, &["NodeWentOffline"]))
+            .unwrap();
+        let hub = EventHub::new(16);
+        dispatcher.start(&hub);
+
+        hub.publish(
+            EventKind::Registry,
+            Some("node-1".into()),
+            &crate::hardware::registry::RegistryEvent::NodeCameOnline {
+                node_id: "node-1".into(),
+            },
+        );
+        hub.publish(
+            EventKind::Registry,
+            Some("node-1".into()),
+            &crate::hardware::registry::RegistryEvent::NodeWentOffline {
+                node_id: "node-1".into(),
+            },
+        );
+
+        wait_for(|| dispatcher.get("oncall").unwrap().delivered == 1).await;
+        let status = dispatcher.get("oncall").unwrap();
+        assert_eq!(status.retried, 1);
+        assert!(status.signed);
+
+        // Only the matching event was sent: one failed attempt, one success
+        let received = receiver.received.lock();
+        assert_eq!(received.len(), 2);
+        let (headers, body) = &received[1];
+        assert_eq!(headers[WEBHOOK_EVENT_HEADER], "NodeWentOffline");
+        assert_eq!(headers[WEBHOOK_ID_HEADER], "2");
+        let timestamp: i64 = headers[WEBHOOK_TIMESTAMP_HEADER].to_str().unwrap().parse().unwrap();
+        assert_eq!(
+            headers[WEBHOOK_SIGNATURE_HEADER],
+            webhook_signature("s3cret", timestamp, body.as_bytes()).as_str()
+        );
+        let event: StreamEvent = serde_json::from_str(body).unwrap();
+        assert_eq!(event.node.as_deref(), Some("node-1"));
+    }
+
+    #[tokio::test]
+    async fn test_exhausted_retries_are_dead_lettered() {
+        let (url, receiver) = local_receiver(usize::MAX).await;
+        let dispatcher = WebhookDispatcher::new(fast_delivery(2)).unwrap();
+        dispatcher.set_subscription(subscription(&url, &[])).unwrap();
+        let hub = EventHub::new(16);
+        dispatcher.start(&hub);
+
+        hub.publish(
+            EventKind::Orchestrator,
+            None,
+            &crate::controlplane::OrchestratorEvent::StorageLost {
+                storage_id: "vol-1".into(),
+            },
+        );
+
+        wait_for(|| dispatcher.dead_letters().len() == 1).await;
+        let letter = &dispatcher.dead_letters()[0];
+        assert_eq!(letter.subscription, "oncall");
+        assert_eq!(letter.attempts, 2);
+        assert_eq!(letter.event.event_type, "StorageLost");
+        assert_eq!(receiver.received.lock().len(), 2);
+
+        // Redelivery succeeds once the endpoint recovers
+        receiver.failures.store(0, Ordering::SeqCst);
+        dispatcher.redeliver(letter.id).unwrap();
+        wait_for(|| dispatcher.get("oncall").unwrap().delivered == 1).await;
+        assert!(dispatcher.dead_letters().is_empty());
+        assert!(dispatcher.redeliver(letter.id).is_err());
+    }
+
+    #[test]
+    fn test_subscription_validation() {
+        assert!(subscription("http://localhost:9000/hook", &[]).validate().is_ok());
+        assert!(subscription("ftp://localhost/hook", &[]).validate().is_err());
+        assert!(subscription("not a url", &[]).validate().is_err());
+
+        let mut unlimited = subscription("https://example.com", &[]);
+        unlimited.max_per_minute = Some(0);
+        assert!(unlimited.validate().is_err());
+    }
+}
@@ -0,0 +1,88 @@
+//! DriveFingerprint CRD
+//!
+//! Declares the expected characteristics of a drive model so that fleet
+//! models can be classified without rebuilding the operator. Resources
+//! take precedence over fingerprint files and the built-in list.
+
+use kube::CustomResource;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+// =============================================================================
+// DriveFingerprint CRD
+// =============================================================================
+
+/// DriveFingerprint describes one drive model
+#[derive(CustomResource, Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[kube(
+    group = "storage.billyronks.io",
+    version = "v1",
+    kind = "DriveFingerprint",
+    plural = "drivefingerprints",
+    shortname = "dfp",
+    printcolumn = r#"{"name": "Pattern", "type": "string", "jsonPath": ".spec.modelPattern"}"#,
+    printcolumn = r#"{"name": "Vendor", "type": "string", "jsonPath": ".spec.vendor"}"#,
+    printcolumn = r#"{"name": "Enterprise", "type": "boolean", "jsonPath": ".spec.enterprise"}"#,
+    printcolumn = r#"{"name": "Age", "type": "date", "jsonPath": ".metadata.creationTimestamp"}"#
+)]
+#[serde(rename_all = "camelCase")]
+pub struct DriveFingerprintSpec {
+    /// Case-insensitive substring of the model name
+    pub model_pattern: String,
+
+    /// Vendor name
+    #[serde(default)]
+    pub vendor: String,
+
+    /// Expected sequential read IOPS
+    #[serde(default)]
+    pub seq_read_iops: Option<u64>,
+
+    /// Expected sequential write IOPS
+    #[serde(default)]
+    pub seq_write_iops: Option<u64>,
+
+    /// Expected random read IOPS (4K)
+    #[serde(default)]
+    pub rand_read_iops: Option<u64>,
+
+    /// Expected random write IOPS (4K)
+    #[serde(default)]
+    pub rand_write_iops: Option<u64>,
+
+    /// Expected sequential read throughput (MB/s)
+    #[serde(default)]
+    pub seq_read_mbps: Option<u32>,
+
+    /// Expected sequential write throughput (MB/s)
+    #[serde(default)]
+    pub seq_write_mbps: Option<u32>,
+
+    /// TBW (Total Bytes Written) rating in TB
+    #[serde(default)]
+    pub tbw_tb: Option<u32>,
+
+    /// DWPD (Drive Writes Per Day) rating
+    #[serde(default)]
+    pub dwpd: Option<f32>,
+
+    /// Enterprise grade
+    #[serde(default)]
+    pub enterprise: bool,
+
+    /// Classified as a high-performance model
+    #[serde(default)]
+    pub high_performance: bool,
+
+    /// Supports ZNS
+    #[serde(default)]
+    pub zns: bool,
+
+    /// Generation/year
+    #[serde(default)]
+    pub generation: Option<String>,
+
+    /// Notes
+    #[serde(default)]
+    pub notes: Option<String>,
+}
@@ -4,14 +4,23 @@
 //! - UnifiedStorageClass: Storage class with cross-platform support
 //! - StorageNode: Node hardware inventory and status
 //! - UnifiedPool: Storage pool spanning multiple backends
+//! - StorageQuota: Per-tenant storage limits
+//! - StoragePolicy: Admission rules for provisioning requests
+//! - DriveFingerprint: Expected characteristics of a drive model
 
 pub mod unified_storage;
 pub mod storage_node;
 pub mod unified_pool;
+pub mod storage_quota;
+pub mod storage_policy;
+pub mod drive_fingerprint;
 
 pub use unified_storage::*;
 pub use storage_node::*;
 pub use unified_pool::*;
+pub use storage_quota::*;
+pub use storage_policy::*;
+pub use drive_fingerprint::*;
 
 // Re-export common types for convenience
 pub use chrono::{DateTime, Utc};
@@ -3,6 +3,9 @@
 //! Represents a node's storage hardware inventory including drives,
 //! their classification, and real-time metrics.
 
+use crate::domain::health::{HealthAssessment, HealthState};
+use crate::domain::ports::{self, DriveInfo, NodeHardwareInfo};
+use crate::domain::risk::{FailureRisk, SmartSample};
 use chrono::{DateTime, Utc};
 use kube::CustomResource;
 use schemars::JsonSchema;
@@ -259,6 +262,14 @@ pub struct DriveStatus {
     #[serde(default)]
     pub smart: Option<SmartStatus>,
 
+    /// Normalized health from interface-specific SMART data
+    #[serde(default)]
+    pub health: Option<DriveHealthStatus>,
+
+    /// Failure risk predicted from SMART history
+    #[serde(default)]
+    pub failure_risk: Option<FailureRiskStatus>,
+
     /// Pool this drive is assigned to (if any)
     #[serde(default)]
     pub pool_ref: Option<String>,
@@ -266,6 +277,31 @@ pub struct DriveStatus {
     /// Whether drive is healthy
     #[serde(default = "default_true")]
     pub healthy: bool,
+
+    /// Why the drive is not eligible for allocation (partitions, mounts,
+    /// holders, filesystem or volume signatures); empty when eligible
+    #[serde(default)]
+    pub in_use: Vec<String>,
+
+    /// World-wide identifier (SCSI WWN, NVMe EUI-64/NGUID), when reported
+    #[serde(default)]
+    pub wwid: Option<String>,
+
+    /// Kernel names of every path to this drive, preferred path first; a
+    /// multipathed drive is listed once with all of its paths
+    #[serde(default)]
+    pub paths: Vec<String>,
+
+    /// Physical location (enclosure bay, PCIe address, NUMA node)
+    #[serde(default)]
+    pub location: Option<DriveLocationStatus>,
+}
+
+impl DriveStatus {
+    /// Whether the drive could be claimed for a pool
+    pub fn is_allocatable(&self) -> bool {
+        self.pool_ref.is_none() && self.healthy && self.in_use.is_empty()
+    }
 }
 
 /// Drive type from discovery
@@ -406,6 +442,134 @@ pub struct SmartStatus {
     /// Overall health status
     #[serde(default = "default_true")]
     pub healthy: bool,
+
+    /// Unrecoverable media errors (NVMe media errors, ATA pending and
+    /// uncorrectable sectors, SCSI uncorrected errors)
+    #[serde(default)]
+    pub media_errors: u64,
+
+    /// Reallocated sectors or grown defects
+    #[serde(default)]
+    pub reallocations: u64,
+}
+
+impl SmartStatus {
+    /// The trend counters as a sample taken at `timestamp`
+    pub fn sample(&self, timestamp: DateTime<Utc>) -> SmartSample {
+        SmartSample {
+            timestamp,
+            temperature_celsius: Some(self.temperature_celsius),
+            wear_percent: Some(self.percentage_used),
+            media_errors: self.media_errors,
+            reallocations: self.reallocations,
+            critical_warning: self.critical_warning,
+        }
+    }
+}
+
+/// Normalized drive health, comparable across ATA, SCSI and NVMe
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DriveHealthStatus {
+    /// Score from 0 (failed) to 100 (no findings)
+    pub score: u8,
+
+    /// healthy, degraded or failing
+    pub state: String,
+
+    /// Findings that lowered the score
+    #[serde(default)]
+    pub reasons: Vec<String>,
+}
+
+impl From<&HealthAssessment> for DriveHealthStatus {
+    fn from(assessment: &HealthAssessment) -> Self {
+        Self {
+            score: assessment.score,
+            state: assessment.state.to_string(),
+            reasons: assessment.reasons.clone(),
+        }
+    }
+}
+
+/// Where a drive sits in the node
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DriveLocationStatus {
+    /// SES enclosure holding the drive
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub enclosure: Option<String>,
+
+    /// Enclosure bay, or PCIe slot for NVMe
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub slot: Option<String>,
+
+    /// PCIe address of the NVMe controller
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pci_address: Option<String>,
+
+    /// NUMA node the drive is attached to
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub numa_node: Option<u32>,
+}
+
+impl From<&ports::DriveLocation> for DriveLocationStatus {
+    fn from(location: &ports::DriveLocation) -> Self {
+        Self {
+            enclosure: location.enclosure.clone(),
+            slot: location.slot.clone(),
+            pci_address: location.pci_address.clone(),
+            numa_node: location.numa_node,
+        }
+    }
+}
+
+impl std::fmt::Display for DriveLocationStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut parts = Vec::new();
+        if let Some(enclosure) = &self.enclosure {
+            parts.push(format!("enclosure {}", enclosure));
+        }
+        if let Some(slot) = &self.slot {
+            parts.push(format!("slot {}", slot));
+        }
+        if let Some(address) = &self.pci_address {
+            parts.push(format!("pci {}", address));
+        }
+        if let Some(node) = self.numa_node {
+            parts.push(format!("numa node {}", node));
+        }
+        if parts.is_empty() {
+            write!(f, "unknown")
+        } else {
+            write!(f, "{}", parts.join(", "))
+        }
+    }
+}
+
+/// Failure risk predicted from SMART trends
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct FailureRiskStatus {
+    /// Score from 0 (no risk) to 100 (failure imminent)
+    pub score: u8,
+
+    /// low, elevated or high
+    pub level: String,
+
+    /// Trends that raised the score
+    #[serde(default)]
+    pub reasons: Vec<String>,
+}
+
+impl From<&FailureRisk> for FailureRiskStatus {
+    fn from(risk: &FailureRisk) -> Self {
+        Self {
+            score: risk.score,
+            level: risk.level.to_string(),
+            reasons: risk.reasons.clone(),
+        }
+    }
 }
 
 /// System information
@@ -539,6 +703,103 @@ impl StorageNode {
     }
 }
 
+impl From<&DriveInfo> for DriveStatus {
+    /// Status for a freshly discovered drive, not yet classified or assigned
+    fn from(info: &DriveInfo) -> Self {
+        let health = info.health.as_ref().map(|h| h.assess());
+        Self {
+            id: info.device_id.clone(),
+            device_path: info.device_path.clone(),
+            drive_type: match info.drive_type {
+                ports::DriveType::Nvme => DriveType::Nvme,
+                ports::DriveType::Ssd => DriveType::Ssd,
+                ports::DriveType::Hdd => DriveType::Hdd,
+                ports::DriveType::Unknown => DriveType::Unknown,
+            },
+            model: info.model.clone(),
+            serial: info.serial.clone(),
+            firmware: info.firmware.clone(),
+            capacity_bytes: info.capacity_bytes,
+            used_bytes: 0,
+            namespaces: info
+                .nvme_namespaces
+                .iter()
+                .map(|ns| NamespaceStatus {
+                    nsid: ns.nsid,
+                    capacity_bytes: ns.capacity_bytes,
+                    active: ns.active,
+                    is_zns: ns.is_zns,
+                    pool_ref: None,
+                })
+                .collect(),
+            classification: DriveClassification::default(),
+            metrics: None,
+            smart: smart_status(info),
+            healthy: health
+                .as_ref()
+                .is_none_or(|h| h.state != HealthState::Failing),
+            health: health.as_ref().map(DriveHealthStatus::from),
+            failure_risk: None,
+            pool_ref: None,
+            in_use: info.in_use.iter().map(ToString::to_string).collect(),
+            wwid: info.wwid.clone(),
+            paths: info.paths.clone(),
+            location: (!info.location.is_empty()).then(|| (&info.location).into()),
+        }
+    }
+}
+
+impl From<&NodeHardwareInfo> for StorageNodeStatus {
+    /// Status for a freshly discovered node
+    fn from(info: &NodeHardwareInfo) -> Self {
+        let mut status = Self {
+            phase: NodePhase::Ready,
+            drives: info.drives.iter().map(DriveStatus::from).collect(),
+            system_info: Some(SystemInfo {
+                memory_bytes: info.memory_bytes,
+                cpu_count: info.cpu_count,
+                ..Default::default()
+            }),
+            last_discovery_time: Some(info.discovered_at),
+            ..Default::default()
+        };
+        status.update_counts();
+        status
+    }
+}
+
+/// SMART status from the raw data and the interface-specific health, when
+/// either was read
+fn smart_status(info: &DriveInfo) -> Option<SmartStatus> {
+    let counters = info
+        .health
+        .as_ref()
+        .map(|h| SmartSample::from_health(h, Utc::now()));
+    let mut status = match (&info.smart_data, &counters) {
+        (Some(smart), _) => SmartStatus {
+            temperature_celsius: smart.temperature_celsius,
+            percentage_used: smart.percentage_used,
+            power_on_hours: smart.power_on_hours,
+            critical_warning: smart.critical_warning,
+            healthy: smart.critical_warning == 0,
+            ..Default::default()
+        },
+        (None, Some(counters)) => SmartStatus {
+            temperature_celsius: counters.temperature_celsius.unwrap_or_default(),
+            percentage_used: counters.wear_percent.unwrap_or_default(),
+            critical_warning: counters.critical_warning,
+            healthy: counters.critical_warning == 0,
+            ..Default::default()
+        },
+        (None, None) => return None,
+    };
+    if let Some(counters) = counters {
+        status.media_errors = counters.media_errors;
+        status.reallocations = counters.reallocations;
+    }
+    Some(status)
+}
+
 impl StorageNodeStatus {
     /// Update drive counts from drives list
     pub fn update_counts(&mut self) {
@@ -549,7 +810,7 @@ impl StorageNodeStatus {
         self.total_capacity_bytes = self.drives.iter().map(|d| d.capacity_bytes).sum();
         self.available_capacity_bytes = self.drives
             .iter()
-            .filter(|d| d.pool_ref.is_none())
+            .filter(|d| d.pool_ref.is_none() && d.in_use.is_empty())
             .map(|d| d.capacity_bytes)
             .sum();
         self.total_capacity = format_bytes(self.total_capacity_bytes);
@@ -627,8 +888,14 @@ mod tests {
                 classification: DriveClassification::default(),
                 metrics: None,
                 smart: None,
+                health: None,
+                failure_risk: None,
                 pool_ref: None,
                 healthy: true,
+                in_use: vec![],
+                wwid: None,
+                paths: vec![],
+                location: None,
             },
             DriveStatus {
                 id: "sda".into(),
@@ -643,8 +910,14 @@ mod tests {
                 classification: DriveClassification::default(),
                 metrics: None,
                 smart: None,
+                health: None,
+                failure_risk: None,
                 pool_ref: Some("cold-pool".into()),
                 healthy: true,
+                in_use: vec![],
+                wwid: None,
+                paths: vec![],
+                location: None,
             },
         ];
         status.update_counts();
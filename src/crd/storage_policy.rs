@@ -0,0 +1,75 @@
+//! StoragePolicy CRD
+//!
+//! Declares admission rules for provisioning requests. Each rule lists
+//! conditions that must all hold for it to match, and whether a match
+//! denies the request or only warns.
+
+use kube::CustomResource;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+// =============================================================================
+// StoragePolicy CRD
+// =============================================================================
+
+/// StoragePolicy holds admission rules evaluated before provisioning
+#[derive(CustomResource, Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[kube(
+    group = "storage.billyronks.io",
+    version = "v1",
+    kind = "StoragePolicy",
+    plural = "storagepolicies",
+    shortname = "spol",
+    printcolumn = r#"{"name": "Age", "type": "date", "jsonPath": ".metadata.creationTimestamp"}"#
+)]
+#[serde(rename_all = "camelCase")]
+pub struct StoragePolicySpec {
+    /// Rules, evaluated in order
+    #[serde(default)]
+    pub rules: Vec<PolicyRule>,
+}
+
+// =============================================================================
+// Rules
+// =============================================================================
+
+/// A single admission rule
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PolicyRule {
+    /// Rule name, reported with every decision it makes
+    pub name: String,
+
+    /// What happens when the rule matches
+    #[serde(default)]
+    pub effect: PolicyEffect,
+
+    /// Conditions that must all hold, e.g. `labels.env == prod`,
+    /// `capacity > 2Ti`, `tier in (hot, warm)`, `params.versioning missing`
+    #[serde(default)]
+    pub when: Vec<String>,
+
+    /// Message returned to the caller
+    #[serde(default)]
+    pub message: Option<String>,
+}
+
+/// Result of a matching rule
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum PolicyEffect {
+    /// Reject the request
+    #[default]
+    Deny,
+    /// Admit the request and return a warning
+    Warn,
+}
+
+impl std::fmt::Display for PolicyEffect {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PolicyEffect::Deny => write!(f, "deny"),
+            PolicyEffect::Warn => write!(f, "warn"),
+        }
+    }
+}
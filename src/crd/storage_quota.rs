@@ -0,0 +1,79 @@
+//! StorageQuota CRD
+//!
+//! Declares per-tenant limits on provisioned storage: capacity per tier,
+//! total capacity, volume count and maximum single-volume size.
+
+use chrono::{DateTime, Utc};
+use kube::CustomResource;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+// =============================================================================
+// StorageQuota CRD
+// =============================================================================
+
+/// StorageQuota limits how much storage a tenant may provision.
+/// The tenant defaults to the namespace the quota is created in.
+#[derive(CustomResource, Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[kube(
+    group = "storage.billyronks.io",
+    version = "v1",
+    kind = "StorageQuota",
+    plural = "storagequotas",
+    shortname = "sq",
+    status = "StorageQuotaStatus",
+    printcolumn = r#"{"name": "Tenant", "type": "string", "jsonPath": ".spec.tenant"}"#,
+    printcolumn = r#"{"name": "Volumes", "type": "integer", "jsonPath": ".status.volumeCount"}"#,
+    printcolumn = r#"{"name": "Used", "type": "integer", "jsonPath": ".status.usedBytes"}"#,
+    printcolumn = r#"{"name": "Age", "type": "date", "jsonPath": ".metadata.creationTimestamp"}"#,
+    namespaced
+)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageQuotaSpec {
+    /// Tenant the quota applies to (defaults to the namespace)
+    #[serde(default)]
+    pub tenant: Option<String>,
+
+    /// Capacity limit per tier ("hot", "warm", "cold"), e.g. "500Gi"
+    #[serde(default)]
+    pub tier_capacity: BTreeMap<String, String>,
+
+    /// Capacity limit across all tiers
+    #[serde(default)]
+    pub total_capacity: Option<String>,
+
+    /// Maximum number of volumes, buckets and shares
+    #[serde(default)]
+    pub max_volumes: Option<u64>,
+
+    /// Maximum size of a single volume
+    #[serde(default)]
+    pub max_volume_size: Option<String>,
+}
+
+// =============================================================================
+// Status
+// =============================================================================
+
+/// Observed usage for a StorageQuota
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageQuotaStatus {
+    /// Bytes used per tier
+    #[serde(default)]
+    pub used_by_tier: BTreeMap<String, u64>,
+
+    /// Bytes used across all tiers
+    #[serde(default)]
+    pub used_bytes: u64,
+
+    /// Number of volumes charged to the tenant
+    #[serde(default)]
+    pub volume_count: u64,
+
+    /// Last time usage was observed
+    #[serde(default)]
+    #[schemars(with = "Option<String>")]
+    pub last_updated: Option<DateTime<Utc>>,
+}
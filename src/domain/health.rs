@@ -0,0 +1,447 @@
+//! Drive Health Model
+//!
+//! Interface-specific health data (ATA SMART attributes, SCSI log pages,
+//! NVMe SMART / Health log) and its normalization into a comparable
+//! 0-100 score. `SmartData` stays the cross-interface summary; this model
+//! keeps the counters that matter for each interface, such as reallocated
+//! and pending sectors on ATA or grown defects on SAS.
+
+use serde::{Deserialize, Serialize};
+
+// =============================================================================
+// Constants
+// =============================================================================
+
+/// Scores at or above this are healthy
+pub const HEALTHY_SCORE: u8 = 80;
+
+/// Scores below this are failing
+pub const FAILING_SCORE: u8 = 50;
+
+// =============================================================================
+// Per-Interface Health
+// =============================================================================
+
+/// A single ATA SMART attribute
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AtaAttribute {
+    /// Attribute ID
+    pub id: u8,
+    /// Attribute name as reported by the drive database
+    pub name: String,
+    /// Normalized current value
+    pub value: u8,
+    /// Worst normalized value seen
+    pub worst: u8,
+    /// Failure threshold (0 = never fails)
+    pub threshold: u8,
+    /// Raw value
+    pub raw: u64,
+    /// Pre-failure (vs. old-age) attribute
+    pub prefailure: bool,
+}
+
+impl AtaAttribute {
+    /// Whether the normalized value has reached the failure threshold
+    pub fn is_failing(&self) -> bool {
+        self.threshold > 0 && self.value <= self.threshold
+    }
+}
+
+/// ATA SMART health
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AtaHealth {
+    /// Overall SMART self-assessment passed
+    pub smart_passed: bool,
+    /// Reallocated sectors (attribute 5)
+    pub reallocated_sectors: u64,
+    /// Sectors pending reallocation (attribute 197)
+    pub pending_sectors: u64,
+    /// Offline uncorrectable sectors (attribute 198)
+    pub offline_uncorrectable: u64,
+    /// Errors reported as uncorrectable to the host (attribute 187)
+    pub reported_uncorrectable: u64,
+    /// Interface CRC errors, usually cabling (attribute 199)
+    pub crc_errors: u64,
+    /// Remaining SSD life in percent, when the drive reports it
+    pub wear_remaining_percent: Option<u8>,
+    /// All attributes
+    pub attributes: Vec<AtaAttribute>,
+}
+
+/// Error counters from a SCSI error counter log page
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScsiErrorCounters {
+    /// Errors corrected (ECC, rereads/rewrites)
+    pub corrected: u64,
+    /// Uncorrected errors
+    pub uncorrected: u64,
+}
+
+/// SCSI/SAS health from log pages
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScsiHealth {
+    /// Informational exceptions reported no failure prediction
+    pub smart_passed: bool,
+    /// Grown defect list length
+    pub grown_defects: u64,
+    /// Write error counters (log page 0x02)
+    pub write_errors: ScsiErrorCounters,
+    /// Read error counters (log page 0x03)
+    pub read_errors: ScsiErrorCounters,
+    /// Verify error counters (log page 0x05)
+    pub verify_errors: ScsiErrorCounters,
+    /// Current temperature (log page 0x0D)
+    pub temperature_celsius: Option<i32>,
+    /// Drive trip temperature (log page 0x0D)
+    pub trip_temperature_celsius: Option<i32>,
+    /// Informational exception ASC/ASCQ (log page 0x2F), non-zero when a
+    /// failure is predicted
+    pub ie_asc: u8,
+    /// Informational exception ASCQ
+    pub ie_ascq: u8,
+    /// Percentage of SSD endurance used (log page 0x11)
+    pub endurance_used_percent: Option<u8>,
+}
+
+impl ScsiHealth {
+    /// Uncorrected errors across read, write and verify
+    pub fn total_uncorrected(&self) -> u64 {
+        self.read_errors.uncorrected
+            + self.write_errors.uncorrected
+            + self.verify_errors.uncorrected
+    }
+}
+
+/// NVMe SMART / Health Information log
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NvmeHealth {
+    /// Critical warning bitmap
+    pub critical_warning: u8,
+    /// Composite temperature in Celsius
+    pub temperature_celsius: i32,
+    /// Available spare in percent
+    pub available_spare: u8,
+    /// Available spare threshold in percent
+    pub spare_threshold: u8,
+    /// Percentage of rated endurance used (may exceed 100)
+    pub percentage_used: u8,
+    /// Unrecovered data integrity errors
+    pub media_errors: u64,
+    /// Error information log entries
+    pub error_log_entries: u64,
+    /// Unsafe shutdowns
+    pub unsafe_shutdowns: u64,
+}
+
+/// Interface-specific drive health
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "interface", rename_all = "lowercase")]
+pub enum DriveHealth {
+    /// SATA drive
+    Ata(AtaHealth),
+    /// SAS/SCSI drive
+    Scsi(ScsiHealth),
+    /// NVMe drive
+    Nvme(NvmeHealth),
+}
+
+// =============================================================================
+// Health Assessment
+// =============================================================================
+
+/// Coarse health state derived from the score
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HealthState {
+    /// Score at or above `HEALTHY_SCORE`
+    Healthy,
+    /// Wearing or accumulating errors
+    Degraded,
+    /// Score below `FAILING_SCORE`
+    Failing,
+}
+
+impl std::fmt::Display for HealthState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HealthState::Healthy => write!(f, "healthy"),
+            HealthState::Degraded => write!(f, "degraded"),
+            HealthState::Failing => write!(f, "failing"),
+        }
+    }
+}
+
+/// Normalized health, comparable across interfaces
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HealthAssessment {
+    /// 0 (failed) to 100 (no findings)
+    pub score: u8,
+    /// State derived from the score
+    pub state: HealthState,
+    /// Findings that lowered the score
+    pub reasons: Vec<String>,
+}
+
+/// Accumulates penalties and caps into a score
+struct Scorer {
+    penalty: u32,
+    cap: u8,
+    reasons: Vec<String>,
+}
+
+impl Scorer {
+    fn new() -> Self {
+        Self {
+            penalty: 0,
+            cap: 100,
+            reasons: Vec::new(),
+        }
+    }
+
+    fn penalize(&mut self, points: u32, reason: String) {
+        self.penalty += points;
+        self.reasons.push(reason);
+    }
+
+    /// Limit the score regardless of other findings
+    fn cap(&mut self, max: u8, reason: String) {
+        self.cap = self.cap.min(max);
+        self.reasons.push(reason);
+    }
+
+    /// Penalty for a counter that should stay at zero, by magnitude
+    fn counter(&mut self, count: u64, what: &str, points: [u32; 3]) {
+        let p = match count {
+            0 => return,
+            1..=9 => points[0],
+            10..=99 => points[1],
+            _ => points[2],
+        };
+        self.penalize(p, format!("{} {}", count, what));
+    }
+
+    fn finish(self) -> HealthAssessment {
+        let score = 100u32.saturating_sub(self.penalty).min(self.cap as u32) as u8;
+        let state = if score >= HEALTHY_SCORE {
+            HealthState::Healthy
+        } else if score >= FAILING_SCORE {
+            HealthState::Degraded
+        } else {
+            HealthState::Failing
+        };
+        HealthAssessment {
+            score,
+            state,
+            reasons: self.reasons,
+        }
+    }
+}
+
+impl DriveHealth {
+    /// Normalize into a comparable score
+    pub fn assess(&self) -> HealthAssessment {
+        let mut s = Scorer::new();
+        match self {
+            DriveHealth::Ata(h) => assess_ata(h, &mut s),
+            DriveHealth::Scsi(h) => assess_scsi(h, &mut s),
+            DriveHealth::Nvme(h) => assess_nvme(h, &mut s),
+        }
+        s.finish()
+    }
+}
+
+fn assess_ata(h: &AtaHealth, s: &mut Scorer) {
+    if !h.smart_passed {
+        s.cap(10, "SMART self-assessment failed".into());
+    }
+    for attr in h.attributes.iter().filter(|a| a.is_failing()) {
+        let max = if attr.prefailure { 10 } else { 40 };
+        s.cap(max, format!("attribute {} ({}) at threshold", attr.id, attr.name));
+    }
+
+    s.counter(h.reallocated_sectors, "reallocated sectors", [10, 20, 40]);
+    s.counter(h.pending_sectors, "pending sectors", [15, 30, 50]);
+    s.counter(h.offline_uncorrectable, "offline uncorrectable sectors", [20, 30, 50]);
+    s.counter(h.reported_uncorrectable, "reported uncorrectable errors", [10, 20, 30]);
+    s.counter(h.crc_errors, "interface CRC errors", [2, 5, 10]);
+
+    if let Some(remaining) = h.wear_remaining_percent {
+        wear(100u8.saturating_sub(remaining), s);
+    }
+}
+
+fn assess_scsi(h: &ScsiHealth, s: &mut Scorer) {
+    if !h.smart_passed || h.ie_asc != 0 {
+        s.cap(
+            10,
+            format!(
+                "failure predicted (ASC {:#04x}, ASCQ {:#04x})",
+                h.ie_asc, h.ie_ascq
+            ),
+        );
+    }
+
+    s.counter(h.grown_defects, "grown defects", [10, 20, 40]);
+    s.counter(h.total_uncorrected(), "uncorrected read/write/verify errors", [25, 40, 60]);
+
+    if let (Some(temp), Some(trip)) = (h.temperature_celsius, h.trip_temperature_celsius) {
+        if trip > 0 && temp >= trip {
+            s.penalize(20, format!("temperature {}C at trip point {}C", temp, trip));
+        }
+    }
+    if let Some(used) = h.endurance_used_percent {
+        wear(used, s);
+    }
+}
+
+fn assess_nvme(h: &NvmeHealth, s: &mut Scorer) {
+    let cw = h.critical_warning;
+    if cw & 0x04 != 0 {
+        s.cap(10, "reliability degraded".into());
+    }
+    if cw & 0x08 != 0 {
+        s.cap(0, "media placed in read-only mode".into());
+    }
+    if cw & 0x10 != 0 {
+        s.penalize(20, "volatile memory backup failed".into());
+    }
+    if cw & 0x02 != 0 {
+        s.penalize(10, "temperature outside thresholds".into());
+    }
+    if cw & 0x01 != 0 || (h.spare_threshold > 0 && h.available_spare <= h.spare_threshold) {
+        s.penalize(
+            30,
+            format!(
+                "available spare {}% at threshold {}%",
+                h.available_spare, h.spare_threshold
+            ),
+        );
+    }
+
+    s.counter(h.media_errors, "media errors", [20, 30, 50]);
+    wear(h.percentage_used, s);
+}
+
+/// Penalty for consumed endurance, shared by SSD interfaces
+fn wear(used_percent: u8, s: &mut Scorer) {
+    let p = match used_percent {
+        0..=79 => return,
+        80..=89 => 10,
+        90..=99 => 30,
+        _ => 45,
+    };
+    s.penalize(p, format!("{}% of rated endurance used", used_percent));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clean_drives_score_full() {
+        let ata = DriveHealth::Ata(AtaHealth {
+            smart_passed: true,
+            ..Default::default()
+        });
+        let scsi = DriveHealth::Scsi(ScsiHealth {
+            smart_passed: true,
+            ..Default::default()
+        });
+        let nvme = DriveHealth::Nvme(NvmeHealth {
+            available_spare: 100,
+            spare_threshold: 10,
+            ..Default::default()
+        });
+
+        for health in [ata, scsi, nvme] {
+            let a = health.assess();
+            assert_eq!(a.score, 100);
+            assert_eq!(a.state, HealthState::Healthy);
+            assert!(a.reasons.is_empty());
+        }
+    }
+
+    #[test]
+    fn test_ata_pending_and_reallocated_sectors() {
+        let a = DriveHealth::Ata(AtaHealth {
+            smart_passed: true,
+            reallocated_sectors: 24,
+            pending_sectors: 3,
+            ..Default::default()
+        })
+        .assess();
+        assert_eq!(a.score, 65);
+        assert_eq!(a.state, HealthState::Degraded);
+        assert_eq!(a.reasons.len(), 2);
+    }
+
+    #[test]
+    fn test_failing_prefailure_attribute_caps_score() {
+        let a = DriveHealth::Ata(AtaHealth {
+            smart_passed: true,
+            attributes: vec![AtaAttribute {
+                id: 5,
+                name: "Reallocated_Sector_Ct".into(),
+                value: 5,
+                worst: 5,
+                threshold: 10,
+                raw: 0,
+                prefailure: true,
+            }],
+            ..Default::default()
+        })
+        .assess();
+        assert_eq!(a.score, 10);
+        assert_eq!(a.state, HealthState::Failing);
+    }
+
+    #[test]
+    fn test_scsi_failure_prediction_and_uncorrected_errors() {
+        let a = DriveHealth::Scsi(ScsiHealth {
+            smart_passed: true,
+            read_errors: ScsiErrorCounters {
+                corrected: 1000,
+                uncorrected: 2,
+            },
+            ..Default::default()
+        })
+        .assess();
+        assert_eq!(a.score, 75);
+
+        let a = DriveHealth::Scsi(ScsiHealth {
+            smart_passed: true,
+            ie_asc: 0x5d,
+            ie_ascq: 0x10,
+            ..Default::default()
+        })
+        .assess();
+        assert_eq!(a.score, 10);
+    }
+
+    #[test]
+    fn test_nvme_critical_warnings() {
+        let a = DriveHealth::Nvme(NvmeHealth {
+            critical_warning: 0x08,
+            ..Default::default()
+        })
+        .assess();
+        assert_eq!(a.score, 0);
+
+        let a = DriveHealth::Nvme(NvmeHealth {
+            available_spare: 8,
+            spare_threshold: 10,
+            percentage_used: 92,
+            ..Default::default()
+        })
+        .assess();
+        assert_eq!(a.score, 40);
+        assert_eq!(a.state, HealthState::Failing);
+    }
+
+    #[test]
+    fn test_serde_tagged_by_interface() {
+        let json = serde_json::to_value(DriveHealth::Nvme(NvmeHealth::default())).unwrap();
+        assert_eq!(json["interface"], "nvme");
+    }
+}
@@ -3,6 +3,12 @@
 //! This module defines the core traits (ports) that adapters implement,
 //! following hexagonal architecture principles.
 
+pub mod capacity;
+pub mod health;
 pub mod ports;
+pub mod risk;
 
+pub use capacity::*;
+pub use health::*;
 pub use ports::*;
+pub use risk::*;
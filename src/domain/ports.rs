@@ -3,6 +3,7 @@
 //! These traits define the boundaries between the domain logic and external systems.
 //! Adapters implement these traits to provide concrete functionality.
 
+use super::health::DriveHealth;
 use crate::error::Result;
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
@@ -32,8 +33,24 @@ impl std::fmt::Display for StorageType {
     }
 }
 
+impl std::str::FromStr for StorageType {
+    type Err = crate::error::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "block" => Ok(StorageType::Block),
+            "file" => Ok(StorageType::File),
+            "object" => Ok(StorageType::Object),
+            _ => Err(crate::error::Error::ApiValidation(format!(
+                "Invalid storage type: {}. Use 'block', 'file', or 'object'",
+                s
+            ))),
+        }
+    }
+}
+
 /// Storage tier for performance classification
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum StorageTier {
     Hot,
@@ -51,6 +68,63 @@ impl std::fmt::Display for StorageTier {
     }
 }
 
+impl std::str::FromStr for StorageTier {
+    type Err = crate::error::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "hot" => Ok(StorageTier::Hot),
+            "warm" => Ok(StorageTier::Warm),
+            "cold" => Ok(StorageTier::Cold),
+            _ => Err(crate::error::Error::ApiValidation(format!(
+                "Invalid tier: {}. Use 'hot', 'warm', or 'cold'",
+                s
+            ))),
+        }
+    }
+}
+
+/// Access mode requested by a workload
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum AccessMode {
+    /// Single-node read/write (RWO)
+    ReadWriteOnce,
+    /// Multi-node read-only (ROX)
+    ReadOnlyMany,
+    /// Multi-node read/write (RWX)
+    ReadWriteMany,
+    /// S3-compatible object access
+    S3,
+}
+
+impl std::fmt::Display for AccessMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AccessMode::ReadWriteOnce => write!(f, "RWO"),
+            AccessMode::ReadOnlyMany => write!(f, "ROX"),
+            AccessMode::ReadWriteMany => write!(f, "RWX"),
+            AccessMode::S3 => write!(f, "S3"),
+        }
+    }
+}
+
+impl std::str::FromStr for AccessMode {
+    type Err = crate::error::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "rwo" | "readwriteonce" => Ok(AccessMode::ReadWriteOnce),
+            "rox" | "readonlymany" => Ok(AccessMode::ReadOnlyMany),
+            "rwx" | "readwritemany" => Ok(AccessMode::ReadWriteMany),
+            "s3" => Ok(AccessMode::S3),
+            _ => Err(crate::error::Error::ApiValidation(format!(
+                "Invalid access mode: {}. Use RWO, ROX, RWX, or S3",
+                s
+            ))),
+        }
+    }
+}
+
 // =============================================================================
 // Provisioning Request/Response
 // =============================================================================
@@ -72,6 +146,12 @@ pub struct ProvisionRequest {
     pub max_iops: Option<u64>,
     /// Labels for the provisioned resource
     pub labels: BTreeMap<String, String>,
+    /// Annotations kept on the storage record
+    #[serde(default)]
+    pub annotations: BTreeMap<String, String>,
+    /// Tenant (namespace) the storage is charged to
+    #[serde(default)]
+    pub tenant: Option<String>,
     /// Platform-specific parameters
     pub platform_params: BTreeMap<String, String>,
 }
@@ -93,6 +173,156 @@ pub struct ProvisionResponse {
     pub primary_node: Option<String>,
     /// Platform-specific details
     pub platform_details: BTreeMap<String, String>,
+    /// How the storage type, tier and backend were chosen
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resolution: Option<Resolution>,
+}
+
+/// Outcome of storage type, tier and backend resolution
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Resolution {
+    /// Resolved storage type
+    pub storage_type: StorageType,
+    /// Resolved tier
+    pub tier: StorageTier,
+    /// Backend chosen to serve the request
+    pub backend: String,
+    /// Pool whose headroom was checked (if any is known)
+    pub pool_name: Option<String>,
+    /// Remaining candidates, best first, tried if the chosen backend is unavailable
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub fallbacks: Vec<String>,
+    /// Whether the storage type was chosen automatically
+    pub auto_type: bool,
+    /// Whether the tier was chosen automatically
+    pub auto_tier: bool,
+    /// Why each choice was made
+    pub reasons: Vec<String>,
+    /// Admission policy warnings raised for the request
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<String>,
+}
+
+// =============================================================================
+// Backend Capabilities
+// =============================================================================
+
+/// `platform_params` key requesting erasure coding, as `<data>+<parity>` (e.g. "4+2")
+pub const PARAM_ERASURE_CODING: &str = "erasureCoding";
+
+/// `platform_params` key requesting encryption at rest ("true"/"false")
+pub const PARAM_ENCRYPTED: &str = "encrypted";
+
+/// `platform_params` key requesting object versioning ("true"/"false")
+pub const PARAM_VERSIONING: &str = "versioning";
+
+/// `platform_params` key naming a snapshot to restore from
+pub const PARAM_SNAPSHOT_SOURCE: &str = "snapshotSource";
+
+/// `platform_params` key naming a volume to clone
+pub const PARAM_CLONE_SOURCE: &str = "cloneSource";
+
+/// What a storage backend can honour
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackendCapabilities {
+    /// Can create volumes from snapshots
+    pub snapshots: bool,
+    /// Can clone existing volumes
+    pub clone: bool,
+    /// Can resize provisioned storage
+    pub resize: bool,
+    /// Can enforce IOPS limits (`max_iops`)
+    pub qos: bool,
+    /// Supports erasure-coded redundancy
+    pub erasure_coding: bool,
+    /// Supports encryption at rest
+    pub encryption: bool,
+    /// Supports object versioning
+    pub versioning: bool,
+    /// Can attach labels to provisioned storage as tags
+    pub tagging: bool,
+    /// Smallest capacity the backend will provision
+    pub min_size_bytes: u64,
+    /// Largest capacity the backend will provision (None = unbounded)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_size_bytes: Option<u64>,
+}
+
+impl BackendCapabilities {
+    /// Check that a request only asks for features this backend supports
+    pub fn validate(&self, backend: &str, request: &ProvisionRequest) -> crate::error::Result<()> {
+        let reject = |reason: String| {
+            Err(crate::error::Error::ApiValidation(format!(
+                "Backend {} cannot serve {}: {}",
+                backend, request.name, reason
+            )))
+        };
+
+        if request.capacity_bytes < self.min_size_bytes {
+            return reject(format!(
+                "capacity {} bytes is below the minimum of {} bytes",
+                request.capacity_bytes, self.min_size_bytes
+            ));
+        }
+        if let Some(max) = self.max_size_bytes {
+            if request.capacity_bytes > max {
+                return reject(format!(
+                    "capacity {} bytes exceeds the maximum of {} bytes",
+                    request.capacity_bytes, max
+                ));
+            }
+        }
+        if let Some(iops) = request.max_iops {
+            if !self.qos {
+                return reject(format!("maxIops={} requires QoS support", iops));
+            }
+        }
+
+        let params = &request.platform_params;
+        if let Some(ec) = params.get(PARAM_ERASURE_CODING) {
+            if parse_ec(ec).is_none() {
+                return reject(format!(
+                    "{}={} is not of the form <data>+<parity>",
+                    PARAM_ERASURE_CODING, ec
+                ));
+            }
+            if !self.erasure_coding {
+                return reject(format!("{}={} requires erasure coding", PARAM_ERASURE_CODING, ec));
+            }
+        }
+        for (key, supported, feature) in [
+            (PARAM_ENCRYPTED, self.encryption, "encryption at rest"),
+            (PARAM_VERSIONING, self.versioning, "versioning"),
+        ] {
+            match params.get(key).map(|v| v.to_lowercase()) {
+                Some(v) if v == "true" && !supported => {
+                    return reject(format!("{}=true requires {}", key, feature));
+                }
+                Some(v) if v != "true" && v != "false" => {
+                    return reject(format!("{}={} must be true or false", key, v));
+                }
+                _ => {}
+            }
+        }
+        if params.contains_key(PARAM_SNAPSHOT_SOURCE) && !self.snapshots {
+            return reject(format!("{} requires snapshot support", PARAM_SNAPSHOT_SOURCE));
+        }
+        if params.contains_key(PARAM_CLONE_SOURCE) && !self.clone {
+            return reject(format!("{} requires clone support", PARAM_CLONE_SOURCE));
+        }
+
+        Ok(())
+    }
+}
+
+/// Parse an erasure coding spec of the form `<data>+<parity>`
+pub fn parse_ec(spec: &str) -> Option<(u32, u32)> {
+    let (data, parity) = spec.split_once('+')?;
+    let data = data.trim().parse().ok().filter(|d| *d > 0)?;
+    let parity = parity.trim().parse().ok()?;
+    Some((data, parity))
 }
 
 // =============================================================================
@@ -145,6 +375,74 @@ pub struct DriveInfo {
     pub nvme_namespaces: Vec<NvmeNamespaceInfo>,
     /// SMART health data
     pub smart_data: Option<SmartData>,
+    /// Interface-specific health (ATA attributes, SCSI log pages, NVMe log)
+    #[serde(default)]
+    pub health: Option<DriveHealth>,
+    /// Existing uses of the device; a drive with any is not allocatable
+    #[serde(default)]
+    pub in_use: Vec<DeviceUsage>,
+    /// World-wide identifier (SCSI WWN, NVMe EUI-64/NGUID), when reported
+    #[serde(default)]
+    pub wwid: Option<String>,
+    /// Kernel names of every path to this drive, preferred path first
+    #[serde(default)]
+    pub paths: Vec<String>,
+    /// Where the drive sits: enclosure bay, PCIe address, NUMA node
+    #[serde(default)]
+    pub location: DriveLocation,
+}
+
+/// Physical location of a drive, as far as sysfs can tell
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DriveLocation {
+    /// SES enclosure holding the drive (logical ID, else device name)
+    pub enclosure: Option<String>,
+    /// Bay within the enclosure, or the PCIe slot of an NVMe drive
+    pub slot: Option<String>,
+    /// PCIe address of the NVMe controller (e.g. 0000:3b:00.0)
+    pub pci_address: Option<String>,
+    /// NUMA node the drive is attached to
+    pub numa_node: Option<u32>,
+}
+
+impl DriveLocation {
+    /// Check if nothing about the location is known
+    pub fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+}
+
+/// Evidence that a device already holds data or is in use by the host
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum DeviceUsage {
+    /// The device has partitions
+    Partitions { names: Vec<String> },
+    /// Other block devices (device-mapper, md) are stacked on it
+    Holders { names: Vec<String> },
+    /// The device or one of its partitions is mounted
+    Mounted { device: String, mountpoint: String },
+    /// The device or one of its partitions is active swap
+    Swap { device: String },
+    /// An on-disk signature (filesystem, LVM, RAID, Ceph, partition table)
+    Signature { signature: String },
+    /// The device could not be read, so it cannot be shown to be empty
+    Unreadable { error: String },
+}
+
+impl std::fmt::Display for DeviceUsage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DeviceUsage::Partitions { names } => write!(f, "has partitions {}", names.join(", ")),
+            DeviceUsage::Holders { names } => write!(f, "held by {}", names.join(", ")),
+            DeviceUsage::Mounted { device, mountpoint } => {
+                write!(f, "{} mounted at {}", device, mountpoint)
+            }
+            DeviceUsage::Swap { device } => write!(f, "{} is active swap", device),
+            DeviceUsage::Signature { signature } => write!(f, "{} signature", signature),
+            DeviceUsage::Unreadable { error } => write!(f, "cannot read device: {}", error),
+        }
+    }
 }
 
 /// NVMe namespace information
@@ -213,6 +511,39 @@ pub trait StorageProvisioner: Send + Sync {
     /// List all provisioned storage
     async fn list(&self) -> Result<Vec<ProvisionResponse>>;
 
+    /// Move provisioned storage to a different tier
+    ///
+    /// Backends that cannot relocate data in place keep the default, which
+    /// reports the operation as unsupported.
+    async fn migrate_tier(&self, storage_id: &str, tier: StorageTier) -> Result<ProvisionResponse> {
+        Err(crate::error::Error::MigrationFailed {
+            volume_name: storage_id.to_string(),
+            reason: format!("{} does not support moving to tier {}", self.backend_name(), tier),
+        })
+    }
+
+    /// Change the capacity of provisioned storage
+    ///
+    /// Only called when `capabilities().resize` is set.
+    async fn resize(&self, storage_id: &str, _capacity_bytes: u64) -> Result<ProvisionResponse> {
+        Err(crate::error::Error::BackendOperationFailed {
+            backend: self.backend_name().to_string(),
+            operation: "resize".into(),
+            reason: format!("resize not supported for {}", storage_id),
+        })
+    }
+
+    /// Replace the labels attached to provisioned storage
+    ///
+    /// Only called when `capabilities().tagging` is set.
+    async fn set_labels(&self, storage_id: &str, _labels: &BTreeMap<String, String>) -> Result<()> {
+        Err(crate::error::Error::BackendOperationFailed {
+            backend: self.backend_name().to_string(),
+            operation: "set_labels".into(),
+            reason: format!("tagging not supported for {}", storage_id),
+        })
+    }
+
     /// Check if backend is healthy
     async fn health_check(&self) -> Result<bool>;
 
@@ -221,6 +552,14 @@ pub trait StorageProvisioner: Send + Sync {
 
     /// Get supported storage types
     fn supported_types(&self) -> Vec<StorageType>;
+
+    /// Describe which optional features the backend honours
+    ///
+    /// The default advertises none, so requests that need any of them are
+    /// rejected rather than silently ignored.
+    fn capabilities(&self) -> BackendCapabilities {
+        BackendCapabilities::default()
+    }
 }
 
 // =============================================================================
@@ -257,7 +596,7 @@ pub enum Platform {
 }
 
 /// Platform-specific storage class info
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct PlatformStorageClass {
     /// Name of the storage class
     pub name: String,
@@ -269,6 +608,24 @@ pub struct PlatformStorageClass {
     pub parameters: BTreeMap<String, String>,
 }
 
+/// Storage that exists on a platform (volume, share or container)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlatformStorage {
+    /// Platform-assigned ID
+    pub storage_id: String,
+    /// Name of the storage resource
+    pub name: String,
+    /// Type of storage
+    pub storage_type: StorageType,
+    /// Capacity in bytes (0 if unbounded)
+    pub capacity_bytes: u64,
+    /// Platform storage class, volume type or policy
+    pub storage_class: String,
+    /// Platform-specific details
+    pub details: BTreeMap<String, String>,
+}
+
 /// Port for platform-specific operations
 #[async_trait]
 pub trait PlatformAdapter: Send + Sync {
@@ -287,6 +644,15 @@ pub trait PlatformAdapter: Send + Sync {
     /// Delete storage class
     async fn delete_storage_class(&self, name: &str) -> Result<()>;
 
+    /// Recreate a deleted storage class exactly as listed: same name,
+    /// parameters and default flag (used to undo a deletion)
+    async fn restore_storage_class(
+        &self,
+        class: &PlatformStorageClass,
+        storage_type: StorageType,
+        tier: StorageTier,
+    ) -> Result<()>;
+
     /// List storage classes
     async fn list_storage_classes(&self) -> Result<Vec<PlatformStorageClass>>;
 
@@ -302,6 +668,24 @@ pub trait PlatformAdapter: Send + Sync {
     /// Delete provisioned storage
     async fn delete_storage(&self, storage_id: &str) -> Result<()>;
 
+    /// List storage that exists on the platform
+    async fn list_storage(&self) -> Result<Vec<PlatformStorage>> {
+        Ok(Vec::new())
+    }
+
+    /// Whether `set_labels` is implemented
+    fn supports_labels(&self) -> bool {
+        false
+    }
+
+    /// Replace the labels (tags, metadata) attached to platform storage
+    async fn set_labels(&self, storage_id: &str, _labels: &BTreeMap<String, String>) -> Result<()> {
+        Err(crate::error::Error::PlatformAdapter {
+            platform: format!("{:?}", self.platform()),
+            reason: format!("labels not supported for {}", storage_id),
+        })
+    }
+
     /// Check platform connectivity
     async fn health_check(&self) -> Result<bool>;
 }
@@ -313,6 +697,8 @@ pub trait PlatformAdapter: Send + Sync {
 /// Allocation result
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AllocationResult {
+    /// Allocation ID (pass to `release`)
+    pub allocation_id: String,
     /// Allocated drive
     pub drive_id: String,
     /// Node where drive is located
@@ -338,6 +724,9 @@ pub struct AllocationConstraints {
     pub prefer_nodes: Vec<String>,
     /// Minimum fault domains
     pub min_fault_domains: Option<u32>,
+    /// Preferred NUMA node for the drives
+    #[serde(default)]
+    pub numa_node: Option<u32>,
 }
 
 /// Port for drive allocation operations
@@ -387,4 +776,54 @@ mod tests {
         assert_eq!(format!("{}", DriveType::Ssd), "ssd");
         assert_eq!(format!("{}", DriveType::Hdd), "hdd");
     }
+
+    #[test]
+    fn test_access_mode_parse() {
+        assert_eq!("RWO".parse::<AccessMode>().unwrap(), AccessMode::ReadWriteOnce);
+        assert_eq!("ReadWriteMany".parse::<AccessMode>().unwrap(), AccessMode::ReadWriteMany);
+        assert_eq!("s3".parse::<AccessMode>().unwrap(), AccessMode::S3);
+        assert!("nfs".parse::<AccessMode>().is_err());
+    }
+
+    #[test]
+    fn test_capabilities_validate() {
+        let caps = BackendCapabilities {
+            erasure_coding: true,
+            min_size_bytes: 1024,
+            max_size_bytes: Some(1 << 30),
+            ..Default::default()
+        };
+        let mut request = ProvisionRequest {
+            request_id: "req-1".into(),
+            name: "vol".into(),
+            storage_type: StorageType::Object,
+            capacity_bytes: 1 << 20,
+            tier: None,
+            max_iops: None,
+            labels: BTreeMap::new(),
+            annotations: BTreeMap::new(),
+            tenant: None,
+            platform_params: BTreeMap::new(),
+        };
+        assert!(caps.validate("test", &request).is_ok());
+
+        request.platform_params.insert(PARAM_ERASURE_CODING.into(), "4+2".into());
+        assert!(caps.validate("test", &request).is_ok());
+
+        request.platform_params.insert(PARAM_VERSIONING.into(), "true".into());
+        let err = caps.validate("test", &request).unwrap_err();
+        assert!(err.to_string().contains("versioning"));
+
+        request.platform_params.clear();
+        request.max_iops = Some(1000);
+        assert!(caps.validate("test", &request).is_err());
+
+        request.max_iops = None;
+        request.capacity_bytes = 2 << 30;
+        assert!(caps.validate("test", &request).is_err());
+
+        assert_eq!(parse_ec("8+3"), Some((8, 3)));
+        assert_eq!(parse_ec("0+2"), None);
+        assert_eq!(parse_ec("4"), None);
+    }
 }
@@ -0,0 +1,415 @@
+//! Predictive Failure Risk
+//!
+//! A drive's current health score says how it is doing now; the failure risk
+//! says where it is heading. Each drive keeps a bounded history of SMART
+//! samples and the risk is computed from trends across that history: how
+//! fast endurance is being consumed, whether media errors or reallocations
+//! are growing, and whether new critical warning bits have appeared.
+
+use super::health::DriveHealth;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+// =============================================================================
+// Constants
+// =============================================================================
+
+/// Samples kept per drive
+pub const DEFAULT_HISTORY_LEN: usize = 64;
+
+/// Risk scores at or above this deprioritize the drive
+pub const ELEVATED_RISK_SCORE: u8 = 30;
+
+/// Risk scores at or above this mark the drive unhealthy
+pub const HIGH_RISK_SCORE: u8 = 60;
+
+/// ATA temperature attribute (Temperature_Celsius)
+const ATA_TEMPERATURE_ATTR: u8 = 194;
+
+// =============================================================================
+// SMART Sample
+// =============================================================================
+
+/// Point-in-time SMART counters used for trend analysis
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SmartSample {
+    /// When the sample was taken
+    pub timestamp: DateTime<Utc>,
+    /// Temperature in Celsius, when reported
+    pub temperature_celsius: Option<i32>,
+    /// Percentage of rated endurance used, when reported
+    pub wear_percent: Option<u8>,
+    /// Unrecoverable media errors (NVMe media errors, ATA pending/uncorrectable
+    /// sectors, SCSI uncorrected errors)
+    pub media_errors: u64,
+    /// Reallocated sectors or grown defects
+    pub reallocations: u64,
+    /// NVMe critical warning bitmap (0 for other interfaces)
+    pub critical_warning: u8,
+}
+
+impl SmartSample {
+    /// Extract the trend counters from interface-specific health
+    pub fn from_health(health: &DriveHealth, timestamp: DateTime<Utc>) -> Self {
+        match health {
+            DriveHealth::Ata(h) => Self {
+                timestamp,
+                temperature_celsius: h
+                    .attributes
+                    .iter()
+                    .find(|a| a.id == ATA_TEMPERATURE_ATTR)
+                    .map(|a| (a.raw & 0xff) as i32),
+                wear_percent: h.wear_remaining_percent.map(|r| 100u8.saturating_sub(r)),
+                media_errors: h.pending_sectors
+                    + h.offline_uncorrectable
+                    + h.reported_uncorrectable,
+                reallocations: h.reallocated_sectors,
+                critical_warning: 0,
+            },
+            DriveHealth::Scsi(h) => Self {
+                timestamp,
+                temperature_celsius: h.temperature_celsius,
+                wear_percent: h.endurance_used_percent,
+                media_errors: h.total_uncorrected(),
+                reallocations: h.grown_defects,
+                critical_warning: 0,
+            },
+            DriveHealth::Nvme(h) => Self {
+                timestamp,
+                temperature_celsius: Some(h.temperature_celsius),
+                wear_percent: Some(h.percentage_used),
+                media_errors: h.media_errors,
+                reallocations: 0,
+                critical_warning: h.critical_warning,
+            },
+        }
+    }
+}
+
+// =============================================================================
+// Failure Risk
+// =============================================================================
+
+/// Coarse risk level derived from the score
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RiskLevel {
+    /// No worrying trend
+    Low,
+    /// Score at or above `ELEVATED_RISK_SCORE`
+    Elevated,
+    /// Score at or above `HIGH_RISK_SCORE`
+    High,
+}
+
+impl std::fmt::Display for RiskLevel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RiskLevel::Low => write!(f, "low"),
+            RiskLevel::Elevated => write!(f, "elevated"),
+            RiskLevel::High => write!(f, "high"),
+        }
+    }
+}
+
+/// Predicted failure risk from SMART trends
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FailureRisk {
+    /// 0 (no risk) to 100 (failure imminent)
+    pub score: u8,
+    /// Level derived from the score
+    pub level: RiskLevel,
+    /// Trends that raised the score
+    pub reasons: Vec<String>,
+}
+
+impl FailureRisk {
+    /// No samples or no worrying trend
+    pub fn none() -> Self {
+        Self {
+            score: 0,
+            level: RiskLevel::Low,
+            reasons: Vec::new(),
+        }
+    }
+
+    fn from_parts(points: u32, reasons: Vec<String>) -> Self {
+        let score = points.min(100) as u8;
+        let level = if score >= HIGH_RISK_SCORE {
+            RiskLevel::High
+        } else if score >= ELEVATED_RISK_SCORE {
+            RiskLevel::Elevated
+        } else {
+            RiskLevel::Low
+        };
+        Self {
+            score,
+            level,
+            reasons,
+        }
+    }
+
+    /// Whether the drive should no longer be considered healthy
+    pub fn is_high(&self) -> bool {
+        self.level == RiskLevel::High
+    }
+
+    /// Reasons joined for events and logs
+    pub fn summary(&self) -> String {
+        if self.reasons.is_empty() {
+            format!("failure risk {} ({})", self.level, self.score)
+        } else {
+            format!(
+                "failure risk {} ({}): {}",
+                self.level,
+                self.score,
+                self.reasons.join("; ")
+            )
+        }
+    }
+}
+
+// =============================================================================
+// SMART History
+// =============================================================================
+
+/// Bounded, time-ordered SMART samples for one drive
+#[derive(Debug, Clone)]
+pub struct SmartHistory {
+    samples: VecDeque<SmartSample>,
+    capacity: usize,
+}
+
+impl Default for SmartHistory {
+    fn default() -> Self {
+        Self::new(DEFAULT_HISTORY_LEN)
+    }
+}
+
+impl SmartHistory {
+    /// Create a history keeping at most `capacity` samples
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            samples: VecDeque::with_capacity(capacity),
+            capacity: capacity.max(2),
+        }
+    }
+
+    /// Append a sample, dropping the oldest when full. Samples older than
+    /// the latest one are ignored.
+    pub fn push(&mut self, sample: SmartSample) -> bool {
+        if let Some(last) = self.samples.back() {
+            if sample.timestamp < last.timestamp {
+                return false;
+            }
+        }
+        if self.samples.len() == self.capacity {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(sample);
+        true
+    }
+
+    /// Number of retained samples
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    /// Whether no samples have been recorded
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+
+    /// Most recent sample
+    pub fn latest(&self) -> Option<&SmartSample> {
+        self.samples.back()
+    }
+
+    /// Retained samples, oldest first
+    pub fn samples(&self) -> impl Iterator<Item = &SmartSample> {
+        self.samples.iter()
+    }
+
+    /// Compute the failure risk from trends across the retained samples
+    pub fn assess(&self) -> FailureRisk {
+        let (first, last) = match (self.samples.front(), self.samples.back()) {
+            (Some(first), Some(last)) if self.samples.len() >= 2 => (first, last),
+            _ => return FailureRisk::none(),
+        };
+        let prev = &self.samples[self.samples.len() - 2];
+
+        let mut points = 0u32;
+        let mut reasons = Vec::new();
+
+        // Endurance consumption rate, projected to exhaustion
+        if let (Some(w0), Some(w1)) = (first.wear_percent, last.wear_percent) {
+            let days = (last.timestamp - first.timestamp).num_seconds() as f64 / 86_400.0;
+            if w1 > w0 && days > 0.0 {
+                let rate = (w1 - w0) as f64 / days;
+                let remaining_days = 100u8.saturating_sub(w1) as f64 / rate;
+                let p = if remaining_days < 30.0 {
+                    50
+                } else if remaining_days < 180.0 {
+                    25
+                } else {
+                    0
+                };
+                if p > 0 {
+                    points += p;
+                    reasons.push(format!(
+                        "wear rising {:.2}%/day, endurance exhausted in ~{:.0} days",
+                        rate, remaining_days
+                    ));
+                }
+            }
+        }
+
+        // Growth of counters that should stay flat
+        let media = last.media_errors.saturating_sub(first.media_errors);
+        points += growth(media, "media errors", [25, 40, 60], &mut reasons);
+        let realloc = last.reallocations.saturating_sub(first.reallocations);
+        points += growth(realloc, "reallocations", [20, 35, 50], &mut reasons);
+
+        // A jump since the previous sample is worse than slow accumulation
+        let sudden = last.media_errors.saturating_sub(prev.media_errors)
+            + last.reallocations.saturating_sub(prev.reallocations);
+        if sudden >= 10 {
+            points += 20;
+            reasons.push(format!("{} new errors since previous sample", sudden));
+        }
+
+        // Critical warning bits that were not set at the start of the window
+        let new_bits = last.critical_warning & !first.critical_warning;
+        if new_bits != 0 {
+            points += 40;
+            reasons.push(format!("new critical warning bits {:#04x}", new_bits));
+        }
+
+        if let (Some(t0), Some(t1)) = (first.temperature_celsius, last.temperature_celsius) {
+            if t1 - t0 >= 15 {
+                points += 10;
+                reasons.push(format!("temperature rose from {}C to {}C", t0, t1));
+            }
+        }
+
+        FailureRisk::from_parts(points, reasons)
+    }
+}
+
+/// Points for counter growth across the window, by magnitude
+fn growth(delta: u64, what: &str, points: [u32; 3], reasons: &mut Vec<String>) -> u32 {
+    let p = match delta {
+        0 => return 0,
+        1..=9 => points[0],
+        10..=99 => points[1],
+        _ => points[2],
+    };
+    reasons.push(format!("{} new {}", delta, what));
+    p
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::health::{AtaHealth, NvmeHealth};
+    use chrono::Duration;
+
+    fn nvme_sample(days: i64, wear: u8, media_errors: u64, critical_warning: u8) -> SmartSample {
+        let base = DateTime::parse_from_rfc3339("2024-01-01T00:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        SmartSample::from_health(
+            &DriveHealth::Nvme(NvmeHealth {
+                critical_warning,
+                temperature_celsius: 40,
+                available_spare: 100,
+                spare_threshold: 10,
+                percentage_used: wear,
+                media_errors,
+                ..Default::default()
+            }),
+            base + Duration::days(days),
+        )
+    }
+
+    #[test]
+    fn test_single_sample_has_no_risk() {
+        let mut history = SmartHistory::default();
+        history.push(nvme_sample(0, 90, 500, 0x04));
+        assert_eq!(history.assess(), FailureRisk::none());
+    }
+
+    #[test]
+    fn test_stable_drive_is_low_risk() {
+        let mut history = SmartHistory::default();
+        for day in 0..10 {
+            history.push(nvme_sample(day, 20, 0, 0));
+        }
+        let risk = history.assess();
+        assert_eq!(risk.score, 0);
+        assert_eq!(risk.level, RiskLevel::Low);
+    }
+
+    #[test]
+    fn test_fast_wear_is_elevated() {
+        let mut history = SmartHistory::default();
+        history.push(nvme_sample(0, 50, 0, 0));
+        history.push(nvme_sample(10, 60, 0, 0));
+        let risk = history.assess();
+        // 1%/day with 40% left is ~40 days
+        assert_eq!(risk.score, 25);
+        assert_eq!(risk.level, RiskLevel::Low);
+
+        history.push(nvme_sample(20, 80, 0, 0));
+        let risk = history.assess();
+        // 1.5%/day with 20% left is ~13 days
+        assert_eq!(risk.score, 50);
+        assert_eq!(risk.level, RiskLevel::Elevated);
+    }
+
+    #[test]
+    fn test_sudden_error_growth_and_new_warning_is_high() {
+        let mut history = SmartHistory::default();
+        history.push(nvme_sample(0, 10, 0, 0));
+        history.push(nvme_sample(1, 10, 0, 0));
+        history.push(nvme_sample(2, 10, 25, 0x04));
+        let risk = history.assess();
+        assert_eq!(risk.score, 100);
+        assert!(risk.is_high());
+        assert_eq!(risk.reasons.len(), 3);
+    }
+
+    #[test]
+    fn test_ata_reallocation_growth() {
+        let t0 = Utc::now();
+        let sample = |realloc: u64, t| {
+            SmartSample::from_health(
+                &DriveHealth::Ata(AtaHealth {
+                    smart_passed: true,
+                    reallocated_sectors: realloc,
+                    ..Default::default()
+                }),
+                t,
+            )
+        };
+        let mut history = SmartHistory::default();
+        history.push(sample(8, t0));
+        history.push(sample(20, t0 + Duration::hours(1)));
+        let risk = history.assess();
+        // 12 new reallocations, all since the previous sample
+        assert_eq!(risk.score, 55);
+        assert_eq!(risk.level, RiskLevel::Elevated);
+    }
+
+    #[test]
+    fn test_history_is_bounded_and_ordered() {
+        let mut history = SmartHistory::new(3);
+        for day in 0..5 {
+            assert!(history.push(nvme_sample(day, 0, 0, 0)));
+        }
+        assert_eq!(history.len(), 3);
+        assert!(!history.push(nvme_sample(1, 0, 0, 0)));
+        assert_eq!(history.samples().next().unwrap(), &nvme_sample(2, 0, 0, 0));
+    }
+}
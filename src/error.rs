@@ -78,6 +78,9 @@ pub enum Error {
     #[error("SMART data unavailable for device: {device}")]
     SmartUnavailable { device: String },
 
+    #[error("Command timed out after {timeout:?}: {command}")]
+    CommandTimeout { command: String, timeout: Duration },
+
     // =========================================================================
     // Allocation Errors
     // =========================================================================
@@ -186,6 +189,32 @@ pub enum Error {
 
     #[error("Cache prefetch failed: {reason}")]
     CachePrefetchFailed { reason: String },
+
+    // =========================================================================
+    // Quota Errors
+    // =========================================================================
+    #[error("Quota exceeded for tenant {tenant}: {reason}")]
+    QuotaExceeded { tenant: String, reason: String },
+
+    // =========================================================================
+    // Policy Errors
+    // =========================================================================
+    #[error("Denied by policy {policy} rule {rule}: {message}")]
+    PolicyDenied {
+        policy: String,
+        rule: String,
+        message: String,
+    },
+
+    // =========================================================================
+    // Saga Errors
+    // =========================================================================
+    #[error("Saga {saga_id} could not compensate step {step}: {reason}")]
+    SagaCompensationFailed {
+        saga_id: String,
+        step: String,
+        reason: String,
+    },
 }
 
 /// Action to take on error during reconciliation
@@ -232,7 +261,10 @@ impl Error {
             | Error::DurationParse(_)
             | Error::CapacityParse(_)
             | Error::CacheBypass { .. }
-            | Error::CacheEntryCorrupted { .. } => ErrorAction::NoRequeue,
+            | Error::CacheEntryCorrupted { .. }
+            | Error::QuotaExceeded { .. }
+            | Error::PolicyDenied { .. }
+            | Error::SagaCompensationFailed { .. } => ErrorAction::NoRequeue,
 
             // Cache tier unavailable - retry with backoff
             Error::CacheTierUnavailable { .. } => ErrorAction::RequeueWithBackoff,
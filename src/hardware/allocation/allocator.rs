@@ -7,8 +7,9 @@ use super::placement::{PlacementCandidate, PlacementEngine, PlacementResult};
 use super::policy::AllocationPolicy;
 use crate::crd::DriveStatus;
 use crate::domain::ports::{AllocationConstraints, AllocationEngine, AllocationResult, StorageType};
+use crate::domain::risk::{ELEVATED_RISK_SCORE, HIGH_RISK_SCORE};
 use crate::error::{Error, Result};
-use crate::hardware::classification::DeviceClassifier;
+use crate::hardware::classification::{DeviceClassifier, FingerprintDatabase, FingerprintDatabaseRef};
 use crate::hardware::registry::NodeRegistry;
 use async_trait::async_trait;
 use std::collections::HashMap;
@@ -16,6 +17,9 @@ use std::sync::Arc;
 use tokio::sync::RwLock;
 use tracing::{debug, info, warn};
 
+/// Score penalty for drives on a NUMA node other than the preferred one
+const REMOTE_NUMA_PENALTY: u32 = 20;
+
 // =============================================================================
 // Allocation Record
 // =============================================================================
@@ -56,9 +60,18 @@ pub struct DriveAllocator {
 impl DriveAllocator {
     /// Create a new allocator
     pub fn new(registry: Arc<NodeRegistry>) -> Arc<Self> {
+        Self::with_fingerprints(registry, FingerprintDatabase::shared())
+    }
+
+    /// Create an allocator that classifies drives with a shared
+    /// fingerprint database
+    pub fn with_fingerprints(
+        registry: Arc<NodeRegistry>,
+        fingerprints: FingerprintDatabaseRef,
+    ) -> Arc<Self> {
         Arc::new(Self {
             registry,
-            classifier: DeviceClassifier::new(),
+            classifier: DeviceClassifier::new().with_fingerprints(fingerprints),
             allocations: RwLock::new(HashMap::new()),
             allocation_counter: std::sync::atomic::AtomicU64::new(0),
         })
@@ -125,6 +138,7 @@ impl DriveAllocator {
             allocations.insert(alloc_id.clone(), record);
 
             results.push(AllocationResult {
+                allocation_id: alloc_id,
                 drive_id: candidate.drive_id,
                 node_id: candidate.node_id,
                 capacity_bytes: candidate.capacity_bytes,
@@ -171,6 +185,23 @@ impl DriveAllocator {
                         continue;
                     }
 
+                    // Never offer drives holding the OS or existing data
+                    if !drive.in_use.is_empty() {
+                        debug!(
+                            "Skipping {} on {}: in use ({})",
+                            drive.id,
+                            node_str,
+                            drive.in_use.join("; ")
+                        );
+                        continue;
+                    }
+
+                    // Skip drives predicted to fail soon
+                    let Some(risk_penalty) = failure_risk_penalty(drive) else {
+                        debug!("Skipping {} on {}: high failure risk", drive.id, node_str);
+                        continue;
+                    };
+
                     // Get classification
                     let tier = drive.classification.tier;
                     let workload = drive.classification.workload;
@@ -193,7 +224,9 @@ impl DriveAllocator {
                             drive_id: drive.id.clone(),
                             fault_domain: entry.fault_domain.clone(),
                             capacity_bytes: drive.capacity_bytes,
-                            score,
+                            score: score
+                                .saturating_sub(risk_penalty)
+                                .saturating_sub(numa_penalty(drive, policy.numa_node)),
                             enterprise: is_enterprise_model(&drive.model),
                         });
                     }
@@ -253,8 +286,8 @@ impl DriveAllocator {
                         continue;
                     }
 
-                    // Skip unhealthy
-                    if !drive.healthy {
+                    // Skip unhealthy, in use or about to fail
+                    if !drive.is_allocatable() || failure_risk_penalty(drive).is_none() {
                         continue;
                     }
 
@@ -312,6 +345,26 @@ pub struct AllocationStats {
     pub nodes_with_allocations: usize,
 }
 
+/// Score penalty for a drive's predicted failure risk, or `None` when the
+/// risk is high enough that the drive should not be offered at all
+fn failure_risk_penalty(drive: &DriveStatus) -> Option<u32> {
+    match &drive.failure_risk {
+        Some(risk) if risk.score >= HIGH_RISK_SCORE => None,
+        Some(risk) if risk.score >= ELEVATED_RISK_SCORE => Some(risk.score as u32 / 2),
+        _ => Some(0),
+    }
+}
+
+/// Score penalty for a drive attached to another NUMA node than the one
+/// preferred; drives whose node is unknown are not penalized
+fn numa_penalty(drive: &DriveStatus, preferred: Option<u32>) -> u32 {
+    let actual = drive.location.as_ref().and_then(|l| l.numa_node);
+    match (preferred, actual) {
+        (Some(preferred), Some(actual)) if preferred != actual => REMOTE_NUMA_PENALTY,
+        _ => 0,
+    }
+}
+
 /// Check if a model is enterprise-grade
 fn is_enterprise_model(model: &str) -> bool {
     let model_upper = model.to_uppercase();
@@ -360,6 +413,7 @@ impl AllocationEngine for DriveAllocator {
         if let Some(min_fd) = constraints.min_fault_domains {
             policy.fault_domains.min_domains = min_fd;
         }
+        policy.numa_node = constraints.numa_node;
 
         self.allocate_with_policy(&policy, count).await
     }
@@ -374,6 +428,7 @@ impl AllocationEngine for DriveAllocator {
     async fn get_allocation(&self, allocation_id: &str) -> Result<Option<AllocationResult>> {
         Ok(self.get_allocation(allocation_id).await.map(|record| {
             AllocationResult {
+                allocation_id: record.id,
                 drive_id: record.drive_id,
                 node_id: record.node_id,
                 capacity_bytes: record.capacity_bytes,
@@ -390,6 +445,7 @@ impl AllocationEngine for DriveAllocator {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::crd::FailureRiskStatus;
 
     #[test]
     fn test_is_enterprise_model() {
@@ -399,4 +455,84 @@ mod tests {
         assert!(!is_enterprise_model("Samsung 980 PRO"));
         assert!(!is_enterprise_model("WD Red"));
     }
+
+    #[test]
+    fn test_failure_risk_penalty() {
+        let status = |score: u8| FailureRiskStatus {
+            score,
+            level: String::new(),
+            reasons: vec![],
+        };
+        let mut drive: DriveStatus = serde_json::from_value(serde_json::json!({
+            "id": "nvme0n1",
+            "devicePath": "/dev/nvme0n1",
+            "driveType": "nvme",
+            "model": "Samsung PM1733",
+            "serial": "S1",
+            "capacityBytes": 1_000_000_000_000u64,
+            "classification": {}
+        }))
+        .unwrap();
+        assert_eq!(failure_risk_penalty(&drive), Some(0));
+
+        drive.failure_risk = Some(status(10));
+        assert_eq!(failure_risk_penalty(&drive), Some(0));
+
+        drive.failure_risk = Some(status(50));
+        assert_eq!(failure_risk_penalty(&drive), Some(25));
+
+        drive.failure_risk = Some(status(HIGH_RISK_SCORE));
+        assert_eq!(failure_risk_penalty(&drive), None);
+    }
+
+    #[tokio::test]
+    async fn test_numa_preference() {
+        use crate::crd::StorageNodeStatus;
+
+        let drive = |id: &str, numa: u32| -> DriveStatus {
+            serde_json::from_value(serde_json::json!({
+                "id": id,
+                "devicePath": format!("/dev/{}", id),
+                "driveType": "nvme",
+                "model": "Intel Optane P5800X",
+                "serial": id,
+                "capacityBytes": 800_000_000_000u64,
+                "classification": {
+                    "tier": "ultrafast",
+                    "workload": "blockoptimized",
+                    "confidenceScore": 95
+                },
+                "location": {"numaNode": numa}
+            }))
+            .unwrap()
+        };
+        assert_eq!(numa_penalty(&drive("nvme0n1", 0), None), 0);
+        assert_eq!(numa_penalty(&drive("nvme0n1", 0), Some(0)), 0);
+        assert_eq!(numa_penalty(&drive("nvme0n1", 0), Some(1)), REMOTE_NUMA_PENALTY);
+
+        let registry = NodeRegistry::new();
+        let status = StorageNodeStatus {
+            drives: vec![drive("nvme0n1", 0), drive("nvme1n1", 1)],
+            ..Default::default()
+        };
+        registry
+            .register("node-001", "host-001.local".to_string(), status)
+            .unwrap();
+        let allocator = DriveAllocator::new(registry);
+
+        // A cache tier for a backend pinned to node 1 stays on node 1
+        let policy = AllocationPolicy {
+            numa_node: Some(1),
+            ..AllocationPolicy::for_cache()
+        };
+        let allocated = allocator.allocate_with_policy(&policy, 1).await.unwrap();
+        assert_eq!(allocated[0].drive_id, "nvme1n1");
+
+        let policy = AllocationPolicy {
+            numa_node: Some(0),
+            ..AllocationPolicy::for_cache()
+        };
+        let allocated = allocator.allocate_with_policy(&policy, 1).await.unwrap();
+        assert_eq!(allocated[0].drive_id, "nvme0n1");
+    }
 }
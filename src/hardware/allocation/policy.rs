@@ -139,6 +139,11 @@ pub struct AllocationPolicy {
 
     /// Node anti-affinity (avoid these nodes)
     pub node_anti_affinity: Vec<String>,
+
+    /// Preferred NUMA node, e.g. the one the backend's CPUs run on; drives
+    /// attached to another node rank lower
+    #[serde(default)]
+    pub numa_node: Option<u32>,
 }
 
 impl Default for AllocationPolicy {
@@ -157,6 +162,7 @@ impl Default for AllocationPolicy {
             prefer_enterprise: false,
             node_affinity: Vec::new(),
             node_anti_affinity: Vec::new(),
+            numa_node: None,
         }
     }
 }
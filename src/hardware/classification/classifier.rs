@@ -3,6 +3,7 @@
 //! Classifies storage devices into performance tiers based on
 //! device characteristics, model fingerprints, and observed metrics.
 
+use super::fingerprint::{FingerprintDatabase, FingerprintDatabaseRef};
 use crate::crd::{CapacityTier, DriveTier, WorkloadSuitability};
 use crate::domain::ports::{DriveInfo, DriveType};
 use crate::error::Result;
@@ -56,10 +57,12 @@ pub struct ClassifierConfig {
     /// ZNS bonus score
     pub zns_bonus: u32,
 
-    /// Known high-performance models (substring match)
+    /// Known high-performance models (substring match), consulted only for
+    /// models no fingerprint matches
     pub high_perf_models: Vec<String>,
 
-    /// Known enterprise models (substring match)
+    /// Known enterprise models (substring match), consulted only for models
+    /// no fingerprint matches
     pub enterprise_models: Vec<String>,
 }
 
@@ -104,19 +107,33 @@ impl Default for ClassifierConfig {
 /// Classifies storage devices into tiers
 pub struct DeviceClassifier {
     config: ClassifierConfig,
+    fingerprints: FingerprintDatabaseRef,
 }
 
 impl DeviceClassifier {
     /// Create a new classifier with default config
     pub fn new() -> Self {
-        Self {
-            config: ClassifierConfig::default(),
-        }
+        Self::with_config(ClassifierConfig::default())
     }
 
     /// Create a classifier with custom config
     pub fn with_config(config: ClassifierConfig) -> Self {
-        Self { config }
+        Self {
+            config,
+            fingerprints: FingerprintDatabase::shared(),
+        }
+    }
+
+    /// Use a shared fingerprint database, so that fingerprints loaded or
+    /// reloaded elsewhere apply to classification
+    pub fn with_fingerprints(mut self, fingerprints: FingerprintDatabaseRef) -> Self {
+        self.fingerprints = fingerprints;
+        self
+    }
+
+    /// The fingerprint database used for classification
+    pub fn fingerprints(&self) -> &FingerprintDatabaseRef {
+        &self.fingerprints
     }
 
     /// Classify a drive
@@ -252,13 +269,16 @@ impl DeviceClassifier {
         }
 
         // Known model increases confidence
-        if self.is_high_perf_model(&drive.model) || self.is_enterprise_model(&drive.model) {
+        if self.fingerprints.lookup(&drive.model).is_some()
+            || self.is_high_perf_model(&drive.model)
+            || self.is_enterprise_model(&drive.model)
+        {
             confidence += 0.1;
             reasons.push("known model fingerprint matched");
         }
 
         // SMART data available increases confidence
-        if drive.smart_data.is_some() {
+        if drive.smart_data.is_some() || drive.health.is_some() {
             confidence += 0.05;
             reasons.push("SMART data available");
         }
@@ -299,21 +319,23 @@ impl DeviceClassifier {
     }
 
     /// Check if model is a known high-performance model
+    ///
+    /// A matching fingerprint decides; the configured list is the fallback.
     fn is_high_perf_model(&self, model: &str) -> bool {
-        let model_upper = model.to_uppercase();
-        self.config
-            .high_perf_models
-            .iter()
-            .any(|m| model_upper.contains(&m.to_uppercase()))
+        match self.fingerprints.lookup(model) {
+            Some(found) => found.fingerprint.high_performance,
+            None => model_listed(model, &self.config.high_perf_models),
+        }
     }
 
     /// Check if model is a known enterprise model
+    ///
+    /// A matching fingerprint decides; the configured list is the fallback.
     fn is_enterprise_model(&self, model: &str) -> bool {
-        let model_upper = model.to_uppercase();
-        self.config
-            .enterprise_models
-            .iter()
-            .any(|m| model_upper.contains(&m.to_uppercase()))
+        match self.fingerprints.lookup(model) {
+            Some(found) => found.fingerprint.enterprise,
+            None => model_listed(model, &self.config.enterprise_models),
+        }
     }
 
     /// Get a numerical score for a drive (0-100)
@@ -340,8 +362,12 @@ impl DeviceClassifier {
             score += 5;
         }
 
-        // Health penalty (if SMART data available)
-        if let Some(smart) = &drive.smart_data {
+        // Health penalty: prefer the normalized interface health score,
+        // falling back to the SMART summary
+        if let Some(health) = &drive.health {
+            let assessment = health.assess();
+            score = score.saturating_sub((100 - assessment.score as u32) / 2);
+        } else if let Some(smart) = &drive.smart_data {
             if smart.critical_warning != 0 {
                 score = score.saturating_sub(20);
             }
@@ -360,6 +386,14 @@ impl Default for DeviceClassifier {
     }
 }
 
+/// Whether `model` contains any of `models` (case-insensitive)
+fn model_listed(model: &str, models: &[String]) -> bool {
+    let model_upper = model.to_uppercase();
+    models
+        .iter()
+        .any(|m| model_upper.contains(&m.to_uppercase()))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -378,6 +412,11 @@ mod tests {
             zns_supported: false,
             nvme_namespaces: vec![],
             smart_data: None,
+            health: None,
+            in_use: Vec::new(),
+            wwid: None,
+            paths: Vec::new(),
+            location: Default::default(),
         }
     }
 
@@ -394,6 +433,11 @@ mod tests {
             zns_supported: false,
             nvme_namespaces: vec![],
             smart_data: None,
+            health: None,
+            in_use: Vec::new(),
+            wwid: None,
+            paths: Vec::new(),
+            location: Default::default(),
         }
     }
 
@@ -463,4 +507,69 @@ mod tests {
         let hdd = make_hdd_drive(4_000_000_000_000);
         assert!(classifier.calculate_score(&hdd) <= 40);
     }
+
+    #[test]
+    fn test_health_score_lowers_classification_score() {
+        use crate::domain::health::{DriveHealth, NvmeHealth};
+
+        let classifier = DeviceClassifier::new();
+        let healthy = make_nvme_drive("Generic NVMe", 1_000_000_000_000);
+
+        let mut worn = healthy.clone();
+        worn.health = Some(DriveHealth::Nvme(NvmeHealth {
+            percentage_used: 95,
+            ..Default::default()
+        }));
+
+        // 95% endurance used scores 70, costing 15 points
+        assert_eq!(
+            classifier.calculate_score(&healthy) - classifier.calculate_score(&worn),
+            15
+        );
+    }
+
+    #[test]
+    fn test_fleet_fingerprint_changes_classification() {
+        let db = FingerprintDatabase::shared();
+        let classifier = DeviceClassifier::new().with_fingerprints(db.clone());
+        let drive = make_nvme_drive("KIOXIA KCD6XLUL3T84", 4_000_000_000_000);
+        assert_eq!(classifier.classify(&drive).performance, DriveTier::FastNvme);
+
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("fleet.yaml");
+        std::fs::write(
+            &file,
+            "- modelPattern: KCD6XLUL\n  vendor: Kioxia\n  enterprise: true\n  highPerformance: true\n",
+        )
+        .unwrap();
+        db.load_files(&[file]).unwrap();
+
+        let result = classifier.classify(&drive);
+        assert_eq!(result.performance, DriveTier::UltraFast);
+        assert!(classifier.is_enterprise_model(&drive.model));
+        assert!(result.reason.contains("fingerprint"));
+    }
+
+    #[test]
+    fn test_file_fingerprint_overrides_configured_model() {
+        let db = FingerprintDatabase::shared();
+        let classifier = DeviceClassifier::new().with_fingerprints(db.clone());
+        let drive = make_nvme_drive("SAMSUNG MZWLJ3T8HBLS PM1733", 4_000_000_000_000);
+        assert!(classifier.is_high_perf_model(&drive.model));
+        assert!(classifier.is_enterprise_model(&drive.model));
+        assert_eq!(classifier.classify(&drive).performance, DriveTier::UltraFast);
+
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("fleet.yaml");
+        std::fs::write(
+            &file,
+            "- modelPattern: PM1733\n  vendor: Samsung\n  enterprise: false\n  highPerformance: false\n",
+        )
+        .unwrap();
+        db.load_files(&[file]).unwrap();
+
+        assert!(!classifier.is_high_perf_model(&drive.model));
+        assert!(!classifier.is_enterprise_model(&drive.model));
+        assert_eq!(classifier.classify(&drive).performance, DriveTier::FastNvme);
+    }
 }
@@ -1,10 +1,18 @@
 //! Model Fingerprinting
 //!
 //! Identifies drive models from known fingerprints to provide
-//! accurate performance expectations without benchmarking.
+//! accurate performance expectations without benchmarking. Fingerprints
+//! beyond the built-in list are loaded from YAML/JSON files and
+//! `DriveFingerprint` resources.
 
+use crate::crd::{DriveFingerprint as DriveFingerprintResource, DriveFingerprintSpec};
+use crate::error::{Error, Result};
+use parking_lot::RwLock;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tracing::{debug, info};
 
 // =============================================================================
 // Drive Fingerprint
@@ -35,6 +43,9 @@ pub struct DriveFingerprint {
     pub dwpd: Option<f32>,
     /// Is enterprise grade
     pub enterprise: bool,
+    /// Counts as a high-performance model in classification
+    #[serde(default)]
+    pub high_performance: bool,
     /// Supports ZNS
     pub zns: bool,
     /// Generation/year
@@ -87,9 +98,65 @@ impl DriveFingerprint {
 // Fingerprint Database
 // =============================================================================
 
-/// Database of known drive fingerprints
+/// Where a fingerprint came from, lowest precedence first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FingerprintSource {
+    /// Compiled into the operator
+    Builtin,
+    /// Loaded from a fingerprint file
+    File,
+    /// A `DriveFingerprint` resource
+    Resource,
+}
+
+impl std::fmt::Display for FingerprintSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FingerprintSource::Builtin => write!(f, "builtin"),
+            FingerprintSource::File => write!(f, "file"),
+            FingerprintSource::Resource => write!(f, "resource"),
+        }
+    }
+}
+
+/// A fingerprint with its provenance
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FingerprintMatch {
+    /// The fingerprint
+    pub fingerprint: DriveFingerprint,
+    /// Where it came from
+    pub source: FingerprintSource,
+    /// File path or resource name (`builtin` for the built-in list)
+    pub origin: String,
+}
+
+impl FingerprintMatch {
+    /// Precedence among fingerprints matching the same model: the longest
+    /// (most specific) pattern, then the highest source
+    fn rank(&self) -> (usize, FingerprintSource) {
+        (self.fingerprint.model_pattern.len(), self.source)
+    }
+}
+
+/// Shared, reloadable fingerprint database
+pub type FingerprintDatabaseRef = Arc<FingerprintDatabase>;
+
+/// Database of known drive fingerprints.
+///
+/// Fingerprints come from three sources: the built-in list, fingerprint
+/// files and `DriveFingerprint` resources. When several match a model,
+/// the longest pattern wins; between equal patterns a resource overrides a
+/// file, which overrides the built-in list, and within a source the one
+/// loaded last wins.
 pub struct FingerprintDatabase {
+    /// Built-in fingerprints
     fingerprints: Vec<DriveFingerprint>,
+    /// Fingerprints from files, in load order
+    files: RwLock<Vec<FingerprintMatch>>,
+    /// Fingerprints from resources, by resource name
+    resources: RwLock<BTreeMap<String, DriveFingerprint>>,
 }
 
 impl FingerprintDatabase {
@@ -97,26 +164,136 @@ impl FingerprintDatabase {
     pub fn new() -> Self {
         let mut db = Self {
             fingerprints: Vec::new(),
+            files: RwLock::new(Vec::new()),
+            resources: RwLock::new(BTreeMap::new()),
         };
         db.load_builtin_fingerprints();
         db
     }
 
-    /// Look up a drive by model
-    pub fn lookup(&self, model: &str) -> Option<&DriveFingerprint> {
-        self.fingerprints.iter().find(|fp| fp.matches(model))
+    /// Create a shared database with built-in fingerprints
+    pub fn shared() -> FingerprintDatabaseRef {
+        Arc::new(Self::new())
+    }
+
+    /// Look up a drive by model, returning the fingerprint that takes
+    /// precedence
+    pub fn lookup(&self, model: &str) -> Option<FingerprintMatch> {
+        self.lookup_all(model).into_iter().next()
     }
 
-    /// Look up all matching fingerprints
-    pub fn lookup_all(&self, model: &str) -> Vec<&DriveFingerprint> {
-        self.fingerprints.iter().filter(|fp| fp.matches(model)).collect()
+    /// Look up all matching fingerprints, highest precedence first
+    pub fn lookup_all(&self, model: &str) -> Vec<FingerprintMatch> {
+        let mut matches = self.collect(|fp| fp.matches(model));
+        // Stable sort on reversed load order keeps the last loaded first
+        matches.reverse();
+        matches.sort_by_key(|m| std::cmp::Reverse(m.rank()));
+        matches
     }
 
-    /// Add a custom fingerprint
+    /// Every fingerprint, lowest precedence source first
+    pub fn entries(&self) -> Vec<FingerprintMatch> {
+        self.collect(|_| true)
+    }
+
+    fn collect(&self, keep: impl Fn(&DriveFingerprint) -> bool) -> Vec<FingerprintMatch> {
+        let mut entries: Vec<FingerprintMatch> = self
+            .fingerprints
+            .iter()
+            .filter(|fp| keep(fp))
+            .map(|fp| FingerprintMatch {
+                fingerprint: fp.clone(),
+                source: FingerprintSource::Builtin,
+                origin: "builtin".to_string(),
+            })
+            .collect();
+        entries.extend(
+            self.files
+                .read()
+                .iter()
+                .filter(|entry| keep(&entry.fingerprint))
+                .cloned(),
+        );
+        entries.extend(
+            self.resources
+                .read()
+                .iter()
+                .filter(|(_, fp)| keep(fp))
+                .map(|(name, fp)| FingerprintMatch {
+                    fingerprint: fp.clone(),
+                    source: FingerprintSource::Resource,
+                    origin: name.clone(),
+                }),
+        );
+        entries
+    }
+
+    /// Add a custom fingerprint to the built-in list
     pub fn add(&mut self, fingerprint: DriveFingerprint) {
         self.fingerprints.push(fingerprint);
     }
 
+    /// Replace all file fingerprints with those in the given files or
+    /// directories (`.yaml`, `.yml` and `.json` files, in name order).
+    ///
+    /// Nothing changes if any file fails to parse. Returns the number of
+    /// fingerprints loaded.
+    pub fn load_files(&self, paths: &[PathBuf]) -> Result<usize> {
+        let mut loaded = Vec::new();
+        for file in fingerprint_files(paths)? {
+            let content = std::fs::read_to_string(&file)?;
+            let origin = file.display().to_string();
+            for fingerprint in parse_fingerprints(&content)
+                .map_err(|e| Error::Configuration(format!("{}: {}", origin, e)))?
+            {
+                loaded.push(FingerprintMatch {
+                    fingerprint,
+                    source: FingerprintSource::File,
+                    origin: origin.clone(),
+                });
+            }
+        }
+
+        let count = loaded.len();
+        *self.files.write() = loaded;
+        info!("Loaded {} drive fingerprints from files", count);
+        Ok(count)
+    }
+
+    /// Install (or replace) a `DriveFingerprint` resource, returning its name
+    pub fn apply(&self, resource: &DriveFingerprintResource) -> Result<String> {
+        let name = resource
+            .metadata
+            .name
+            .clone()
+            .ok_or_else(|| Error::Configuration("DriveFingerprint has no name".into()))?;
+        debug!(
+            "Applied drive fingerprint {} ({})",
+            name, resource.spec.model_pattern
+        );
+        self.resources
+            .write()
+            .insert(name.clone(), DriveFingerprint::from(&resource.spec));
+        Ok(name)
+    }
+
+    /// Remove a `DriveFingerprint` resource
+    pub fn remove_resource(&self, name: &str) -> bool {
+        self.resources.write().remove(name).is_some()
+    }
+
+    /// Replace all resource fingerprints, e.g. after a watch restarts
+    pub fn replace_resources(&self, resources: &[DriveFingerprintResource]) {
+        let replaced = resources
+            .iter()
+            .filter_map(|r| {
+                let name = r.metadata.name.clone()?;
+                Some((name, DriveFingerprint::from(&r.spec)))
+            })
+            .collect();
+        *self.resources.write() = replaced;
+    }
+
     /// Load built-in fingerprints for common enterprise and consumer drives
     fn load_builtin_fingerprints(&mut self) {
         // Intel Optane
@@ -132,6 +309,7 @@ impl FingerprintDatabase {
             tbw_tb: Some(100000),
             dwpd: Some(100.0),
             enterprise: true,
+            high_performance: true,
             zns: false,
             generation: Some("2021".to_string()),
             notes: Some("Intel Optane SSD DC P5800X".to_string()),
@@ -149,6 +327,7 @@ impl FingerprintDatabase {
             tbw_tb: Some(27000),
             dwpd: Some(3.0),
             enterprise: true,
+            high_performance: true,
             zns: false,
             generation: Some("2020".to_string()),
             notes: Some("Intel Optane SSD DC P5510".to_string()),
@@ -167,6 +346,7 @@ impl FingerprintDatabase {
             tbw_tb: Some(27000),
             dwpd: Some(1.0),
             enterprise: true,
+            high_performance: true,
             zns: false,
             generation: Some("2020".to_string()),
             notes: Some("Samsung PM1733 Enterprise NVMe".to_string()),
@@ -184,6 +364,7 @@ impl FingerprintDatabase {
             tbw_tb: Some(41000),
             dwpd: Some(3.0),
             enterprise: true,
+            high_performance: false,
             zns: false,
             generation: Some("2020".to_string()),
             notes: Some("Samsung PM1735 High-Endurance".to_string()),
@@ -202,6 +383,7 @@ impl FingerprintDatabase {
             tbw_tb: Some(600),
             dwpd: Some(0.3),
             enterprise: false,
+            high_performance: true,
             zns: false,
             generation: Some("2020".to_string()),
             notes: Some("Samsung 980 PRO Consumer NVMe".to_string()),
@@ -219,6 +401,7 @@ impl FingerprintDatabase {
             tbw_tb: Some(600),
             dwpd: Some(0.3),
             enterprise: false,
+            high_performance: false,
             zns: false,
             generation: Some("2022".to_string()),
             notes: Some("Samsung 990 PRO Consumer NVMe".to_string()),
@@ -237,6 +420,7 @@ impl FingerprintDatabase {
             tbw_tb: Some(600),
             dwpd: Some(0.3),
             enterprise: false,
+            high_performance: true,
             zns: false,
             generation: Some("2020".to_string()),
             notes: Some("WD Black SN850".to_string()),
@@ -255,6 +439,7 @@ impl FingerprintDatabase {
             tbw_tb: Some(10000),
             dwpd: Some(1.0),
             enterprise: true,
+            high_performance: false,
             zns: true,
             generation: Some("2021".to_string()),
             notes: Some("WD Ultrastar DC ZN540 ZNS SSD".to_string()),
@@ -273,6 +458,7 @@ impl FingerprintDatabase {
             tbw_tb: Some(56000),
             dwpd: Some(3.0),
             enterprise: true,
+            high_performance: false,
             zns: false,
             generation: Some("2022".to_string()),
             notes: Some("Micron 9400 Enterprise NVMe".to_string()),
@@ -291,6 +477,7 @@ impl FingerprintDatabase {
             tbw_tb: None,
             dwpd: None,
             enterprise: true,
+            high_performance: false,
             zns: false,
             generation: Some("2021".to_string()),
             notes: Some("Seagate Exos X18 18TB Enterprise HDD".to_string()),
@@ -308,6 +495,7 @@ impl FingerprintDatabase {
             tbw_tb: None,
             dwpd: None,
             enterprise: true,
+            high_performance: false,
             zns: false,
             generation: Some("2022".to_string()),
             notes: Some("Seagate Exos X20 20TB Enterprise HDD".to_string()),
@@ -326,6 +514,7 @@ impl FingerprintDatabase {
             tbw_tb: None,
             dwpd: None,
             enterprise: true,
+            high_performance: false,
             zns: false,
             generation: Some("2021".to_string()),
             notes: Some("WD Ultrastar DC HC550 18TB".to_string()),
@@ -344,6 +533,7 @@ impl FingerprintDatabase {
             tbw_tb: None,
             dwpd: None,
             enterprise: false,
+            high_performance: false,
             zns: false,
             generation: None,
             notes: Some("WD Red NAS HDD".to_string()),
@@ -357,25 +547,113 @@ impl Default for FingerprintDatabase {
     }
 }
 
+impl From<&DriveFingerprintSpec> for DriveFingerprint {
+    fn from(spec: &DriveFingerprintSpec) -> Self {
+        Self {
+            model_pattern: spec.model_pattern.clone(),
+            vendor: spec.vendor.clone(),
+            seq_read_iops: spec.seq_read_iops,
+            seq_write_iops: spec.seq_write_iops,
+            rand_read_iops: spec.rand_read_iops,
+            rand_write_iops: spec.rand_write_iops,
+            seq_read_mbps: spec.seq_read_mbps,
+            seq_write_mbps: spec.seq_write_mbps,
+            tbw_tb: spec.tbw_tb,
+            dwpd: spec.dwpd,
+            enterprise: spec.enterprise,
+            high_performance: spec.high_performance,
+            zns: spec.zns,
+            generation: spec.generation.clone(),
+            notes: spec.notes.clone(),
+        }
+    }
+}
+
+// =============================================================================
+// Fingerprint Files
+// =============================================================================
+
+/// Fingerprint files named by `paths`: files as given, directories expanded
+/// to their `.yaml`, `.yml` and `.json` files in name order
+pub fn fingerprint_files(paths: &[PathBuf]) -> Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    for path in paths {
+        if path.is_dir() {
+            let mut found = Vec::new();
+            for entry in std::fs::read_dir(path)? {
+                let file = entry?.path();
+                if matches!(
+                    file.extension().and_then(|e| e.to_str()),
+                    Some("yaml" | "yml" | "json")
+                ) {
+                    found.push(file);
+                }
+            }
+            found.sort();
+            files.extend(found);
+        } else {
+            files.push(path.clone());
+        }
+    }
+    Ok(files)
+}
+
+/// Parse the fingerprints in a YAML or JSON file. Each document is a
+/// `DriveFingerprint` manifest, a single fingerprint spec, a list of specs,
+/// or a map with a `fingerprints` list.
+fn parse_fingerprints(content: &str) -> std::result::Result<Vec<DriveFingerprint>, String> {
+    let mut specs: Vec<DriveFingerprintSpec> = Vec::new();
+    for document in serde_yaml::Deserializer::from_str(content) {
+        let value = serde_yaml::Value::deserialize(document).map_err(|e| e.to_string())?;
+        match value {
+            serde_yaml::Value::Null => {}
+            serde_yaml::Value::Sequence(_) => {
+                specs.extend(serde_yaml::from_value::<Vec<_>>(value).map_err(|e| e.to_string())?)
+            }
+            serde_yaml::Value::Mapping(ref map) if map.contains_key("kind") => {
+                let resource: DriveFingerprintResource = serde_yaml::from_value(value)
+                    .map_err(|e| format!("invalid DriveFingerprint: {}", e))?;
+                specs.push(resource.spec);
+            }
+            serde_yaml::Value::Mapping(ref map) if map.contains_key("fingerprints") => {
+                #[derive(Deserialize)]
+                struct FingerprintList {
+                    fingerprints: Vec<DriveFingerprintSpec>,
+                }
+                let list: FingerprintList =
+                    serde_yaml::from_value(value).map_err(|e| e.to_string())?;
+                specs.extend(list.fingerprints);
+            }
+            value => specs.push(serde_yaml::from_value(value).map_err(|e| e.to_string())?),
+        }
+    }
+
+    if let Some(spec) = specs.iter().find(|s| s.model_pattern.trim().is_empty()) {
+        return Err(format!("fingerprint for vendor '{}' has an empty modelPattern", spec.vendor));
+    }
+    Ok(specs.iter().map(DriveFingerprint::from).collect())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::fs;
 
     #[test]
     fn test_fingerprint_matching() {
         let db = FingerprintDatabase::new();
 
         // Should match Samsung 980 PRO
-        let fp = db.lookup("Samsung 980 PRO 2TB").unwrap();
+        let fp = db.lookup("Samsung 980 PRO 2TB").unwrap().fingerprint;
         assert_eq!(fp.vendor, "Samsung");
         assert!(!fp.enterprise);
 
         // Should match PM1733
-        let fp = db.lookup("SAMSUNG MZWLJ3T2HBLS-00007 (PM1733)").unwrap();
+        let fp = db.lookup("SAMSUNG MZWLJ3T2HBLS-00007 (PM1733)").unwrap().fingerprint;
         assert!(fp.enterprise);
 
         // Should match ZNS drive
-        let fp = db.lookup("WD Ultrastar DC ZN540").unwrap();
+        let fp = db.lookup("WD Ultrastar DC ZN540").unwrap().fingerprint;
         assert!(fp.zns);
     }
 
@@ -384,11 +662,11 @@ mod tests {
         let db = FingerprintDatabase::new();
 
         // Optane should have highest score
-        let optane = db.lookup("Optane P5800").unwrap();
+        let optane = db.lookup("Optane P5800").unwrap().fingerprint;
         assert!(optane.performance_score() >= 95);
 
         // HDD should have lower score
-        let hdd = db.lookup("WD Red").unwrap();
+        let hdd = db.lookup("WD Red").unwrap().fingerprint;
         assert!(hdd.performance_score() <= 40);
     }
 
@@ -397,11 +675,105 @@ mod tests {
         let db = FingerprintDatabase::new();
 
         // High-endurance enterprise drive
-        let pm1735 = db.lookup("PM1735").unwrap();
+        let pm1735 = db.lookup("PM1735").unwrap().fingerprint;
         assert!(pm1735.endurance_score() >= 80);
 
         // Consumer drive
-        let consumer = db.lookup("980 PRO").unwrap();
+        let consumer = db.lookup("980 PRO").unwrap().fingerprint;
         assert!(consumer.endurance_score() <= 50);
     }
+
+    #[test]
+    fn test_parse_fingerprint_formats() {
+        let list = "- modelPattern: KCD6XLUL\n  vendor: Kioxia\n  enterprise: true\n";
+        assert_eq!(parse_fingerprints(list).unwrap().len(), 1);
+
+        let wrapped = "fingerprints:\n  - modelPattern: A1\n  - modelPattern: B2\n";
+        assert_eq!(parse_fingerprints(wrapped).unwrap().len(), 2);
+
+        let manifests = r#"
+apiVersion: storage.billyronks.io/v1
+kind: DriveFingerprint
+metadata:
+  name: micron-7450
+spec:
+  modelPattern: "7450"
+  vendor: Micron
+  highPerformance: true
+---
+modelPattern: P4510
+vendor: Intel
+"#;
+        let parsed = parse_fingerprints(manifests).unwrap();
+        assert_eq!(parsed.len(), 2);
+        assert!(parsed[0].high_performance);
+        assert_eq!(parsed[1].vendor, "Intel");
+
+        let json = r#"[{"modelPattern": "PM9A3", "vendor": "Samsung", "dwpd": 1.0}]"#;
+        assert_eq!(parse_fingerprints(json).unwrap()[0].dwpd, Some(1.0));
+
+        assert!(parse_fingerprints("- vendor: Nobody\n  modelPattern: \"\"\n").is_err());
+        assert!(parse_fingerprints("- modelPattern: [oops\n").is_err());
+    }
+
+    #[test]
+    fn test_fingerprint_precedence() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(
+            dir.path().join("fleet.yaml"),
+            "- modelPattern: PM1733\n  vendor: Fleet\n\
+             - modelPattern: MZWLJ3T2HBLS\n  vendor: Specific\n",
+        )
+        .unwrap();
+        let db = FingerprintDatabase::new();
+
+        // A file overrides the built-in entry with the same pattern
+        db.load_files(&[dir.path().to_path_buf()]).unwrap();
+        let found = db.lookup("Samsung PM1733").unwrap();
+        assert_eq!(found.source, FingerprintSource::File);
+        assert_eq!(found.fingerprint.vendor, "Fleet");
+        assert_eq!(db.lookup_all("Samsung PM1733").len(), 2);
+
+        // The longest pattern wins regardless of source
+        let found = db.lookup("SAMSUNG MZWLJ3T2HBLS-00007 (PM1733)").unwrap();
+        assert_eq!(found.fingerprint.vendor, "Specific");
+
+        // A resource overrides a file
+        db.apply(&DriveFingerprintResource::new(
+            "pm1733",
+            DriveFingerprintSpec {
+                model_pattern: "pm1733".to_string(),
+                vendor: "Cluster".to_string(),
+                ..Default::default()
+            },
+        ))
+        .unwrap();
+        let found = db.lookup("Samsung PM1733").unwrap();
+        assert_eq!(found.source, FingerprintSource::Resource);
+        assert_eq!(found.origin, "pm1733");
+
+        assert!(db.remove_resource("pm1733"));
+        assert_eq!(db.lookup("Samsung PM1733").unwrap().fingerprint.vendor, "Fleet");
+
+        db.replace_resources(&[]);
+        db.load_files(&[]).unwrap();
+        assert_eq!(
+            db.lookup("Samsung PM1733").unwrap().source,
+            FingerprintSource::Builtin
+        );
+    }
+
+    #[test]
+    fn test_load_files_is_all_or_nothing() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("a.yaml"), "- modelPattern: KCD6XLUL\n").unwrap();
+        fs::write(dir.path().join("notes.txt"), "not a fingerprint").unwrap();
+        let db = FingerprintDatabase::new();
+        assert_eq!(db.load_files(&[dir.path().to_path_buf()]).unwrap(), 1);
+
+        fs::write(dir.path().join("b.json"), "{not json").unwrap();
+        let err = db.load_files(&[dir.path().to_path_buf()]).unwrap_err();
+        assert!(err.to_string().contains("b.json"));
+        assert!(db.lookup("KCD6XLUL").is_some());
+    }
 }
@@ -5,6 +5,8 @@
 
 pub mod classifier;
 pub mod fingerprint;
+pub mod reload;
 
 pub use classifier::*;
 pub use fingerprint::*;
+pub use reload::*;
@@ -0,0 +1,197 @@
+//! Fingerprint Reloading
+//!
+//! Keeps a shared fingerprint database current: fingerprint files are
+//! re-read when they are added, removed or modified, and `DriveFingerprint`
+//! resources are followed through a Kubernetes watch.
+
+use super::fingerprint::{fingerprint_files, FingerprintDatabaseRef};
+use crate::crd::DriveFingerprint as DriveFingerprintResource;
+use crate::error::Result;
+use futures::StreamExt;
+use kube::runtime::watcher::{self, Event};
+use kube::Api;
+use parking_lot::Mutex;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+use tokio::sync::broadcast;
+use tracing::{debug, info, warn};
+
+// =============================================================================
+// Constants
+// =============================================================================
+
+/// How often fingerprint files are checked for changes
+pub const DEFAULT_RELOAD_INTERVAL: Duration = Duration::from_secs(10);
+
+// =============================================================================
+// File Watcher
+// =============================================================================
+
+/// A file with its modification time and size
+type FileStamp = (PathBuf, Option<SystemTime>, u64);
+
+/// Reloads fingerprint files into the database when they change
+pub struct FingerprintFileWatcher {
+    fingerprints: FingerprintDatabaseRef,
+    paths: Vec<PathBuf>,
+    interval: Duration,
+    /// Files as of the last load attempt
+    loaded: Mutex<Option<Vec<FileStamp>>>,
+}
+
+impl FingerprintFileWatcher {
+    /// Watch files and directories of fingerprint files
+    pub fn new(
+        fingerprints: FingerprintDatabaseRef,
+        paths: Vec<PathBuf>,
+        interval: Duration,
+    ) -> Arc<Self> {
+        Arc::new(Self {
+            fingerprints,
+            paths,
+            interval,
+            loaded: Mutex::new(None),
+        })
+    }
+
+    /// Load the files now, returning the number of fingerprints
+    pub fn load(&self) -> Result<usize> {
+        *self.loaded.lock() = Some(self.stamp()?);
+        self.fingerprints.load_files(&self.paths)
+    }
+
+    /// Reload if any file was added, removed or modified since the last
+    /// load. Returns the number of fingerprints, or `None` if unchanged.
+    ///
+    /// A file that fails to parse leaves the previous fingerprints in
+    /// place and is not retried until it changes again.
+    pub fn reload_if_changed(&self) -> Result<Option<usize>> {
+        let stamp = self.stamp()?;
+        if self.loaded.lock().as_ref() == Some(&stamp) {
+            return Ok(None);
+        }
+        debug!("Fingerprint files changed, reloading");
+        *self.loaded.lock() = Some(stamp);
+        self.fingerprints.load_files(&self.paths).map(Some)
+    }
+
+    fn stamp(&self) -> Result<Vec<FileStamp>> {
+        Ok(fingerprint_files(&self.paths)?
+            .into_iter()
+            .map(|file| {
+                let metadata = std::fs::metadata(&file).ok();
+                let modified = metadata.as_ref().and_then(|m| m.modified().ok());
+                let len = metadata.map(|m| m.len()).unwrap_or(0);
+                (file, modified, len)
+            })
+            .collect())
+    }
+
+    /// Check for changes until shutdown
+    pub async fn run(self: Arc<Self>, mut shutdown: broadcast::Receiver<()>) {
+        let mut interval = tokio::time::interval(self.interval);
+        info!(
+            "Watching {} fingerprint path(s) for changes (every {:?})",
+            self.paths.len(),
+            self.interval
+        );
+
+        loop {
+            tokio::select! {
+                _ = interval.tick() => {
+                    if let Err(e) = self.reload_if_changed() {
+                        warn!("Fingerprint reload failed, keeping previous fingerprints: {}", e);
+                    }
+                }
+                _ = shutdown.recv() => {
+                    debug!("Fingerprint file watcher shutting down");
+                    break;
+                }
+            }
+        }
+    }
+}
+
+// =============================================================================
+// Resource Watcher
+// =============================================================================
+
+/// Follow `DriveFingerprint` resources in the cluster until shutdown
+pub async fn watch_fingerprint_resources(
+    fingerprints: FingerprintDatabaseRef,
+    client: kube::Client,
+    mut shutdown: broadcast::Receiver<()>,
+) {
+    let api: Api<DriveFingerprintResource> = Api::all(client);
+    let mut events = watcher::watcher(api, watcher::Config::default()).boxed();
+    info!("Watching DriveFingerprint resources");
+
+    loop {
+        tokio::select! {
+            event = events.next() => match event {
+                Some(Ok(Event::Applied(resource))) => {
+                    if let Err(e) = fingerprints.apply(&resource) {
+                        warn!("Ignoring DriveFingerprint: {}", e);
+                    }
+                }
+                Some(Ok(Event::Deleted(resource))) => {
+                    if let Some(name) = &resource.metadata.name {
+                        fingerprints.remove_resource(name);
+                    }
+                }
+                Some(Ok(Event::Restarted(resources))) => {
+                    info!("Loaded {} DriveFingerprint resources", resources.len());
+                    fingerprints.replace_resources(&resources);
+                }
+                // The watcher retries on its own
+                Some(Err(e)) => warn!("DriveFingerprint watch error: {}", e),
+                None => break,
+            },
+            _ = shutdown.recv() => {
+                debug!("DriveFingerprint watcher shutting down");
+                break;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hardware::classification::{FingerprintDatabase, FingerprintSource};
+    use std::fs;
+
+    #[test]
+    fn test_reload_when_files_change() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = FingerprintDatabase::shared();
+        let watcher = FingerprintFileWatcher::new(
+            db.clone(),
+            vec![dir.path().to_path_buf()],
+            DEFAULT_RELOAD_INTERVAL,
+        );
+        assert_eq!(watcher.load().unwrap(), 0);
+        assert_eq!(watcher.reload_if_changed().unwrap(), None);
+
+        fs::write(
+            dir.path().join("fleet.yaml"),
+            "- modelPattern: KCD6XLUL\n  vendor: Kioxia\n  enterprise: true\n",
+        )
+        .unwrap();
+        assert_eq!(watcher.reload_if_changed().unwrap(), Some(1));
+        let found = db.lookup("KIOXIA KCD6XLUL3T84").unwrap();
+        assert_eq!(found.source, FingerprintSource::File);
+        assert!(found.fingerprint.enterprise);
+
+        // A broken edit keeps the last good fingerprints
+        fs::write(dir.path().join("fleet.yaml"), "- modelPattern: [oops\n").unwrap();
+        assert!(watcher.reload_if_changed().is_err());
+        assert!(db.lookup("KIOXIA KCD6XLUL3T84").is_some());
+        assert_eq!(watcher.reload_if_changed().unwrap(), None);
+
+        fs::remove_file(dir.path().join("fleet.yaml")).unwrap();
+        assert_eq!(watcher.reload_if_changed().unwrap(), Some(0));
+        assert!(db.lookup("KIOXIA KCD6XLUL3T84").is_none());
+    }
+}
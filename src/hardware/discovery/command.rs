@@ -0,0 +1,405 @@
+//! Command Execution
+//!
+//! Abstracts the external tools (nvme-cli, smartctl) that discovery shells
+//! out to. `SystemCommandRunner` spawns real processes on the tokio runtime
+//! with a per-invocation timeout; `RecordingCommandRunner` captures what
+//! another runner returned, and `FixtureCommandRunner` replays captured
+//! outputs so the whole discovery pipeline can run without hardware.
+
+use crate::error::{Error, Result};
+use async_trait::async_trait;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::process::Command;
+use tracing::debug;
+
+// =============================================================================
+// Constants
+// =============================================================================
+
+/// Default timeout for a single external command
+pub const DEFAULT_COMMAND_TIMEOUT: Duration = Duration::from_secs(30);
+
+// =============================================================================
+// Command Output
+// =============================================================================
+
+/// Captured result of an external command
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CommandOutput {
+    /// Exit code (`None` if the process was killed by a signal)
+    pub exit_code: Option<i32>,
+    /// Standard output
+    pub stdout: Vec<u8>,
+    /// Standard error
+    pub stderr: Vec<u8>,
+}
+
+impl CommandOutput {
+    /// Successful output with the given stdout
+    pub fn success(stdout: impl Into<Vec<u8>>) -> Self {
+        Self {
+            exit_code: Some(0),
+            stdout: stdout.into(),
+            stderr: Vec::new(),
+        }
+    }
+
+    /// Failed output with the given exit code and stderr
+    pub fn failure(exit_code: i32, stderr: impl Into<Vec<u8>>) -> Self {
+        Self {
+            exit_code: Some(exit_code),
+            stdout: Vec::new(),
+            stderr: stderr.into(),
+        }
+    }
+
+    /// Whether the command exited with status 0
+    pub fn is_success(&self) -> bool {
+        self.exit_code == Some(0)
+    }
+
+    /// Stderr as lossy UTF-8
+    pub fn stderr_lossy(&self) -> String {
+        String::from_utf8_lossy(&self.stderr).trim().to_string()
+    }
+}
+
+/// Render a command line for logs, errors and fixture keys
+pub fn command_line(program: &str, args: &[&str]) -> String {
+    std::iter::once(program)
+        .chain(args.iter().copied())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+// =============================================================================
+// Command Runner Port
+// =============================================================================
+
+/// Executes external commands on behalf of hardware discovery
+#[async_trait]
+pub trait CommandRunner: Send + Sync {
+    /// Run `program` with `args` and capture its output.
+    ///
+    /// A non-zero exit is not an error at this level; callers decide how to
+    /// interpret it (smartctl, for instance, uses exit bits for warnings).
+    /// Errors are reserved for failing to spawn or timing out.
+    async fn run(&self, program: &str, args: &[&str]) -> Result<CommandOutput>;
+}
+
+/// Shared command runner reference
+pub type CommandRunnerRef = Arc<dyn CommandRunner>;
+
+// =============================================================================
+// System Command Runner
+// =============================================================================
+
+/// Runs commands as child processes with a timeout
+#[derive(Debug, Clone)]
+pub struct SystemCommandRunner {
+    timeout: Duration,
+}
+
+impl SystemCommandRunner {
+    /// Create a runner with the given per-command timeout
+    pub fn new(timeout: Duration) -> Self {
+        Self { timeout }
+    }
+}
+
+impl Default for SystemCommandRunner {
+    fn default() -> Self {
+        Self::new(DEFAULT_COMMAND_TIMEOUT)
+    }
+}
+
+#[async_trait]
+impl CommandRunner for SystemCommandRunner {
+    async fn run(&self, program: &str, args: &[&str]) -> Result<CommandOutput> {
+        let line = command_line(program, args);
+        debug!("Running {}", line);
+
+        let child = Command::new(program)
+            .args(args)
+            .kill_on_drop(true)
+            .output();
+
+        let output = tokio::time::timeout(self.timeout, child)
+            .await
+            .map_err(|_| Error::CommandTimeout {
+                command: line.clone(),
+                timeout: self.timeout,
+            })?
+            .map_err(|e| Error::HardwareDiscovery(format!("Failed to run {}: {}", line, e)))?;
+
+        Ok(CommandOutput {
+            exit_code: output.status.code(),
+            stdout: output.stdout,
+            stderr: output.stderr,
+        })
+    }
+}
+
+// =============================================================================
+// Recording Command Runner
+// =============================================================================
+
+/// Passes commands through to another runner and keeps what it returned
+///
+/// Only completed commands are recorded; spawn failures and timeouts are
+/// left out so a replay treats them like a missing binary.
+pub struct RecordingCommandRunner {
+    inner: CommandRunnerRef,
+    recorded: Mutex<Vec<RecordedCommand>>,
+}
+
+impl RecordingCommandRunner {
+    /// Record everything `inner` runs
+    pub fn new(inner: CommandRunnerRef) -> Self {
+        Self {
+            inner,
+            recorded: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Recorded commands, one per distinct command line (latest wins)
+    pub fn recordings(&self) -> Vec<RecordedCommand> {
+        let recorded = self.recorded.lock();
+        let mut seen = std::collections::HashSet::new();
+        let mut unique: Vec<RecordedCommand> = recorded
+            .iter()
+            .rev()
+            .filter(|r| seen.insert(r.command.clone()))
+            .cloned()
+            .collect();
+        unique.reverse();
+        unique
+    }
+}
+
+#[async_trait]
+impl CommandRunner for RecordingCommandRunner {
+    async fn run(&self, program: &str, args: &[&str]) -> Result<CommandOutput> {
+        let output = self.inner.run(program, args).await?;
+        self.recorded
+            .lock()
+            .push(RecordedCommand::from_output(command_line(program, args), &output));
+        Ok(output)
+    }
+}
+
+// =============================================================================
+// Fixture Command Runner
+// =============================================================================
+
+/// A captured command invocation, as stored in fixture files
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordedCommand {
+    /// Full command line, e.g. `nvme smart-log /dev/nvme0 -o json`
+    pub command: String,
+    /// Exit code
+    #[serde(default)]
+    pub exit_code: i32,
+    /// Stdout; JSON values are re-serialized, strings are used verbatim
+    #[serde(default)]
+    pub stdout: serde_json::Value,
+    /// Stderr
+    #[serde(default)]
+    pub stderr: String,
+}
+
+impl RecordedCommand {
+    /// Capture an output; JSON documents are kept structured so fixture
+    /// files stay readable and diffable
+    pub fn from_output(command: String, output: &CommandOutput) -> Self {
+        let stdout = match serde_json::from_slice::<serde_json::Value>(&output.stdout) {
+            Ok(value @ (serde_json::Value::Object(_) | serde_json::Value::Array(_))) => value,
+            _ if output.stdout.is_empty() => serde_json::Value::Null,
+            _ => serde_json::Value::String(String::from_utf8_lossy(&output.stdout).into_owned()),
+        };
+        Self {
+            command,
+            exit_code: output.exit_code.unwrap_or(-1),
+            stdout,
+            stderr: String::from_utf8_lossy(&output.stderr).into_owned(),
+        }
+    }
+
+    fn into_output(self) -> CommandOutput {
+        let stdout = match self.stdout {
+            serde_json::Value::Null => Vec::new(),
+            serde_json::Value::String(s) => s.into_bytes(),
+            value => value.to_string().into_bytes(),
+        };
+        CommandOutput {
+            exit_code: Some(self.exit_code),
+            stdout,
+            stderr: self.stderr.into_bytes(),
+        }
+    }
+}
+
+/// Replays recorded command outputs keyed by command line
+///
+/// Commands without a recording fail as if the binary were missing, which
+/// is how discovery already treats absent tooling.
+#[derive(Debug, Default)]
+pub struct FixtureCommandRunner {
+    outputs: HashMap<String, CommandOutput>,
+    calls: Mutex<Vec<String>>,
+}
+
+impl FixtureCommandRunner {
+    /// Create an empty runner
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Load recordings from a JSON file containing an array of `RecordedCommand`
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let raw = std::fs::read_to_string(path).map_err(|e| {
+            Error::Configuration(format!("Failed to read fixture {}: {}", path.display(), e))
+        })?;
+        let recorded: Vec<RecordedCommand> = serde_json::from_str(&raw).map_err(|e| {
+            Error::Configuration(format!("Invalid fixture {}: {}", path.display(), e))
+        })?;
+        Ok(Self::from_recordings(recorded))
+    }
+
+    /// Build a runner from recordings
+    pub fn from_recordings(recorded: impl IntoIterator<Item = RecordedCommand>) -> Self {
+        let mut runner = Self::new();
+        for r in recorded {
+            runner.outputs.insert(r.command.clone(), r.into_output());
+        }
+        runner
+    }
+
+    /// Record a JSON output for a command line
+    pub fn with_json(mut self, command: &str, stdout: serde_json::Value) -> Self {
+        self.outputs
+            .insert(command.to_string(), CommandOutput::success(stdout.to_string()));
+        self
+    }
+
+    /// Record an arbitrary output for a command line
+    pub fn with_output(mut self, command: &str, output: CommandOutput) -> Self {
+        self.outputs.insert(command.to_string(), output);
+        self
+    }
+
+    /// Command lines that have been run, in order
+    pub fn calls(&self) -> Vec<String> {
+        self.calls.lock().clone()
+    }
+}
+
+#[async_trait]
+impl CommandRunner for FixtureCommandRunner {
+    async fn run(&self, program: &str, args: &[&str]) -> Result<CommandOutput> {
+        let line = command_line(program, args);
+        self.calls.lock().push(line.clone());
+
+        self.outputs.get(&line).cloned().ok_or_else(|| {
+            Error::HardwareDiscovery(format!("Failed to run {}: no recorded output", line))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_fixture_runner_replays_and_records_calls() {
+        let runner = FixtureCommandRunner::new()
+            .with_json("nvme list -o json", serde_json::json!({"Devices": []}))
+            .with_output("smartctl --scan -j", CommandOutput::failure(2, "boom"));
+
+        let out = runner.run("nvme", &["list", "-o", "json"]).await.unwrap();
+        assert!(out.is_success());
+        assert_eq!(out.stdout, br#"{"Devices":[]}"#);
+
+        let out = runner.run("smartctl", &["--scan", "-j"]).await.unwrap();
+        assert!(!out.is_success());
+        assert_eq!(out.stderr_lossy(), "boom");
+
+        assert!(runner.run("nvme", &["version"]).await.is_err());
+        assert_eq!(
+            runner.calls(),
+            vec!["nvme list -o json", "smartctl --scan -j", "nvme version"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_fixture_runner_from_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("fixture.json");
+        std::fs::write(
+            &path,
+            r#"[
+                {"command": "nvme smart-log /dev/nvme0 -o json", "stdout": {"temperature": 310}},
+                {"command": "hostname", "stdout": "node-a\n"},
+                {"command": "nvme zns id-ns /dev/nvme0n1 -o json", "exit_code": 1, "stderr": "invalid"}
+            ]"#,
+        )
+        .unwrap();
+
+        let runner = FixtureCommandRunner::from_file(&path).unwrap();
+        let out = runner
+            .run("nvme", &["smart-log", "/dev/nvme0", "-o", "json"])
+            .await
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&out.stdout).unwrap();
+        assert_eq!(json["temperature"], 310);
+
+        let out = runner.run("hostname", &[]).await.unwrap();
+        assert_eq!(out.stdout, b"node-a\n");
+
+        let out = runner
+            .run("nvme", &["zns", "id-ns", "/dev/nvme0n1", "-o", "json"])
+            .await
+            .unwrap();
+        assert_eq!(out.exit_code, Some(1));
+    }
+
+    #[tokio::test]
+    async fn test_recording_runner_round_trips_through_fixture() {
+        let source = Arc::new(
+            FixtureCommandRunner::new()
+                .with_json("nvme list -o json", serde_json::json!({"Devices": [{"DevicePath": "/dev/nvme0n1"}]}))
+                .with_output("hostname", CommandOutput::success("node-a\n")),
+        );
+        let recorder = RecordingCommandRunner::new(source);
+
+        recorder.run("nvme", &["list", "-o", "json"]).await.unwrap();
+        recorder.run("hostname", &[]).await.unwrap();
+        recorder.run("hostname", &[]).await.unwrap();
+        assert!(recorder.run("smartctl", &["--scan", "-j"]).await.is_err());
+
+        let recordings = recorder.recordings();
+        assert_eq!(recordings.len(), 2);
+        assert!(recordings[0].stdout.is_object());
+        assert_eq!(recordings[1].stdout, serde_json::json!("node-a\n"));
+
+        let replay = FixtureCommandRunner::from_recordings(recordings);
+        let out = replay.run("hostname", &[]).await.unwrap();
+        assert_eq!(out.stdout, b"node-a\n");
+        let out = replay.run("nvme", &["list", "-o", "json"]).await.unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&out.stdout).unwrap();
+        assert_eq!(json["Devices"][0]["DevicePath"], "/dev/nvme0n1");
+    }
+
+    #[tokio::test]
+    async fn test_system_runner_timeout() {
+        let runner = SystemCommandRunner::new(Duration::from_millis(50));
+        let err = runner.run("sleep", &["5"]).await.unwrap_err();
+        assert!(matches!(err, Error::CommandTimeout { .. }));
+    }
+}
@@ -0,0 +1,699 @@
+//! Hot-Plug Detection
+//!
+//! Picks up drives as they are inserted or pulled instead of waiting for the
+//! next full discovery pass. Kernel uevents are read from a netlink socket;
+//! where that is unavailable (no host network namespace, non-Linux, tests)
+//! a poller diffs `class/block` under the scanner's sysfs path. Either way,
+//! each change triggers a scan of just the affected device and the result is
+//! applied to the `NodeRegistry`, which emits `DriveAdded`/`DriveRemoved`.
+
+use super::scanner::HardwareScanner;
+use crate::crd::DriveStatus;
+use crate::error::Result;
+use crate::hardware::registry::{NodeId, NodeRegistry};
+use async_trait::async_trait;
+use std::collections::{BTreeMap, VecDeque};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::broadcast;
+use tracing::{debug, info, warn};
+
+// =============================================================================
+// Constants
+// =============================================================================
+
+/// Default interval between sysfs snapshots when polling
+pub const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Default quiet period before a burst of events is applied
+pub const DEFAULT_SETTLE: Duration = Duration::from_millis(500);
+
+// =============================================================================
+// Events
+// =============================================================================
+
+/// What happened to a block device
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HotplugAction {
+    /// Device appeared
+    Add,
+    /// Device disappeared
+    Remove,
+    /// Device changed (media change, resize)
+    Change,
+}
+
+/// A change to a whole-disk block device
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HotplugEvent {
+    /// What happened
+    pub action: HotplugAction,
+    /// Kernel device name (e.g. `sdb`, `nvme1n1`)
+    pub device: String,
+}
+
+impl HotplugEvent {
+    pub fn new(action: HotplugAction, device: impl Into<String>) -> Self {
+        Self {
+            action,
+            device: device.into(),
+        }
+    }
+}
+
+/// Parse a kernel uevent message, keeping only whole-disk block devices.
+///
+/// Messages are a `action@devpath` header followed by NUL-separated
+/// `KEY=value` pairs.
+pub fn parse_uevent(msg: &[u8]) -> Option<HotplugEvent> {
+    // Messages re-broadcast by udev carry a binary header; only kernel
+    // messages are read
+    if msg.starts_with(b"libudev") {
+        return None;
+    }
+
+    let mut action = None;
+    let mut subsystem = None;
+    let mut devtype = None;
+    let mut devname = None;
+    for field in msg.split(|&b| b == 0).skip(1) {
+        let field = std::str::from_utf8(field).ok()?;
+        if let Some((key, value)) = field.split_once('=') {
+            match key {
+                "ACTION" => action = Some(value),
+                "SUBSYSTEM" => subsystem = Some(value),
+                "DEVTYPE" => devtype = Some(value),
+                "DEVNAME" => devname = Some(value),
+                _ => {}
+            }
+        }
+    }
+
+    if subsystem != Some("block") || devtype != Some("disk") {
+        return None;
+    }
+    let action = match action? {
+        "add" => HotplugAction::Add,
+        "remove" => HotplugAction::Remove,
+        "change" => HotplugAction::Change,
+        _ => return None,
+    };
+    let device = devname?;
+    let device = device.strip_prefix("/dev/").unwrap_or(device);
+    Some(HotplugEvent::new(action, device))
+}
+
+// =============================================================================
+// Event Sources
+// =============================================================================
+
+/// A stream of block device changes
+#[async_trait]
+pub trait HotplugSource: Send {
+    /// Wait for the next event; `None` once the source has shut down
+    async fn next_event(&mut self) -> Option<HotplugEvent>;
+
+    /// Short name for logs
+    fn name(&self) -> &'static str;
+}
+
+/// Which event source the monitor should use
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum HotplugSourceKind {
+    /// Netlink if the socket can be opened, otherwise polling
+    #[default]
+    Auto,
+    /// Kernel uevents only
+    Netlink,
+    /// Sysfs polling only
+    Poll,
+}
+
+/// Detects changes by diffing `class/block` snapshots.
+///
+/// Devices present when the poller is created are not reported.
+pub struct SysfsPoller {
+    block_path: PathBuf,
+    interval: Duration,
+    /// Whole-disk device name -> size attribute
+    known: BTreeMap<String, String>,
+    pending: VecDeque<HotplugEvent>,
+}
+
+impl SysfsPoller {
+    /// Create a poller over `<sysfs_path>/class/block`
+    pub fn new(sysfs_path: &Path, interval: Duration) -> Self {
+        let block_path = sysfs_path.join("class/block");
+        let known = snapshot(&block_path).unwrap_or_default();
+        Self {
+            block_path,
+            interval,
+            known,
+            pending: VecDeque::new(),
+        }
+    }
+
+    /// Take a snapshot and return the changes since the previous one
+    pub fn poll(&mut self) -> Vec<HotplugEvent> {
+        let current = match snapshot(&self.block_path) {
+            Ok(current) => current,
+            Err(e) => {
+                // Never report every device as removed because sysfs was
+                // briefly unreadable
+                warn!("Failed to read {}: {}", self.block_path.display(), e);
+                return Vec::new();
+            }
+        };
+
+        let mut events = Vec::new();
+        for (name, size) in &current {
+            match self.known.get(name) {
+                None => events.push(HotplugEvent::new(HotplugAction::Add, name)),
+                Some(old) if old != size => {
+                    events.push(HotplugEvent::new(HotplugAction::Change, name))
+                }
+                Some(_) => {}
+            }
+        }
+        for name in self.known.keys() {
+            if !current.contains_key(name) {
+                events.push(HotplugEvent::new(HotplugAction::Remove, name));
+            }
+        }
+
+        self.known = current;
+        events
+    }
+}
+
+#[async_trait]
+impl HotplugSource for SysfsPoller {
+    async fn next_event(&mut self) -> Option<HotplugEvent> {
+        loop {
+            if let Some(event) = self.pending.pop_front() {
+                return Some(event);
+            }
+            tokio::time::sleep(self.interval).await;
+            let events = self.poll();
+            self.pending.extend(events);
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        "sysfs-poll"
+    }
+}
+
+/// Whole-disk block devices and their sizes
+fn snapshot(block_path: &Path) -> std::io::Result<BTreeMap<String, String>> {
+    let mut devices = BTreeMap::new();
+    for entry in fs::read_dir(block_path)? {
+        let entry = entry?;
+        let path = entry.path();
+        if path.join("partition").exists() {
+            continue;
+        }
+        let size = fs::read_to_string(path.join("size")).unwrap_or_default();
+        devices.insert(
+            entry.file_name().to_string_lossy().to_string(),
+            size.trim().to_string(),
+        );
+    }
+    Ok(devices)
+}
+
+/// Kernel uevents from a `NETLINK_KOBJECT_UEVENT` socket, read on a
+/// dedicated thread
+pub struct UeventSource {
+    rx: tokio::sync::mpsc::Receiver<HotplugEvent>,
+}
+
+impl UeventSource {
+    /// Open the netlink socket and start reading
+    #[cfg(target_os = "linux")]
+    pub fn open() -> Result<Self> {
+        let socket = netlink::UeventSocket::open().map_err(|e| {
+            crate::error::Error::HardwareDiscovery(format!("uevent socket: {}", e))
+        })?;
+        let (tx, rx) = tokio::sync::mpsc::channel(256);
+
+        std::thread::Builder::new()
+            .name("uevent".into())
+            .spawn(move || {
+                let mut buf = vec![0u8; 8192];
+                // The socket times out periodically so a dropped receiver
+                // is noticed even when no devices change
+                while !tx.is_closed() {
+                    match socket.recv(&mut buf) {
+                        Ok(Some(n)) => {
+                            if let Some(event) = parse_uevent(&buf[..n]) {
+                                if tx.blocking_send(event).is_err() {
+                                    break;
+                                }
+                            }
+                        }
+                        Ok(None) => {}
+                        Err(e) => {
+                            warn!("uevent socket failed: {}", e);
+                            break;
+                        }
+                    }
+                }
+            })?;
+
+        Ok(Self { rx })
+    }
+
+    /// Netlink uevents are Linux-only
+    #[cfg(not(target_os = "linux"))]
+    pub fn open() -> Result<Self> {
+        Err(crate::error::Error::HardwareDiscovery(
+            "uevents are only available on Linux".into(),
+        ))
+    }
+}
+
+#[async_trait]
+impl HotplugSource for UeventSource {
+    async fn next_event(&mut self) -> Option<HotplugEvent> {
+        self.rx.recv().await
+    }
+
+    fn name(&self) -> &'static str {
+        "netlink"
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod netlink {
+    use std::io;
+    use std::mem::size_of;
+    use std::os::fd::{AsRawFd, FromRawFd, OwnedFd};
+
+    /// Multicast group the kernel sends uevents to
+    const KERNEL_UEVENT_GROUP: u32 = 1;
+
+    pub struct UeventSocket(OwnedFd);
+
+    impl UeventSocket {
+        pub fn open() -> io::Result<Self> {
+            // SAFETY: plain socket syscalls on a descriptor we own; the
+            // address and timeout structs outlive the calls
+            unsafe {
+                let fd = libc::socket(
+                    libc::AF_NETLINK,
+                    libc::SOCK_DGRAM | libc::SOCK_CLOEXEC,
+                    libc::NETLINK_KOBJECT_UEVENT,
+                );
+                if fd < 0 {
+                    return Err(io::Error::last_os_error());
+                }
+                let socket = OwnedFd::from_raw_fd(fd);
+
+                let mut addr: libc::sockaddr_nl = std::mem::zeroed();
+                addr.nl_family = libc::AF_NETLINK as libc::sa_family_t;
+                addr.nl_groups = KERNEL_UEVENT_GROUP;
+                if libc::bind(
+                    fd,
+                    &addr as *const libc::sockaddr_nl as *const libc::sockaddr,
+                    size_of::<libc::sockaddr_nl>() as libc::socklen_t,
+                ) < 0
+                {
+                    return Err(io::Error::last_os_error());
+                }
+
+                let timeout = libc::timeval {
+                    tv_sec: 1,
+                    tv_usec: 0,
+                };
+                if libc::setsockopt(
+                    fd,
+                    libc::SOL_SOCKET,
+                    libc::SO_RCVTIMEO,
+                    &timeout as *const libc::timeval as *const libc::c_void,
+                    size_of::<libc::timeval>() as libc::socklen_t,
+                ) < 0
+                {
+                    return Err(io::Error::last_os_error());
+                }
+
+                Ok(Self(socket))
+            }
+        }
+
+        /// Receive one message; `None` when the receive timed out
+        pub fn recv(&self, buf: &mut [u8]) -> io::Result<Option<usize>> {
+            // SAFETY: buf is valid for writes of buf.len() bytes
+            let n = unsafe {
+                libc::recv(
+                    self.0.as_raw_fd(),
+                    buf.as_mut_ptr() as *mut libc::c_void,
+                    buf.len(),
+                    0,
+                )
+            };
+            if n < 0 {
+                let err = io::Error::last_os_error();
+                return match err.kind() {
+                    io::ErrorKind::WouldBlock
+                    | io::ErrorKind::TimedOut
+                    | io::ErrorKind::Interrupted => Ok(None),
+                    _ => Err(err),
+                };
+            }
+            Ok(Some(n as usize))
+        }
+    }
+}
+
+// =============================================================================
+// Hot-Plug Monitor
+// =============================================================================
+
+/// Configuration for the hot-plug monitor
+#[derive(Debug, Clone)]
+pub struct HotplugConfig {
+    /// Event source
+    pub source: HotplugSourceKind,
+    /// Interval between sysfs snapshots when polling
+    pub poll_interval: Duration,
+    /// Quiet period before a burst of events is applied, so a device that
+    /// is still settling is scanned once
+    pub settle: Duration,
+}
+
+impl Default for HotplugConfig {
+    fn default() -> Self {
+        Self {
+            source: HotplugSourceKind::Auto,
+            poll_interval: DEFAULT_POLL_INTERVAL,
+            settle: DEFAULT_SETTLE,
+        }
+    }
+}
+
+/// Result of applying a hot-plug event
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HotplugChange {
+    /// A new drive was added to the registry
+    Added,
+    /// A known drive was rescanned
+    Updated,
+    /// A known drive was removed
+    Removed,
+    /// The device is excluded by the scanner, was never known, or is one
+    /// of several paths to a drive
+    Ignored,
+}
+
+/// Applies block device changes on the local node to the registry
+pub struct HotplugMonitor {
+    node_id: NodeId,
+    scanner: Arc<HardwareScanner>,
+    registry: Arc<NodeRegistry>,
+    config: HotplugConfig,
+}
+
+impl HotplugMonitor {
+    /// Create a monitor for `node_id`, which must already be registered
+    pub fn new(
+        node_id: impl Into<NodeId>,
+        scanner: Arc<HardwareScanner>,
+        registry: Arc<NodeRegistry>,
+        config: HotplugConfig,
+    ) -> Arc<Self> {
+        Arc::new(Self {
+            node_id: node_id.into(),
+            scanner,
+            registry,
+            config,
+        })
+    }
+
+    /// Open the configured event source
+    pub fn open_source(&self) -> Result<Box<dyn HotplugSource>> {
+        let poller = || -> Box<dyn HotplugSource> {
+            Box::new(SysfsPoller::new(
+                self.scanner.sysfs_path(),
+                self.config.poll_interval,
+            ))
+        };
+        match self.config.source {
+            HotplugSourceKind::Poll => Ok(poller()),
+            HotplugSourceKind::Netlink => Ok(Box::new(UeventSource::open()?)),
+            HotplugSourceKind::Auto => match UeventSource::open() {
+                Ok(source) => Ok(Box::new(source)),
+                Err(e) => {
+                    info!("Kernel uevents unavailable ({}), polling sysfs", e);
+                    Ok(poller())
+                }
+            },
+        }
+    }
+
+    /// Apply one event with an incremental scan of just that device
+    pub async fn apply(&self, event: &HotplugEvent) -> Result<HotplugChange> {
+        let scanned = match event.action {
+            HotplugAction::Remove => None,
+            HotplugAction::Add | HotplugAction::Change => {
+                self.scanner.scan_device(&event.device).await?
+            }
+        };
+
+        let change = match scanned {
+            Some(info) => {
+                let drive = DriveStatus::from(&info);
+                if self.registry.upsert_drive(self.node_id.clone(), drive)? {
+                    HotplugChange::Added
+                } else {
+                    HotplugChange::Updated
+                }
+            }
+            None => match self.registry.remove_drive(self.node_id.clone(), &event.device)? {
+                Some(_) => HotplugChange::Removed,
+                None => HotplugChange::Ignored,
+            },
+        };
+
+        debug!("Hot-plug {:?} {}: {:?}", event.action, event.device, change);
+        Ok(change)
+    }
+
+    /// Watch the configured source until shutdown
+    pub async fn run(self: Arc<Self>, shutdown: broadcast::Receiver<()>) {
+        match self.open_source() {
+            Ok(source) => self.run_with_source(source, shutdown).await,
+            Err(e) => warn!("Hot-plug detection disabled: {}", e),
+        }
+    }
+
+    /// Watch `source` until it closes or shutdown is signalled
+    pub async fn run_with_source(
+        self: Arc<Self>,
+        mut source: Box<dyn HotplugSource>,
+        mut shutdown: broadcast::Receiver<()>,
+    ) {
+        info!(
+            "Hot-plug monitor started for {} ({})",
+            self.node_id,
+            source.name()
+        );
+
+        loop {
+            let first = tokio::select! {
+                event = source.next_event() => match event {
+                    Some(event) => event,
+                    None => break,
+                },
+                _ = shutdown.recv() => break,
+            };
+
+            // Coalesce a burst per device; the last action wins
+            let mut batch = BTreeMap::new();
+            batch.insert(first.device.clone(), first);
+            while let Ok(Some(event)) =
+                tokio::time::timeout(self.config.settle, source.next_event()).await
+            {
+                batch.insert(event.device.clone(), event);
+            }
+
+            for event in batch.values() {
+                match self.apply(event).await {
+                    Ok(HotplugChange::Added) => info!("Drive {} added", event.device),
+                    Ok(HotplugChange::Removed) => info!("Drive {} removed", event.device),
+                    Ok(_) => {}
+                    Err(e) => warn!("Failed to rescan {}: {}", event.device, e),
+                }
+            }
+        }
+
+        info!("Hot-plug monitor stopped for {}", self.node_id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crd::StorageNodeStatus;
+    use crate::hardware::discovery::command::FixtureCommandRunner;
+    use crate::hardware::discovery::scanner::ScannerConfig;
+    use crate::hardware::registry::RegistryEvent;
+
+    fn write_attr(dir: &Path, attr: &str, value: &str) {
+        let path = dir.join(attr);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, value).unwrap();
+    }
+
+    fn add_disk(root: &Path, name: &str, sectors: u64) {
+        let dir = root.join("class/block").join(name);
+        write_attr(&dir, "device/model", "ST16000NM001G\n");
+        write_attr(&dir, "device/serial", "ZL2ABCDE\n");
+        write_attr(&dir, "size", &format!("{}\n", sectors));
+        write_attr(&dir, "queue/rotational", "1\n");
+    }
+
+    fn monitor(root: &Path) -> (Arc<HotplugMonitor>, Arc<NodeRegistry>) {
+        let registry = NodeRegistry::new();
+        registry
+            .register("node-001", "host-001".to_string(), StorageNodeStatus::default())
+            .unwrap();
+        let config = ScannerConfig {
+            sysfs_path: root.to_path_buf(),
+            proc_path: root.join("proc"),
+            dev_path: root.join("dev"),
+            collect_smart: false,
+            ..Default::default()
+        };
+        let scanner = HardwareScanner::with_runner(config, Arc::new(FixtureCommandRunner::new()));
+        let monitor = HotplugMonitor::new(
+            "node-001",
+            Arc::new(scanner),
+            registry.clone(),
+            HotplugConfig {
+                source: HotplugSourceKind::Poll,
+                poll_interval: Duration::from_millis(10),
+                settle: Duration::from_millis(20),
+            },
+        );
+        (monitor, registry)
+    }
+
+    #[test]
+    fn test_parse_uevent() {
+        let msg = b"add@/devices/pci0000:00/0000:00:17.0/ata3/host2/target2:0:0/2:0:0:0/block/sdb\0\
+            ACTION=add\0DEVPATH=/devices/pci0000:00/0000:00:17.0/ata3/host2/target2:0:0/2:0:0:0/block/sdb\0\
+            SUBSYSTEM=block\0MAJOR=8\0MINOR=16\0DEVNAME=sdb\0DEVTYPE=disk\0SEQNUM=4242\0";
+        assert_eq!(
+            parse_uevent(msg),
+            Some(HotplugEvent::new(HotplugAction::Add, "sdb"))
+        );
+
+        let remove = b"remove@/devices/virtual/nvme-subsystem/nvme-subsys1/nvme1n1\0\
+            ACTION=remove\0SUBSYSTEM=block\0DEVNAME=nvme1n1\0DEVTYPE=disk\0";
+        assert_eq!(
+            parse_uevent(remove),
+            Some(HotplugEvent::new(HotplugAction::Remove, "nvme1n1"))
+        );
+
+        let partition = b"add@/block/sdb/sdb1\0ACTION=add\0SUBSYSTEM=block\0DEVNAME=sdb1\0DEVTYPE=partition\0";
+        assert_eq!(parse_uevent(partition), None);
+
+        let scsi = b"add@/devices/x\0ACTION=add\0SUBSYSTEM=scsi\0DEVTYPE=scsi_device\0";
+        assert_eq!(parse_uevent(scsi), None);
+
+        assert_eq!(parse_uevent(b"libudev\0\xfe\xed\xca\xfe"), None);
+    }
+
+    #[test]
+    fn test_sysfs_poller_diffs_snapshots() {
+        let dir = tempfile::tempdir().unwrap();
+        add_disk(dir.path(), "sdb", 31251759104);
+        let mut poller = SysfsPoller::new(dir.path(), DEFAULT_POLL_INTERVAL);
+        assert!(poller.poll().is_empty());
+
+        add_disk(dir.path(), "sdc", 31251759104);
+        write_attr(&dir.path().join("class/block/sdc1"), "partition", "1\n");
+        assert_eq!(
+            poller.poll(),
+            vec![HotplugEvent::new(HotplugAction::Add, "sdc")]
+        );
+
+        write_attr(&dir.path().join("class/block/sdc"), "size", "0\n");
+        fs::remove_dir_all(dir.path().join("class/block/sdb")).unwrap();
+        assert_eq!(
+            poller.poll(),
+            vec![
+                HotplugEvent::new(HotplugAction::Change, "sdc"),
+                HotplugEvent::new(HotplugAction::Remove, "sdb"),
+            ]
+        );
+
+        // An unreadable sysfs is not a mass removal
+        fs::remove_dir_all(dir.path().join("class/block")).unwrap();
+        assert!(poller.poll().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_apply_updates_registry_incrementally() {
+        let dir = tempfile::tempdir().unwrap();
+        let (monitor, registry) = monitor(dir.path());
+        let mut events = registry.subscribe();
+
+        add_disk(dir.path(), "sdb", 31251759104);
+        let add = HotplugEvent::new(HotplugAction::Add, "sdb");
+        assert_eq!(monitor.apply(&add).await.unwrap(), HotplugChange::Added);
+        assert_eq!(monitor.apply(&add).await.unwrap(), HotplugChange::Updated);
+
+        let entry = registry.get("node-001").unwrap();
+        assert_eq!(entry.drives().len(), 1);
+        assert_eq!(entry.status.hdd_count, 1);
+        assert_eq!(registry.stats().total_drives, 1);
+        assert_eq!(registry.stats().total_capacity_bytes, 31251759104 * 512);
+        assert!(matches!(
+            events.try_recv().unwrap(),
+            RegistryEvent::DriveAdded { drive_id, .. } if drive_id == "sdb"
+        ));
+        assert!(events.try_recv().is_err());
+
+        // Devices the scanner excludes are never added
+        add_disk(dir.path(), "sdz", 1024);
+        let small = HotplugEvent::new(HotplugAction::Add, "sdz");
+        assert_eq!(monitor.apply(&small).await.unwrap(), HotplugChange::Ignored);
+
+        fs::remove_dir_all(dir.path().join("class/block/sdb")).unwrap();
+        let remove = HotplugEvent::new(HotplugAction::Remove, "sdb");
+        assert_eq!(monitor.apply(&remove).await.unwrap(), HotplugChange::Removed);
+        assert!(registry.get("node-001").unwrap().drives().is_empty());
+        assert_eq!(registry.stats().total_drives, 0);
+        assert_eq!(registry.stats().total_capacity_bytes, 0);
+        assert!(matches!(
+            events.try_recv().unwrap(),
+            RegistryEvent::DriveRemoved { drive_id, .. } if drive_id == "sdb"
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_run_with_poller_emits_drive_added() {
+        let dir = tempfile::tempdir().unwrap();
+        let (monitor, registry) = monitor(dir.path());
+        fs::create_dir_all(dir.path().join("class/block")).unwrap();
+        let mut events = registry.subscribe();
+
+        let (shutdown_tx, shutdown_rx) = broadcast::channel(1);
+        let source = monitor.open_source().unwrap();
+        let task = tokio::spawn(monitor.clone().run_with_source(source, shutdown_rx));
+
+        add_disk(dir.path(), "sdd", 31251759104);
+        let event = tokio::time::timeout(Duration::from_secs(5), events.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(matches!(event, RegistryEvent::DriveAdded { drive_id, .. } if drive_id == "sdd"));
+
+        shutdown_tx.send(()).unwrap();
+        task.await.unwrap();
+    }
+}
@@ -1,12 +1,23 @@
 //! Hardware Discovery Module
 //!
 //! Discovers and enumerates storage hardware on Linux systems including
-//! NVMe, SAS, and SATA devices.
+//! NVMe, SAS, and SATA devices, watches for hot-plugged drives, detects drives already in use, and captures/replays hardware profiles
+//! for offline testing.
 
+pub mod command;
+pub mod hotplug;
 pub mod scanner;
 pub mod nvme;
+pub mod nvme_admin;
 pub mod sas_sata;
+pub mod profile;
+pub mod usage;
 
+pub use command::*;
+pub use hotplug::*;
 pub use scanner::*;
 pub use nvme::*;
+pub use nvme_admin::*;
 pub use sas_sata::*;
+pub use profile::*;
+pub use usage::*;
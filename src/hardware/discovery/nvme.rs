@@ -1,13 +1,18 @@
 //! NVMe-specific Discovery
 //!
 //! Provides detailed NVMe device discovery including namespace enumeration,
-//! ZNS detection, and SMART data retrieval via nvme-cli.
-
+//! ZNS detection, and SMART data retrieval. When an admin transport is
+//! configured, Identify and SMART data come straight from the controller
+//! through kernel passthrough; nvme-cli (run through a `CommandRunner` so
+//! it can be replayed from fixtures) is the fallback.
+
+use super::command::{CommandOutput, CommandRunnerRef};
+use super::nvme_admin::{self, AdminCommand, NvmeAdminRef};
+use crate::domain::health::NvmeHealth;
 use crate::domain::ports::{NvmeNamespaceInfo, SmartData};
 use crate::error::{Error, Result};
 use serde::{Deserialize, Serialize};
-use std::process::Command;
-use tracing::{debug, warn};
+use tracing::debug;
 
 // =============================================================================
 // NVMe Identify Structures
@@ -83,38 +88,50 @@ pub struct ZnsNamespaceInfo {
 // NVMe Discovery
 // =============================================================================
 
-/// NVMe-specific discovery operations
-pub struct NvmeDiscovery;
+/// NVMe-specific discovery operations backed by admin passthrough and
+/// nvme-cli
+#[derive(Clone)]
+pub struct NvmeDiscovery {
+    runner: CommandRunnerRef,
+    admin: Option<NvmeAdminRef>,
+}
 
 impl NvmeDiscovery {
-    /// Get controller info using nvme-cli
-    pub async fn get_controller_info(device: &str) -> Result<NvmeControllerInfo> {
+    /// Create NVMe discovery that runs nvme-cli through `runner`
+    pub fn new(runner: CommandRunnerRef) -> Self {
+        Self {
+            runner,
+            admin: None,
+        }
+    }
+
+    /// Issue admin commands natively through `admin`, falling back to
+    /// nvme-cli when they fail
+    pub fn with_admin(mut self, admin: NvmeAdminRef) -> Self {
+        self.admin = Some(admin);
+        self
+    }
+
+    /// Get controller info
+    pub async fn get_controller_info(&self, device: &str) -> Result<NvmeControllerInfo> {
         // Extract controller from device (e.g., /dev/nvme0n1 -> /dev/nvme0)
         let controller = Self::extract_controller_path(device)?;
 
-        // Run nvme id-ctrl command
-        let output = Command::new("nvme")
-            .args(["id-ctrl", &controller, "-o", "json"])
-            .output()
-            .map_err(|e| Error::NvmeCommand {
-                command: "id-ctrl".into(),
-                reason: e.to_string(),
-            })?;
+        if let Some(page) = self.native(&controller, AdminCommand::identify_controller()).await {
+            match nvme_admin::parse_identify_controller(&page) {
+                Ok(info) => return Ok(info),
+                Err(e) => debug!("Native identify-controller unusable for {}: {}", device, e),
+            }
+        }
 
-        if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
+        let output = self.run_nvme("id-ctrl", &["id-ctrl", &controller, "-o", "json"]).await?;
+        if !output.is_success() {
             return Err(Error::NvmeCommand {
                 command: "id-ctrl".into(),
-                reason: stderr.to_string(),
+                reason: output.stderr_lossy(),
             });
         }
-
-        // Parse JSON output
-        let json: serde_json::Value = serde_json::from_slice(&output.stdout)
-            .map_err(|e| Error::NvmeCommand {
-                command: "id-ctrl".into(),
-                reason: format!("JSON parse error: {}", e),
-            })?;
+        let json = Self::parse_json("id-ctrl", &output)?;
 
         Ok(NvmeControllerInfo {
             model: json["mn"].as_str().unwrap_or("Unknown").trim().to_string(),
@@ -134,28 +151,19 @@ impl NvmeDiscovery {
     }
 
     /// Get namespace info
-    pub async fn get_namespace_info(device: &str) -> Result<NvmeNamespaceExtended> {
-        let output = Command::new("nvme")
-            .args(["id-ns", device, "-o", "json"])
-            .output()
-            .map_err(|e| Error::NvmeCommand {
-                command: "id-ns".into(),
-                reason: e.to_string(),
-            })?;
+    pub async fn get_namespace_info(&self, device: &str) -> Result<NvmeNamespaceExtended> {
+        if let Some(info) = self.native_namespace_info(device).await {
+            return Ok(info);
+        }
 
-        if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
+        let output = self.run_nvme("id-ns", &["id-ns", device, "-o", "json"]).await?;
+        if !output.is_success() {
             return Err(Error::NvmeCommand {
                 command: "id-ns".into(),
-                reason: stderr.to_string(),
+                reason: output.stderr_lossy(),
             });
         }
-
-        let json: serde_json::Value = serde_json::from_slice(&output.stdout)
-            .map_err(|e| Error::NvmeCommand {
-                command: "id-ns".into(),
-                reason: format!("JSON parse error: {}", e),
-            })?;
+        let json = Self::parse_json("id-ns", &output)?;
 
         let nsze = json["nsze"].as_u64().unwrap_or(0);
         let nuse = json["nuse"].as_u64().unwrap_or(0);
@@ -167,7 +175,7 @@ impl NvmeDiscovery {
         let ms = lbaf["ms"].as_u64().unwrap_or(0) as u16;
 
         // Check for ZNS
-        let zns_info = Self::get_zns_info(device).await.ok();
+        let zns_info = self.get_zns_info(device).await.ok();
         let is_zns = zns_info.is_some();
 
         let nsid = Self::extract_nsid(device)?;
@@ -188,27 +196,21 @@ impl NvmeDiscovery {
     }
 
     /// Get ZNS-specific information
-    pub async fn get_zns_info(device: &str) -> Result<ZnsNamespaceInfo> {
-        let output = Command::new("nvme")
-            .args(["zns", "id-ns", device, "-o", "json"])
-            .output()
-            .map_err(|e| Error::NvmeCommand {
-                command: "zns id-ns".into(),
-                reason: e.to_string(),
-            })?;
+    pub async fn get_zns_info(&self, device: &str) -> Result<ZnsNamespaceInfo> {
+        if let Some(result) = self.native_zns_info(device).await {
+            return result;
+        }
 
-        if !output.status.success() {
+        let output = self
+            .run_nvme("zns id-ns", &["zns", "id-ns", device, "-o", "json"])
+            .await?;
+        if !output.is_success() {
             return Err(Error::NvmeCommand {
                 command: "zns id-ns".into(),
                 reason: "Not a ZNS namespace".into(),
             });
         }
-
-        let json: serde_json::Value = serde_json::from_slice(&output.stdout)
-            .map_err(|e| Error::NvmeCommand {
-                command: "zns id-ns".into(),
-                reason: format!("JSON parse error: {}", e),
-            })?;
+        let json = Self::parse_json("zns id-ns", &output)?;
 
         Ok(ZnsNamespaceInfo {
             zone_size_lba: json["zsze"].as_u64().unwrap_or(0),
@@ -219,29 +221,26 @@ impl NvmeDiscovery {
     }
 
     /// Get SMART data
-    pub async fn get_smart_data(device: &str) -> Result<SmartData> {
+    pub async fn get_smart_data(&self, device: &str) -> Result<SmartData> {
         let controller = Self::extract_controller_path(device)?;
 
-        let output = Command::new("nvme")
-            .args(["smart-log", &controller, "-o", "json"])
-            .output()
-            .map_err(|e| Error::NvmeCommand {
-                command: "smart-log".into(),
-                reason: e.to_string(),
-            })?;
+        if let Some(page) = self.native(&controller, AdminCommand::smart_log()).await {
+            match nvme_admin::parse_smart_log(&page) {
+                Ok(smart) => return Ok(smart),
+                Err(e) => debug!("Native smart-log unusable for {}: {}", device, e),
+            }
+        }
 
-        if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
+        let output = self
+            .run_nvme("smart-log", &["smart-log", &controller, "-o", "json"])
+            .await?;
+        if !output.is_success() {
+            debug!("smart-log failed for {}: {}", device, output.stderr_lossy());
             return Err(Error::SmartUnavailable {
                 device: device.to_string(),
             });
         }
-
-        let json: serde_json::Value = serde_json::from_slice(&output.stdout)
-            .map_err(|e| Error::NvmeCommand {
-                command: "smart-log".into(),
-                reason: format!("JSON parse error: {}", e),
-            })?;
+        let json = Self::parse_json("smart-log", &output)?;
 
         Ok(SmartData {
             temperature_celsius: (json["temperature"].as_i64().unwrap_or(0) - 273) as i32,
@@ -253,26 +252,47 @@ impl NvmeDiscovery {
         })
     }
 
+    /// Get the SMART / Health log as the NVMe health model
+    pub async fn get_health(&self, device: &str) -> Result<NvmeHealth> {
+        let controller = Self::extract_controller_path(device)?;
+
+        if let Some(page) = self.native(&controller, AdminCommand::smart_log()).await {
+            match nvme_admin::parse_nvme_health(&page) {
+                Ok(health) => return Ok(health),
+                Err(e) => debug!("Native smart-log unusable for {}: {}", device, e),
+            }
+        }
+
+        let output = self
+            .run_nvme("smart-log", &["smart-log", &controller, "-o", "json"])
+            .await?;
+        if !output.is_success() {
+            return Err(Error::SmartUnavailable {
+                device: device.to_string(),
+            });
+        }
+        let json = Self::parse_json("smart-log", &output)?;
+
+        Ok(NvmeHealth {
+            critical_warning: json["critical_warning"].as_u64().unwrap_or(0) as u8,
+            temperature_celsius: (json["temperature"].as_i64().unwrap_or(0) - 273) as i32,
+            available_spare: json["avail_spare"].as_u64().unwrap_or(0) as u8,
+            spare_threshold: json["spare_thresh"].as_u64().unwrap_or(0) as u8,
+            percentage_used: json["percent_used"].as_u64().unwrap_or(0).min(255) as u8,
+            media_errors: json["media_errors"].as_u64().unwrap_or(0),
+            error_log_entries: json["num_err_log_entries"].as_u64().unwrap_or(0),
+            unsafe_shutdowns: json["unsafe_shutdowns"].as_u64().unwrap_or(0),
+        })
+    }
+
     /// List all NVMe devices in the system
-    pub async fn list_devices() -> Result<Vec<String>> {
-        let output = Command::new("nvme")
-            .args(["list", "-o", "json"])
-            .output()
-            .map_err(|e| Error::NvmeCommand {
-                command: "list".into(),
-                reason: e.to_string(),
-            })?;
-
-        if !output.status.success() {
+    pub async fn list_devices(&self) -> Result<Vec<String>> {
+        let output = self.run_nvme("list", &["list", "-o", "json"]).await?;
+        if !output.is_success() {
             // nvme list may fail if no devices
             return Ok(Vec::new());
         }
-
-        let json: serde_json::Value = serde_json::from_slice(&output.stdout)
-            .map_err(|e| Error::NvmeCommand {
-                command: "list".into(),
-                reason: format!("JSON parse error: {}", e),
-            })?;
+        let json = Self::parse_json("list", &output)?;
 
         let devices: Vec<String> = json["Devices"]
             .as_array()
@@ -287,14 +307,92 @@ impl NvmeDiscovery {
     }
 
     /// Check if nvme-cli is available
-    pub fn is_nvme_cli_available() -> bool {
-        Command::new("nvme")
-            .arg("version")
-            .output()
-            .map(|o| o.status.success())
+    pub async fn is_nvme_cli_available(&self) -> bool {
+        self.runner
+            .run("nvme", &["version"])
+            .await
+            .map(|o| o.is_success())
             .unwrap_or(false)
     }
 
+    /// Execute an admin command natively; `None` means use nvme-cli
+    async fn native(&self, controller: &str, command: AdminCommand) -> Option<Vec<u8>> {
+        let admin = self.admin.as_ref()?;
+        match admin.execute(controller, command).await {
+            Ok(page) => Some(page),
+            Err(e) => {
+                debug!("Native {} failed on {}, using nvme-cli: {}", command.name(), controller, e);
+                None
+            }
+        }
+    }
+
+    async fn native_namespace_info(&self, device: &str) -> Option<NvmeNamespaceExtended> {
+        let controller = Self::extract_controller_path(device).ok()?;
+        let nsid = Self::extract_nsid(device).ok()?;
+
+        let page = self.native(&controller, AdminCommand::identify_namespace(nsid)).await?;
+        let ns = nvme_admin::parse_identify_namespace(&page).ok()?;
+
+        let zns_info = match self.native(&controller, AdminCommand::zns_identify_namespace(nsid)).await {
+            Some(page) => nvme_admin::parse_zns_identify_namespace(&page, &ns).ok(),
+            // Controllers without ZNS reject the command set specific Identify
+            None => None,
+        };
+
+        Some(NvmeNamespaceExtended {
+            base: NvmeNamespaceInfo {
+                nsid,
+                capacity_bytes: ns.nsze * ns.lba_size as u64,
+                active: true,
+                is_zns: zns_info.is_some(),
+            },
+            lba_size: ns.lba_size,
+            ms: ns.ms,
+            nsze: ns.nsze,
+            nuse: ns.nuse,
+            zns_info,
+        })
+    }
+
+    /// Native ZNS lookup; `None` means the transport could not answer
+    async fn native_zns_info(&self, device: &str) -> Option<Result<ZnsNamespaceInfo>> {
+        let controller = Self::extract_controller_path(device).ok()?;
+        let nsid = Self::extract_nsid(device).ok()?;
+
+        let page = self.native(&controller, AdminCommand::identify_namespace(nsid)).await?;
+        let ns = nvme_admin::parse_identify_namespace(&page).ok()?;
+
+        // Identify Namespace worked, so a rejected ZNS Identify is a real
+        // "not zoned" answer rather than a transport problem
+        let zns = match self.native(&controller, AdminCommand::zns_identify_namespace(nsid)).await {
+            Some(page) => nvme_admin::parse_zns_identify_namespace(&page, &ns),
+            None => Err(Error::NvmeCommand {
+                command: "zns id-ns".into(),
+                reason: "Not a ZNS namespace".into(),
+            }),
+        };
+        Some(zns)
+    }
+
+    /// Run an nvme-cli subcommand, mapping spawn failures to `NvmeCommand`
+    async fn run_nvme(&self, command: &str, args: &[&str]) -> Result<CommandOutput> {
+        self.runner.run("nvme", args).await.map_err(|e| match e {
+            Error::CommandTimeout { .. } => e,
+            other => Error::NvmeCommand {
+                command: command.into(),
+                reason: other.to_string(),
+            },
+        })
+    }
+
+    fn parse_json(command: &str, output: &CommandOutput) -> Result<serde_json::Value> {
+        serde_json::from_slice(&output.stdout).map_err(|e| Error::NvmeCommand {
+            command: command.into(),
+            reason: format!("JSON parse error: {}", e),
+        })
+    }
+
     // Helper functions
 
     fn extract_controller_path(device: &str) -> Result<String> {
@@ -352,6 +450,70 @@ impl NvmeDiscovery {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::hardware::discovery::command::FixtureCommandRunner;
+    use crate::hardware::discovery::nvme_admin::NvmeAdmin;
+    use async_trait::async_trait;
+    use std::collections::HashMap;
+    use std::sync::Arc;
+
+    /// Admin transport answering from binary fixtures
+    struct FixtureAdmin(HashMap<&'static str, &'static [u8]>);
+
+    #[async_trait]
+    impl NvmeAdmin for FixtureAdmin {
+        async fn execute(&self, _controller: &str, command: AdminCommand) -> Result<Vec<u8>> {
+            self.0
+                .get(command.name())
+                .map(|page| page.to_vec())
+                .ok_or_else(|| Error::NvmeCommand {
+                    command: command.name().into(),
+                    reason: "Invalid Field in Command".into(),
+                })
+        }
+    }
+
+    fn fixture_admin() -> NvmeAdminRef {
+        Arc::new(FixtureAdmin(HashMap::from([
+            ("identify-controller", &include_bytes!("testdata/nvme/id-ctrl.bin")[..]),
+            ("identify-namespace", &include_bytes!("testdata/nvme/id-ns.bin")[..]),
+            ("smart-log", &include_bytes!("testdata/nvme/smart-log.bin")[..]),
+        ])))
+    }
+
+    #[tokio::test]
+    async fn test_native_admin_preferred_over_nvme_cli() {
+        let runner = Arc::new(FixtureCommandRunner::new());
+        let nvme = NvmeDiscovery::new(runner.clone()).with_admin(fixture_admin());
+
+        let ctrl = nvme.get_controller_info("/dev/nvme0n1").await.unwrap();
+        assert_eq!(ctrl.model, "Samsung SSD 980 PRO 2TB");
+
+        let ns = nvme.get_namespace_info("/dev/nvme0n1").await.unwrap();
+        assert_eq!(ns.lba_size, 4096);
+        assert_eq!(ns.base.capacity_bytes, 3907029168 * 4096);
+        assert!(!ns.base.is_zns);
+        assert!(nvme.get_zns_info("/dev/nvme0n1").await.is_err());
+
+        let smart = nvme.get_smart_data("/dev/nvme0n1").await.unwrap();
+        assert_eq!(smart.temperature_celsius, 41);
+
+        assert!(runner.calls().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_falls_back_to_nvme_cli() {
+        let runner = Arc::new(FixtureCommandRunner::new().with_json(
+            "nvme smart-log /dev/nvme0 -o json",
+            serde_json::json!({"temperature": 300, "percent_used": 7}),
+        ));
+        let nvme = NvmeDiscovery::new(runner.clone())
+            .with_admin(Arc::new(FixtureAdmin(HashMap::new())));
+
+        let smart = nvme.get_smart_data("/dev/nvme0n1").await.unwrap();
+        assert_eq!(smart.temperature_celsius, 27);
+        assert_eq!(smart.percentage_used, 7);
+        assert_eq!(runner.calls(), vec!["nvme smart-log /dev/nvme0 -o json"]);
+    }
 
     #[test]
     fn test_extract_controller_path() {
@@ -0,0 +1,494 @@
+//! Native NVMe Admin Commands
+//!
+//! Issues Identify and Get Log Page admin commands through the kernel NVMe
+//! passthrough ioctl and parses the raw 4 KiB data structures, so NVMe
+//! discovery works without nvme-cli in the container and without depending
+//! on nvme-cli's JSON field names. Offsets follow the NVM Express Base and
+//! Zoned Namespace Command Set specifications.
+
+use super::nvme::{NvmeControllerInfo, NvmeFeatures, ZnsNamespaceInfo};
+use crate::domain::health::NvmeHealth;
+use crate::domain::ports::SmartData;
+use crate::error::{Error, Result};
+use async_trait::async_trait;
+use std::sync::Arc;
+use std::time::Duration;
+
+// =============================================================================
+// Constants
+// =============================================================================
+
+/// Size of Identify data structures and the SMART / Health log page
+pub const NVME_PAGE_SIZE: usize = 4096;
+
+const OPCODE_GET_LOG_PAGE: u8 = 0x02;
+const OPCODE_IDENTIFY: u8 = 0x06;
+
+const CNS_NAMESPACE: u32 = 0x00;
+const CNS_CONTROLLER: u32 = 0x01;
+const CNS_CSI_NAMESPACE: u32 = 0x05;
+
+const CSI_ZNS: u32 = 0x02;
+const LID_SMART: u32 = 0x02;
+
+/// Namespace ID addressing all namespaces (controller-wide log pages)
+const NSID_ALL: u32 = 0xFFFF_FFFF;
+
+// =============================================================================
+// Admin Commands
+// =============================================================================
+
+/// An admin command returning one 4 KiB page
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AdminCommand {
+    /// Opcode
+    pub opcode: u8,
+    /// Namespace ID
+    pub nsid: u32,
+    /// Command dword 10
+    pub cdw10: u32,
+    /// Command dword 11
+    pub cdw11: u32,
+}
+
+impl AdminCommand {
+    /// Identify Controller (CNS 01h)
+    pub fn identify_controller() -> Self {
+        Self {
+            opcode: OPCODE_IDENTIFY,
+            nsid: 0,
+            cdw10: CNS_CONTROLLER,
+            cdw11: 0,
+        }
+    }
+
+    /// Identify Namespace (CNS 00h)
+    pub fn identify_namespace(nsid: u32) -> Self {
+        Self {
+            opcode: OPCODE_IDENTIFY,
+            nsid,
+            cdw10: CNS_NAMESPACE,
+            cdw11: 0,
+        }
+    }
+
+    /// ZNS command set specific Identify Namespace (CNS 05h, CSI 02h)
+    pub fn zns_identify_namespace(nsid: u32) -> Self {
+        Self {
+            opcode: OPCODE_IDENTIFY,
+            nsid,
+            cdw10: CNS_CSI_NAMESPACE,
+            cdw11: CSI_ZNS << 24,
+        }
+    }
+
+    /// SMART / Health Information log page (LID 02h), controller-wide
+    pub fn smart_log() -> Self {
+        let numd = (NVME_PAGE_SIZE / 4 - 1) as u32;
+        Self {
+            opcode: OPCODE_GET_LOG_PAGE,
+            nsid: NSID_ALL,
+            cdw10: LID_SMART | (numd << 16),
+            cdw11: 0,
+        }
+    }
+
+    /// Short name for logs and errors
+    pub fn name(&self) -> &'static str {
+        match (self.opcode, self.cdw10 & 0xFF) {
+            (OPCODE_IDENTIFY, CNS_CONTROLLER) => "identify-controller",
+            (OPCODE_IDENTIFY, CNS_NAMESPACE) => "identify-namespace",
+            (OPCODE_IDENTIFY, CNS_CSI_NAMESPACE) => "zns-identify-namespace",
+            (OPCODE_GET_LOG_PAGE, LID_SMART) => "smart-log",
+            _ => "admin",
+        }
+    }
+}
+
+// =============================================================================
+// Admin Transport Port
+// =============================================================================
+
+/// Sends NVMe admin commands to a controller
+#[async_trait]
+pub trait NvmeAdmin: Send + Sync {
+    /// Execute `command` against `controller` (e.g. `/dev/nvme0`) and
+    /// return the data page
+    async fn execute(&self, controller: &str, command: AdminCommand) -> Result<Vec<u8>>;
+}
+
+/// Shared admin transport reference
+pub type NvmeAdminRef = Arc<dyn NvmeAdmin>;
+
+// =============================================================================
+// Ioctl Transport
+// =============================================================================
+
+/// Admin passthrough via `NVME_IOCTL_ADMIN_CMD` (Linux only)
+#[derive(Debug, Clone)]
+pub struct IoctlNvmeAdmin {
+    timeout: Duration,
+}
+
+impl IoctlNvmeAdmin {
+    /// Create a transport with the given per-command timeout
+    pub fn new(timeout: Duration) -> Self {
+        Self { timeout }
+    }
+}
+
+#[async_trait]
+impl NvmeAdmin for IoctlNvmeAdmin {
+    async fn execute(&self, controller: &str, command: AdminCommand) -> Result<Vec<u8>> {
+        let controller = controller.to_string();
+        let timeout_ms = self.timeout.as_millis().min(u32::MAX as u128) as u32;
+
+        tokio::task::spawn_blocking(move || passthrough::admin(&controller, command, timeout_ms))
+            .await
+            .map_err(|e| Error::NvmeCommand {
+                command: command.name().into(),
+                reason: e.to_string(),
+            })?
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod passthrough {
+    use super::{AdminCommand, NVME_PAGE_SIZE};
+    use crate::error::{Error, Result};
+    use std::fs::OpenOptions;
+    use std::os::unix::io::AsRawFd;
+
+    /// `struct nvme_passthru_cmd` from `<linux/nvme_ioctl.h>`
+    #[repr(C)]
+    #[derive(Default)]
+    struct NvmePassthruCmd {
+        opcode: u8,
+        flags: u8,
+        rsvd1: u16,
+        nsid: u32,
+        cdw2: u32,
+        cdw3: u32,
+        metadata: u64,
+        addr: u64,
+        metadata_len: u32,
+        data_len: u32,
+        cdw10: u32,
+        cdw11: u32,
+        cdw12: u32,
+        cdw13: u32,
+        cdw14: u32,
+        cdw15: u32,
+        timeout_ms: u32,
+        result: u32,
+    }
+
+    /// `_IOWR('N', 0x41, struct nvme_admin_cmd)`
+    const NVME_IOCTL_ADMIN_CMD: u64 = 0xC048_4E41;
+
+    pub(super) fn admin(controller: &str, command: AdminCommand, timeout_ms: u32) -> Result<Vec<u8>> {
+        let fail = |reason: String| Error::NvmeCommand {
+            command: command.name().into(),
+            reason,
+        };
+
+        let file = OpenOptions::new()
+            .read(true)
+            .open(controller)
+            .map_err(|e| fail(format!("open {}: {}", controller, e)))?;
+
+        let mut data = vec![0u8; NVME_PAGE_SIZE];
+        let mut cmd = NvmePassthruCmd {
+            opcode: command.opcode,
+            nsid: command.nsid,
+            addr: data.as_mut_ptr() as u64,
+            data_len: NVME_PAGE_SIZE as u32,
+            cdw10: command.cdw10,
+            cdw11: command.cdw11,
+            timeout_ms,
+            ..Default::default()
+        };
+
+        // SAFETY: `cmd` matches the kernel's nvme_passthru_cmd layout and
+        // `addr` points at a live buffer of `data_len` bytes for the whole
+        // call; the fd is kept open by `file`.
+        let rc = unsafe {
+            libc::ioctl(
+                file.as_raw_fd(),
+                NVME_IOCTL_ADMIN_CMD as _,
+                &mut cmd as *mut NvmePassthruCmd,
+            )
+        };
+
+        if rc < 0 {
+            return Err(fail(std::io::Error::last_os_error().to_string()));
+        }
+        if rc > 0 {
+            // Positive return values are NVMe status codes
+            return Err(fail(format!("NVMe status {:#x}", rc)));
+        }
+        Ok(data)
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+mod passthrough {
+    use super::AdminCommand;
+    use crate::error::{Error, Result};
+
+    pub(super) fn admin(_controller: &str, command: AdminCommand, _timeout_ms: u32) -> Result<Vec<u8>> {
+        Err(Error::NvmeCommand {
+            command: command.name().into(),
+            reason: "NVMe passthrough is only supported on Linux".into(),
+        })
+    }
+}
+
+// =============================================================================
+// Parsers
+// =============================================================================
+
+/// Formatted LBA size and metadata size from Identify Namespace
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NamespaceIdentify {
+    /// Namespace size in logical blocks
+    pub nsze: u64,
+    /// Namespace capacity in logical blocks
+    pub ncap: u64,
+    /// Namespace utilization in logical blocks
+    pub nuse: u64,
+    /// Index of the formatted LBA format
+    pub lba_format: usize,
+    /// Formatted LBA data size in bytes
+    pub lba_size: u32,
+    /// Formatted metadata size in bytes
+    pub ms: u16,
+}
+
+/// Parse an Identify Controller data structure
+pub fn parse_identify_controller(data: &[u8]) -> Result<NvmeControllerInfo> {
+    check_len("identify-controller", data)?;
+
+    let ver = le_u32(data, 80);
+    let oacs = le_u16(data, 256);
+    let cmic = data[76];
+
+    Ok(NvmeControllerInfo {
+        model: ascii(&data[24..64]),
+        serial: ascii(&data[4..24]),
+        firmware: ascii(&data[64..72]),
+        namespace_count: le_u32(data, 516),
+        mdts: data[77] as u32,
+        cntlid: le_u16(data, 78),
+        version: format!("{}.{}.{}", ver >> 16, (ver >> 8) & 0xFF, ver & 0xFF),
+        features: NvmeFeatures {
+            // Same rule as the nvme-cli path so both agree
+            zns: data[111] == 2,
+            ns_management: (oacs & 0x8) != 0,
+            multipath: (cmic & 0x1) != 0,
+            sriov: (cmic & 0x4) != 0,
+            // PMR support is advertised in controller registers, not Identify
+            pmr: false,
+        },
+    })
+}
+
+/// Parse an Identify Namespace data structure
+pub fn parse_identify_namespace(data: &[u8]) -> Result<NamespaceIdentify> {
+    check_len("identify-namespace", data)?;
+
+    // FLBAS bits 3:0 are the low bits of the format index, 6:5 the high bits
+    let flbas = data[26] as usize;
+    let lba_format = (flbas & 0xF) | ((flbas >> 5) & 0x3) << 4;
+    let lbaf = 128 + lba_format * 4;
+    let lbads = data[lbaf + 2];
+
+    Ok(NamespaceIdentify {
+        nsze: le_u64(data, 0),
+        ncap: le_u64(data, 8),
+        nuse: le_u64(data, 16),
+        lba_format,
+        lba_size: if (9..32).contains(&lbads) { 1u32 << lbads } else { 512 },
+        ms: le_u16(data, lbaf),
+    })
+}
+
+/// Parse a ZNS Identify Namespace data structure for the namespace's
+/// formatted LBA format
+pub fn parse_zns_identify_namespace(data: &[u8], ns: &NamespaceIdentify) -> Result<ZnsNamespaceInfo> {
+    check_len("zns-identify-namespace", data)?;
+
+    // LBA Format Extensions start at byte 2816, 16 bytes each
+    let zone_size_lba = le_u64(data, 2816 + ns.lba_format * 16);
+    if zone_size_lba == 0 {
+        return Err(Error::NvmeCommand {
+            command: "zns-identify-namespace".into(),
+            reason: "Not a ZNS namespace".into(),
+        });
+    }
+
+    Ok(ZnsNamespaceInfo {
+        zone_size_lba,
+        zone_count: ns.nsze / zone_size_lba,
+        // 0-based values; all ones means no limit
+        max_open_zones: le_u32(data, 8).saturating_add(1),
+        max_active_zones: le_u32(data, 4).saturating_add(1),
+    })
+}
+
+/// Parse the SMART / Health Information log page
+pub fn parse_smart_log(data: &[u8]) -> Result<SmartData> {
+    check_len("smart-log", data)?;
+
+    Ok(SmartData {
+        // Composite temperature is reported in Kelvin
+        temperature_celsius: le_u16(data, 1) as i32 - 273,
+        percentage_used: data[5],
+        data_units_read: le_u128_saturating(data, 32),
+        data_units_written: le_u128_saturating(data, 48),
+        power_on_hours: le_u128_saturating(data, 128),
+        critical_warning: data[0],
+    })
+}
+
+/// Parse the SMART / Health Information log page into the health model
+pub fn parse_nvme_health(data: &[u8]) -> Result<NvmeHealth> {
+    check_len("smart-log", data)?;
+
+    Ok(NvmeHealth {
+        critical_warning: data[0],
+        temperature_celsius: le_u16(data, 1) as i32 - 273,
+        available_spare: data[3],
+        spare_threshold: data[4],
+        percentage_used: data[5],
+        unsafe_shutdowns: le_u128_saturating(data, 144),
+        media_errors: le_u128_saturating(data, 160),
+        error_log_entries: le_u128_saturating(data, 176),
+    })
+}
+
+fn check_len(what: &str, data: &[u8]) -> Result<()> {
+    if data.len() < NVME_PAGE_SIZE {
+        return Err(Error::NvmeCommand {
+            command: what.into(),
+            reason: format!("short data: {} of {} bytes", data.len(), NVME_PAGE_SIZE),
+        });
+    }
+    Ok(())
+}
+
+fn le_u16(data: &[u8], off: usize) -> u16 {
+    u16::from_le_bytes([data[off], data[off + 1]])
+}
+
+fn le_u32(data: &[u8], off: usize) -> u32 {
+    let mut b = [0u8; 4];
+    b.copy_from_slice(&data[off..off + 4]);
+    u32::from_le_bytes(b)
+}
+
+fn le_u64(data: &[u8], off: usize) -> u64 {
+    let mut b = [0u8; 8];
+    b.copy_from_slice(&data[off..off + 8]);
+    u64::from_le_bytes(b)
+}
+
+fn le_u128_saturating(data: &[u8], off: usize) -> u64 {
+    let mut b = [0u8; 16];
+    b.copy_from_slice(&data[off..off + 16]);
+    u64::try_from(u128::from_le_bytes(b)).unwrap_or(u64::MAX)
+}
+
+/// Space-padded ASCII field
+fn ascii(field: &[u8]) -> String {
+    String::from_utf8_lossy(field)
+        .trim_matches(|c: char| c == ' ' || c == '\0')
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ID_CTRL: &[u8] = include_bytes!("testdata/nvme/id-ctrl.bin");
+    const ID_NS: &[u8] = include_bytes!("testdata/nvme/id-ns.bin");
+    const ZNS_ID_NS: &[u8] = include_bytes!("testdata/nvme/zns-id-ns.bin");
+    const SMART_LOG: &[u8] = include_bytes!("testdata/nvme/smart-log.bin");
+
+    #[test]
+    fn test_parse_identify_controller() {
+        let info = parse_identify_controller(ID_CTRL).unwrap();
+        assert_eq!(info.model, "Samsung SSD 980 PRO 2TB");
+        assert_eq!(info.serial, "S6B0NL0T123456");
+        assert_eq!(info.firmware, "5B2QGXA7");
+        assert_eq!(info.namespace_count, 1);
+        assert_eq!(info.mdts, 9);
+        assert_eq!(info.cntlid, 6);
+        assert_eq!(info.version, "1.3.0");
+        assert!(info.features.ns_management);
+        assert!(!info.features.multipath);
+    }
+
+    #[test]
+    fn test_parse_identify_namespace_uses_formatted_lba() {
+        let ns = parse_identify_namespace(ID_NS).unwrap();
+        assert_eq!(ns.nsze, 3907029168);
+        assert_eq!(ns.nuse, 1250000000);
+        assert_eq!(ns.lba_format, 1);
+        assert_eq!(ns.lba_size, 4096);
+        assert_eq!(ns.ms, 0);
+    }
+
+    #[test]
+    fn test_parse_zns_identify_namespace() {
+        let ns = parse_identify_namespace(ID_NS).unwrap();
+        let zns = parse_zns_identify_namespace(ZNS_ID_NS, &ns).unwrap();
+        assert_eq!(zns.zone_size_lba, 0x10000);
+        assert_eq!(zns.zone_count, 3907029168 / 0x10000);
+        assert_eq!(zns.max_open_zones, 14);
+        assert_eq!(zns.max_active_zones, 14);
+
+        // A conventional namespace has no zone size
+        assert!(parse_zns_identify_namespace(&[0u8; NVME_PAGE_SIZE], &ns).is_err());
+    }
+
+    #[test]
+    fn test_parse_smart_log() {
+        let smart = parse_smart_log(SMART_LOG).unwrap();
+        assert_eq!(smart.temperature_celsius, 41);
+        assert_eq!(smart.percentage_used, 3);
+        assert_eq!(smart.data_units_read, 1234567);
+        assert_eq!(smart.data_units_written, 7654321);
+        assert_eq!(smart.power_on_hours, 4821);
+        assert_eq!(smart.critical_warning, 0x04);
+    }
+
+    #[test]
+    fn test_parse_nvme_health() {
+        let health = parse_nvme_health(SMART_LOG).unwrap();
+        assert_eq!(health.critical_warning, 0x04);
+        assert_eq!(health.available_spare, 100);
+        assert_eq!(health.spare_threshold, 10);
+        assert_eq!(health.percentage_used, 3);
+        assert_eq!(health.media_errors, 0);
+    }
+
+    #[test]
+    fn test_rejects_short_pages() {
+        assert!(parse_identify_controller(&ID_CTRL[..512]).is_err());
+        assert!(parse_smart_log(&[]).is_err());
+    }
+
+    #[test]
+    fn test_admin_command_encoding() {
+        let smart = AdminCommand::smart_log();
+        assert_eq!(smart.opcode, 0x02);
+        assert_eq!(smart.nsid, 0xFFFF_FFFF);
+        assert_eq!(smart.cdw10, 0x03FF_0002);
+
+        let zns = AdminCommand::zns_identify_namespace(1);
+        assert_eq!(zns.cdw10, 0x05);
+        assert_eq!(zns.cdw11, 0x0200_0000);
+        assert_eq!(zns.name(), "zns-identify-namespace");
+    }
+}
@@ -0,0 +1,461 @@
+//! Hardware Profiles
+//!
+//! A hardware profile is a single-file snapshot of everything discovery
+//! reads on a node: the sysfs attributes the scanner looks at, the host
+//! facts from procfs and `/etc/hostname`, and the output of every
+//! nvme-cli/smartctl command discovery runs. Profiles are captured on real
+//! servers (`smart-storage-operator capture-hardware`) and replayed offline,
+//! so they can be attached to bug reports and kept as a regression corpus.
+//!
+//! Profiles are JSON; a `.zst` extension stores them zstd-compressed.
+
+use super::command::{
+    CommandRunnerRef, FixtureCommandRunner, RecordedCommand, RecordingCommandRunner,
+    SystemCommandRunner,
+};
+use super::nvme::NvmeDiscovery;
+use super::sas_sata::SasSataDiscovery;
+use super::scanner::{HardwareScanner, ScannerConfig};
+use crate::domain::ports::DriveType;
+use crate::error::{Error, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+use tracing::{debug, info};
+
+// =============================================================================
+// Constants
+// =============================================================================
+
+/// Current profile format version
+pub const PROFILE_FORMAT_VERSION: u32 = 1;
+
+/// Per-device sysfs attributes read during discovery
+const BLOCK_ATTRS: &[&str] = &[
+    "size",
+    "partition",
+    "queue/logical_block_size",
+    "queue/rotational",
+    "queue/zoned",
+    "device/model",
+    "device/name",
+    "device/serial",
+    "device/firmware_rev",
+    "device/firmware",
+    "device/rev",
+    "device/transport",
+    "device/wwid",
+    "wwid",
+    "nguid",
+    "eui",
+    "hidden",
+    "dm/uuid",
+    "dm/name",
+    "device/address",
+    "device/numa_node",
+    "device/device/numa_node",
+];
+
+/// Profile keys for the host facts outside sysfs
+const MEMINFO_KEY: &str = "proc/meminfo";
+const CPUINFO_KEY: &str = "proc/cpuinfo";
+const MOUNTS_KEY: &str = "proc/mounts";
+const SWAPS_KEY: &str = "proc/swaps";
+const HOSTNAME_KEY: &str = "etc/hostname";
+
+// =============================================================================
+// Hardware Profile
+// =============================================================================
+
+/// Snapshot of a node's discovery inputs
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HardwareProfile {
+    /// Profile format version
+    pub format_version: u32,
+    /// When the profile was captured
+    pub captured_at: DateTime<Utc>,
+    /// Hostname of the captured node
+    pub hostname: String,
+    /// Operator version that captured the profile
+    pub operator_version: String,
+    /// Directories that must exist, relative to the replay root
+    /// (e.g. `sys/class/nvme/nvme0`)
+    pub directories: BTreeSet<String>,
+    /// File contents keyed by path relative to the replay root
+    /// (e.g. `sys/class/block/sda/size`)
+    pub files: BTreeMap<String, String>,
+    /// Recorded nvme-cli/smartctl invocations
+    pub commands: Vec<RecordedCommand>,
+}
+
+impl HardwareProfile {
+    /// Capture a profile of the node described by `config`
+    pub async fn capture(config: ScannerConfig) -> Result<Self> {
+        let mut profile = Self {
+            format_version: PROFILE_FORMAT_VERSION,
+            captured_at: Utc::now(),
+            hostname: String::new(),
+            operator_version: crate::VERSION.to_string(),
+            directories: BTreeSet::new(),
+            files: BTreeMap::new(),
+            commands: Vec::new(),
+        };
+
+        profile.capture_sysfs(&config.sysfs_path)?;
+        profile.capture_host_files(&config);
+
+        let recorder = Arc::new(RecordingCommandRunner::new(Arc::new(
+            SystemCommandRunner::new(config.command_timeout),
+        )));
+        let runner: CommandRunnerRef = recorder.clone();
+
+        // A full discovery pass records SMART queries and the hostname
+        // fallback exactly as the scanner issues them
+        let scanner = HardwareScanner::with_runner(
+            ScannerConfig {
+                collect_smart: true,
+                ..config
+            },
+            runner.clone(),
+        );
+        let info = scanner.discover().await?;
+        profile.hostname = info.hostname;
+
+        // Also record the per-device queries the discovery modules expose
+        let nvme = NvmeDiscovery::new(runner.clone());
+        let sata = SasSataDiscovery::new(runner);
+        nvme.is_nvme_cli_available().await;
+        sata.is_smartctl_available().await;
+        let _ = nvme.list_devices().await;
+        let _ = sata.list_devices().await;
+        for drive in &info.drives {
+            match drive.drive_type {
+                DriveType::Nvme => {
+                    let _ = nvme.get_controller_info(&drive.device_path).await;
+                    let _ = nvme.get_namespace_info(&drive.device_path).await;
+                }
+                _ => {
+                    let _ = sata.get_device_info(&drive.device_path).await;
+                }
+            }
+        }
+
+        profile.commands = recorder.recordings();
+        info!(
+            "Captured hardware profile of {}: {} drives, {} files, {} commands",
+            profile.hostname,
+            info.drives.len(),
+            profile.files.len(),
+            profile.commands.len()
+        );
+
+        Ok(profile)
+    }
+
+    /// Load a profile from a file
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let raw = fs::read(path).map_err(|e| {
+            Error::Configuration(format!("Failed to read profile {}: {}", path.display(), e))
+        })?;
+        let raw = if is_compressed(path) {
+            zstd::decode_all(raw.as_slice()).map_err(|e| {
+                Error::Configuration(format!("Failed to decompress {}: {}", path.display(), e))
+            })?
+        } else {
+            raw
+        };
+
+        let profile: Self = serde_json::from_slice(&raw).map_err(|e| {
+            Error::Configuration(format!("Invalid profile {}: {}", path.display(), e))
+        })?;
+        if profile.format_version > PROFILE_FORMAT_VERSION {
+            return Err(Error::Configuration(format!(
+                "Profile {} has format version {}, newest supported is {}",
+                path.display(),
+                profile.format_version,
+                PROFILE_FORMAT_VERSION
+            )));
+        }
+        Ok(profile)
+    }
+
+    /// Save the profile to a file
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+        let json = serde_json::to_vec_pretty(self)?;
+        let data = if is_compressed(path) {
+            zstd::encode_all(json.as_slice(), 0)?
+        } else {
+            json
+        };
+        fs::write(path, data)?;
+        Ok(())
+    }
+
+    /// Command runner that replays the recorded commands
+    pub fn command_runner(&self) -> FixtureCommandRunner {
+        FixtureCommandRunner::from_recordings(self.commands.iter().cloned())
+    }
+
+    /// Write the captured files under `root` and return a scanner
+    /// configuration that reads from them
+    pub fn materialize(&self, root: &Path) -> Result<ScannerConfig> {
+        for dir in &self.directories {
+            fs::create_dir_all(root.join(checked_relative(dir)?))?;
+        }
+        for (key, contents) in &self.files {
+            let path = root.join(checked_relative(key)?);
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::write(path, contents)?;
+        }
+
+        Ok(ScannerConfig {
+            sysfs_path: root.join("sys"),
+            proc_path: root.join("proc"),
+            hostname_path: root.join(HOSTNAME_KEY),
+            // Device contents are not captured, so signature checks find
+            // no device nodes and are skipped on replay
+            dev_path: root.join("dev"),
+            ..Default::default()
+        })
+    }
+
+    /// Build a scanner that replays this profile from a tree under `root`
+    pub fn replay(&self, root: &Path) -> Result<HardwareScanner> {
+        let config = self.materialize(root)?;
+        Ok(HardwareScanner::with_runner(
+            config,
+            Arc::new(self.command_runner()),
+        ))
+    }
+
+    fn capture_sysfs(&mut self, sysfs: &Path) -> Result<()> {
+        let nvme = sysfs.join("class/nvme");
+        if nvme.exists() {
+            for entry in fs::read_dir(&nvme)? {
+                let name = entry?.file_name().to_string_lossy().to_string();
+                self.directories.insert(format!("sys/class/nvme/{}", name));
+            }
+        }
+
+        let block = sysfs.join("class/block");
+        if !block.exists() {
+            return Err(Error::HardwareDiscovery(format!(
+                "Block device sysfs not found at {}",
+                block.display()
+            )));
+        }
+        for entry in fs::read_dir(&block)? {
+            let entry = entry?;
+            let name = entry.file_name().to_string_lossy().to_string();
+            let key = format!("sys/class/block/{}", name);
+            for attr in BLOCK_ATTRS {
+                // Attributes vary by device type; absent ones are skipped
+                if let Ok(contents) = fs::read_to_string(entry.path().join(attr)) {
+                    self.files.insert(format!("{}/{}", key, attr), contents);
+                }
+            }
+            if let Ok(links) = fs::read_dir(entry.path().join("device")) {
+                for link in links.flatten() {
+                    let link = link.file_name().to_string_lossy().to_string();
+                    if !link.starts_with("enclosure_device:") {
+                        continue;
+                    }
+                    let slot = format!("device/{}/slot", link);
+                    if let Ok(contents) = fs::read_to_string(entry.path().join(&slot)) {
+                        self.files.insert(format!("{}/{}", key, slot), contents);
+                    }
+                }
+            }
+            for link_dir in ["holders", "slaves"] {
+                if let Ok(links) = fs::read_dir(entry.path().join(link_dir)) {
+                    for link in links.flatten() {
+                        let link = link.file_name().to_string_lossy().to_string();
+                        self.directories.insert(format!("{}/{}/{}", key, link_dir, link));
+                    }
+                }
+            }
+            self.directories.insert(key);
+        }
+
+        // PCIe slots, to place NVMe drives
+        if let Ok(slots) = fs::read_dir(sysfs.join("bus/pci/slots")) {
+            for slot in slots.flatten() {
+                if let Ok(address) = fs::read_to_string(slot.path().join("address")) {
+                    let name = slot.file_name().to_string_lossy().to_string();
+                    self.files
+                        .insert(format!("sys/bus/pci/slots/{}/address", name), address);
+                }
+            }
+        }
+        debug!("Captured {} sysfs attributes", self.files.len());
+        Ok(())
+    }
+
+    fn capture_host_files(&mut self, config: &ScannerConfig) {
+        if let Ok(hostname) = fs::read_to_string(&config.hostname_path) {
+            self.files.insert(HOSTNAME_KEY.to_string(), hostname);
+        }
+        // Only the lines discovery reads, to keep profiles small
+        if let Ok(meminfo) = fs::read_to_string(config.proc_path.join("meminfo")) {
+            let lines = filter_lines(&meminfo, "MemTotal:");
+            self.files.insert(MEMINFO_KEY.to_string(), lines);
+        }
+        if let Ok(cpuinfo) = fs::read_to_string(config.proc_path.join("cpuinfo")) {
+            let lines = filter_lines(&cpuinfo, "processor");
+            self.files.insert(CPUINFO_KEY.to_string(), lines);
+        }
+        if let Ok(mounts) = fs::read_to_string(config.proc_path.join("mounts")) {
+            let lines = filter_lines(&mounts, "/dev/");
+            self.files.insert(MOUNTS_KEY.to_string(), lines);
+        }
+        if let Ok(swaps) = fs::read_to_string(config.proc_path.join("swaps")) {
+            self.files.insert(SWAPS_KEY.to_string(), swaps);
+        }
+    }
+}
+
+fn filter_lines(contents: &str, prefix: &str) -> String {
+    contents
+        .lines()
+        .filter(|l| l.starts_with(prefix))
+        .map(|l| format!("{}\n", l))
+        .collect()
+}
+
+fn is_compressed(path: &Path) -> bool {
+    path.extension().map(|e| e == "zst").unwrap_or(false)
+}
+
+/// Reject profile keys that would escape the replay root
+fn checked_relative(key: &str) -> Result<&Path> {
+    let path = Path::new(key);
+    let safe = path
+        .components()
+        .all(|c| matches!(c, std::path::Component::Normal(_)));
+    if !safe {
+        return Err(Error::Configuration(format!(
+            "Invalid path in hardware profile: {}",
+            key
+        )));
+    }
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::ports::HardwareDiscoverer;
+
+    fn sample_profile() -> HardwareProfile {
+        let files = [
+            ("sys/class/block/nvme0n1/size", "3907029168\n"),
+            ("sys/class/block/nvme0n1/device/model", "Samsung SSD 980 PRO 2TB\n"),
+            ("sys/class/block/nvme0n1/device/serial", "S6B0NL0T123456\n"),
+            ("sys/class/block/nvme0n1/device/firmware_rev", "5B2QGXA7\n"),
+            ("sys/class/block/sda/size", "31251759104\n"),
+            ("sys/class/block/sda/device/model", "ST16000NM001G\n"),
+            ("sys/class/block/sda/queue/rotational", "1\n"),
+            ("proc/meminfo", "MemTotal:       263958240 kB\n"),
+            ("proc/cpuinfo", "processor\t: 0\nprocessor\t: 1\nprocessor\t: 2\n"),
+            ("etc/hostname", "storage-07\n"),
+        ];
+
+        HardwareProfile {
+            format_version: PROFILE_FORMAT_VERSION,
+            captured_at: Utc::now(),
+            hostname: "storage-07".into(),
+            operator_version: crate::VERSION.into(),
+            directories: ["sys/class/nvme/nvme0".to_string()].into_iter().collect(),
+            files: files
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+            commands: vec![RecordedCommand {
+                command: "nvme smart-log /dev/nvme0 -o json".into(),
+                exit_code: 0,
+                stdout: serde_json::json!({"temperature": 318, "percent_used": 12}),
+                stderr: String::new(),
+            }],
+        }
+    }
+
+    #[tokio::test]
+    async fn test_replay_profile() {
+        let dir = tempfile::tempdir().unwrap();
+        let scanner = sample_profile().replay(dir.path()).unwrap();
+
+        let info = scanner.discover_local().await.unwrap();
+        assert_eq!(info.hostname, "storage-07");
+        assert_eq!(info.cpu_count, 3);
+        assert_eq!(info.memory_bytes, 263958240 * 1024);
+        assert_eq!(info.drives.len(), 2);
+
+        let nvme = info.drives.iter().find(|d| d.device_id == "nvme0n1").unwrap();
+        assert_eq!(nvme.smart_data.as_ref().unwrap().temperature_celsius, 45);
+
+        // No recording for smartctl: replayed as a missing binary
+        let sda = info.drives.iter().find(|d| d.device_id == "sda").unwrap();
+        assert!(sda.smart_data.is_none());
+    }
+
+    #[test]
+    fn test_save_and_load_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let profile = sample_profile();
+
+        for name in ["profile.json", "profile.json.zst"] {
+            let path = dir.path().join(name);
+            profile.save(&path).unwrap();
+            let loaded = HardwareProfile::load(&path).unwrap();
+            assert_eq!(loaded.files, profile.files);
+            assert_eq!(loaded.commands.len(), 1);
+        }
+    }
+
+    #[test]
+    fn test_rejects_escaping_paths_and_newer_versions() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let mut profile = sample_profile();
+        profile.files.insert("../outside".into(), "x".into());
+        assert!(profile.materialize(dir.path()).is_err());
+
+        let mut profile = sample_profile();
+        profile.format_version = PROFILE_FORMAT_VERSION + 1;
+        let path = dir.path().join("future.json");
+        profile.save(&path).unwrap();
+        assert!(HardwareProfile::load(&path).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_capture_fake_sysfs() {
+        let dir = tempfile::tempdir().unwrap();
+        let sys = dir.path().join("sys");
+        fs::create_dir_all(sys.join("class/nvme/nvme0")).unwrap();
+        fs::create_dir_all(sys.join("class/block/sda/queue")).unwrap();
+        fs::write(sys.join("class/block/sda/size"), "100\n").unwrap();
+        fs::write(sys.join("class/block/sda/queue/rotational"), "0\n").unwrap();
+
+        let profile = HardwareProfile::capture(ScannerConfig {
+            sysfs_path: sys,
+            proc_path: dir.path().join("proc"),
+            hostname_path: dir.path().join("hostname"),
+            dev_path: dir.path().join("dev"),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+
+        assert!(profile.directories.contains("sys/class/nvme/nvme0"));
+        assert_eq!(profile.files["sys/class/block/sda/size"], "100\n");
+        assert_eq!(profile.files["sys/class/block/sda/queue/rotational"], "0\n");
+        assert!(!profile.files.contains_key("sys/class/block/sda/device/model"));
+    }
+}
@@ -1,13 +1,18 @@
 //! SAS/SATA Discovery
 //!
 //! Provides discovery and SMART data retrieval for SAS and SATA devices
-//! using smartctl from smartmontools.
-
+//! using smartctl from smartmontools, invoked through a `CommandRunner`.
+//! smartctl's JSON is also parsed into the interface-specific health model
+//! for ATA, SCSI and NVMe devices.
+
+use super::command::CommandRunnerRef;
+use crate::domain::health::{
+    AtaAttribute, AtaHealth, DriveHealth, NvmeHealth, ScsiErrorCounters, ScsiHealth,
+};
 use crate::domain::ports::SmartData;
 use crate::error::{Error, Result};
 use serde::{Deserialize, Serialize};
-use std::process::Command;
-use tracing::{debug, warn};
+use tracing::debug;
 
 // =============================================================================
 // SATA/SAS Device Info
@@ -44,15 +49,24 @@ pub struct SataDeviceInfo {
 // SATA/SAS Discovery
 // =============================================================================
 
-/// SATA/SAS-specific discovery operations
-pub struct SasSataDiscovery;
+/// SATA/SAS-specific discovery operations backed by smartctl
+#[derive(Clone)]
+pub struct SasSataDiscovery {
+    runner: CommandRunnerRef,
+}
 
 impl SasSataDiscovery {
+    /// Create SATA/SAS discovery that runs smartctl through `runner`
+    pub fn new(runner: CommandRunnerRef) -> Self {
+        Self { runner }
+    }
+
     /// Get device info using smartctl
-    pub async fn get_device_info(device: &str) -> Result<SataDeviceInfo> {
-        let output = Command::new("smartctl")
-            .args(["-i", "-j", device])
-            .output()
+    pub async fn get_device_info(&self, device: &str) -> Result<SataDeviceInfo> {
+        let output = self
+            .runner
+            .run("smartctl", &["-i", "-j", device])
+            .await
             .map_err(|e| Error::HardwareDiscovery(format!(
                 "smartctl failed for {}: {}",
                 device, e
@@ -108,7 +122,7 @@ impl SasSataDiscovery {
             .to_string();
 
         // Get SMART data
-        let smart = Self::get_smart_data(device).await.ok();
+        let smart = self.get_smart_data(device).await.ok();
 
         Ok(SataDeviceInfo {
             device_path: device.to_string(),
@@ -126,16 +140,20 @@ impl SasSataDiscovery {
     }
 
     /// Get SMART data using smartctl
-    pub async fn get_smart_data(device: &str) -> Result<SmartData> {
-        let output = Command::new("smartctl")
-            .args(["-A", "-H", "-j", device])
-            .output()
-            .map_err(|e| Error::SmartUnavailable {
-                device: device.to_string(),
-            })?;
+    pub async fn get_smart_data(&self, device: &str) -> Result<SmartData> {
+        let output = match self.runner.run("smartctl", &["-A", "-H", "-j", device]).await {
+            Ok(output) => output,
+            Err(e @ Error::CommandTimeout { .. }) => return Err(e),
+            Err(e) => {
+                debug!("smartctl failed for {}: {}", device, e);
+                return Err(Error::SmartUnavailable {
+                    device: device.to_string(),
+                });
+            }
+        };
 
         let json: serde_json::Value = serde_json::from_slice(&output.stdout)
-            .map_err(|e| Error::SmartUnavailable {
+            .map_err(|_| Error::SmartUnavailable {
                 device: device.to_string(),
             })?;
 
@@ -183,31 +201,54 @@ impl SasSataDiscovery {
         })
     }
 
+    /// Get interface-specific health from `smartctl -a`
+    pub async fn get_health(&self, device: &str) -> Result<DriveHealth> {
+        let output = match self.runner.run("smartctl", &["-a", "-j", device]).await {
+            Ok(output) => output,
+            Err(e @ Error::CommandTimeout { .. }) => return Err(e),
+            Err(e) => {
+                debug!("smartctl failed for {}: {}", device, e);
+                return Err(Error::SmartUnavailable {
+                    device: device.to_string(),
+                });
+            }
+        };
+
+        // Non-zero exits flag warnings (e.g. failing attributes); the JSON
+        // body is still complete
+        let json: serde_json::Value = serde_json::from_slice(&output.stdout)
+            .map_err(|_| Error::SmartUnavailable {
+                device: device.to_string(),
+            })?;
+
+        parse_smartctl_health(&json).ok_or_else(|| Error::SmartUnavailable {
+            device: device.to_string(),
+        })
+    }
+
     /// Perform a SMART self-test
-    pub async fn run_self_test(device: &str, test_type: &str) -> Result<()> {
-        let test_arg = match test_type {
-            "short" => "-t short",
-            "long" => "-t long",
-            "conveyance" => "-t conveyance",
-            "offline" => "-t offline",
+    pub async fn run_self_test(&self, device: &str, test_type: &str) -> Result<()> {
+        match test_type {
+            "short" | "long" | "conveyance" | "offline" => {}
             _ => return Err(Error::HardwareDiscovery(
                 format!("Unknown test type: {}", test_type)
             )),
-        };
+        }
 
-        let output = Command::new("smartctl")
-            .args([test_arg, device])
-            .output()
+        let output = self
+            .runner
+            .run("smartctl", &["-t", test_type, device])
+            .await
             .map_err(|e| Error::HardwareDiscovery(format!(
                 "Failed to start SMART test on {}: {}",
                 device, e
             )))?;
 
-        if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
+        if !output.is_success() {
             return Err(Error::HardwareDiscovery(format!(
                 "SMART test failed on {}: {}",
-                device, stderr
+                device,
+                output.stderr_lossy()
             )));
         }
 
@@ -215,19 +256,20 @@ impl SasSataDiscovery {
     }
 
     /// Check if smartmontools is available
-    pub fn is_smartctl_available() -> bool {
-        Command::new("smartctl")
-            .arg("--version")
-            .output()
-            .map(|o| o.status.success())
+    pub async fn is_smartctl_available(&self) -> bool {
+        self.runner
+            .run("smartctl", &["--version"])
+            .await
+            .map(|o| o.is_success())
             .unwrap_or(false)
     }
 
     /// List all SATA/SAS devices
-    pub async fn list_devices() -> Result<Vec<String>> {
-        let output = Command::new("smartctl")
-            .args(["--scan", "-j"])
-            .output()
+    pub async fn list_devices(&self) -> Result<Vec<String>> {
+        let output = self
+            .runner
+            .run("smartctl", &["--scan", "-j"])
+            .await
             .map_err(|e| Error::HardwareDiscovery(format!(
                 "smartctl scan failed: {}",
                 e
@@ -264,6 +306,116 @@ impl SasSataDiscovery {
     }
 }
 
+/// Parse smartctl JSON (`-a -j`) into the health model for the device's
+/// protocol. Returns `None` when the output carries no health data.
+pub fn parse_smartctl_health(json: &serde_json::Value) -> Option<DriveHealth> {
+    let protocol = json["device"]["protocol"].as_str().unwrap_or_default();
+
+    if protocol.eq_ignore_ascii_case("nvme") || json["nvme_smart_health_information_log"].is_object() {
+        return parse_nvme_log(&json["nvme_smart_health_information_log"]).map(DriveHealth::Nvme);
+    }
+    if protocol.eq_ignore_ascii_case("scsi") || json["scsi_error_counter_log"].is_object() {
+        return parse_scsi_health(json).map(DriveHealth::Scsi);
+    }
+    if protocol.eq_ignore_ascii_case("ata") || json["ata_smart_attributes"].is_object() {
+        return parse_ata_health(json).map(DriveHealth::Ata);
+    }
+    None
+}
+
+fn parse_ata_health(json: &serde_json::Value) -> Option<AtaHealth> {
+    let smart_passed = json["smart_status"]["passed"].as_bool()?;
+
+    let attributes: Vec<AtaAttribute> = json["ata_smart_attributes"]["table"]
+        .as_array()
+        .map(|table| {
+            table
+                .iter()
+                .filter_map(|a| {
+                    Some(AtaAttribute {
+                        id: a["id"].as_u64()? as u8,
+                        name: a["name"].as_str().unwrap_or_default().to_string(),
+                        value: a["value"].as_u64().unwrap_or(0) as u8,
+                        worst: a["worst"].as_u64().unwrap_or(0) as u8,
+                        threshold: a["thresh"].as_u64().unwrap_or(0) as u8,
+                        raw: a["raw"]["value"].as_u64().unwrap_or(0),
+                        prefailure: a["flags"]["prefailure"].as_bool().unwrap_or(false),
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let raw = |id: u8| {
+        attributes
+            .iter()
+            .find(|a| a.id == id)
+            .map(|a| a.raw)
+            .unwrap_or(0)
+    };
+
+    // Vendors report remaining life as the normalized value of one of these
+    let wear_remaining_percent = attributes
+        .iter()
+        .find(|a| matches!(a.id, 177 | 231 | 233))
+        .map(|a| a.value.min(100));
+
+    Some(AtaHealth {
+        smart_passed,
+        reallocated_sectors: raw(5),
+        pending_sectors: raw(197),
+        offline_uncorrectable: raw(198),
+        reported_uncorrectable: raw(187),
+        crc_errors: raw(199),
+        wear_remaining_percent,
+        attributes,
+    })
+}
+
+fn parse_scsi_health(json: &serde_json::Value) -> Option<ScsiHealth> {
+    let smart_passed = json["smart_status"]["passed"].as_bool()?;
+
+    let counters = |page: &str| {
+        let log = &json["scsi_error_counter_log"][page];
+        ScsiErrorCounters {
+            corrected: log["total_errors_corrected"].as_u64().unwrap_or(0),
+            uncorrected: log["total_uncorrected_errors"].as_u64().unwrap_or(0),
+        }
+    };
+    let ie = &json["smart_status"]["scsi"];
+
+    Some(ScsiHealth {
+        smart_passed,
+        grown_defects: json["scsi_grown_defect_list"].as_u64().unwrap_or(0),
+        write_errors: counters("write"),
+        read_errors: counters("read"),
+        verify_errors: counters("verify"),
+        temperature_celsius: json["temperature"]["current"].as_i64().map(|t| t as i32),
+        trip_temperature_celsius: json["temperature"]["drive_trip"].as_i64().map(|t| t as i32),
+        ie_asc: ie["asc"].as_u64().unwrap_or(0) as u8,
+        ie_ascq: ie["ascq"].as_u64().unwrap_or(0) as u8,
+        endurance_used_percent: json["scsi_percentage_used_endurance_indicator"]
+            .as_u64()
+            .map(|p| p.min(255) as u8),
+    })
+}
+
+fn parse_nvme_log(log: &serde_json::Value) -> Option<NvmeHealth> {
+    if !log.is_object() {
+        return None;
+    }
+    Some(NvmeHealth {
+        critical_warning: log["critical_warning"].as_u64().unwrap_or(0) as u8,
+        temperature_celsius: log["temperature"].as_i64().unwrap_or(0) as i32,
+        available_spare: log["available_spare"].as_u64().unwrap_or(0) as u8,
+        spare_threshold: log["available_spare_threshold"].as_u64().unwrap_or(0) as u8,
+        percentage_used: log["percentage_used"].as_u64().unwrap_or(0).min(255) as u8,
+        media_errors: log["media_errors"].as_u64().unwrap_or(0),
+        error_log_entries: log["num_err_log_entries"].as_u64().unwrap_or(0),
+        unsafe_shutdowns: log["unsafe_shutdowns"].as_u64().unwrap_or(0),
+    })
+}
+
 /// Determine drive interface from sysfs
 pub fn detect_interface(sysfs_path: &std::path::Path) -> String {
     // Check for SCSI transport
@@ -316,4 +468,65 @@ mod tests {
         assert_eq!(SasSataDiscovery::find_smart_attr(&attrs, &[999]), None);
         assert_eq!(SasSataDiscovery::find_smart_attr(&attrs, &[190, 194]), Some(42));
     }
+
+    fn fixture(name: &str) -> serde_json::Value {
+        let raw = match name {
+            "ata" => include_str!("testdata/smartctl/ata-hdd.json"),
+            "scsi" => include_str!("testdata/smartctl/scsi-sas.json"),
+            _ => include_str!("testdata/smartctl/nvme.json"),
+        };
+        serde_json::from_str(raw).unwrap()
+    }
+
+    #[test]
+    fn test_parse_ata_health() {
+        let Some(DriveHealth::Ata(h)) = parse_smartctl_health(&fixture("ata")) else {
+            panic!("expected ATA health");
+        };
+        assert!(h.smart_passed);
+        assert_eq!(h.reallocated_sectors, 24);
+        assert_eq!(h.pending_sectors, 3);
+        assert_eq!(h.offline_uncorrectable, 0);
+        assert_eq!(h.wear_remaining_percent, None);
+        assert_eq!(h.attributes.len(), 8);
+        assert!(h.attributes.iter().all(|a| !a.is_failing()));
+
+        let assessment = DriveHealth::Ata(h).assess();
+        assert_eq!(assessment.score, 65);
+    }
+
+    #[test]
+    fn test_parse_scsi_health() {
+        let Some(DriveHealth::Scsi(h)) = parse_smartctl_health(&fixture("scsi")) else {
+            panic!("expected SCSI health");
+        };
+        assert_eq!(h.grown_defects, 12);
+        assert_eq!(h.read_errors.corrected, 14);
+        assert_eq!(h.verify_errors.uncorrected, 1);
+        assert_eq!(h.total_uncorrected(), 1);
+        assert_eq!(h.temperature_celsius, Some(38));
+        assert_eq!(h.trip_temperature_celsius, Some(85));
+        assert_eq!(h.ie_asc, 0);
+
+        // 12 grown defects (-20) and one uncorrected verify error (-25)
+        assert_eq!(DriveHealth::Scsi(h).assess().score, 55);
+    }
+
+    #[test]
+    fn test_parse_nvme_health() {
+        let Some(DriveHealth::Nvme(h)) = parse_smartctl_health(&fixture("nvme")) else {
+            panic!("expected NVMe health");
+        };
+        assert_eq!(h.temperature_celsius, 41);
+        assert_eq!(h.percentage_used, 84);
+        assert_eq!(h.unsafe_shutdowns, 17);
+        assert_eq!(h.error_log_entries, 3);
+        assert_eq!(DriveHealth::Nvme(h).assess().score, 90);
+    }
+
+    #[test]
+    fn test_parse_health_without_smart() {
+        let json = serde_json::json!({"device": {"protocol": "ATA"}, "smartctl": {"exit_status": 4}});
+        assert!(parse_smartctl_health(&json).is_none());
+    }
 }
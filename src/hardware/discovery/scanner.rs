@@ -1,13 +1,25 @@
 //! Block Device Scanner
 //!
 //! Enumerates block devices from sysfs and determines their type
-//! (NVMe, SSD, HDD) for further classification.
-
-use crate::domain::ports::{DriveInfo, DriveType, NodeHardwareInfo, NvmeNamespaceInfo, SmartData};
+//! (NVMe, SSD, HDD) for further classification. SMART and ZNS details come
+//! from nvme-cli/smartctl, run through the scanner's `CommandRunner`.
+
+use super::command::{CommandRunnerRef, SystemCommandRunner, DEFAULT_COMMAND_TIMEOUT};
+use super::nvme::NvmeDiscovery;
+use super::nvme_admin::{IoctlNvmeAdmin, NvmeAdminRef};
+use super::sas_sata::SasSataDiscovery;
+use super::usage::UsageProbe;
+use crate::domain::health::DriveHealth;
+use crate::domain::ports::{
+    DeviceUsage, DriveInfo, DriveLocation, DriveType, HardwareDiscoverer, NodeHardwareInfo, NvmeNamespaceInfo, SmartData,
+};
 use crate::error::{Error, Result};
+use async_trait::async_trait;
 use chrono::Utc;
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
 use tracing::{debug, info, warn};
 
 // =============================================================================
@@ -34,6 +46,20 @@ pub struct ScannerConfig {
     pub min_size_bytes: u64,
     /// Path to sysfs (for testing)
     pub sysfs_path: PathBuf,
+    /// Path to procfs (for testing)
+    pub proc_path: PathBuf,
+    /// File holding the node hostname
+    pub hostname_path: PathBuf,
+    /// Directory holding device nodes, read for on-disk signatures
+    pub dev_path: PathBuf,
+    /// Look for partitions, mounts, holders and signatures on each drive
+    pub check_in_use: bool,
+    /// Query SMART data for each discovered drive
+    pub collect_smart: bool,
+    /// Timeout for each nvme-cli/smartctl invocation or admin command
+    pub command_timeout: Duration,
+    /// Query NVMe controllers through kernel passthrough before nvme-cli
+    pub nvme_passthrough: bool,
 }
 
 impl Default for ScannerConfig {
@@ -44,6 +70,13 @@ impl Default for ScannerConfig {
             include_dm: false,
             min_size_bytes: 1_000_000_000, // 1GB minimum
             sysfs_path: PathBuf::from("/sys"),
+            proc_path: PathBuf::from("/proc"),
+            hostname_path: PathBuf::from("/etc/hostname"),
+            dev_path: PathBuf::from("/dev"),
+            check_in_use: true,
+            collect_smart: true,
+            command_timeout: DEFAULT_COMMAND_TIMEOUT,
+            nvme_passthrough: true,
         }
     }
 }
@@ -55,12 +88,41 @@ impl Default for ScannerConfig {
 /// Scans for storage hardware on Linux systems
 pub struct HardwareScanner {
     config: ScannerConfig,
+    runner: CommandRunnerRef,
+    nvme: NvmeDiscovery,
+    sata: SasSataDiscovery,
 }
 
 impl HardwareScanner {
-    /// Create a new hardware scanner
+    /// Create a new hardware scanner that talks to real devices and spawns
+    /// real processes
     pub fn new(config: ScannerConfig) -> Self {
-        Self { config }
+        let runner = Arc::new(SystemCommandRunner::new(config.command_timeout));
+        let admin = Arc::new(IoctlNvmeAdmin::new(config.command_timeout));
+        let passthrough = config.nvme_passthrough;
+
+        let scanner = Self::with_runner(config, runner);
+        if passthrough {
+            scanner.with_nvme_admin(admin)
+        } else {
+            scanner
+        }
+    }
+
+    /// Create a scanner that runs external commands through `runner`
+    pub fn with_runner(config: ScannerConfig, runner: CommandRunnerRef) -> Self {
+        Self {
+            config,
+            nvme: NvmeDiscovery::new(runner.clone()),
+            sata: SasSataDiscovery::new(runner.clone()),
+            runner,
+        }
+    }
+
+    /// Send NVMe admin commands through `admin` before trying nvme-cli
+    pub fn with_nvme_admin(mut self, admin: NvmeAdminRef) -> Self {
+        self.nvme = self.nvme.with_admin(admin);
+        self
     }
 
     /// Create a scanner with default configuration
@@ -70,7 +132,7 @@ impl HardwareScanner {
 
     /// Discover all storage hardware on the local node
     pub async fn discover(&self) -> Result<NodeHardwareInfo> {
-        let hostname = self.get_hostname()?;
+        let hostname = self.get_hostname().await?;
         let node_id = hostname.clone();
 
         info!("Starting hardware discovery on {}", hostname);
@@ -99,6 +161,37 @@ impl HardwareScanner {
             }
         }
 
+        // Discover dm-multipath devices
+        match self.discover_multipath_devices().await {
+            Ok(mpath_drives) => {
+                if !mpath_drives.is_empty() {
+                    info!("Found {} multipath devices", mpath_drives.len());
+                }
+                drives.extend(mpath_drives);
+            }
+            Err(e) => {
+                warn!("Multipath discovery failed: {}", e);
+            }
+        }
+
+        // Each physical drive once, however many paths lead to it
+        let path_count = drives.len();
+        let mut drives = merge_paths(drives);
+        if drives.len() < path_count {
+            info!(
+                "Collapsed {} device paths into {} drives",
+                path_count,
+                drives.len()
+            );
+        }
+
+        if self.config.collect_smart {
+            for drive in &mut drives {
+                drive.smart_data = self.query_smart(drive).await;
+                drive.health = self.query_health(drive).await;
+            }
+        }
+
         // Get system info
         let (memory_bytes, cpu_count) = self.get_system_info();
 
@@ -112,6 +205,41 @@ impl HardwareScanner {
         })
     }
 
+    /// Scan a single block device by name (e.g. `sdb`, `nvme1n1`), applying
+    /// the same filters as a full discovery. Returns `None` when the device
+    /// is gone or excluded.
+    pub async fn scan_device(&self, name: &str) -> Result<Option<DriveInfo>> {
+        let sysfs_path = match self.block_sysfs_path(name) {
+            Ok(path) => path,
+            Err(Error::DeviceNotFound { .. }) => return Ok(None),
+            Err(e) => return Err(e),
+        };
+        let mut drive = if let Some(slaves) = self.multipath_slaves(&sysfs_path) {
+            self.scan_multipath_device(&sysfs_path, &slaves).await?
+        } else if !self.should_include_device(name) || self.is_partition(&sysfs_path) {
+            return Ok(None);
+        } else if name.starts_with("nvme") {
+            self.scan_nvme_namespace(&sysfs_path).await?
+        } else {
+            self.scan_block_device(&sysfs_path).await?
+        };
+        if drive.capacity_bytes < self.config.min_size_bytes {
+            return Ok(None);
+        }
+
+        if self.config.collect_smart {
+            drive.smart_data = self.query_smart(&drive).await;
+            drive.health = self.query_health(&drive).await;
+        }
+
+        Ok(Some(drive))
+    }
+
+    /// Path to sysfs used by this scanner
+    pub fn sysfs_path(&self) -> &Path {
+        &self.config.sysfs_path
+    }
+
     /// Discover NVMe devices
     async fn discover_nvme_devices(&self) -> Result<Vec<DriveInfo>> {
         let nvme_path = self.config.sysfs_path.join("class/nvme");
@@ -136,6 +264,12 @@ impl HardwareScanner {
                 let ns_entry = ns_entry?;
                 let ns_name = ns_entry.file_name().to_string_lossy().to_string();
 
+                // Per-controller paths of a natively multipathed namespace
+                // (nvme0c0n1) are hidden behind nvme0n1
+                if self.is_hidden(&ns_entry.path()) {
+                    continue;
+                }
+
                 // Match nvme0n1, nvme0n2, etc.
                 if ns_name.starts_with(&controller_name) && ns_name.contains('n') {
                     let parts: Vec<&str> = ns_name.split('n').collect();
@@ -183,11 +317,15 @@ impl HardwareScanner {
         let block_size: u32 = block_size_str.trim().parse().unwrap_or(512);
 
         // Check for ZNS support
-        let zns_supported = self.check_zns_support(sysfs_path);
+        let zns_supported = self.is_zoned(sysfs_path);
 
         // Parse namespace ID from device name (nvme0n1 -> nsid 1)
         let nsid = self.parse_nsid(&device_name).unwrap_or(1);
 
+        let in_use = self.check_in_use(&device_name);
+        let wwid = self.read_wwid(sysfs_path);
+        let location = self.read_location(sysfs_path);
+
         let namespaces = vec![NvmeNamespaceInfo {
             nsid,
             capacity_bytes,
@@ -197,7 +335,7 @@ impl HardwareScanner {
 
         Ok(DriveInfo {
             device_path,
-            device_id: device_name,
+            device_id: device_name.clone(),
             drive_type: DriveType::Nvme,
             model: model.trim().to_string(),
             serial: serial.trim().to_string(),
@@ -207,6 +345,11 @@ impl HardwareScanner {
             zns_supported,
             nvme_namespaces: namespaces,
             smart_data: None, // Filled by separate SMART query
+            health: None,
+            in_use,
+            wwid,
+            paths: vec![device_name],
+            location,
         })
     }
 
@@ -235,6 +378,11 @@ impl HardwareScanner {
                 continue;
             }
 
+            // Skip multipath devices (handled separately)
+            if self.multipath_slaves(&entry.path()).is_some() {
+                continue;
+            }
+
             // Skip partitions
             if self.is_partition(&entry.path()) {
                 continue;
@@ -286,10 +434,13 @@ impl HardwareScanner {
 
         // Determine drive type (SSD vs HDD)
         let drive_type = self.detect_drive_type(sysfs_path);
+        let in_use = self.check_in_use(&device_name);
+        let wwid = self.read_wwid(sysfs_path);
+        let location = self.read_location(sysfs_path);
 
         Ok(DriveInfo {
             device_path,
-            device_id: device_name,
+            device_id: device_name.clone(),
             drive_type,
             model: model.trim().to_string(),
             serial: serial.trim().to_string(),
@@ -299,9 +450,176 @@ impl HardwareScanner {
             zns_supported: false,
             nvme_namespaces: Vec::new(),
             smart_data: None,
+            health: None,
+            in_use,
+            wwid,
+            paths: vec![device_name],
+            location,
         })
     }
 
+    /// Discover dm-multipath devices, which are kept even when `include_dm`
+    /// is off because they replace their individual paths
+    async fn discover_multipath_devices(&self) -> Result<Vec<DriveInfo>> {
+        let block_path = self.config.sysfs_path.join("class/block");
+        if !block_path.exists() {
+            return Ok(Vec::new());
+        }
+
+        let mut drives = Vec::new();
+        for entry in fs::read_dir(&block_path)? {
+            let entry = entry?;
+            if let Some(slaves) = self.multipath_slaves(&entry.path()) {
+                match self.scan_multipath_device(&entry.path(), &slaves).await {
+                    Ok(drive) if drive.capacity_bytes >= self.config.min_size_bytes => {
+                        drives.push(drive)
+                    }
+                    Ok(_) => {}
+                    Err(e) => debug!("Skipping {:?}: {}", entry.file_name(), e),
+                }
+            }
+        }
+        Ok(drives)
+    }
+
+    /// Paths under a dm-multipath device, or `None` for any other device
+    fn multipath_slaves(&self, sysfs_path: &Path) -> Option<Vec<String>> {
+        let uuid = self.read_sysfs_attr(sysfs_path, "dm/uuid").ok()?;
+        if !uuid.trim().starts_with("mpath-") {
+            return None;
+        }
+        let mut slaves: Vec<String> = fs::read_dir(sysfs_path.join("slaves"))
+            .ok()?
+            .flatten()
+            .map(|e| e.file_name().to_string_lossy().to_string())
+            .collect();
+        slaves.sort();
+        (!slaves.is_empty()).then_some(slaves)
+    }
+
+    /// Scan a dm-multipath device, taking drive details from its first path
+    async fn scan_multipath_device(&self, sysfs_path: &Path, slaves: &[String]) -> Result<DriveInfo> {
+        let device_name = sysfs_path
+            .file_name()
+            .ok_or_else(|| Error::HardwareDiscovery("Invalid sysfs path".into()))?
+            .to_string_lossy()
+            .to_string();
+
+        let first = self.config.sysfs_path.join("class/block").join(&slaves[0]);
+        let mut drive = if slaves[0].starts_with("nvme") {
+            self.scan_nvme_namespace(&first).await?
+        } else {
+            self.scan_block_device(&first).await?
+        };
+
+        let size_str = self.read_sysfs_attr(sysfs_path, "size")?;
+        let sectors: u64 = size_str.trim().parse().map_err(|_| {
+            Error::HardwareDiscovery(format!("Invalid size: {}", size_str))
+        })?;
+        drive.capacity_bytes = sectors * 512;
+
+        drive.device_path = match self.read_sysfs_attr(sysfs_path, "dm/name") {
+            Ok(alias) => format!("/dev/mapper/{}", alias.trim()),
+            Err(_) => format!("/dev/{}", device_name),
+        };
+        if drive.wwid.is_none() {
+            let uuid = self.read_sysfs_attr(sysfs_path, "dm/uuid")?;
+            drive.wwid = normalize_wwid(uuid.trim().trim_start_matches("mpath-"));
+        }
+        drive.in_use = self.check_in_use(&device_name);
+        drive.paths = std::iter::once(device_name.clone())
+            .chain(slaves.iter().cloned())
+            .collect();
+        drive.device_id = device_name;
+        Ok(drive)
+    }
+
+    /// World-wide identifier (SCSI WWN, NVMe EUI-64/NGUID) from sysfs
+    fn read_wwid(&self, sysfs_path: &Path) -> Option<String> {
+        ["wwid", "device/wwid", "nguid", "eui"]
+            .iter()
+            .filter_map(|attr| self.read_sysfs_attr(sysfs_path, attr).ok())
+            .find_map(|value| normalize_wwid(&value))
+    }
+
+    /// Resolve where a drive sits: the SES enclosure bay for SAS/SATA, the
+    /// PCIe address, slot and NUMA node for NVMe
+    fn read_location(&self, sysfs_path: &Path) -> DriveLocation {
+        let mut location = DriveLocation::default();
+
+        // SES links each bay to the SCSI device as enclosure_device:<bay>
+        if let Ok(entries) = fs::read_dir(sysfs_path.join("device")) {
+            for entry in entries.flatten() {
+                let name = entry.file_name().to_string_lossy().to_string();
+                let Some(bay) = name.strip_prefix("enclosure_device:") else {
+                    continue;
+                };
+                location.slot = Some(
+                    self.read_sysfs_attr(&entry.path(), "slot")
+                        .map(|s| s.trim().to_string())
+                        .unwrap_or_else(|_| bay.to_string()),
+                );
+                // The link points into /sys/class/enclosure/<enclosure>/<bay>;
+                // replayed profiles keep the bay but not the link
+                let is_link = entry.file_type().is_ok_and(|t| t.is_symlink());
+                if let Some(enclosure) = fs::canonicalize(entry.path())
+                    .ok()
+                    .filter(|_| is_link)
+                    .and_then(|p| p.parent().map(Path::to_path_buf))
+                {
+                    location.enclosure = self
+                        .read_sysfs_attr(&enclosure, "id")
+                        .ok()
+                        .map(|id| id.trim().to_string())
+                        .filter(|id| !id.is_empty())
+                        .or_else(|| {
+                            enclosure.file_name().map(|n| n.to_string_lossy().to_string())
+                        });
+                }
+                break;
+            }
+        }
+
+        // An NVMe namespace's device is its controller, whose device is the
+        // PCIe function
+        location.pci_address = self
+            .read_sysfs_attr(sysfs_path, "device/address")
+            .ok()
+            .map(|a| a.trim().to_string())
+            .filter(|a| !a.is_empty());
+        location.numa_node = ["device/numa_node", "device/device/numa_node"]
+            .iter()
+            .filter_map(|attr| self.read_sysfs_attr(sysfs_path, attr).ok())
+            .find_map(|node| node.trim().parse::<u32>().ok());
+
+        if let (None, Some(address)) = (&location.slot, &location.pci_address) {
+            location.slot = self.pci_slot(address);
+        }
+
+        location
+    }
+
+    /// Physical PCIe slot holding a device, from /sys/bus/pci/slots
+    fn pci_slot(&self, address: &str) -> Option<String> {
+        // Slots are addressed without the function number
+        let device = address.rsplit_once('.').map_or(address, |(dev, _)| dev);
+        fs::read_dir(self.config.sysfs_path.join("bus/pci/slots"))
+            .ok()?
+            .flatten()
+            .find(|slot| {
+                self.read_sysfs_attr(&slot.path(), "address")
+                    .is_ok_and(|a| a.trim() == device)
+            })
+            .map(|slot| slot.file_name().to_string_lossy().to_string())
+    }
+
+    /// Check if the kernel hides a device (per-controller NVMe paths)
+    fn is_hidden(&self, sysfs_path: &Path) -> bool {
+        self.read_sysfs_attr(sysfs_path, "hidden")
+            .map(|h| h.trim() == "1")
+            .unwrap_or(false)
+    }
+
     /// Detect whether a device is SSD or HDD
     fn detect_drive_type(&self, sysfs_path: &Path) -> DriveType {
         // Check rotational flag
@@ -316,8 +634,8 @@ impl HardwareScanner {
         DriveType::Unknown
     }
 
-    /// Check if ZNS is supported
-    fn check_zns_support(&self, sysfs_path: &Path) -> bool {
+    /// Check if the block layer reports the device as zoned
+    fn is_zoned(&self, sysfs_path: &Path) -> bool {
         // Check for zoned model
         if let Ok(zoned) = self.read_sysfs_attr(sysfs_path, "queue/zoned") {
             return zoned.trim() == "host-managed" || zoned.trim() == "host-aware";
@@ -385,21 +703,93 @@ impl HardwareScanner {
         })
     }
 
+    /// Existing uses of a device that make it unsafe to allocate
+    fn check_in_use(&self, name: &str) -> Vec<DeviceUsage> {
+        if !self.config.check_in_use {
+            return Vec::new();
+        }
+        let usage = UsageProbe::new(
+            &self.config.sysfs_path,
+            &self.config.proc_path,
+            &self.config.dev_path,
+        )
+        .check(name);
+        if !usage.is_empty() {
+            debug!("{} is in use: {:?}", name, usage);
+        }
+        usage
+    }
+
+    /// Device to query for SMART data; device-mapper devices do not pass
+    /// SMART commands through, so a multipathed drive is asked via a path
+    fn smart_device(drive: &DriveInfo) -> String {
+        if drive.device_id.starts_with("dm-") {
+            if let Some(path) = drive.paths.iter().find(|p| !p.starts_with("dm-")) {
+                return format!("/dev/{}", path);
+            }
+        }
+        drive.device_path.clone()
+    }
+
+    /// Query SMART data for a drive, logging rather than failing
+    async fn query_smart(&self, drive: &DriveInfo) -> Option<SmartData> {
+        let device = Self::smart_device(drive);
+        let result = match drive.drive_type {
+            DriveType::Nvme => self.nvme.get_smart_data(&device).await,
+            _ => self.sata.get_smart_data(&device).await,
+        };
+        match result {
+            Ok(smart) => Some(smart),
+            Err(e) => {
+                debug!("No SMART data for {}: {}", drive.device_path, e);
+                None
+            }
+        }
+    }
+
+    /// Query interface-specific health for a drive, logging rather than failing
+    async fn query_health(&self, drive: &DriveInfo) -> Option<DriveHealth> {
+        let device = Self::smart_device(drive);
+        let result = match drive.drive_type {
+            DriveType::Nvme => self
+                .nvme
+                .get_health(&device)
+                .await
+                .map(DriveHealth::Nvme),
+            _ => self.sata.get_health(&device).await,
+        };
+        match result {
+            Ok(health) => Some(health),
+            Err(e) => {
+                debug!("No health data for {}: {}", drive.device_path, e);
+                None
+            }
+        }
+    }
+
+    /// Resolve `/dev/<name>` (or a bare name) to its sysfs block directory
+    fn block_sysfs_path(&self, device_path: &str) -> Result<PathBuf> {
+        let name = device_path.strip_prefix("/dev/").unwrap_or(device_path);
+        let path = self.config.sysfs_path.join("class/block").join(name);
+        if name.is_empty() || name.contains('/') || !path.exists() {
+            return Err(Error::DeviceNotFound {
+                device: device_path.to_string(),
+            });
+        }
+        Ok(path)
+    }
+
     /// Get the system hostname
-    fn get_hostname(&self) -> Result<String> {
+    async fn get_hostname(&self) -> Result<String> {
         // Try /etc/hostname first
-        if let Ok(hostname) = fs::read_to_string("/etc/hostname") {
+        if let Ok(hostname) = fs::read_to_string(&self.config.hostname_path) {
             return Ok(hostname.trim().to_string());
         }
 
         // Fall back to hostname command
-        #[cfg(unix)]
-        {
-            use std::process::Command;
-            if let Ok(output) = Command::new("hostname").output() {
-                if output.status.success() {
-                    return Ok(String::from_utf8_lossy(&output.stdout).trim().to_string());
-                }
+        if let Ok(output) = self.runner.run("hostname", &[]).await {
+            if output.is_success() {
+                return Ok(String::from_utf8_lossy(&output.stdout).trim().to_string());
             }
         }
 
@@ -415,7 +805,7 @@ impl HardwareScanner {
 
     /// Get total system memory from /proc/meminfo
     fn get_total_memory(&self) -> Option<u64> {
-        let meminfo = fs::read_to_string("/proc/meminfo").ok()?;
+        let meminfo = fs::read_to_string(self.config.proc_path.join("meminfo")).ok()?;
         for line in meminfo.lines() {
             if line.starts_with("MemTotal:") {
                 let parts: Vec<&str> = line.split_whitespace().collect();
@@ -430,6 +820,15 @@ impl HardwareScanner {
 
     /// Get CPU count
     fn get_cpu_count(&self) -> Option<u32> {
+        // Count processors in cpuinfo so the node total is reported even
+        // inside a CPU-limited pod
+        if let Ok(cpuinfo) = fs::read_to_string(self.config.proc_path.join("cpuinfo")) {
+            let count = cpuinfo.lines().filter(|l| l.starts_with("processor")).count();
+            if count > 0 {
+                return Some(count as u32);
+            }
+        }
+
         // Use std::thread::available_parallelism for portability
         std::thread::available_parallelism()
             .ok()
@@ -437,9 +836,154 @@ impl HardwareScanner {
     }
 }
 
+// =============================================================================
+// Multipath
+// =============================================================================
+
+/// Normalize a world-wide identifier; all-zero identifiers (unset EUI-64 or
+/// NGUID) are treated as absent
+fn normalize_wwid(value: &str) -> Option<String> {
+    let id = value.trim().to_lowercase();
+    // Skip the naa./eui./t10. designator type
+    let unset = id
+        .split_once('.')
+        .map_or(id.as_str(), |(_, rest)| rest)
+        .chars()
+        .filter(|c| c.is_ascii_hexdigit())
+        .all(|c| c == '0');
+    (!id.is_empty() && !unset).then_some(id)
+}
+
+/// Key identifying the physical drive behind a path: the WWID, else model
+/// and serial (with the namespace for NVMe)
+fn identity_key(drive: &DriveInfo) -> Option<String> {
+    if let Some(wwid) = &drive.wwid {
+        return Some(format!("wwid:{}", wwid));
+    }
+    let serial = drive.serial.trim();
+    if serial.is_empty() || serial.eq_ignore_ascii_case("unknown") {
+        return None;
+    }
+    let nsid = drive.nvme_namespaces.first().map(|ns| ns.nsid).unwrap_or(0);
+    Some(format!("serial:{}:{}:{}", drive.model, serial, nsid))
+}
+
+/// Collapse drives seen through several paths into one drive per physical
+/// device. The dm-multipath device is preferred when present, otherwise the
+/// lowest-named path; `paths` lists every path, preferred first.
+pub fn merge_paths(drives: Vec<DriveInfo>) -> Vec<DriveInfo> {
+    let mut merged: Vec<DriveInfo> = Vec::new();
+
+    for drive in drives {
+        let key = identity_key(&drive);
+        let existing = key
+            .as_ref()
+            .and_then(|key| merged.iter_mut().find(|d| identity_key(d).as_ref() == Some(key)));
+
+        let Some(existing) = existing else {
+            merged.push(drive);
+            continue;
+        };
+
+        let mut paths: Vec<String> = existing.paths.clone();
+        for path in &drive.paths {
+            if !paths.contains(path) {
+                paths.push(path.clone());
+            }
+        }
+
+        let is_mpath = |d: &DriveInfo| d.device_id.starts_with("dm-");
+        let prefer_new = match (is_mpath(&drive), is_mpath(existing)) {
+            (true, false) => true,
+            (false, true) => false,
+            _ => drive.device_id < existing.device_id,
+        };
+        debug!(
+            "{} and {} are paths to the same drive",
+            existing.device_id, drive.device_id
+        );
+        if prefer_new {
+            *existing = drive;
+        }
+
+        // Preferred path first, the rest in name order
+        paths.retain(|p| p != &existing.device_id);
+        paths.sort();
+        paths.insert(0, existing.device_id.clone());
+        existing.paths = paths;
+    }
+
+    merged
+}
+
+// =============================================================================
+// HardwareDiscoverer Implementation
+// =============================================================================
+
+#[async_trait]
+impl HardwareDiscoverer for HardwareScanner {
+    async fn discover_local(&self) -> Result<NodeHardwareInfo> {
+        self.discover().await
+    }
+
+    async fn discover_device(&self, device_path: &str) -> Result<DriveInfo> {
+        let sysfs_path = self.block_sysfs_path(device_path)?;
+
+        let mut drive = if let Some(slaves) = self.multipath_slaves(&sysfs_path) {
+            self.scan_multipath_device(&sysfs_path, &slaves).await?
+        } else if sysfs_path
+            .file_name()
+            .map(|n| n.to_string_lossy().starts_with("nvme"))
+            .unwrap_or(false)
+        {
+            self.scan_nvme_namespace(&sysfs_path).await?
+        } else {
+            self.scan_block_device(&sysfs_path).await?
+        };
+
+        if self.config.collect_smart {
+            drive.smart_data = self.query_smart(&drive).await;
+            drive.health = self.query_health(&drive).await;
+        }
+
+        Ok(drive)
+    }
+
+    async fn refresh_smart(&self, device_path: &str) -> Result<SmartData> {
+        let name = device_path.strip_prefix("/dev/").unwrap_or(device_path);
+        let device = format!("/dev/{}", name);
+        if name.starts_with("nvme") {
+            self.nvme.get_smart_data(&device).await
+        } else {
+            self.sata.get_smart_data(&device).await
+        }
+    }
+
+    async fn check_zns_support(&self, device_path: &str) -> Result<bool> {
+        let sysfs_path = self.block_sysfs_path(device_path)?;
+        if self.is_zoned(&sysfs_path) {
+            return Ok(true);
+        }
+
+        // Only NVMe namespaces can be ZNS; ask the controller directly in
+        // case the kernel lacks zoned block device support
+        let name = device_path.strip_prefix("/dev/").unwrap_or(device_path);
+        if !name.starts_with("nvme") {
+            return Ok(false);
+        }
+
+        match self.nvme.get_zns_info(&format!("/dev/{}", name)).await {
+            Ok(_) => Ok(true),
+            Err(e @ Error::CommandTimeout { .. }) => Err(e),
+            Err(_) => Ok(false),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::hardware::discovery::command::{CommandOutput, FixtureCommandRunner};
 
     #[test]
     fn test_parse_nsid() {
@@ -473,4 +1017,316 @@ mod tests {
         assert!(!config.include_dm);
         assert_eq!(config.min_size_bytes, 1_000_000_000);
     }
+
+    fn write_attr(base: &Path, attr: &str, value: &str) {
+        let path = base.join(attr);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, value).unwrap();
+    }
+
+    /// Lay out a minimal sysfs with one NVMe namespace and one SATA HDD
+    fn fake_sysfs() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        fs::create_dir_all(root.join("class/nvme/nvme0")).unwrap();
+
+        let nvme = root.join("class/block/nvme0n1");
+        write_attr(&nvme, "device/model", "Samsung SSD 980 PRO 2TB\n");
+        write_attr(&nvme, "device/serial", "S6B0NL0T123456\n");
+        write_attr(&nvme, "device/firmware_rev", "5B2QGXA7\n");
+        write_attr(&nvme, "size", "3907029168\n");
+        write_attr(&nvme, "queue/logical_block_size", "512\n");
+        write_attr(&nvme, "queue/zoned", "none\n");
+
+        let sda = root.join("class/block/sda");
+        write_attr(&sda, "device/model", "ST16000NM001G\n");
+        write_attr(&sda, "device/rev", "SN03\n");
+        write_attr(&sda, "size", "31251759104\n");
+        write_attr(&sda, "queue/rotational", "1\n");
+
+        // Partition and loop devices must be skipped
+        write_attr(&root.join("class/block/sda1"), "partition", "1\n");
+        write_attr(&root.join("class/block/sda1"), "size", "31251759104\n");
+        write_attr(&root.join("class/block/loop0"), "size", "31251759104\n");
+
+        dir
+    }
+
+    /// Outputs captured from nvme-cli 2.x and smartctl 7.x
+    fn recorded_runner() -> Arc<FixtureCommandRunner> {
+        Arc::new(
+            FixtureCommandRunner::new()
+                .with_json(
+                    "nvme smart-log /dev/nvme0 -o json",
+                    serde_json::json!({
+                        "critical_warning": 0,
+                        "temperature": 313,
+                        "percent_used": 3,
+                        "data_units_read": 1234567,
+                        "data_units_written": 7654321,
+                        "power_on_hours": 4821
+                    }),
+                )
+                .with_json(
+                    "smartctl -A -H -j /dev/sda",
+                    serde_json::json!({
+                        "smart_status": {"passed": true},
+                        "ata_smart_attributes": {"table": [
+                            {"id": 9, "raw": {"value": 20311}},
+                            {"id": 194, "raw": {"value": 36}}
+                        ]}
+                    }),
+                )
+                .with_json(
+                    "smartctl -a -j /dev/sda",
+                    serde_json::from_str(include_str!("testdata/smartctl/ata-hdd.json")).unwrap(),
+                )
+                .with_output(
+                    "nvme zns id-ns /dev/nvme0n1 -o json",
+                    CommandOutput::failure(1, "NVMe status: Invalid Field in Command"),
+                ),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_discover_local_from_fixtures() {
+        let sysfs = fake_sysfs();
+        let runner = recorded_runner();
+        let scanner = HardwareScanner::with_runner(
+            ScannerConfig {
+                sysfs_path: sysfs.path().to_path_buf(),
+                proc_path: sysfs.path().join("proc"),
+                dev_path: sysfs.path().join("dev"),
+                ..Default::default()
+            },
+            runner.clone(),
+        );
+
+        let info = scanner.discover_local().await.unwrap();
+        assert_eq!(info.drives.len(), 2);
+
+        let nvme = info.drives.iter().find(|d| d.device_id == "nvme0n1").unwrap();
+        assert_eq!(nvme.drive_type, DriveType::Nvme);
+        assert_eq!(nvme.model, "Samsung SSD 980 PRO 2TB");
+        assert_eq!(nvme.capacity_bytes, 3907029168 * 512);
+        let smart = nvme.smart_data.as_ref().unwrap();
+        assert_eq!(smart.temperature_celsius, 40);
+        assert_eq!(smart.power_on_hours, 4821);
+
+        let sda = info.drives.iter().find(|d| d.device_id == "sda").unwrap();
+        assert_eq!(sda.drive_type, DriveType::Hdd);
+        assert_eq!(sda.firmware, "SN03");
+        let smart = sda.smart_data.as_ref().unwrap();
+        assert_eq!(smart.temperature_celsius, 36);
+        assert_eq!(smart.power_on_hours, 20311);
+        assert!(matches!(sda.health, Some(DriveHealth::Ata(_))));
+
+        // The fake sda carries a partition, so it must not be allocatable
+        assert!(nvme.in_use.is_empty());
+        assert_eq!(
+            sda.in_use,
+            vec![DeviceUsage::Partitions {
+                names: vec!["sda1".into()],
+            }]
+        );
+
+        assert!(runner.calls().iter().all(|c| !c.contains("sda1") && !c.contains("loop0")));
+    }
+
+    #[tokio::test]
+    async fn test_discover_device_and_zns() {
+        let sysfs = fake_sysfs();
+        let scanner = HardwareScanner::with_runner(
+            ScannerConfig {
+                sysfs_path: sysfs.path().to_path_buf(),
+                proc_path: sysfs.path().join("proc"),
+                dev_path: sysfs.path().join("dev"),
+                collect_smart: false,
+                ..Default::default()
+            },
+            recorded_runner(),
+        );
+
+        let drive = scanner.discover_device("/dev/sda").await.unwrap();
+        assert_eq!(drive.model, "ST16000NM001G");
+        assert!(drive.smart_data.is_none());
+
+        assert!(matches!(
+            scanner.discover_device("/dev/sdz").await,
+            Err(Error::DeviceNotFound { .. })
+        ));
+
+        assert!(!scanner.check_zns_support("/dev/nvme0n1").await.unwrap());
+        assert!(!scanner.check_zns_support("/dev/sda").await.unwrap());
+
+        let smart = scanner.refresh_smart("nvme0n1").await.unwrap();
+        assert_eq!(smart.percentage_used, 3);
+        assert!(matches!(
+            scanner.refresh_smart("/dev/sdb").await,
+            Err(Error::SmartUnavailable { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_drive_locations() {
+        let sysfs = fake_sysfs();
+        let root = sysfs.path();
+
+        // sda sits in bay 7 of an SES enclosure
+        let bay = root.join("class/enclosure/0:0:12:0/Slot 07");
+        write_attr(&bay, "slot", "7\n");
+        write_attr(&root.join("class/enclosure/0:0:12:0"), "id", "500605b0000272bf\n");
+        let link = root.join("class/block/sda/device/enclosure_device:Slot 07");
+        std::os::unix::fs::symlink(&bay, link).unwrap();
+
+        // nvme0n1 hangs off a PCIe function in slot 3 on NUMA node 1
+        let nvme = root.join("class/block/nvme0n1");
+        write_attr(&nvme, "device/address", "0000:3b:00.0\n");
+        write_attr(&nvme, "device/device/numa_node", "1\n");
+        write_attr(&root.join("bus/pci/slots/2"), "address", "0000:3a:00\n");
+        write_attr(&root.join("bus/pci/slots/3"), "address", "0000:3b:00\n");
+
+        let scanner = HardwareScanner::with_runner(
+            ScannerConfig {
+                sysfs_path: root.to_path_buf(),
+                proc_path: root.join("proc"),
+                dev_path: root.join("dev"),
+                collect_smart: false,
+                ..Default::default()
+            },
+            recorded_runner(),
+        );
+        let info = scanner.discover_local().await.unwrap();
+
+        let sda = info.drives.iter().find(|d| d.device_id == "sda").unwrap();
+        assert_eq!(
+            sda.location,
+            DriveLocation {
+                enclosure: Some("500605b0000272bf".into()),
+                slot: Some("7".into()),
+                ..Default::default()
+            }
+        );
+
+        let nvme = info.drives.iter().find(|d| d.device_id == "nvme0n1").unwrap();
+        assert_eq!(
+            nvme.location,
+            DriveLocation {
+                slot: Some("3".into()),
+                pci_address: Some("0000:3b:00.0".into()),
+                numa_node: Some(1),
+                ..Default::default()
+            }
+        );
+    }
+
+    fn path_info(device_id: &str, wwid: Option<&str>, serial: &str) -> DriveInfo {
+        DriveInfo {
+            device_path: format!("/dev/{}", device_id),
+            device_id: device_id.to_string(),
+            drive_type: DriveType::Hdd,
+            model: "ST16000NM001G".to_string(),
+            serial: serial.to_string(),
+            firmware: "SN03".to_string(),
+            capacity_bytes: 16_000_000_000_000,
+            block_size: 512,
+            zns_supported: false,
+            nvme_namespaces: Vec::new(),
+            smart_data: None,
+            health: None,
+            in_use: Vec::new(),
+            wwid: wwid.map(str::to_string),
+            paths: vec![device_id.to_string()],
+            location: Default::default(),
+        }
+    }
+
+    #[test]
+    fn test_normalize_wwid() {
+        assert_eq!(
+            normalize_wwid(" naa.5000C500A1B2C3D4\n").as_deref(),
+            Some("naa.5000c500a1b2c3d4")
+        );
+        assert_eq!(normalize_wwid("eui.0000000000000000"), None);
+        assert_eq!(normalize_wwid("00000000-0000-0000-0000-000000000000"), None);
+        assert_eq!(normalize_wwid(""), None);
+    }
+
+    #[test]
+    fn test_merge_paths() {
+        let merged = merge_paths(vec![
+            path_info("sdq", Some("naa.5000c500a1b2c3d4"), "ZL2A"),
+            path_info("sdc", None, "ZL2B"),
+            path_info("sdb", Some("naa.5000c500a1b2c3d4"), "ZL2A"),
+            // No WWID, but the same model and serial as sdc
+            path_info("sdr", None, "ZL2B"),
+            // Serials that identify nothing are never merged
+            path_info("sdx", None, "unknown"),
+            path_info("sdy", None, "unknown"),
+        ]);
+
+        let ids: Vec<_> = merged.iter().map(|d| d.device_id.as_str()).collect();
+        assert_eq!(ids, vec!["sdb", "sdc", "sdx", "sdy"]);
+        assert_eq!(merged[0].paths, vec!["sdb", "sdq"]);
+        assert_eq!(merged[1].paths, vec!["sdc", "sdr"]);
+        assert_eq!(merged[2].paths, vec!["sdx"]);
+    }
+
+    #[tokio::test]
+    async fn test_multipath_collapses_to_one_drive() {
+        let sysfs = fake_sysfs();
+        let root = sysfs.path();
+
+        // One dual-ported SAS drive seen as sdb and sdq, under dm-0
+        for name in ["sdb", "sdq"] {
+            let dev = root.join("class/block").join(name);
+            write_attr(&dev, "device/model", "ST16000NM002G\n");
+            write_attr(&dev, "device/serial", "ZL2ABCDE\n");
+            write_attr(&dev, "device/wwid", "naa.5000c500a1b2c3d4\n");
+            write_attr(&dev, "size", "31251759104\n");
+            write_attr(&dev, "queue/rotational", "1\n");
+            write_attr(&dev, "holders/dm-0/dev", "253:0\n");
+        }
+        let dm = root.join("class/block/dm-0");
+        write_attr(&dm, "dm/uuid", "mpath-35000c500a1b2c3d4\n");
+        write_attr(&dm, "dm/name", "mpatha\n");
+        write_attr(&dm, "size", "31251759104\n");
+        write_attr(&dm, "slaves/sdb/dev", "8:16\n");
+        write_attr(&dm, "slaves/sdq/dev", "65:0\n");
+
+        // An NVMe namespace reporting an unset EUI-64 keeps no WWID
+        write_attr(&root.join("class/block/nvme0n1"), "eui", "0000000000000000\n");
+
+        let scanner = HardwareScanner::with_runner(
+            ScannerConfig {
+                sysfs_path: root.to_path_buf(),
+                proc_path: root.join("proc"),
+                dev_path: root.join("dev"),
+                collect_smart: false,
+                ..Default::default()
+            },
+            recorded_runner(),
+        );
+
+        let info = scanner.discover_local().await.unwrap();
+        assert_eq!(info.drives.len(), 3);
+        let capacity: u64 = info.drives.iter().map(|d| d.capacity_bytes).sum();
+        assert_eq!(capacity, 3907029168 * 512 + 2 * 31251759104 * 512);
+
+        let mpath = info.drives.iter().find(|d| d.device_id == "dm-0").unwrap();
+        assert_eq!(mpath.device_path, "/dev/mapper/mpatha");
+        assert_eq!(mpath.wwid.as_deref(), Some("naa.5000c500a1b2c3d4"));
+        assert_eq!(mpath.paths, vec!["dm-0", "sdb", "sdq"]);
+        assert_eq!(mpath.model, "ST16000NM002G");
+        // The paths are held by dm-0, but the multipath device itself is free
+        assert!(mpath.in_use.is_empty());
+        assert_eq!(HardwareScanner::smart_device(mpath), "/dev/sdb");
+
+        let nvme = info.drives.iter().find(|d| d.device_id == "nvme0n1").unwrap();
+        assert_eq!(nvme.wwid, None);
+
+        // Hot-plug scans resolve a multipath device the same way
+        let scanned = scanner.scan_device("dm-0").await.unwrap().unwrap();
+        assert_eq!(scanned.paths, mpath.paths);
+    }
 }
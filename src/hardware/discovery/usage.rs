@@ -0,0 +1,392 @@
+//! In-Use Device Detection
+//!
+//! Finds evidence that a block device already belongs to the host or holds
+//! data: partitions, stacked holders (device-mapper, md), mounts, active
+//! swap, and on-disk signatures such as filesystems, LVM physical volumes,
+//! md RAID members, Ceph BlueStore and partition tables. Any finding makes
+//! the drive ineligible for allocation.
+
+use crate::domain::ports::DeviceUsage;
+use std::fs::{self, File};
+use std::io::{ErrorKind, Read};
+use std::path::{Path, PathBuf};
+
+// =============================================================================
+// Constants
+// =============================================================================
+
+/// Bytes read from the start of a device for signature detection; covers
+/// the btrfs superblock at 64 KiB
+const SIGNATURE_PROBE_BYTES: usize = 128 * 1024;
+
+/// A signature at a fixed offset
+struct Magic {
+    name: &'static str,
+    offset: usize,
+    bytes: &'static [u8],
+}
+
+const MAGICS: &[Magic] = &[
+    Magic { name: "xfs", offset: 0, bytes: b"XFSB" },
+    Magic { name: "ext4", offset: 0x438, bytes: &[0x53, 0xef] },
+    Magic { name: "btrfs", offset: 0x10040, bytes: b"_BHRfS_M" },
+    Magic { name: "LVM2_member", offset: 0x218, bytes: b"LVM2 001" },
+    Magic { name: "LVM2_member", offset: 0x18, bytes: b"LVM2 001" },
+    Magic { name: "linux_raid_member", offset: 0x1000, bytes: &[0xfc, 0x4e, 0x2b, 0xa9] },
+    Magic { name: "linux_raid_member", offset: 0, bytes: &[0xfc, 0x4e, 0x2b, 0xa9] },
+    Magic { name: "ceph_bluestore", offset: 0, bytes: b"bluestore block device" },
+    Magic { name: "swap", offset: 0xff6, bytes: b"SWAPSPACE2" },
+    Magic { name: "swap", offset: 0xff6, bytes: b"SWAP-SPACE" },
+    Magic { name: "ntfs", offset: 3, bytes: b"NTFS    " },
+    Magic { name: "vfat", offset: 0x52, bytes: b"FAT32   " },
+    Magic { name: "gpt", offset: 0x200, bytes: b"EFI PART" },
+    Magic { name: "gpt", offset: 0x1000, bytes: b"EFI PART" },
+];
+
+/// Detect known signatures in the first bytes of a device
+pub fn detect_signatures(data: &[u8]) -> Vec<&'static str> {
+    let mut found: Vec<&'static str> = Vec::new();
+    for magic in MAGICS {
+        let end = magic.offset + magic.bytes.len();
+        if data.len() >= end
+            && &data[magic.offset..end] == magic.bytes
+            && !found.contains(&magic.name)
+        {
+            found.push(magic.name);
+        }
+    }
+
+    // A protective MBR accompanies GPT; report a DOS table only on its own
+    if !found.contains(&"gpt") && has_dos_partition_table(data) {
+        found.push("dos");
+    }
+    found
+}
+
+/// MBR boot signature with at least one non-empty partition entry
+fn has_dos_partition_table(data: &[u8]) -> bool {
+    if data.len() < 512 || data[510..512] != [0x55, 0xaa] {
+        return false;
+    }
+    // FAT and NTFS boot sectors also end in 55 AA
+    if data[3..11] == *b"NTFS    " || data[0x52..0x5a] == *b"FAT32   " {
+        return false;
+    }
+    (0..4).any(|i| data[446 + i * 16 + 4] != 0)
+}
+
+// =============================================================================
+// Usage Probe
+// =============================================================================
+
+/// Checks a device for existing uses against sysfs, procfs and the device
+/// node, each rooted at a configurable path for testing
+#[derive(Debug, Clone)]
+pub struct UsageProbe {
+    sysfs_path: PathBuf,
+    proc_path: PathBuf,
+    dev_path: PathBuf,
+}
+
+impl UsageProbe {
+    pub fn new(sysfs_path: &Path, proc_path: &Path, dev_path: &Path) -> Self {
+        Self {
+            sysfs_path: sysfs_path.to_path_buf(),
+            proc_path: proc_path.to_path_buf(),
+            dev_path: dev_path.to_path_buf(),
+        }
+    }
+
+    /// All evidence that `name` (e.g. `sda`) is in use; empty if none
+    pub fn check(&self, name: &str) -> Vec<DeviceUsage> {
+        let mut usage = Vec::new();
+
+        let partitions = self.partitions(name);
+        if !partitions.is_empty() {
+            usage.push(DeviceUsage::Partitions {
+                names: partitions.clone(),
+            });
+        }
+
+        let mut holders = Vec::new();
+        for dev in std::iter::once(name).chain(partitions.iter().map(String::as_str)) {
+            holders.extend(self.holders(dev));
+        }
+        if !holders.is_empty() {
+            usage.push(DeviceUsage::Holders { names: holders });
+        }
+
+        let mut devices = vec![format!("/dev/{}", name)];
+        devices.extend(partitions.iter().map(|p| format!("/dev/{}", p)));
+        // Device-mapper devices are usually mounted by their mapper alias
+        let dm_name = self.sysfs_path.join("class/block").join(name).join("dm/name");
+        if let Ok(alias) = fs::read_to_string(dm_name) {
+            devices.push(format!("/dev/mapper/{}", alias.trim()));
+        }
+
+        if let Ok(mounts) = fs::read_to_string(self.proc_path.join("mounts")) {
+            for line in mounts.lines() {
+                let mut fields = line.split_whitespace();
+                if let (Some(device), Some(mountpoint)) = (fields.next(), fields.next()) {
+                    if devices.iter().any(|d| d == device) {
+                        usage.push(DeviceUsage::Mounted {
+                            device: device.to_string(),
+                            mountpoint: unescape_mount(mountpoint),
+                        });
+                    }
+                }
+            }
+        }
+
+        if let Ok(swaps) = fs::read_to_string(self.proc_path.join("swaps")) {
+            for line in swaps.lines().skip(1) {
+                if let Some(device) = line.split_whitespace().next() {
+                    if devices.iter().any(|d| d == device) {
+                        usage.push(DeviceUsage::Swap {
+                            device: device.to_string(),
+                        });
+                    }
+                }
+            }
+        }
+
+        match self.read_head(name) {
+            Ok(Some(data)) => {
+                for signature in detect_signatures(&data) {
+                    usage.push(DeviceUsage::Signature {
+                        signature: signature.to_string(),
+                    });
+                }
+            }
+            // No device node: the device went away or this is a replayed
+            // profile; the sysfs checks above still apply
+            Ok(None) => {}
+            Err(e) => usage.push(DeviceUsage::Unreadable {
+                error: e.to_string(),
+            }),
+        }
+
+        usage
+    }
+
+    /// Partition names of a whole-disk device
+    fn partitions(&self, name: &str) -> Vec<String> {
+        let block = self.sysfs_path.join("class/block");
+        let mut found = Vec::new();
+
+        // Partitions are children of the disk in sysfs...
+        if let Ok(entries) = fs::read_dir(block.join(name)) {
+            for entry in entries.flatten() {
+                if entry.path().join("partition").exists() {
+                    found.push(entry.file_name().to_string_lossy().to_string());
+                }
+            }
+        }
+
+        // ...and also listed flat under class/block
+        if let Ok(entries) = fs::read_dir(&block) {
+            for entry in entries.flatten() {
+                let child = entry.file_name().to_string_lossy().to_string();
+                if is_partition_name(name, &child)
+                    && entry.path().join("partition").exists()
+                    && !found.contains(&child)
+                {
+                    found.push(child);
+                }
+            }
+        }
+
+        found.sort();
+        found
+    }
+
+    /// Devices stacked on `name`
+    fn holders(&self, name: &str) -> Vec<String> {
+        let path = self.sysfs_path.join("class/block").join(name).join("holders");
+        let mut holders: Vec<String> = fs::read_dir(path)
+            .map(|entries| {
+                entries
+                    .flatten()
+                    .map(|e| e.file_name().to_string_lossy().to_string())
+                    .collect()
+            })
+            .unwrap_or_default();
+        holders.sort();
+        holders
+    }
+
+    /// Leading bytes of the device node, or `None` if it does not exist
+    fn read_head(&self, name: &str) -> std::io::Result<Option<Vec<u8>>> {
+        let file = match File::open(self.dev_path.join(name)) {
+            Ok(file) => file,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e),
+        };
+        let mut data = Vec::with_capacity(SIGNATURE_PROBE_BYTES);
+        file.take(SIGNATURE_PROBE_BYTES as u64).read_to_end(&mut data)?;
+        Ok(Some(data))
+    }
+}
+
+/// `sda1` for `sda`, `nvme0n1p2` for `nvme0n1`
+fn is_partition_name(disk: &str, candidate: &str) -> bool {
+    let Some(rest) = candidate.strip_prefix(disk) else {
+        return false;
+    };
+    let rest = if disk.ends_with(|c: char| c.is_ascii_digit()) {
+        match rest.strip_prefix('p') {
+            Some(rest) => rest,
+            None => return false,
+        }
+    } else {
+        rest
+    };
+    !rest.is_empty() && rest.chars().all(|c| c.is_ascii_digit())
+}
+
+/// Undo the octal escaping of spaces and tabs in /proc/mounts
+fn unescape_mount(field: &str) -> String {
+    field
+        .replace("\\040", " ")
+        .replace("\\011", "\t")
+        .replace("\\134", "\\")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(path: &Path, contents: &[u8]) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, contents).unwrap();
+    }
+
+    /// A device image with `magic` written at `offset`
+    fn image(offset: usize, magic: &[u8]) -> Vec<u8> {
+        let mut data = vec![0u8; SIGNATURE_PROBE_BYTES];
+        data[offset..offset + magic.len()].copy_from_slice(magic);
+        data
+    }
+
+    fn probe(root: &Path) -> UsageProbe {
+        UsageProbe::new(&root.join("sys"), &root.join("proc"), &root.join("dev"))
+    }
+
+    #[test]
+    fn test_detect_signatures() {
+        assert_eq!(detect_signatures(&image(0x438, &[0x53, 0xef])), vec!["ext4"]);
+        assert_eq!(detect_signatures(&image(0, b"XFSB")), vec!["xfs"]);
+        assert_eq!(detect_signatures(&image(0x10040, b"_BHRfS_M")), vec!["btrfs"]);
+        assert_eq!(detect_signatures(&image(0x218, b"LVM2 001")), vec!["LVM2_member"]);
+        assert_eq!(
+            detect_signatures(&image(0x1000, &[0xfc, 0x4e, 0x2b, 0xa9])),
+            vec!["linux_raid_member"]
+        );
+        assert_eq!(
+            detect_signatures(&image(0, b"bluestore block device\nabc")),
+            vec!["ceph_bluestore"]
+        );
+        assert_eq!(detect_signatures(&image(0xff6, b"SWAPSPACE2")), vec!["swap"]);
+
+        // GPT comes with a protective MBR, which is not reported separately
+        let mut gpt = image(0x200, b"EFI PART");
+        gpt[510] = 0x55;
+        gpt[511] = 0xaa;
+        gpt[446 + 4] = 0xee;
+        assert_eq!(detect_signatures(&gpt), vec!["gpt"]);
+
+        let mut dos = image(510, &[0x55, 0xaa]);
+        dos[446 + 4] = 0x83;
+        assert_eq!(detect_signatures(&dos), vec!["dos"]);
+
+        // Blank and truncated devices are clean
+        assert!(detect_signatures(&vec![0u8; SIGNATURE_PROBE_BYTES]).is_empty());
+        assert!(detect_signatures(&[0u8; 100]).is_empty());
+    }
+
+    #[test]
+    fn test_is_partition_name() {
+        assert!(is_partition_name("sda", "sda1"));
+        assert!(is_partition_name("nvme0n1", "nvme0n1p2"));
+        assert!(!is_partition_name("sda", "sdaa"));
+        assert!(!is_partition_name("nvme0n1", "nvme0n12"));
+        assert!(!is_partition_name("sda", "sda"));
+    }
+
+    #[test]
+    fn test_clean_device_has_no_usage() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        write(&root.join("sys/class/block/sdb/size"), b"31251759104\n");
+        fs::create_dir_all(root.join("sys/class/block/sdb/holders")).unwrap();
+        write(&root.join("dev/sdb"), &vec![0u8; 4096]);
+
+        assert!(probe(root).check("sdb").is_empty());
+    }
+
+    #[test]
+    fn test_os_disk_is_in_use() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        write(&root.join("sys/class/block/sda/size"), b"937703088\n");
+        write(&root.join("sys/class/block/sda/sda1/partition"), b"1\n");
+        write(&root.join("sys/class/block/sda/sda2/partition"), b"2\n");
+        write(&root.join("sys/class/block/sda3/partition"), b"3\n");
+        fs::create_dir_all(root.join("sys/class/block/sda3/holders/dm-0")).unwrap();
+        write(
+            &root.join("proc/mounts"),
+            b"sysfs /sys sysfs rw 0 0\n/dev/sda1 /boot\\040efi vfat rw 0 0\n/dev/mapper/vg-root / xfs rw 0 0\n",
+        );
+        write(
+            &root.join("proc/swaps"),
+            b"Filename\tType\tSize\tUsed\tPriority\n/dev/sda2 partition 8388604 0 -2\n",
+        );
+        let mut gpt = image(0x200, b"EFI PART");
+        gpt[510] = 0x55;
+        gpt[511] = 0xaa;
+        write(&root.join("dev/sda"), &gpt);
+
+        let usage = probe(root).check("sda");
+        assert_eq!(
+            usage,
+            vec![
+                DeviceUsage::Partitions {
+                    names: vec!["sda1".into(), "sda2".into(), "sda3".into()],
+                },
+                DeviceUsage::Holders {
+                    names: vec!["dm-0".into()],
+                },
+                DeviceUsage::Mounted {
+                    device: "/dev/sda1".into(),
+                    mountpoint: "/boot efi".into(),
+                },
+                DeviceUsage::Swap {
+                    device: "/dev/sda2".into(),
+                },
+                DeviceUsage::Signature {
+                    signature: "gpt".into(),
+                },
+            ]
+        );
+        assert_eq!(usage[2].to_string(), "/dev/sda1 mounted at /boot efi");
+    }
+
+    #[test]
+    fn test_whole_disk_lvm_and_missing_node() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        write(&root.join("sys/class/block/sdc/size"), b"31251759104\n");
+        write(&root.join("dev/sdc"), &image(0x218, b"LVM2 001"));
+
+        assert_eq!(
+            probe(root).check("sdc"),
+            vec![DeviceUsage::Signature {
+                signature: "LVM2_member".into(),
+            }]
+        );
+
+        // No device node (e.g. a replayed profile) is not itself a finding
+        write(&root.join("sys/class/block/sdd/size"), b"31251759104\n");
+        assert!(probe(root).check("sdd").is_empty());
+    }
+}
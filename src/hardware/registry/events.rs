@@ -37,12 +37,15 @@ pub enum RegistryEvent {
     /// A drive was removed from a node
     DriveRemoved { node_id: String, drive_id: String },
 
-    /// A drive's health status changed
+    /// A drive's health status changed; `location` names the enclosure
+    /// bay or PCIe slot to service, when known
     DriveHealthChanged {
         node_id: String,
         drive_id: String,
         healthy: bool,
         reason: Option<String>,
+        #[serde(default)]
+        location: Option<String>,
     },
 
     /// Drive metrics exceeded threshold
@@ -3,7 +3,8 @@
 //! A 256-way sharded registry for tracking storage nodes and their hardware.
 //! Optimized for high-throughput updates using Data-Oriented Design principles.
 
-use crate::crd::{DriveStatus, StorageNodeStatus};
+use crate::crd::{DriveStatus, FailureRiskStatus, StorageNodeStatus};
+use crate::domain::risk::{FailureRisk, RiskLevel, SmartHistory, SmartSample};
 use crate::error::{Error, Result};
 use chrono::{DateTime, Utc};
 use parking_lot::RwLock;
@@ -23,6 +24,11 @@ pub const REGISTRY_SHARD_COUNT: usize = 256;
 /// Cache line size for alignment
 const CACHE_LINE_SIZE: usize = 64;
 
+/// Reported SMART counters that repeat the latest sample are recorded again
+/// only after this long, so frequent status reports do not crowd the
+/// history with identical samples
+pub const SMART_SAMPLE_INTERVAL_SECS: i64 = 15 * 60;
+
 // =============================================================================
 // Node ID
 // =============================================================================
@@ -190,6 +196,26 @@ impl DriveMetrics {
 // Node Entry
 // =============================================================================
 
+/// A drive's failure risk after a SMART sample was recorded
+#[derive(Debug, Clone)]
+pub struct RiskUpdate {
+    pub drive_id: String,
+    pub risk: FailureRisk,
+    /// Risk level before the sample, if the drive had one
+    pub previous: Option<RiskLevel>,
+    /// Whether the drive is healthy after the sample
+    pub healthy: bool,
+    /// Where the drive sits, for events
+    pub location: Option<String>,
+}
+
+impl RiskUpdate {
+    /// Whether the sample moved the drive to another risk level
+    pub fn level_changed(&self) -> bool {
+        self.previous.unwrap_or(RiskLevel::Low) != self.risk.level
+    }
+}
+
 /// Entry for a single node in the registry
 #[derive(Debug)]
 pub struct NodeEntry {
@@ -201,6 +227,8 @@ pub struct NodeEntry {
     pub status: StorageNodeStatus,
     /// Per-drive metrics (indexed by drive ID)
     pub drive_metrics: HashMap<String, Arc<DriveMetrics>>,
+    /// Per-drive SMART sample history (indexed by drive ID)
+    pub smart_history: HashMap<String, SmartHistory>,
     /// Registration timestamp
     pub registered_at: DateTime<Utc>,
     /// Last heartbeat timestamp
@@ -224,21 +252,30 @@ impl NodeEntry {
             drive_metrics.insert(drive.id.clone(), Arc::new(DriveMetrics::default()));
         }
 
-        Self {
+        let mut entry = Self {
             node_id,
             hostname,
             status,
             drive_metrics,
+            smart_history: HashMap::new(),
             registered_at: now,
             last_heartbeat: now,
             online: true,
             labels: HashMap::new(),
             fault_domain: None,
+        };
+
+        // Start each drive's history with the SMART counters it reported
+        let ids: Vec<String> = entry.status.drives.iter().map(|d| d.id.clone()).collect();
+        for id in &ids {
+            entry.sample_reported_smart(id, now);
         }
+        entry
     }
 
-    /// Update node status
-    pub fn update_status(&mut self, status: StorageNodeStatus) {
+    /// Update node status, sampling the SMART counters reported for each
+    /// drive. Returns the risk of every drive that was sampled.
+    pub fn update_status(&mut self, status: StorageNodeStatus) -> Vec<RiskUpdate> {
         // Add metrics for any new drives
         for drive in &status.drives {
             if !self.drive_metrics.contains_key(&drive.id) {
@@ -249,9 +286,198 @@ impl NodeEntry {
         // Remove metrics for removed drives
         let current_ids: std::collections::HashSet<_> = status.drives.iter().map(|d| &d.id).collect();
         self.drive_metrics.retain(|id, _| current_ids.contains(id));
+        self.smart_history.retain(|id, _| current_ids.contains(id));
 
         self.status = status;
         self.last_heartbeat = Utc::now();
+
+        // Reported status does not know about trends; keep the predicted risk
+        for drive in &mut self.status.drives {
+            if let Some(history) = self.smart_history.get(&drive.id) {
+                let risk = history.assess();
+                if risk.is_high() {
+                    drive.healthy = false;
+                }
+                drive.failure_risk = Some(FailureRiskStatus::from(&risk));
+            }
+        }
+
+        let now = Utc::now();
+        let ids: Vec<String> = self.status.drives.iter().map(|d| d.id.clone()).collect();
+        ids.iter()
+            .filter_map(|id| self.sample_reported_smart(id, now))
+            .collect()
+    }
+
+    /// Record the SMART counters reported in a drive's status as a sample.
+    ///
+    /// Counters that repeat the latest sample are skipped until
+    /// [`SMART_SAMPLE_INTERVAL_SECS`] have passed. Returns None if the drive
+    /// reports no SMART data or the sample was skipped.
+    pub fn sample_reported_smart(&mut self, drive_id: &str, now: DateTime<Utc>) -> Option<RiskUpdate> {
+        let drive = self.status.drives.iter().find(|d| d.id == drive_id)?;
+        let sample = drive.smart.as_ref()?.sample(now);
+
+        if let Some(latest) = self.smart_history.get(drive_id).and_then(|h| h.latest()) {
+            let repeated = SmartSample {
+                timestamp: latest.timestamp,
+                ..sample.clone()
+            } == *latest;
+            if repeated && (now - latest.timestamp).num_seconds() < SMART_SAMPLE_INTERVAL_SECS {
+                return None;
+            }
+        }
+
+        self.record_smart_sample(drive_id, sample).ok()
+    }
+
+    /// Record a SMART sample for a drive and re-assess its failure risk.
+    ///
+    /// High risk marks the drive unhealthy; a drive this marked unhealthy
+    /// becomes healthy again once the risk drops.
+    pub fn record_smart_sample(&mut self, drive_id: &str, sample: SmartSample) -> Result<RiskUpdate> {
+        let drive = self
+            .status
+            .drives
+            .iter_mut()
+            .find(|d| d.id == drive_id)
+            .ok_or_else(|| Error::DeviceNotFound {
+                device: format!("{}:{}", self.node_id, drive_id),
+            })?;
+
+        let history = self.smart_history.entry(drive_id.to_string()).or_default();
+        history.push(sample);
+        let risk = history.assess();
+
+        let previous = drive.failure_risk.as_ref().map(|r| parse_risk_level(&r.level));
+        if risk.is_high() {
+            drive.healthy = false;
+        } else if previous == Some(RiskLevel::High) {
+            drive.healthy = true;
+        }
+        drive.failure_risk = Some(FailureRiskStatus::from(&risk));
+
+        Ok(RiskUpdate {
+            drive_id: drive_id.to_string(),
+            risk,
+            previous,
+            healthy: drive.healthy,
+            location: drive.location.as_ref().map(ToString::to_string),
+        })
+    }
+
+    /// Add a drive, or refresh one that is already known while keeping its
+    /// classification, pool assignment and predicted risk. A drive whose
+    /// WWID is already known under another name is another path to that
+    /// drive and is merged into it. Returns true if the drive is new.
+    pub fn upsert_drive(&mut self, mut drive: DriveStatus) -> bool {
+        let position = self
+            .status
+            .drives
+            .iter()
+            .position(|d| d.id == drive.id)
+            .or_else(|| {
+                let wwid = drive.wwid.as_ref()?;
+                self.status.drives.iter().position(|d| d.wwid.as_ref() == Some(wwid))
+            });
+
+        let Some(idx) = position else {
+            self.drive_metrics
+                .insert(drive.id.clone(), Arc::new(DriveMetrics::default()));
+            self.status.drives.push(drive);
+            self.status.update_counts();
+            return true;
+        };
+
+        let existing = &mut self.status.drives[idx];
+        if existing.id != drive.id {
+            if existing.paths.is_empty() {
+                existing.paths.push(existing.id.clone());
+            }
+            // Prefer the multipath device over a single path, unless the
+            // drive is already allocated under its current name
+            let promote = drive.id.starts_with("dm-")
+                && !existing.id.starts_with("dm-")
+                && existing.pool_ref.is_none();
+            if !promote {
+                let existing = &mut self.status.drives[idx];
+                for path in drive.paths.iter().chain(std::iter::once(&drive.id)) {
+                    if !existing.paths.contains(path) {
+                        existing.paths.push(path.clone());
+                    }
+                }
+                return false;
+            }
+            let from = existing.id.clone();
+            self.rename_drive(&from, &drive.id);
+        }
+
+        // A rescan of one path reports only that path; keep the others
+        let existing = &mut self.status.drives[idx];
+        for path in &existing.paths {
+            if !drive.paths.contains(path) {
+                drive.paths.push(path.clone());
+            }
+        }
+        drive.classification = existing.classification.clone();
+        drive.pool_ref = existing.pool_ref.clone();
+        drive.metrics = existing.metrics.clone();
+        drive.failure_risk = existing.failure_risk.clone();
+        if existing.failure_risk.as_ref().is_some_and(|r| r.level == "high") {
+            drive.healthy = false;
+        }
+        *existing = drive;
+        self.status.update_counts();
+        false
+    }
+
+    /// Remove a drive path. The drive is removed, and returned, once its
+    /// last path is gone; while other paths remain it is kept under the
+    /// next one.
+    pub fn remove_drive(&mut self, drive_id: &str) -> Option<DriveStatus> {
+        let idx = self
+            .status
+            .drives
+            .iter()
+            .position(|d| d.id == drive_id)
+            .or_else(|| {
+                self.status
+                    .drives
+                    .iter()
+                    .position(|d| d.paths.iter().any(|p| p == drive_id))
+            })?;
+
+        let drive = &mut self.status.drives[idx];
+        drive.paths.retain(|p| p != drive_id);
+        if let Some(next) = drive.paths.first().cloned() {
+            if drive.id == drive_id {
+                drive.device_path = format!("/dev/{}", next);
+                self.rename_drive(drive_id, &next);
+                self.status.drives[idx].id = next;
+            }
+            return None;
+        }
+
+        let drive = self.status.drives.remove(idx);
+        self.drive_metrics.remove(&drive.id);
+        self.smart_history.remove(&drive.id);
+        self.status.update_counts();
+        Some(drive)
+    }
+
+    /// Move per-drive state to a drive's new name
+    fn rename_drive(&mut self, from: &str, to: &str) {
+        if let Some(metrics) = self.drive_metrics.remove(from) {
+            self.drive_metrics.insert(to.to_string(), metrics);
+        }
+        if let Some(history) = self.smart_history.remove(from) {
+            self.smart_history.insert(to.to_string(), history);
+        }
+    }
+
+    /// Get the SMART history for a drive
+    pub fn get_smart_history(&self, drive_id: &str) -> Option<&SmartHistory> {
+        self.smart_history.get(drive_id)
     }
 
     /// Record heartbeat
@@ -286,6 +512,15 @@ impl NodeEntry {
     }
 }
 
+/// Parse a risk level stored in a drive status
+fn parse_risk_level(level: &str) -> RiskLevel {
+    match level {
+        "high" => RiskLevel::High,
+        "elevated" => RiskLevel::Elevated,
+        _ => RiskLevel::Low,
+    }
+}
+
 // =============================================================================
 // Shard Statistics
 // =============================================================================
@@ -365,13 +600,13 @@ impl RegistryShard {
         self.nodes.read().get(node_id).cloned()
     }
 
-    /// Update a node's status
-    fn update_status(&self, node_id: &NodeId, status: StorageNodeStatus) -> Result<()> {
+    /// Update a node's status, returning the drives whose SMART data was sampled
+    fn update_status(&self, node_id: &NodeId, status: StorageNodeStatus) -> Result<Vec<RiskUpdate>> {
         let mut nodes = self.nodes.write();
         if let Some(entry) = nodes.get_mut(node_id) {
-            entry.update_status(status);
+            let updates = entry.update_status(status);
             self.stats.update_count.fetch_add(1, Ordering::Relaxed);
-            Ok(())
+            Ok(updates)
         } else {
             Err(Error::NodeNotFound {
                 node_id: node_id.to_string(),
@@ -379,6 +614,59 @@ impl RegistryShard {
         }
     }
 
+    /// Record a SMART sample for a drive
+    fn record_smart_sample(
+        &self,
+        node_id: &NodeId,
+        drive_id: &str,
+        sample: SmartSample,
+    ) -> Result<RiskUpdate> {
+        let mut nodes = self.nodes.write();
+        let entry = nodes.get_mut(node_id).ok_or_else(|| Error::NodeNotFound {
+            node_id: node_id.to_string(),
+        })?;
+        let update = entry.record_smart_sample(drive_id, sample)?;
+        self.stats.update_count.fetch_add(1, Ordering::Relaxed);
+        Ok(update)
+    }
+
+    /// Add or refresh a drive, returning whether it is new, the change in
+    /// total and available capacity, and its risk if SMART data was sampled
+    fn upsert_drive(
+        &self,
+        node_id: &NodeId,
+        drive: DriveStatus,
+    ) -> Result<(bool, i128, i128, Option<RiskUpdate>)> {
+        let mut nodes = self.nodes.write();
+        let entry = nodes.get_mut(node_id).ok_or_else(|| Error::NodeNotFound {
+            node_id: node_id.to_string(),
+        })?;
+        let (total, available) = (entry.total_capacity_bytes(), entry.available_capacity_bytes());
+        let drive_id = drive.id.clone();
+        let added = entry.upsert_drive(drive);
+        let update = entry.sample_reported_smart(&drive_id, Utc::now());
+        self.stats.update_count.fetch_add(1, Ordering::Relaxed);
+        Ok((
+            added,
+            entry.total_capacity_bytes() as i128 - total as i128,
+            entry.available_capacity_bytes() as i128 - available as i128,
+            update,
+        ))
+    }
+
+    /// Remove a drive from a node
+    fn remove_drive(&self, node_id: &NodeId, drive_id: &str) -> Result<Option<DriveStatus>> {
+        let mut nodes = self.nodes.write();
+        let entry = nodes.get_mut(node_id).ok_or_else(|| Error::NodeNotFound {
+            node_id: node_id.to_string(),
+        })?;
+        let removed = entry.remove_drive(drive_id);
+        if removed.is_some() {
+            self.stats.update_count.fetch_add(1, Ordering::Relaxed);
+        }
+        Ok(removed)
+    }
+
     /// Record heartbeat for a node
     fn heartbeat(&self, node_id: &NodeId) -> Result<()> {
         let mut nodes = self.nodes.write();
@@ -435,6 +723,7 @@ impl Clone for NodeEntry {
             hostname: self.hostname.clone(),
             status: self.status.clone(),
             drive_metrics: self.drive_metrics.clone(),
+            smart_history: self.smart_history.clone(),
             registered_at: self.registered_at,
             last_heartbeat: self.last_heartbeat,
             online: self.online,
@@ -494,6 +783,18 @@ pub struct GlobalStatsSnapshot {
     pub deregistrations: u64,
 }
 
+/// Apply a signed change to an unsigned counter, saturating at zero
+fn adjust(counter: &AtomicU64, delta: i128) {
+    let magnitude = delta.unsigned_abs().min(u64::MAX as u128) as u64;
+    if delta >= 0 {
+        counter.fetch_add(magnitude, Ordering::Relaxed);
+    } else {
+        let _ = counter.fetch_update(Ordering::Relaxed, Ordering::Relaxed, |v| {
+            Some(v.saturating_sub(magnitude))
+        });
+    }
+}
+
 // =============================================================================
 // Node Registry
 // =============================================================================
@@ -606,12 +907,15 @@ impl NodeRegistry {
         let node_id = node_id.into();
         let shard_idx = node_id.shard_index();
 
-        self.shards[shard_idx].update_status(&node_id, status)?;
+        let updates = self.shards[shard_idx].update_status(&node_id, status)?;
 
         // Send event
         let _ = self.event_sender.send(super::RegistryEvent::NodeUpdated {
             node_id: node_id.to_string(),
         });
+        for update in &updates {
+            self.emit_risk_change(&node_id, update);
+        }
 
         Ok(())
     }
@@ -694,6 +998,98 @@ impl NodeRegistry {
         }
     }
 
+    /// Add a newly detected drive to a node, or refresh one already known.
+    ///
+    /// Emits `DriveAdded` only when the drive is new. Returns whether it was.
+    pub fn upsert_drive(&self, node_id: impl Into<NodeId>, drive: DriveStatus) -> Result<bool> {
+        let node_id = node_id.into();
+        let shard_idx = node_id.shard_index();
+        let drive_id = drive.id.clone();
+        let capacity_bytes = drive.capacity_bytes;
+
+        let (added, total_delta, available_delta, update) =
+            self.shards[shard_idx].upsert_drive(&node_id, drive)?;
+
+        if added {
+            self.global_stats.total_drives.fetch_add(1, Ordering::Relaxed);
+        }
+        adjust(&self.global_stats.total_capacity_bytes, total_delta);
+        adjust(&self.global_stats.available_capacity_bytes, available_delta);
+
+        if added {
+            let _ = self.event_sender.send(super::RegistryEvent::DriveAdded {
+                node_id: node_id.to_string(),
+                drive_id,
+                capacity_bytes,
+            });
+        }
+        if let Some(update) = &update {
+            self.emit_risk_change(&node_id, update);
+        }
+
+        Ok(added)
+    }
+
+    /// Remove a drive path that has disappeared from a node.
+    ///
+    /// Emits `DriveRemoved` and returns the drive once its last path is
+    /// gone; removing one of several paths keeps the drive.
+    pub fn remove_drive(&self, node_id: impl Into<NodeId>, drive_id: &str) -> Result<Option<DriveStatus>> {
+        let node_id = node_id.into();
+        let shard_idx = node_id.shard_index();
+
+        let removed = self.shards[shard_idx].remove_drive(&node_id, drive_id)?;
+
+        if let Some(drive) = &removed {
+            self.global_stats.total_drives.fetch_sub(1, Ordering::Relaxed);
+            adjust(&self.global_stats.total_capacity_bytes, -(drive.capacity_bytes as i128));
+            if drive.pool_ref.is_none() && drive.in_use.is_empty() {
+                adjust(
+                    &self.global_stats.available_capacity_bytes,
+                    -(drive.capacity_bytes as i128),
+                );
+            }
+            let _ = self.event_sender.send(super::RegistryEvent::DriveRemoved {
+                node_id: node_id.to_string(),
+                drive_id: drive.id.clone(),
+            });
+        }
+
+        Ok(removed)
+    }
+
+    /// Record a SMART sample for a drive and re-assess its failure risk.
+    ///
+    /// Emits `DriveHealthChanged` when the risk level changes, with the
+    /// trends behind the new level as the reason and the drive's location.
+    pub fn record_smart_sample(
+        &self,
+        node_id: impl Into<NodeId>,
+        drive_id: &str,
+        sample: SmartSample,
+    ) -> Result<FailureRisk> {
+        let node_id = node_id.into();
+        let shard_idx = node_id.shard_index();
+
+        let update = self.shards[shard_idx].record_smart_sample(&node_id, drive_id, sample)?;
+        self.emit_risk_change(&node_id, &update);
+
+        Ok(update.risk)
+    }
+
+    /// Emit `DriveHealthChanged` if a sample moved a drive to another risk level
+    fn emit_risk_change(&self, node_id: &NodeId, update: &RiskUpdate) {
+        if update.level_changed() {
+            let _ = self.event_sender.send(super::RegistryEvent::DriveHealthChanged {
+                node_id: node_id.to_string(),
+                drive_id: update.drive_id.clone(),
+                healthy: update.healthy,
+                reason: Some(update.risk.summary()),
+                location: update.location.clone(),
+            });
+        }
+    }
+
     /// Get global statistics
     pub fn stats(&self) -> GlobalStatsSnapshot {
         self.global_stats.snapshot()
@@ -840,4 +1236,224 @@ mod tests {
         let result = registry.register("node-001", "host-001.local".to_string(), status);
         assert!(result.is_err());
     }
+
+    fn nvme_sample(hours: i64, media_errors: u64, critical_warning: u8) -> SmartSample {
+        let base = DateTime::parse_from_rfc3339("2024-01-01T00:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        SmartSample {
+            timestamp: base + chrono::Duration::hours(hours),
+            temperature_celsius: Some(40),
+            wear_percent: Some(10),
+            media_errors,
+            reallocations: 0,
+            critical_warning,
+        }
+    }
+
+    #[test]
+    fn test_smart_history_drives_health_and_events() {
+        let registry = NodeRegistry::new();
+        let drive: DriveStatus = serde_json::from_value(serde_json::json!({
+            "id": "nvme0n1",
+            "devicePath": "/dev/nvme0n1",
+            "driveType": "nvme",
+            "model": "Test Drive",
+            "serial": "TEST123",
+            "capacityBytes": 1_000_000_000_000u64,
+            "classification": {},
+            "location": {"pciAddress": "0000:3b:00.0", "slot": "3", "numaNode": 1}
+        }))
+        .unwrap();
+        let status = StorageNodeStatus {
+            drives: vec![drive],
+            ..Default::default()
+        };
+        registry
+            .register("node-001", "host-001.local".to_string(), status.clone())
+            .unwrap();
+        let mut events = registry.subscribe();
+
+        // Stable samples do not change anything
+        registry.record_smart_sample("node-001", "nvme0n1", nvme_sample(0, 0, 0)).unwrap();
+        let risk = registry
+            .record_smart_sample("node-001", "nvme0n1", nvme_sample(1, 0, 0))
+            .unwrap();
+        assert_eq!(risk.level, RiskLevel::Low);
+        assert!(events.try_recv().is_err());
+
+        // Sudden media error growth with a new critical warning
+        let risk = registry
+            .record_smart_sample("node-001", "nvme0n1", nvme_sample(2, 40, 0x04))
+            .unwrap();
+        assert!(risk.is_high());
+
+        let drive = registry.get("node-001").unwrap().drives()[0].clone();
+        assert!(!drive.healthy);
+        assert_eq!(drive.failure_risk.unwrap().level, "high");
+
+        match events.try_recv().unwrap() {
+            super::super::RegistryEvent::DriveHealthChanged {
+                drive_id,
+                healthy,
+                reason,
+                location,
+                ..
+            } => {
+                assert_eq!(drive_id, "nvme0n1");
+                assert!(!healthy);
+                assert!(reason.unwrap().contains("media errors"));
+                assert_eq!(
+                    location.as_deref(),
+                    Some("slot 3, pci 0000:3b:00.0, numa node 1")
+                );
+            }
+            other => panic!("unexpected event {:?}", other),
+        }
+
+        // A fresh status report from the node keeps the predicted risk
+        registry.update_status("node-001", status).unwrap();
+        assert!(!registry.get("node-001").unwrap().drives()[0].healthy);
+
+        assert!(registry
+            .record_smart_sample("node-001", "sda", nvme_sample(3, 0, 0))
+            .is_err());
+    }
+
+    #[test]
+    fn test_reported_smart_is_sampled() {
+        let registry = NodeRegistry::new();
+        let drive = |media_errors: u64, critical_warning: u8| -> DriveStatus {
+            serde_json::from_value(serde_json::json!({
+                "id": "nvme0n1",
+                "devicePath": "/dev/nvme0n1",
+                "driveType": "nvme",
+                "model": "Test Drive",
+                "serial": "TEST123",
+                "capacityBytes": 1_000_000_000_000u64,
+                "classification": {},
+                "healthy": true,
+                "smart": {
+                    "temperatureCelsius": 40,
+                    "percentageUsed": 10,
+                    "criticalWarning": critical_warning,
+                    "mediaErrors": media_errors
+                }
+            }))
+            .unwrap()
+        };
+        let status = |drive: DriveStatus| StorageNodeStatus {
+            drives: vec![drive],
+            ..Default::default()
+        };
+        let history_len = |registry: &NodeRegistry| {
+            registry
+                .get("node-001")
+                .unwrap()
+                .get_smart_history("nvme0n1")
+                .map_or(0, |h| h.len())
+        };
+
+        registry
+            .register("node-001", "host-001.local".to_string(), status(drive(0, 0)))
+            .unwrap();
+        assert_eq!(history_len(&registry), 1);
+        let mut events = registry.subscribe();
+
+        // Repeated counters are not sampled again right away
+        registry.update_status("node-001", status(drive(0, 0))).unwrap();
+        assert_eq!(history_len(&registry), 1);
+
+        // A status report with growing errors raises the risk
+        registry.update_status("node-001", status(drive(40, 0x04))).unwrap();
+        assert_eq!(history_len(&registry), 2);
+        let reported = registry.get("node-001").unwrap().drives()[0].clone();
+        assert!(!reported.healthy);
+        assert_eq!(reported.failure_risk.unwrap().level, "high");
+
+        let health_changes = std::iter::from_fn(|| events.try_recv().ok())
+            .filter(|e| matches!(e, super::super::RegistryEvent::DriveHealthChanged { .. }))
+            .count();
+        assert_eq!(health_changes, 1);
+
+        // Hotplug refreshes are sampled too
+        registry.upsert_drive("node-001", drive(45, 0x04)).unwrap();
+        assert_eq!(history_len(&registry), 3);
+        assert!(!registry.get("node-001").unwrap().drives()[0].healthy);
+    }
+
+    fn path_status(id: &str, paths: &[&str]) -> DriveStatus {
+        serde_json::from_value(serde_json::json!({
+            "id": id,
+            "devicePath": format!("/dev/{}", id),
+            "driveType": "hdd",
+            "model": "ST16000NM002G",
+            "serial": "ZL2ABCDE",
+            "capacityBytes": 16_000_000_000_000u64,
+            "classification": {},
+            "wwid": "naa.5000c500a1b2c3d4",
+            "paths": paths
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_multipath_drive_paths() {
+        let registry = NodeRegistry::new();
+        registry
+            .register("node-001", "host-001.local".to_string(), StorageNodeStatus::default())
+            .unwrap();
+
+        // A second path to a known drive is merged, not added
+        assert!(registry.upsert_drive("node-001", path_status("sdb", &["sdb"])).unwrap());
+        assert!(!registry.upsert_drive("node-001", path_status("sdq", &["sdq"])).unwrap());
+        let drives = registry.get("node-001").unwrap().drives().to_vec();
+        assert_eq!(drives.len(), 1);
+        assert_eq!(drives[0].paths, vec!["sdb", "sdq"]);
+        assert_eq!(registry.stats().total_capacity_bytes, 16_000_000_000_000);
+
+        // The multipath device takes over once it appears
+        let dm = path_status("dm-0", &["dm-0", "sdb", "sdq"]);
+        assert!(!registry.upsert_drive("node-001", dm).unwrap());
+        let drives = registry.get("node-001").unwrap().drives().to_vec();
+        assert_eq!(drives.len(), 1);
+        assert_eq!(drives[0].id, "dm-0");
+        assert!(registry.get_drive_metrics("node-001", "dm-0").is_some());
+
+        // Losing paths keeps the drive until the last one is gone
+        assert!(registry.remove_drive("node-001", "sdq").unwrap().is_none());
+        assert!(registry.remove_drive("node-001", "dm-0").unwrap().is_none());
+        let drives = registry.get("node-001").unwrap().drives().to_vec();
+        assert_eq!(drives[0].id, "sdb");
+        assert_eq!(drives[0].device_path, "/dev/sdb");
+
+        let removed = registry.remove_drive("node-001", "sdb").unwrap().unwrap();
+        assert_eq!(removed.id, "sdb");
+        assert!(registry.get("node-001").unwrap().drives().is_empty());
+        assert_eq!(registry.stats().total_drives, 0);
+        assert_eq!(registry.stats().total_capacity_bytes, 0);
+    }
+
+    #[test]
+    fn test_rescanned_path_keeps_other_paths() {
+        let registry = NodeRegistry::new();
+        registry
+            .register("node-001", "host-001.local".to_string(), StorageNodeStatus::default())
+            .unwrap();
+        registry.upsert_drive("node-001", path_status("sdb", &["sdb"])).unwrap();
+        registry.upsert_drive("node-001", path_status("sdq", &["sdq"])).unwrap();
+
+        // A hot-plug change on the preferred path rescans only that path
+        assert!(!registry.upsert_drive("node-001", path_status("sdb", &["sdb"])).unwrap());
+        let drives = registry.get("node-001").unwrap().drives().to_vec();
+        assert_eq!(drives[0].paths, vec!["sdb", "sdq"]);
+
+        // Losing that path leaves the drive on the other one
+        assert!(registry.remove_drive("node-001", "sdb").unwrap().is_none());
+        let drives = registry.get("node-001").unwrap().drives().to_vec();
+        assert_eq!(drives.len(), 1);
+        assert_eq!(drives[0].id, "sdq");
+        assert_eq!(registry.stats().total_drives, 1);
+        assert_eq!(registry.stats().total_capacity_bytes, 16_000_000_000_000);
+    }
 }
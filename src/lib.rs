@@ -37,6 +37,7 @@
 //!
 //! # Modules
 //!
+//! - [`config`]: Operator configuration file
 //! - [`controlplane`]: Unified control plane orchestrator and APIs
 //! - [`hardware`]: Hardware discovery, classification, and allocation
 //! - [`crd`]: Custom Resource Definitions
@@ -44,6 +45,7 @@
 //! - [`error`]: Error types and handling
 
 pub mod cache;
+pub mod config;
 pub mod controlplane;
 pub mod crd;
 pub mod domain;
@@ -56,12 +58,26 @@ pub use controlplane::{
     ApiServer, ApiServerConfig,
     BackendConfig, BackendFactory,
     PlatformConfig, PlatformFactory,
+    TieringConfig, TieringEngine, TieringMode,
+    FileSagaStore, InMemorySagaStore, SagaStore,
+    DriftAction, GhostAction, OrphanAction, Reconciler, ReconcilerConfig,
+    ImportSelection, Importer,
+    QuotaLimits, QuotaManager,
+    CallerIdentity, PolicyEngine,
+    AuditLog, AuditSink, JsonLinesAuditSink, KubernetesEventSink,
+    EventFilter, EventHub, EventKind, OrchestratorEvent, StreamEvent,
+    WebhookDeliveryConfig, WebhookDispatcher, WebhookSubscription,
 };
 
+pub use config::OperatorConfig;
+
 pub use crd::{
     UnifiedStorageClass, UnifiedStorageClassSpec, UnifiedStorageClassStatus,
     StorageNode, StorageNodeSpec, StorageNodeStatus,
     UnifiedPool, UnifiedPoolSpec, UnifiedPoolStatus,
+    StorageQuota, StorageQuotaSpec, StorageQuotaStatus,
+    StoragePolicy, StoragePolicySpec,
+    DriveFingerprint as DriveFingerprintResource, DriveFingerprintSpec,
     BackendType, UnifiedStorageType, UnifiedTier,
     DriveTier, DriveType, WorkloadSuitability,
 };
@@ -76,8 +92,9 @@ pub use error::{Error, Result, ErrorAction};
 
 pub use hardware::{
     NodeRegistry, NodeId, DriveMetrics, GlobalStatsSnapshot,
-    HardwareScanner, ScannerConfig,
+    HardwareScanner, ScannerConfig, HardwareProfile, HotplugMonitor, HotplugConfig,
     DeviceClassifier, DeviceClassification,
+    FingerprintDatabase, FingerprintFileWatcher,
     DriveAllocator, AllocationPolicy, PlacementPolicy,
 };
 
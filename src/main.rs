@@ -35,15 +35,27 @@
 //! └─────────────────────────────────────────────────────────────────────────────┘
 //! ```
 
-use clap::Parser;
+use clap::{Parser, Subcommand};
 use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
-use tracing::{error, info, Level};
+use tracing::{error, info, warn, Level};
 use tracing_subscriber::{fmt, prelude::*, EnvFilter};
 
 use smart_storage_operator::{
-    ApiServer, ApiServerConfig, NodeRegistry, Orchestrator, OrchestratorConfig,
-    Result, Error,
+    ApiServer, NodeRegistry, OperatorConfig, Orchestrator, OrchestratorConfig,
+    Result, Error, TieringConfig, TieringEngine, TieringMode,
+    FileSagaStore, InMemorySagaStore, SagaStore,
+    DriftAction, GhostAction, OrphanAction, Reconciler, ReconcilerConfig,
+    JsonLinesAuditSink, KubernetesEventSink,
+    EventHub, EventKind, WebhookDeliveryConfig, WebhookDispatcher,
+    HardwareProfile, HardwareScanner, HotplugConfig, HotplugMonitor, ScannerConfig,
+    StorageNodeStatus,
+    FingerprintFileWatcher,
+};
+use smart_storage_operator::controlplane::{watch_policy_resources, watch_quota_resources};
+use smart_storage_operator::hardware::classification::{
+    watch_fingerprint_resources, DEFAULT_RELOAD_INTERVAL,
 };
 
 // =============================================================================
@@ -54,13 +66,21 @@ use smart_storage_operator::{
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
 struct Args {
-    /// REST API bind address
-    #[arg(long, env = "API_ADDR", default_value = "0.0.0.0:8090")]
-    api_addr: String,
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    /// Operator configuration file (YAML); flags and environment variables
+    /// that are set take precedence over it
+    #[arg(long, env = "OPERATOR_CONFIG")]
+    config: Option<PathBuf>,
+
+    /// REST API bind address [default: 0.0.0.0:8090]
+    #[arg(long, env = "API_ADDR")]
+    api_addr: Option<String>,
 
-    /// gRPC API bind address
-    #[arg(long, env = "GRPC_ADDR", default_value = "0.0.0.0:8091")]
-    grpc_addr: String,
+    /// gRPC API bind address [default: 0.0.0.0:8091]
+    #[arg(long, env = "GRPC_ADDR")]
+    grpc_addr: Option<String>,
 
     /// Health server bind address
     #[arg(long, env = "HEALTH_ADDR", default_value = "0.0.0.0:8081")]
@@ -70,17 +90,26 @@ struct Args {
     #[arg(long, env = "METRICS_ADDR", default_value = "0.0.0.0:8080")]
     metrics_addr: String,
 
-    /// Mayastor namespace
-    #[arg(long, env = "MAYASTOR_NAMESPACE", default_value = "mayastor")]
-    mayastor_namespace: String,
+    /// Mayastor namespace [default: mayastor]
+    #[arg(long, env = "MAYASTOR_NAMESPACE")]
+    mayastor_namespace: Option<String>,
 
     /// Enable auto-discovery of hardware
     #[arg(long, env = "AUTO_DISCOVER")]
     auto_discover: bool,
 
-    /// Discovery interval in seconds
-    #[arg(long, env = "DISCOVER_INTERVAL", default_value = "300")]
-    discover_interval_secs: u64,
+    /// Discovery interval in seconds [default: 300]
+    #[arg(long, env = "DISCOVER_INTERVAL")]
+    discover_interval_secs: Option<u64>,
+
+    /// Discover this node's drives at startup, register the node and keep
+    /// its drives current as they are hot-plugged or removed
+    #[arg(long, env = "HOTPLUG")]
+    hotplug: bool,
+
+    /// Name this node registers under with --hotplug [default: discovered node ID]
+    #[arg(long, env = "NODE_NAME")]
+    node_name: Option<String>,
 
     /// Log level (trace, debug, info, warn, error)
     #[arg(long, env = "LOG_LEVEL", default_value = "info")]
@@ -93,6 +122,165 @@ struct Args {
     /// Run in standalone mode (no Kubernetes)
     #[arg(long, env = "STANDALONE")]
     standalone: bool,
+
+    /// Automatic tiering mode (off, dry-run, enforce); overrides the
+    /// config file's `tiering.mode` (off if neither is set)
+    #[arg(long, env = "TIERING_MODE")]
+    tiering_mode: Option<String>,
+
+    /// Maximum concurrent tier migrations
+    #[arg(long, env = "TIERING_MAX_CONCURRENT_MOVES")]
+    tiering_max_concurrent_moves: Option<usize>,
+
+    /// Directory for persisted saga state (in-memory if unset)
+    #[arg(long, env = "SAGA_DIR")]
+    saga_dir: Option<std::path::PathBuf>,
+
+    /// Seconds between orchestrator/backend reconciliation passes (0 = off)
+    #[arg(long, env = "RECONCILE_INTERVAL", default_value = "300")]
+    reconcile_interval: u64,
+
+    /// Orphaned backend storage: report, adopt, or delete
+    #[arg(long, env = "RECONCILE_ORPHANS", default_value = "report")]
+    reconcile_orphans: String,
+
+    /// Records missing from their backend: report or mark-lost (cleared when they reappear)
+    #[arg(long, env = "RECONCILE_GHOSTS", default_value = "report")]
+    reconcile_ghosts: String,
+
+    /// Capacity drift: report or update
+    #[arg(long, env = "RECONCILE_DRIFT", default_value = "report")]
+    reconcile_drift: String,
+
+    /// YAML file of StorageQuota resources to load at startup (cluster
+    /// StorageQuota resources are watched as well unless --standalone)
+    #[arg(long, env = "QUOTAS_FILE")]
+    quotas: Option<std::path::PathBuf>,
+
+    /// YAML file, or directory of YAML files, of StoragePolicy resources
+    /// (cluster StoragePolicy resources are watched as well unless --standalone)
+    #[arg(long, env = "POLICIES_PATH")]
+    policies: Option<std::path::PathBuf>,
+
+    /// Drive fingerprint file or directory (YAML/JSON), reloaded on change;
+    /// may be repeated
+    #[arg(long, env = "FINGERPRINTS_PATH", value_delimiter = ',')]
+    fingerprints: Vec<std::path::PathBuf>,
+
+    /// Append audit events to this JSON-lines file
+    #[arg(long, env = "AUDIT_LOG")]
+    audit_log: Option<std::path::PathBuf>,
+
+    /// Rotate the audit log when it would exceed this many bytes
+    #[arg(long, env = "AUDIT_LOG_MAX_BYTES", default_value = "104857600")]
+    audit_log_max_bytes: u64,
+
+    /// Number of rotated audit log files to keep
+    #[arg(long, env = "AUDIT_LOG_MAX_FILES", default_value = "5")]
+    audit_log_max_files: usize,
+
+    /// Publish audit events as Kubernetes Events in this namespace
+    #[arg(long, env = "AUDIT_EVENTS_NAMESPACE")]
+    audit_events_namespace: Option<String>,
+
+    /// Number of events kept for Last-Event-ID replay on /v1/events (0 disables replay)
+    #[arg(long, env = "EVENTS_REPLAY_BUFFER", default_value = "1024")]
+    events_replay_buffer: usize,
+
+    /// Webhook subscriptions and delivery settings (YAML)
+    #[arg(long, env = "WEBHOOKS_FILE")]
+    webhooks: Option<std::path::PathBuf>,
+
+    /// Seconds between backend health checks (0 = only on status requests)
+    #[arg(long, env = "BACKEND_HEALTH_INTERVAL", default_value = "30")]
+    backend_health_interval: u64,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Check a configuration file and exit
+    ValidateConfig {
+        /// File to check (defaults to --config)
+        path: Option<PathBuf>,
+    },
+    /// Snapshot this node's sysfs and nvme-cli/smartctl output to a
+    /// hardware profile and exit
+    CaptureHardware {
+        /// Profile file to write (`.zst` compresses it)
+        #[arg(short, long)]
+        output: PathBuf,
+        /// sysfs mount point
+        #[arg(long, default_value = "/sys")]
+        sysfs: PathBuf,
+    },
+    /// Run hardware discovery and print the result as JSON, optionally
+    /// replaying a captured profile instead of probing this node
+    DiscoverHardware {
+        /// Hardware profile to replay
+        #[arg(long)]
+        profile: Option<PathBuf>,
+    },
+}
+
+impl Args {
+    /// Configuration from `--config` (or defaults) with flag overrides applied
+    fn operator_config(&self) -> Result<OperatorConfig> {
+        let mut config = match &self.config {
+            Some(path) => OperatorConfig::load(path)?,
+            // Without a file, classification only runs with --auto-discover
+            None => OperatorConfig {
+                orchestrator: OrchestratorConfig {
+                    auto_classify: false,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        };
+
+        if let Some(addr) = &self.api_addr {
+            config.api.rest_addr = addr.parse().map_err(|e| {
+                Error::Configuration(format!("Invalid REST API address: {}", e))
+            })?;
+        }
+        if let Some(addr) = &self.grpc_addr {
+            config.api.grpc_addr = addr.parse().map_err(|e| {
+                Error::Configuration(format!("Invalid gRPC API address: {}", e))
+            })?;
+        }
+        if let Some(namespace) = &self.mayastor_namespace {
+            config.orchestrator.backends.mayastor.namespace = namespace.clone();
+        }
+        if let Some(interval) = self.discover_interval_secs {
+            config.orchestrator.classify_interval_secs = interval;
+        }
+        if self.auto_discover {
+            config.orchestrator.auto_classify = true;
+        }
+        if let Some(mode) = &self.tiering_mode {
+            let mode = match mode.to_lowercase().as_str() {
+                "off" => None,
+                "dry-run" => Some(TieringMode::DryRun),
+                "enforce" => Some(TieringMode::Enforce),
+                other => {
+                    return Err(Error::Configuration(format!(
+                        "Invalid tiering mode: {}. Use 'off', 'dry-run', or 'enforce'",
+                        other
+                    )))
+                }
+            };
+            config.tiering = mode.map(|mode| TieringConfig {
+                mode,
+                ..config.tiering.take().unwrap_or_default()
+            });
+        }
+        if let (Some(tiering), Some(moves)) =
+            (config.tiering.as_mut(), self.tiering_max_concurrent_moves)
+        {
+            tiering.max_concurrent_moves = moves;
+        }
+
+        Ok(config)
+    }
 }
 
 // =============================================================================
@@ -103,33 +291,194 @@ struct Args {
 async fn main() -> Result<()> {
     let args = Args::parse();
 
+    match &args.command {
+        Some(Command::ValidateConfig { path }) => {
+            validate_config(path.as_ref().or(args.config.as_ref()));
+        }
+        Some(Command::CaptureHardware { output, sysfs }) => {
+            init_logging(&args);
+            capture_hardware(output, sysfs).await;
+        }
+        Some(Command::DiscoverHardware { profile }) => {
+            // No logging: stdout carries only the JSON result
+            discover_hardware(profile.as_deref()).await;
+        }
+        None => {}
+    }
+    let config = args.operator_config()?;
+
     // Initialize logging
     init_logging(&args);
 
     info!("Starting Smart Storage Operator - Unified Control Plane");
     info!("  Version: {}", smart_storage_operator::VERSION);
-    info!("  REST API: {}", args.api_addr);
-    info!("  gRPC API: {}", args.grpc_addr);
-    info!("  Auto-discover: {}", args.auto_discover);
+    if let Some(path) = &args.config {
+        info!("  Config file: {}", path.display());
+    }
+    info!("  REST API: {}", config.api.rest_addr);
+    info!("  gRPC API: {}", config.api.grpc_addr);
+    info!("  Auto-discover: {}", config.orchestrator.auto_classify);
     info!("  Standalone mode: {}", args.standalone);
 
     // Create node registry
     let registry = NodeRegistry::new();
     info!("Node registry initialized (256-way sharded)");
 
-    // Create orchestrator config
-    let mut orch_config = OrchestratorConfig::default();
-    orch_config.auto_classify = args.auto_discover;
-    orch_config.classify_interval_secs = args.discover_interval_secs;
-    orch_config.backends.mayastor.namespace = args.mayastor_namespace.clone();
-
     // Create orchestrator
-    let orchestrator = Orchestrator::new(orch_config, registry.clone());
+    let saga_store: Arc<dyn SagaStore> = match &args.saga_dir {
+        Some(dir) => {
+            info!("Persisting saga state to {}", dir.display());
+            Arc::new(FileSagaStore::new(dir).await?)
+        }
+        None => Arc::new(InMemorySagaStore::new()),
+    };
+    let orchestrator = Orchestrator::with_saga_store(config.orchestrator, registry.clone(), saga_store);
+
+    // Configure audit sinks before anything is recorded
+    if let Some(path) = &args.audit_log {
+        let sink = JsonLinesAuditSink::new(path, args.audit_log_max_bytes, args.audit_log_max_files)
+            .await?;
+        orchestrator.audit().add_sink(Arc::new(sink));
+        info!("Writing audit log to {}", path.display());
+    }
+    if let Some(namespace) = &args.audit_events_namespace {
+        if args.standalone {
+            return Err(Error::Configuration(
+                "--audit-events-namespace requires a Kubernetes cluster (not --standalone)".into(),
+            ));
+        }
+        let client = kube::Client::try_default().await?;
+        orchestrator
+            .audit()
+            .add_sink(Arc::new(KubernetesEventSink::new(client, namespace)));
+        info!("Publishing audit events to namespace {}", namespace);
+    }
 
     // Initialize orchestrator
     orchestrator.initialize().await?;
     info!("Orchestrator initialized");
 
+    // Load tenant quotas
+    if let Some(path) = &args.quotas {
+        let tenants = orchestrator.quotas().load_manifests(path)?;
+        info!("Loaded storage quotas for {} tenant(s) from {}", tenants.len(), path.display());
+    }
+
+    // Load admission policies
+    if let Some(path) = &args.policies {
+        let policies = orchestrator.policies().load_manifests(path)?;
+        info!("Loaded {} storage policies from {}", policies.len(), path.display());
+    }
+
+    let (background_shutdown_tx, _) = tokio::sync::broadcast::channel(1);
+
+    // Register this node from discovery and follow drive hot-plug
+    if args.hotplug {
+        let scanner = Arc::new(HardwareScanner::default_scanner());
+        let hardware = scanner.discover().await?;
+        let node_name = args.node_name.clone().unwrap_or_else(|| hardware.node_id.clone());
+        let status = StorageNodeStatus::from(&hardware);
+        info!("Registering local node {} with {} drive(s)", node_name, status.drive_count);
+        registry.register(node_name.as_str(), hardware.hostname.clone(), status)?;
+
+        let monitor = HotplugMonitor::new(
+            node_name,
+            scanner,
+            registry.clone(),
+            HotplugConfig::default(),
+        );
+        tokio::spawn(monitor.run(background_shutdown_tx.subscribe()));
+    }
+
+    // Load drive fingerprints, follow changes and watch cluster resources
+    if !args.fingerprints.is_empty() {
+        let watcher = FingerprintFileWatcher::new(
+            orchestrator.fingerprints().clone(),
+            args.fingerprints.clone(),
+            DEFAULT_RELOAD_INTERVAL,
+        );
+        watcher.load()?;
+        tokio::spawn(watcher.run(background_shutdown_tx.subscribe()));
+    }
+    if !args.standalone {
+        match kube::Client::try_default().await {
+            Ok(client) => {
+                tokio::spawn(watch_fingerprint_resources(
+                    orchestrator.fingerprints().clone(),
+                    client.clone(),
+                    background_shutdown_tx.subscribe(),
+                ));
+                tokio::spawn(watch_quota_resources(
+                    orchestrator.quotas().clone(),
+                    client.clone(),
+                    background_shutdown_tx.subscribe(),
+                ));
+                tokio::spawn(watch_policy_resources(
+                    orchestrator.policies().clone(),
+                    client,
+                    background_shutdown_tx.subscribe(),
+                ));
+            }
+            Err(e) => warn!("Not watching cluster resources: {}", e),
+        }
+    }
+
+    // Start tiering engine, fed by agent access reports
+    let tiering = match config.tiering {
+        Some(tiering_config) => {
+            let mode = tiering_config.mode;
+            let rules = tiering_config.rules.len();
+            let engine = TieringEngine::new(tiering_config, orchestrator.clone(), registry.clone());
+            tokio::spawn(engine.clone().run(background_shutdown_tx.subscribe()));
+            info!("Tiering engine started ({:?}, {} rules)", mode, rules);
+            Some(engine)
+        }
+        None => None,
+    };
+
+    // Start reconciler
+    let reconciler = if args.reconcile_interval > 0 {
+        let config = ReconcilerConfig {
+            interval_secs: args.reconcile_interval,
+            orphan_action: match args.reconcile_orphans.to_lowercase().as_str() {
+                "report" => OrphanAction::Report,
+                "adopt" => OrphanAction::Adopt,
+                "delete" => OrphanAction::Delete,
+                other => {
+                    return Err(Error::Configuration(format!(
+                        "Invalid orphan action: {}. Use 'report', 'adopt', or 'delete'",
+                        other
+                    )))
+                }
+            },
+            ghost_action: match args.reconcile_ghosts.to_lowercase().as_str() {
+                "report" => GhostAction::Report,
+                "mark-lost" => GhostAction::MarkLost,
+                other => {
+                    return Err(Error::Configuration(format!(
+                        "Invalid ghost action: {}. Use 'report' or 'mark-lost'",
+                        other
+                    )))
+                }
+            },
+            drift_action: match args.reconcile_drift.to_lowercase().as_str() {
+                "report" => DriftAction::Report,
+                "update" => DriftAction::Update,
+                other => {
+                    return Err(Error::Configuration(format!(
+                        "Invalid drift action: {}. Use 'report' or 'update'",
+                        other
+                    )))
+                }
+            },
+        };
+        let reconciler = Reconciler::new(config, orchestrator.clone());
+        tokio::spawn(reconciler.clone().run(background_shutdown_tx.subscribe()));
+        Some(reconciler)
+    } else {
+        None
+    };
+
     // Start health server
     let health_addr = args.health_addr.clone();
     tokio::spawn(async move {
@@ -147,21 +496,62 @@ async fn main() -> Result<()> {
     });
 
     // Create and run API server
-    let api_config = ApiServerConfig {
-        rest_addr: args.api_addr.parse().map_err(|e| {
-            Error::Configuration(format!("Invalid REST API address: {}", e))
-        })?,
-        grpc_addr: args.grpc_addr.parse().map_err(|e| {
-            Error::Configuration(format!("Invalid gRPC API address: {}", e))
-        })?,
-        ..Default::default()
+    // Fan registry and orchestrator events into the /v1/events stream
+    let events = EventHub::new(args.events_replay_buffer);
+    events.attach(EventKind::Registry, registry.subscribe(), |e| {
+        Some(e.node_id().to_string())
+    });
+    events.attach(EventKind::Orchestrator, orchestrator.subscribe(), |e| {
+        e.node_id().map(str::to_string)
+    });
+
+    // Push matching events to webhook subscribers
+    let webhooks = match &args.webhooks {
+        Some(path) => {
+            let webhooks = WebhookDispatcher::from_file(path)?;
+            info!(
+                "Loaded {} webhook subscription(s) from {}",
+                webhooks.list().len(),
+                path.display()
+            );
+            webhooks
+        }
+        None => WebhookDispatcher::new(WebhookDeliveryConfig::default())?,
     };
+    webhooks.start(&events);
+
+    // Poll backend health so degraded pools are noticed without status requests
+    if args.backend_health_interval > 0 {
+        let orchestrator = orchestrator.clone();
+        let mut shutdown = background_shutdown_tx.subscribe();
+        let period = std::time::Duration::from_secs(args.backend_health_interval);
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(period);
+            loop {
+                tokio::select! {
+                    _ = interval.tick() => {
+                        orchestrator.backends_health().await;
+                    }
+                    _ = shutdown.recv() => break,
+                }
+            }
+        });
+    }
 
-    let api_server = ApiServer::new(api_config, orchestrator.clone(), registry.clone());
+    let mut api_server = ApiServer::new(config.api, orchestrator.clone(), registry.clone())
+        .with_events(events)
+        .with_webhooks(webhooks);
+    if let Some(reconciler) = reconciler {
+        api_server = api_server.with_reconciler(reconciler);
+    }
+    if let Some(tiering) = tiering {
+        api_server = api_server.with_tiering(tiering);
+    }
 
     info!("Starting unified API server");
     api_server.run().await?;
 
+    let _ = background_shutdown_tx.send(());
     info!("Operator shutdown complete");
     Ok(())
 }
@@ -170,6 +560,84 @@ async fn main() -> Result<()> {
 // Logging Setup
 // =============================================================================
 
+/// Check a configuration file, report the result and exit
+fn validate_config(path: Option<&PathBuf>) -> ! {
+    let Some(path) = path else {
+        eprintln!("validate-config: pass a file or --config");
+        std::process::exit(2);
+    };
+    match OperatorConfig::load(path) {
+        Ok(_) => {
+            println!("{}: configuration is valid", path.display());
+            std::process::exit(0);
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+    }
+}
+
+async fn capture_hardware(output: &Path, sysfs: &Path) -> ! {
+    let config = ScannerConfig {
+        sysfs_path: sysfs.to_path_buf(),
+        ..Default::default()
+    };
+    let result = match HardwareProfile::capture(config).await {
+        Ok(profile) => profile.save(output),
+        Err(e) => Err(e),
+    };
+    match result {
+        Ok(()) => {
+            println!("Hardware profile written to {}", output.display());
+            std::process::exit(0);
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+    }
+}
+
+async fn discover_hardware(profile: Option<&Path>) -> ! {
+    // The replay tree must outlive the scan
+    let mut replay_root = None;
+    let scanner = match profile {
+        Some(path) => {
+            let replay = HardwareProfile::load(path).and_then(|profile| {
+                let root = std::env::temp_dir()
+                    .join(format!("hardware-profile-{}", std::process::id()));
+                let scanner = profile.replay(&root)?;
+                replay_root = Some(root);
+                Ok(scanner)
+            });
+            match replay {
+                Ok(scanner) => scanner,
+                Err(e) => {
+                    eprintln!("{}", e);
+                    std::process::exit(1);
+                }
+            }
+        }
+        None => HardwareScanner::default_scanner(),
+    };
+
+    let result = scanner.discover().await;
+    if let Some(root) = replay_root {
+        let _ = std::fs::remove_dir_all(root);
+    }
+    match result.and_then(|info| Ok(serde_json::to_string_pretty(&info)?)) {
+        Ok(json) => {
+            println!("{}", json);
+            std::process::exit(0);
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+    }
+}
+
 fn init_logging(args: &Args) {
     let level = match args.log_level.to_lowercase().as_str() {
         "trace" => Level::TRACE,